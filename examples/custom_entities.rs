@@ -7,13 +7,13 @@
 //! * it assumes only XML 1.0 dialect (most widely used in the world)
 //! * it assumes that the XML file is UTF-8 encoded (custom_entities must only contain UTF-8 data)
 //! * it only handles internal entities;
-//! * the regex in this example is simple but brittle;
 //! * it does not support the use of entities in entity declaration.
 
 use std::borrow::Cow;
 use std::collections::{HashMap, VecDeque};
 use std::str::from_utf8;
 
+use quick_xml::dtd::{DtdEvent, DtdReader, EntityDef};
 use quick_xml::encoding::Decoder;
 use quick_xml::errors::Error;
 use quick_xml::escape::EscapeError;
@@ -21,7 +21,6 @@ use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
 use quick_xml::name::QName;
 use quick_xml::reader::Reader;
 use quick_xml::XmlVersion;
-use regex::bytes::Regex;
 
 use pretty_assertions::assert_eq;
 
@@ -32,26 +31,19 @@ struct MyReader<'i> {
     /// Map of captured internal _parsed general entities_. _Parsed_ means that
     /// value of the entity is parsed by XML reader
     entities: HashMap<&'i [u8], &'i [u8]>,
-    /// In this example we use simple regular expression to capture entities from DTD.
-    /// In real application you should use DTD parser.
-    entity_re: Regex,
 }
 impl<'i> MyReader<'i> {
-    fn new(input: &'i str) -> Result<Self, regex::Error> {
+    fn new(input: &'i str) -> Self {
         let mut reader = Reader::from_str(input);
         reader.config_mut().trim_text(true);
 
         let mut readers = VecDeque::new();
         readers.push_back(reader);
 
-        // Capture "name" and "content" from such string:
-        // <!ENTITY name "content" >
-        let entity_re = Regex::new(r#"<!ENTITY\s+([^ \t\r\n]+)\s+"([^"]*)"\s*>"#)?;
-        Ok(Self {
+        Self {
             readers,
             entities: HashMap::new(),
-            entity_re,
-        })
+        }
     }
     fn read_event(&mut self) -> Result<Event<'i>, Error> {
         loop {
@@ -92,18 +84,26 @@ impl<'i> MyReader<'i> {
         }
     }
 
-    /// In this example we use simple regular expression to capture entities from DTD.
-    /// In real application you should use DTD parser
+    /// Captures internal general entities declared in DTD
     fn capture(&mut self, doctype: BytesText<'i>) {
         let doctype = match doctype.into_inner() {
             Cow::Borrowed(doctype) => doctype,
             Cow::Owned(_) => unreachable!("We are sure that event will be borrowed"),
         };
-        for cap in self.entity_re.captures_iter(doctype) {
-            self.entities.insert(
-                cap.get(1).unwrap().as_bytes(),
-                cap.get(2).unwrap().as_bytes(),
-            );
+        // SAFETY: We are sure that slice is correct UTF-8 because we get it
+        // from rust string
+        let doctype = from_utf8(doctype).unwrap();
+        for event in DtdReader::new(doctype) {
+            match event {
+                Ok(DtdEvent::EntityDecl(decl)) if !decl.parameter => {
+                    if let EntityDef::Internal(value) = decl.def {
+                        self.entities.insert(decl.name.as_bytes(), value.as_bytes());
+                    }
+                }
+                Ok(_) => {}
+                // In this example we simply ignore the rest of malformed DTD
+                Err(_) => break,
+            }
         }
     }
 
@@ -140,7 +140,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         ]>
         <test label="Message: &text;">&#39;&element2;&#x27;</test>
         "#,
-    )?;
+    );
 
     let event = reader.read_event()?;
     assert_eq!(
@@ -1,19 +1,13 @@
-// This example demonstrates how to read a UTF-16 encoded XML file using
-// DecodingReader, which auto-detects the encoding from the BOM or XML
-// declaration and transcodes to UTF-8 for the parser.
+// This example demonstrates how to read a UTF-16 encoded XML file. Reader
+// auto-detects the encoding from the BOM or XML declaration and transcodes
+// it to UTF-8 for the parser.
 
 fn main() -> Result<(), quick_xml::Error> {
-    use std::fs::File;
-    use std::io::BufReader;
-
-    use quick_xml::encoding::DecodingReader;
     use quick_xml::events::Event;
     use quick_xml::reader::Reader;
     use quick_xml::XmlVersion;
 
-    let file = File::open("tests/documents/encoding/utf16le-bom.xml")?;
-    let transcoder = DecodingReader::new(BufReader::new(file));
-    let mut reader = Reader::from_reader(transcoder);
+    let mut reader = Reader::from_file("tests/documents/encoding/utf16le-bom.xml")?;
     reader.config_mut().trim_text(true);
 
     let mut buf = Vec::new();
@@ -24,9 +18,6 @@ fn main() -> Result<(), quick_xml::Error> {
             Ok(Event::Decl(e)) => {
                 version = e.xml_version()?;
                 println!("decl: version={:?}", version);
-                if let Some(encoding) = e.encoder() {
-                    reader.get_mut().set_encoding(encoding);
-                }
             }
             Ok(Event::Start(ref e)) => {
                 let name = e.name();
@@ -0,0 +1,37 @@
+// This example generates Rust types for serde from XML Schema documents and
+// prints them to the standard output:
+//
+//     cargo run --example xsd_codegen --features xsd -- schema.xsd [other.xsd...] > types.rs
+//
+// All documents of the schema should be passed, because `<xs:import>` and
+// `<xs:include>` are not followed.
+
+use std::{env, fs, process};
+
+use quick_xml::xsd::{codegen, Schema};
+
+fn main() {
+    let paths: Vec<_> = env::args().skip(1).collect();
+    if paths.is_empty() {
+        eprintln!("usage: xsd_codegen <schema.xsd>...");
+        process::exit(2);
+    }
+
+    let mut documents = Vec::new();
+    for path in &paths {
+        match fs::read_to_string(path) {
+            Ok(document) => documents.push(document),
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                process::exit(1);
+            }
+        }
+    }
+    match Schema::parse_all(documents.iter().map(String::as_str)) {
+        Ok(schema) => print!("{}", codegen::generate(&schema)),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,241 @@
+//! Abstraction over asynchronous IO traits of different async runtimes.
+//!
+//! All asynchronous methods of [`Reader`], [`NsReader`] and [`Writer`] are
+//! implemented once over the [`AsyncSource`] and [`AsyncSink`] traits:
+//!
+//! - with the `async-tokio` feature they are implemented for all types that
+//!   implement [`tokio::io::AsyncBufRead`] and [`tokio::io::AsyncWrite`];
+//! - with the `async-futures` feature they are implemented for the [`FuturesIo`]
+//!   wrapper over types that implement [`futures_io::AsyncBufRead`] and
+//!   [`futures_io::AsyncWrite`].
+//!
+//! Usually you do not need to use these traits directly.
+//!
+//! [`Reader`]: crate::reader::Reader
+//! [`NsReader`]: crate::reader::NsReader
+//! [`Writer`]: crate::writer::Writer
+
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+/// An asynchronous buffered source of bytes from which XML can be read.
+///
+/// This is a minimal version of `AsyncBufRead` traits of `tokio` and `futures-io`,
+/// which allows to share the implementation of [`Reader`] between them.
+///
+/// [`Reader`]: crate::reader::Reader
+pub trait AsyncSource: Unpin {
+    /// Attempts to return the contents of the internal buffer, filling it with
+    /// more data from the inner reader if it is empty.
+    ///
+    /// An empty buffer returned indicates that the end of stream was reached.
+    fn poll_fill_buf(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>>;
+
+    /// Tells the source that `amt` bytes have been consumed from the buffer,
+    /// so they should no longer be returned by [`poll_fill_buf`](Self::poll_fill_buf).
+    fn consume(&mut self, amt: usize);
+
+    /// Returns the contents of the internal buffer, filling it with more data
+    /// from the inner reader if it is empty.
+    #[inline]
+    fn fill_buf(&mut self) -> FillBuf<'_, Self> {
+        FillBuf(Some(self))
+    }
+}
+
+/// An asynchronous sink of bytes to which XML can be written.
+///
+/// This is a minimal version of `AsyncWrite` traits of `tokio` and `futures-io`,
+/// which allows to share the implementation of [`Writer`] between them.
+///
+/// [`Writer`]: crate::writer::Writer
+pub trait AsyncSink: Unpin {
+    /// Attempts to write bytes from `buf` into the sink and returns how many
+    /// bytes were written.
+    fn poll_write(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>>;
+
+    /// Writes the whole `buf` into the sink.
+    #[inline]
+    fn write_all<'a>(&'a mut self, buf: &'a [u8]) -> WriteAll<'a, Self> {
+        WriteAll { sink: self, buf }
+    }
+}
+
+/// Future for the [`AsyncSource::fill_buf`] method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct FillBuf<'a, R: ?Sized>(Option<&'a mut R>);
+
+impl<'a, R: AsyncSource + ?Sized> Future for FillBuf<'a, R> {
+    type Output = io::Result<&'a [u8]>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let source = self.0.take().expect("`FillBuf` polled after completion");
+        match source.poll_fill_buf(cx) {
+            Poll::Ready(Ok(_)) => {}
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => {
+                self.0 = Some(source);
+                return Poll::Pending;
+            }
+        }
+        // The buffer cannot be returned from the first call, because the borrow
+        // checker does not allow to return a borrow conditionally. The data is
+        // already buffered, so the second call returns it immediately
+        source.poll_fill_buf(cx)
+    }
+}
+
+/// Future for the [`AsyncSink::write_all`] method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WriteAll<'a, W: ?Sized> {
+    sink: &'a mut W,
+    buf: &'a [u8],
+}
+
+impl<'a, W: AsyncSink + ?Sized> Future for WriteAll<'a, W> {
+    type Output = io::Result<()>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        while !this.buf.is_empty() {
+            let n = ready!(this.sink.poll_write(cx, this.buf))?;
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            this.buf = &this.buf[n..];
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "async-tokio")]
+impl<R: tokio::io::AsyncBufRead + Unpin> AsyncSource for R {
+    #[inline]
+    fn poll_fill_buf(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        tokio::io::AsyncBufRead::poll_fill_buf(Pin::new(self), cx)
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        tokio::io::AsyncBufRead::consume(Pin::new(self), amt)
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+impl<W: tokio::io::AsyncWrite + Unpin> AsyncSink for W {
+    #[inline]
+    fn poll_write(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        tokio::io::AsyncWrite::poll_write(Pin::new(self), cx, buf)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A wrapper that allows to use readers and writers implementing [`futures_io`]
+/// traits with asynchronous methods of [`Reader`], [`NsReader`] and [`Writer`].
+///
+/// The wrapper itself implements `futures_io` traits, so it can be used anywhere
+/// the wrapped type is expected.
+///
+/// # Example
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// # use pretty_assertions::assert_eq;
+/// use quick_xml::async_io::FuturesIo;
+/// use quick_xml::events::{BytesStart, Event};
+/// use quick_xml::reader::Reader;
+/// use quick_xml::writer::Writer;
+///
+/// let mut reader = Reader::from_reader(FuturesIo("<tag/>".as_bytes()));
+/// let mut writer = Writer::new(FuturesIo(Vec::new()));
+///
+/// let mut buf = Vec::new();
+/// let event = reader.read_event_into_async(&mut buf).await.unwrap();
+/// assert_eq!(event, Event::Empty(BytesStart::new("tag")));
+///
+/// writer.write_event_async(event).await.unwrap();
+/// assert_eq!(writer.into_inner().0, b"<tag/>");
+/// # }) // tokio_test::block_on
+/// ```
+///
+/// [`Reader`]: crate::reader::Reader
+/// [`NsReader`]: crate::reader::NsReader
+/// [`Writer`]: crate::writer::Writer
+#[cfg(feature = "async-futures")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FuturesIo<T>(pub T);
+
+#[cfg(feature = "async-futures")]
+impl<R: futures_io::AsyncBufRead + Unpin> AsyncSource for FuturesIo<R> {
+    #[inline]
+    fn poll_fill_buf(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        futures_io::AsyncBufRead::poll_fill_buf(Pin::new(&mut self.0), cx)
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        futures_io::AsyncBufRead::consume(Pin::new(&mut self.0), amt)
+    }
+}
+
+#[cfg(feature = "async-futures")]
+impl<W: futures_io::AsyncWrite + Unpin> AsyncSink for FuturesIo<W> {
+    #[inline]
+    fn poll_write(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        futures_io::AsyncWrite::poll_write(Pin::new(&mut self.0), cx, buf)
+    }
+}
+
+#[cfg(feature = "async-futures")]
+impl<R: futures_io::AsyncRead + Unpin> futures_io::AsyncRead for FuturesIo<R> {
+    #[inline]
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        futures_io::AsyncRead::poll_read(Pin::new(&mut self.get_mut().0), cx, buf)
+    }
+}
+
+#[cfg(feature = "async-futures")]
+impl<R: futures_io::AsyncBufRead + Unpin> futures_io::AsyncBufRead for FuturesIo<R> {
+    #[inline]
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        futures_io::AsyncBufRead::poll_fill_buf(Pin::new(&mut self.get_mut().0), cx)
+    }
+
+    #[inline]
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        futures_io::AsyncBufRead::consume(Pin::new(&mut self.get_mut().0), amt)
+    }
+}
+
+#[cfg(feature = "async-futures")]
+impl<W: futures_io::AsyncWrite + Unpin> futures_io::AsyncWrite for FuturesIo<W> {
+    #[inline]
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        futures_io::AsyncWrite::poll_write(Pin::new(&mut self.get_mut().0), cx, buf)
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        futures_io::AsyncWrite::poll_flush(Pin::new(&mut self.get_mut().0), cx)
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        futures_io::AsyncWrite::poll_close(Pin::new(&mut self.get_mut().0), cx)
+    }
+}
@@ -0,0 +1,551 @@
+//! Serialization of XML events in the [Canonical XML 1.0] and [Exclusive XML
+//! Canonicalization 1.0] forms.
+//!
+//! Canonical form of a document is a byte sequence which is the same for all
+//! logically equivalent documents. It is used, for example, to compute and verify
+//! digests of [XML Signatures]. In canonical form:
+//! - the document is encoded in UTF-8;
+//! - line endings are normalized to `#xA`;
+//! - attribute values are normalized and whitespace characters in them are written
+//!   as character references;
+//! - character and predefined entity references are replaced, CDATA sections
+//!   are replaced by their (escaped) content;
+//! - the XML declaration and the document type declaration are removed;
+//! - empty elements are written as start-end tag pairs;
+//! - superfluous namespace declarations are removed from each element;
+//! - namespace declarations and attributes are sorted;
+//! - comments are removed, unless an algorithm "with comments" is used.
+//!
+//! The [`Canonicalizer`] takes a stream of [`Event`]s, for example, from a
+//! [`Reader`] (use [`Config::expand_entities`] if your documents reference
+//! entities declared in the DTD), and writes it in canonical form. You can feed
+//! it with events of the whole document or of any element subtree. In the last case,
+//! the namespaces declared on the ancestors of the subtree should be passed to
+//! [`Canonicalizer::inherit_namespaces`].
+//!
+//! Attributes declared with default values in the DTD are not added to elements
+//! and attributes are always normalized as attributes of `CDATA` type.
+//!
+//! # Example
+//!
+//! ```
+//! # use pretty_assertions::assert_eq;
+//! use quick_xml::c14n::{canonicalize, Algorithm};
+//! use quick_xml::reader::Reader;
+//!
+//! let xml = "<?xml version='1.0'?>\r\n\
+//!     <doc xmlns:b='urn:b' xmlns:a='urn:a' b:attr='2' a:attr='1'>\r\n\
+//!         <e1 xmlns:a='urn:a'/><![CDATA[<text>]]>&#65;\r\n\
+//!     </doc>";
+//!
+//! let mut reader = Reader::from_str(xml);
+//! let canonical = canonicalize(&mut reader, Vec::new(), Algorithm::Canonical).unwrap();
+//! assert_eq!(
+//!     String::from_utf8(canonical).unwrap(),
+//!     "<doc xmlns:a=\"urn:a\" xmlns:b=\"urn:b\" a:attr=\"1\" b:attr=\"2\">\n\
+//!         <e1></e1>&lt;text&gt;A\n\
+//!     </doc>"
+//! );
+//! ```
+//!
+//! [Canonical XML 1.0]: https://www.w3.org/TR/2001/REC-xml-c14n-20010315
+//! [Exclusive XML Canonicalization 1.0]: https://www.w3.org/TR/2002/REC-xml-exc-c14n-20020718/
+//! [XML Signatures]: https://www.w3.org/TR/xmldsig-core1/
+//! [`Reader`]: crate::reader::Reader
+//! [`Config::expand_entities`]: crate::reader::Config::expand_entities
+
+use std::borrow::Cow;
+use std::io::{BufRead, Write};
+
+use crate::encoding::EncodingError;
+use crate::errors::Result;
+use crate::escape::{
+    normalize_xml10_eols, normalize_xml11_eols, resolve_predefined_entity, unescape, EscapeError,
+};
+use crate::events::{BytesStart, Event};
+use crate::name::{Namespace, NamespaceError, NamespaceResolver, PrefixDeclaration, ResolveResult};
+use crate::reader::Reader;
+use crate::XmlVersion;
+
+/// A canonicalization algorithm.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    /// [Canonical XML 1.0] without comments.
+    ///
+    /// [Canonical XML 1.0]: https://www.w3.org/TR/2001/REC-xml-c14n-20010315
+    Canonical,
+    /// [Canonical XML 1.0] with comments.
+    ///
+    /// [Canonical XML 1.0]: https://www.w3.org/TR/2001/REC-xml-c14n-20010315
+    CanonicalWithComments,
+    /// [Exclusive XML Canonicalization 1.0] without comments.
+    ///
+    /// [Exclusive XML Canonicalization 1.0]: https://www.w3.org/TR/2002/REC-xml-exc-c14n-20020718/
+    Exclusive,
+    /// [Exclusive XML Canonicalization 1.0] with comments.
+    ///
+    /// [Exclusive XML Canonicalization 1.0]: https://www.w3.org/TR/2002/REC-xml-exc-c14n-20020718/
+    ExclusiveWithComments,
+}
+
+impl Algorithm {
+    /// Returns the URI which identifies this algorithm in XML Signatures.
+    pub const fn uri(&self) -> &'static str {
+        match self {
+            Self::Canonical => "http://www.w3.org/TR/2001/REC-xml-c14n-20010315",
+            Self::CanonicalWithComments => {
+                "http://www.w3.org/TR/2001/REC-xml-c14n-20010315#WithComments"
+            }
+            Self::Exclusive => "http://www.w3.org/2001/10/xml-exc-c14n#",
+            Self::ExclusiveWithComments => "http://www.w3.org/2001/10/xml-exc-c14n#WithComments",
+        }
+    }
+
+    /// Returns the algorithm identified by the URI, or `None` if URI is unknown.
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::c14n::Algorithm;
+    ///
+    /// assert_eq!(
+    ///     Algorithm::from_uri("http://www.w3.org/2001/10/xml-exc-c14n#"),
+    ///     Some(Algorithm::Exclusive),
+    /// );
+    /// assert_eq!(Algorithm::from_uri("urn:unknown"), None);
+    /// ```
+    pub fn from_uri(uri: &str) -> Option<Self> {
+        [
+            Self::Canonical,
+            Self::CanonicalWithComments,
+            Self::Exclusive,
+            Self::ExclusiveWithComments,
+        ]
+        .into_iter()
+        .find(|a| a.uri() == uri)
+    }
+
+    /// Returns `true` for the algorithms of Exclusive XML Canonicalization.
+    pub const fn is_exclusive(&self) -> bool {
+        matches!(self, Self::Exclusive | Self::ExclusiveWithComments)
+    }
+
+    /// Returns `true` for the algorithms which keep comments.
+    pub const fn with_comments(&self) -> bool {
+        matches!(
+            self,
+            Self::CanonicalWithComments | Self::ExclusiveWithComments
+        )
+    }
+}
+
+/// Writes XML events in canonical form to the underlying writer.
+///
+/// See the [module documentation](self) for details.
+///
+/// # Example
+///
+/// Canonicalization of a subtree using Exclusive XML Canonicalization. Only
+/// namespaces that are used by elements and attributes are declared:
+///
+/// ```
+/// # use pretty_assertions::assert_eq;
+/// use quick_xml::c14n::{Algorithm, Canonicalizer};
+/// use quick_xml::events::Event;
+/// use quick_xml::name::QName;
+/// use quick_xml::reader::NsReader;
+///
+/// let xml = r#"<root xmlns="urn:root" xmlns:s="urn:saml" xmlns:x="urn:unused">
+///     <s:Assertion ID="1"><s:Issuer>me</s:Issuer></s:Assertion>
+/// </root>"#;
+/// let mut reader = NsReader::from_str(xml);
+/// let mut c14n = Canonicalizer::new(Vec::new(), Algorithm::Exclusive);
+///
+/// loop {
+///     match reader.read_event()? {
+///         Event::Start(e) if e.name() == QName(b"s:Assertion") => {
+///             // Namespaces in scope of the <s:Assertion> element
+///             c14n.inherit_namespaces(reader.resolver().bindings())?;
+///             c14n.write_event(Event::Start(e))?;
+///             // Events of the subtree up to and including the end tag
+///             let mut depth = 1;
+///             while depth > 0 {
+///                 let event = reader.read_event()?;
+///                 match event {
+///                     Event::Start(_) => depth += 1,
+///                     Event::End(_) => depth -= 1,
+///                     _ => {}
+///                 }
+///                 c14n.write_event(event)?;
+///             }
+///             break;
+///         }
+///         Event::Eof => break,
+///         _ => {}
+///     }
+/// }
+///
+/// assert_eq!(
+///     String::from_utf8(c14n.into_inner()).unwrap(),
+///     r#"<s:Assertion xmlns:s="urn:saml" ID="1"><s:Issuer>me</s:Issuer></s:Assertion>"#
+/// );
+/// # quick_xml::Result::Ok(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Canonicalizer<W> {
+    /// The underlying writer
+    writer: W,
+    /// Canonicalization algorithm
+    algorithm: Algorithm,
+    /// Prefixes from the `InclusiveNamespaces PrefixList` of Exclusive XML
+    /// Canonicalization. Empty prefix denotes the default namespace
+    inclusive_prefixes: Vec<Vec<u8>>,
+    /// Namespace bindings of the input document
+    ns_resolver: NamespaceResolver,
+    /// Namespace declarations written by the opened elements: nesting level,
+    /// prefix (empty for the default namespace) and namespace name
+    rendered: Vec<(u16, Vec<u8>, Vec<u8>)>,
+    /// Version of the input document, affects normalization of line endings
+    version: XmlVersion,
+    /// Whether the document element was written
+    after_root: bool,
+}
+
+impl<W> Canonicalizer<W> {
+    /// Creates a canonicalizer which writes to the `writer` using the specified
+    /// algorithm.
+    pub fn new(writer: W, algorithm: Algorithm) -> Self {
+        Self {
+            writer,
+            algorithm,
+            inclusive_prefixes: Vec::new(),
+            ns_resolver: NamespaceResolver::default(),
+            rendered: Vec::new(),
+            version: XmlVersion::Implicit1_0,
+            after_root: false,
+        }
+    }
+
+    /// Sets the `InclusiveNamespaces PrefixList` parameter of the Exclusive XML
+    /// Canonicalization: namespaces with these prefixes are declared as in the
+    /// Canonical XML 1.0, even if they are not used. The default namespace is
+    /// denoted by the `#default` token.
+    ///
+    /// This parameter is ignored by the Canonical XML 1.0 algorithms.
+    pub fn inclusive_prefixes<'a, I>(&mut self, prefixes: I) -> &mut Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        self.inclusive_prefixes = prefixes
+            .into_iter()
+            .map(|p| match p {
+                "#default" => Vec::new(),
+                p => p.as_bytes().to_vec(),
+            })
+            .collect();
+        self
+    }
+
+    /// Adds namespace bindings which are in scope of the first element, for
+    /// example, declared on the ancestors of the canonicalized subtree. These
+    /// bindings can be obtained from [`NamespaceResolver::bindings()`] of the
+    /// resolver of an [`NsReader`].
+    ///
+    /// Should be called before writing the first element.
+    ///
+    /// [`NsReader`]: crate::reader::NsReader
+    pub fn inherit_namespaces<'a, I>(&mut self, bindings: I) -> Result<()>
+    where
+        I: IntoIterator<Item = (PrefixDeclaration<'a>, Namespace<'a>)>,
+    {
+        for (prefix, namespace) in bindings {
+            self.ns_resolver.add(prefix, namespace)?;
+        }
+        Ok(())
+    }
+
+    /// Consumes this `Canonicalizer`, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Get a reference to the underlying writer.
+    pub const fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Get a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Returns the namespace declared for the prefix by the opened elements in
+    /// the output. Empty namespace means that prefix was not declared.
+    fn rendered(&self, prefix: &[u8]) -> &[u8] {
+        self.rendered
+            .iter()
+            .rev()
+            .find(|(_, p, _)| p == prefix)
+            .map_or(b"", |(_, _, ns)| ns)
+    }
+
+    /// Returns the namespace declarations that should be written for the
+    /// element, sorted by prefix.
+    fn declarations(&self, start: &BytesStart) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let in_scope: Vec<(&[u8], &[u8])> = self
+            .ns_resolver
+            .bindings()
+            .map(|(prefix, ns)| match prefix {
+                PrefixDeclaration::Default => (&b""[..], ns.0),
+                PrefixDeclaration::Named(prefix) => (prefix, ns.0),
+            })
+            .collect();
+
+        let mut prefixes: Vec<&[u8]> = Vec::new();
+        if self.algorithm.is_exclusive() {
+            // Visibly utilized prefixes
+            prefixes.push(start.name().prefix().map_or(b"", |p| p.into_inner()));
+            for attr in start.attributes().with_checks(false).flatten() {
+                if attr.key.as_namespace_binding().is_none() {
+                    if let Some(prefix) = attr.key.prefix() {
+                        prefixes.push(prefix.into_inner());
+                    }
+                }
+            }
+            prefixes.extend(self.inclusive_prefixes.iter().map(Vec::as_slice));
+        } else {
+            prefixes.push(b"");
+            prefixes.extend(in_scope.iter().map(|(p, _)| *p));
+        }
+        prefixes.sort_unstable();
+        prefixes.dedup();
+
+        let mut declarations = Vec::new();
+        for prefix in prefixes {
+            // The `xml` prefix is bound implicitly and never declared
+            if prefix == b"xml" {
+                continue;
+            }
+            let ns = in_scope
+                .iter()
+                .find(|(p, _)| *p == prefix)
+                .map_or(&b""[..], |(_, ns)| *ns);
+            // Only the default namespace can be undeclared
+            if ns.is_empty() && !prefix.is_empty() {
+                continue;
+            }
+            if self.rendered(prefix) != ns {
+                declarations.push((prefix.to_vec(), ns.to_vec()));
+            }
+        }
+        declarations
+    }
+
+    /// Normalizes line endings according to the version of the document.
+    fn normalize_eols<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match self.version {
+            XmlVersion::Explicit1_1 => normalize_xml11_eols(text),
+            _ => normalize_xml10_eols(text),
+        }
+    }
+}
+
+impl<W: Write> Canonicalizer<W> {
+    /// Writes the event in canonical form.
+    ///
+    /// Events outside the document element, except processing instructions and
+    /// comments, are ignored. Declarations are used only to determine the version
+    /// of the document.
+    pub fn write_event<'a, E: Into<Event<'a>>>(&mut self, event: E) -> Result<()> {
+        match event.into() {
+            Event::Start(e) => self.write_start(&e),
+            Event::Empty(e) => {
+                self.write_start(&e)?;
+                self.write_end(e.name().as_ref())
+            }
+            Event::End(e) => self.write_end(e.name().as_ref()),
+            Event::Text(e) if self.ns_resolver.level() > 0 => {
+                let text = e.xml_content(self.version)?;
+                let text = unescape(&text)?;
+                self.write_escaped(&text, false)
+            }
+            Event::CData(e) if self.ns_resolver.level() > 0 => {
+                let text = e.xml_content(self.version)?;
+                self.write_escaped(&text, false)
+            }
+            Event::GeneralRef(e) if self.ns_resolver.level() > 0 => {
+                let mut buf = [0; 4];
+                let name = e.decode()?;
+                let text = match e.resolve_char_ref()? {
+                    Some(ch) => &*ch.encode_utf8(&mut buf),
+                    None => resolve_predefined_entity(&name).ok_or_else(|| {
+                        EscapeError::UnrecognizedEntity(0..name.len(), name.to_string())
+                    })?,
+                };
+                self.write_escaped(text, false)
+            }
+            Event::Comment(e) if self.algorithm.with_comments() => {
+                let text = e.xml_content(self.version)?;
+                self.write_outside_root(|w| {
+                    w.writer.write_all(b"<!--")?;
+                    w.writer.write_all(text.as_bytes())?;
+                    w.writer.write_all(b"-->")?;
+                    Ok(())
+                })
+            }
+            Event::PI(e) => {
+                let target = std::str::from_utf8(e.target()).map_err(EncodingError::from)?;
+                let data = std::str::from_utf8(e.content()).map_err(EncodingError::from)?;
+                let data = self.normalize_eols(data.trim_start()).into_owned();
+                self.write_outside_root(|w| {
+                    w.writer.write_all(b"<?")?;
+                    w.writer.write_all(target.as_bytes())?;
+                    if !data.is_empty() {
+                        w.writer.write_all(b" ")?;
+                        w.writer.write_all(data.as_bytes())?;
+                    }
+                    w.writer.write_all(b"?>")?;
+                    Ok(())
+                })
+            }
+            Event::Decl(e) => {
+                self.version = e.xml_version()?;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn write_start(&mut self, start: &BytesStart) -> Result<()> {
+        self.ns_resolver.push(start)?;
+        let level = self.ns_resolver.level();
+        let declarations = self.declarations(start);
+
+        let mut attributes = Vec::new();
+        for attr in start.attributes() {
+            let attr = attr?;
+            if attr.key.as_namespace_binding().is_some() {
+                continue;
+            }
+            let ns = match self.ns_resolver.resolve_attribute(attr.key).0 {
+                ResolveResult::Bound(ns) => ns.0.to_vec(),
+                ResolveResult::Unbound => Vec::new(),
+                ResolveResult::Unknown(prefix) => {
+                    return Err(NamespaceError::UnknownPrefix(prefix).into())
+                }
+            };
+            let value = attr.normalized_value(self.version)?.into_owned();
+            attributes.push((ns, attr.key.local_name().as_ref().to_vec(), attr.key, value));
+        }
+        attributes.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+
+        self.writer.write_all(b"<")?;
+        self.writer.write_all(start.name().as_ref())?;
+        for (prefix, ns) in &declarations {
+            self.writer.write_all(b" xmlns")?;
+            if !prefix.is_empty() {
+                self.writer.write_all(b":")?;
+                self.writer.write_all(prefix)?;
+            }
+            self.writer.write_all(b"=\"")?;
+            let ns = start.decoder().decode(ns)?;
+            self.write_escaped(&unescape(&ns)?, true)?;
+            self.writer.write_all(b"\"")?;
+        }
+        for (_, _, key, value) in &attributes {
+            self.writer.write_all(b" ")?;
+            self.writer.write_all(key.as_ref())?;
+            self.writer.write_all(b"=\"")?;
+            self.write_escaped(value, true)?;
+            self.writer.write_all(b"\"")?;
+        }
+        self.writer.write_all(b">")?;
+
+        self.rendered.extend(
+            declarations
+                .into_iter()
+                .map(|(prefix, ns)| (level, prefix, ns)),
+        );
+        Ok(())
+    }
+
+    fn write_end(&mut self, name: &[u8]) -> Result<()> {
+        self.writer.write_all(b"</")?;
+        self.writer.write_all(name)?;
+        self.writer.write_all(b">")?;
+
+        let level = self.ns_resolver.level();
+        while let Some((l, _, _)) = self.rendered.last() {
+            if *l < level {
+                break;
+            }
+            self.rendered.pop();
+        }
+        self.ns_resolver.pop();
+        if self.ns_resolver.level() == 0 {
+            self.after_root = true;
+        }
+        Ok(())
+    }
+
+    /// Writes a node which can appear outside the document element. Such
+    /// nodes are separated from the document element by a line feed.
+    fn write_outside_root<F>(&mut self, write: F) -> Result<()>
+    where
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        let outside = self.ns_resolver.level() == 0;
+        if outside && self.after_root {
+            self.writer.write_all(b"\n")?;
+        }
+        write(self)?;
+        if outside && !self.after_root {
+            self.writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    /// Writes text content or attribute value, escaping characters as required
+    /// by the canonical form.
+    fn write_escaped(&mut self, text: &str, attribute: bool) -> Result<()> {
+        let bytes = text.as_bytes();
+        let mut last = 0;
+        for (i, &b) in bytes.iter().enumerate() {
+            let escaped: &[u8] = match (b, attribute) {
+                (b'&', _) => b"&amp;",
+                (b'<', _) => b"&lt;",
+                (b'\r', _) => b"&#xD;",
+                (b'>', false) => b"&gt;",
+                (b'"', true) => b"&quot;",
+                (b'\t', true) => b"&#x9;",
+                (b'\n', true) => b"&#xA;",
+                _ => continue,
+            };
+            self.writer.write_all(&bytes[last..i])?;
+            self.writer.write_all(escaped)?;
+            last = i + 1;
+        }
+        self.writer.write_all(&bytes[last..])?;
+        Ok(())
+    }
+}
+
+/// Reads all events from the `reader` and writes them in canonical form to
+/// the `writer`. Returns the writer.
+///
+/// See the [module documentation](self) for an example.
+pub fn canonicalize<R: BufRead, W: Write>(
+    reader: &mut Reader<R>,
+    writer: W,
+    algorithm: Algorithm,
+) -> Result<W> {
+    let mut c14n = Canonicalizer::new(writer, algorithm);
+    let mut buf = Vec::new();
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Eof => break,
+            event => c14n.write_event(event)?,
+        }
+        buf.clear();
+    }
+    Ok(c14n.into_inner())
+}
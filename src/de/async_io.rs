@@ -0,0 +1,176 @@
+//! Deserialization from an [`AsyncSource`].
+//!
+//! Serde deserializers are synchronous, so an element is first read asynchronously
+//! into a buffer and then deserialized from it.
+
+use serde::de::DeserializeOwned;
+
+use crate::async_io::AsyncSource;
+use crate::de::{DeError, Deserializer};
+use crate::events::{BytesStart, Event};
+use crate::name::{NamespaceResolver, PrefixDeclaration};
+use crate::reader::NsReader;
+use crate::XmlVersion;
+
+/// Deserialize an instance of type `T` from an asynchronous reader.
+///
+/// The root element is read asynchronously into a buffer and then deserialized.
+/// The rest of the `reader` is not read, so trailing comments and whitespaces
+/// are not checked.
+///
+/// # Example
+///
+#[cfg_attr(feature = "async-tokio", doc = "```")]
+#[cfg_attr(not(feature = "async-tokio"), doc = "```ignore")]
+/// # tokio_test::block_on(async {
+/// # use pretty_assertions::assert_eq;
+/// use serde::Deserialize;
+/// use quick_xml::de::from_async_reader;
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Message {
+///     #[serde(rename = "@id")]
+///     id: u32,
+///     text: String,
+/// }
+///
+/// let xml = r#"<?xml version="1.0"?><message id="42"><text>Hello</text></message>"#;
+/// let message: Message = from_async_reader(xml.as_bytes()).await.unwrap();
+/// assert_eq!(message, Message { id: 42, text: "Hello".to_string() });
+/// # }) // tokio_test::block_on
+/// ```
+pub async fn from_async_reader<R, T>(reader: R) -> Result<T, DeError>
+where
+    R: AsyncSource,
+    T: DeserializeOwned,
+{
+    let mut reader = NsReader::from_reader(reader);
+    match read_element_async(&mut reader).await? {
+        Some(value) => Ok(value),
+        None => Err(DeError::UnexpectedEof),
+    }
+}
+
+/// Reads the next element from the `reader` asynchronously and deserializes
+/// an instance of type `T` from it.
+///
+/// Text, comments and other markup before the element are skipped. Returns
+/// `None` if the end tag of the current element or the end of input is reached
+/// before any element was found.
+///
+/// Namespaces declared on the ancestors of the element are visible to the deserializer.
+/// The element is deserialized with the default configuration of the reader,
+/// the configuration of `reader` is not used. Positions in errors are reported
+/// relative to the start of the element.
+///
+/// # Example
+///
+/// Reading a stream of messages wrapped in a root element:
+///
+#[cfg_attr(feature = "async-tokio", doc = "```")]
+#[cfg_attr(not(feature = "async-tokio"), doc = "```ignore")]
+/// # tokio_test::block_on(async {
+/// # use pretty_assertions::assert_eq;
+/// use serde::Deserialize;
+/// use quick_xml::de::read_element_async;
+/// use quick_xml::events::Event;
+/// use quick_xml::reader::NsReader;
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Message {
+///     #[serde(rename = "$text")]
+///     text: String,
+/// }
+///
+/// let xml = "<stream><message>one</message><message>two</message></stream>";
+/// let mut reader = NsReader::from_reader(xml.as_bytes());
+/// let mut buf = Vec::new();
+///
+/// // Enter into <stream>
+/// assert!(matches!(reader.read_event_into_async(&mut buf).await.unwrap(), Event::Start(_)));
+///
+/// let mut messages = Vec::new();
+/// while let Some(message) = read_element_async::<_, Message>(&mut reader).await.unwrap() {
+///     messages.push(message.text);
+/// }
+/// assert_eq!(messages, ["one", "two"]);
+/// # }) // tokio_test::block_on
+/// ```
+pub async fn read_element_async<R, T>(reader: &mut NsReader<R>) -> Result<Option<T>, DeError>
+where
+    R: AsyncSource,
+    T: DeserializeOwned,
+{
+    let mut buf = Vec::new();
+    let mut xml = Vec::new();
+    loop {
+        buf.clear();
+        match reader.read_event_into_async(&mut buf).await? {
+            Event::Start(e) => {
+                write_start(&mut xml, &e, reader.resolver());
+                xml.push(b'>');
+
+                let end = e.to_end().into_owned();
+                let mut content = Vec::new();
+                let text = reader
+                    .read_text_into_async(end.name(), &mut content)
+                    .await?;
+                xml.extend_from_slice(&text);
+                xml.extend_from_slice(b"</");
+                xml.extend_from_slice(end.name().as_ref());
+                xml.push(b'>');
+                break;
+            }
+            Event::Empty(e) => {
+                write_start(&mut xml, &e, reader.resolver());
+                xml.extend_from_slice(b"/>");
+                break;
+            }
+            Event::End(_) | Event::Eof => return Ok(None),
+            _ => {}
+        }
+    }
+
+    let xml = reader.decoder().decode(&xml)?;
+    let xml = match reader.xml_version() {
+        XmlVersion::Explicit1_1 => format!("<?xml version=\"1.1\"?>{}", xml),
+        _ => xml.into_owned(),
+    };
+    let mut de = Deserializer::from_str(&xml);
+    T::deserialize(&mut de).map(Some)
+}
+
+/// Writes the start tag without the closing `>` to `xml`, adding declarations
+/// of all namespaces that are in scope but not declared on the element.
+fn write_start(xml: &mut Vec<u8>, start: &BytesStart, resolver: &NamespaceResolver) {
+    xml.push(b'<');
+    xml.extend_from_slice(start);
+    for (prefix, namespace) in resolver.bindings() {
+        let declared = start
+            .attributes()
+            .with_checks(false)
+            .flatten()
+            .any(|a| a.key.as_namespace_binding() == Some(prefix));
+        if declared {
+            continue;
+        }
+        match prefix {
+            PrefixDeclaration::Default => xml.extend_from_slice(b" xmlns="),
+            PrefixDeclaration::Named(prefix) => {
+                xml.extend_from_slice(b" xmlns:");
+                xml.extend_from_slice(prefix);
+                xml.push(b'=');
+            }
+        }
+        // Namespaces are stored as they were written in the document, so they
+        // are escaped but can contain quotes of the other kind
+        let quote = if namespace.as_ref().contains(&b'"') {
+            b'\''
+        } else {
+            b'"'
+        };
+        xml.push(quote);
+        xml.extend_from_slice(namespace.as_ref());
+        xml.push(quote);
+    }
+}
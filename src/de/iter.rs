@@ -0,0 +1,108 @@
+//! Lazy deserialization of repeated elements
+
+use crate::{
+    de::resolver::EntityResolver,
+    de::{DeEvent, Deserializer, XmlRead},
+    errors::serialize::DeError,
+};
+use serde::de::Deserialize;
+use std::marker::PhantomData;
+
+/// The position of [`ElementIter`] in the document.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    /// The start tag of the container was not read yet
+    BeforeContainer,
+    /// The iterator is inside the container
+    InContainer,
+    /// The end tag of the container was read or an error was returned
+    Finished,
+}
+
+/// An iterator that deserializes children of a container element with the
+/// specified name one by one.
+///
+/// This struct is created by the [`Deserializer::iter_elements`] method.
+/// See its documentation for more details.
+pub struct ElementIter<'a, 'de, T, R, E>
+where
+    R: XmlRead<'de>,
+    E: EntityResolver,
+{
+    de: &'a mut Deserializer<'de, R, E>,
+    tag: &'a str,
+    state: State,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<'a, 'de, T, R, E> ElementIter<'a, 'de, T, R, E>
+where
+    T: Deserialize<'de>,
+    R: XmlRead<'de>,
+    E: EntityResolver,
+{
+    pub(super) fn new(de: &'a mut Deserializer<'de, R, E>, tag: &'a str) -> Self {
+        Self {
+            de,
+            tag,
+            state: State::BeforeContainer,
+            _item: PhantomData,
+        }
+    }
+
+    fn next_impl(&mut self) -> Result<Option<T>, DeError> {
+        if self.state == State::BeforeContainer {
+            loop {
+                match self.de.next()? {
+                    DeEvent::Start(_) => break,
+                    // Text before the root element
+                    DeEvent::Text(_) => continue,
+                    // SAFETY: The reader is guaranteed that we don't have unmatched tags
+                    // If we here, then our deserializer has a bug
+                    DeEvent::End(e) => unreachable!("{:?}", e),
+                    DeEvent::Eof => return Err(DeError::UnexpectedEof),
+                }
+            }
+            self.state = State::InContainer;
+        }
+        if self.state == State::Finished {
+            return Ok(None);
+        }
+        loop {
+            match self.de.peek()? {
+                DeEvent::Start(e) if e.local_name().as_ref() == self.tag.as_bytes() => {
+                    return T::deserialize(&mut *self.de).map(Some);
+                }
+                DeEvent::Start(_) => self.de.skip_next_tree()?,
+                DeEvent::Text(_) => {
+                    self.de.next()?;
+                }
+                DeEvent::End(_) => {
+                    self.de.next()?;
+                    self.state = State::Finished;
+                    return Ok(None);
+                }
+                DeEvent::Eof => return Err(DeError::UnexpectedEof),
+            }
+        }
+    }
+}
+
+impl<'a, 'de, T, R, E> Iterator for ElementIter<'a, 'de, T, R, E>
+where
+    T: Deserialize<'de>,
+    R: XmlRead<'de>,
+    E: EntityResolver,
+{
+    type Item = Result<T, DeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_impl() {
+            Ok(item) => item.map(Ok),
+            Err(e) => {
+                self.state = State::Finished;
+                Some(Err(e))
+            }
+        }
+    }
+}
@@ -2095,7 +2095,10 @@ macro_rules! deserialize_primitives {
     };
 }
 
+#[cfg(any(feature = "async-tokio", feature = "async-futures"))]
+mod async_io;
 mod attributes;
+mod iter;
 mod key;
 mod map;
 mod resolver;
@@ -2103,8 +2106,13 @@ mod simple_type;
 mod text;
 mod var;
 
+#[cfg(any(feature = "async-tokio", feature = "async-futures"))]
+pub use self::async_io::{from_async_reader, read_element_async};
 pub use self::attributes::AttributesDeserializer;
-pub use self::resolver::{EntityResolver, PredefinedEntityResolver};
+pub use self::iter::ElementIter;
+pub use self::resolver::{
+    DtdEntityResolver, DtdResolverError, EntityResolver, PredefinedEntityResolver,
+};
 pub use self::simple_type::SimpleTypeDeserializer;
 pub use crate::errors::serialize::DeError;
 use crate::XmlVersion;
@@ -2116,7 +2124,7 @@ use crate::{
     escape::{parse_number, EscapeError},
     events::{BytesCData, BytesEnd, BytesRef, BytesStart, BytesText, Event},
     name::QName,
-    reader::NsReader,
+    reader::{NsReader, Position},
 };
 use serde::de::{
     self, Deserialize, DeserializeOwned, DeserializeSeed, IntoDeserializer, SeqAccess, Visitor,
@@ -2510,6 +2518,21 @@ impl<'i, R: XmlRead<'i>, E: EntityResolver> XmlReader<'i, R, E> {
     fn decoder(&self) -> Decoder {
         self.reader.decoder()
     }
+
+    /// Attaches the current position of the reader to the error if the reader
+    /// tracks positions. XML errors and already positioned errors are returned as is.
+    fn with_position(&self, error: DeError) -> DeError {
+        match error {
+            DeError::InvalidXml(_) | DeError::Positioned { .. } => error,
+            error => match self.reader.position() {
+                Some(position) => DeError::Positioned {
+                    position,
+                    error: Box::new(error),
+                },
+                None => error,
+            },
+        }
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -2651,6 +2674,57 @@ where
         &self.reader.reader
     }
 
+    /// Returns an iterator that deserializes children of the next element
+    /// (a container) one by one. Only children with the local name `tag` are
+    /// deserialized, other children are skipped. Iteration ends after the end
+    /// tag of the container.
+    ///
+    /// Because the children are read only when the next item is requested,
+    /// the whole sequence is never stored in memory. Use this method to process
+    /// big documents that consist of many repeated elements.
+    ///
+    /// After an error the iterator returns `None`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use serde::Deserialize;
+    /// use quick_xml::de::Deserializer;
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Package {
+    ///     #[serde(rename = "@type")]
+    ///     ty: String,
+    ///     name: String,
+    /// }
+    ///
+    /// let xml = br#"
+    ///     <metadata packages="2">
+    ///         <package type="rpm"><name>first</name></package>
+    ///         <package type="srpm"><name>second</name></package>
+    ///     </metadata>
+    /// "#;
+    /// let mut de = Deserializer::from_reader(&xml[..]);
+    /// let mut packages = de.iter_elements::<Package>("package");
+    ///
+    /// assert_eq!(
+    ///     packages.next().unwrap().unwrap(),
+    ///     Package { ty: "rpm".to_string(), name: "first".to_string() },
+    /// );
+    /// assert_eq!(
+    ///     packages.next().unwrap().unwrap(),
+    ///     Package { ty: "srpm".to_string(), name: "second".to_string() },
+    /// );
+    /// assert!(packages.next().is_none());
+    /// ```
+    pub fn iter_elements<'a, T>(&'a mut self, tag: &'a str) -> ElementIter<'a, 'de, T, R, E>
+    where
+        T: Deserialize<'de>,
+    {
+        ElementIter::new(self, tag)
+    }
+
     /// Set the maximum number of events that could be skipped during deserialization
     /// of sequences.
     ///
@@ -3230,7 +3304,9 @@ where
         // When document is pretty-printed there could be whitespaces before the root element
         self.skip_whitespaces()?;
         match self.next()? {
-            DeEvent::Start(e) => visitor.visit_map(ElementMapAccess::new(self, e, fields)),
+            DeEvent::Start(e) => visitor
+                .visit_map(ElementMapAccess::new(&mut *self, e, fields))
+                .map_err(|e| self.reader.with_position(e)),
             // SAFETY: The reader is guaranteed that we don't have unmatched tags
             // If we here, then our deserializer has a bug
             DeEvent::End(e) => unreachable!("{:?}", e),
@@ -3241,7 +3317,7 @@ where
                 Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
                 Cow::Owned(s) => visitor.visit_string(s),
             },
-            DeEvent::Eof => Err(DeError::UnexpectedEof),
+            DeEvent::Eof => Err(self.reader.with_position(DeError::UnexpectedEof)),
         }
     }
 
@@ -3438,6 +3514,11 @@ pub trait XmlRead<'i> {
     ///
     /// [`xsi:nil`]: https://www.w3.org/TR/xmlschema-1/#xsi_nil
     fn has_nil_attr(&self, start: &BytesStart) -> bool;
+
+    /// Returns the current position of the reader, if the reader tracks it.
+    ///
+    /// See [`Config::track_position`](crate::reader::Config::track_position).
+    fn position(&self) -> Option<Position>;
 }
 
 /// XML input source that reads from a std::io input stream.
@@ -3520,6 +3601,14 @@ impl<'i, R: BufRead> XmlRead<'i> for IoReader<R> {
     fn has_nil_attr(&self, start: &BytesStart) -> bool {
         start.attributes().has_nil(self.reader.resolver())
     }
+
+    fn position(&self) -> Option<Position> {
+        if self.reader.config().track_position {
+            Some(self.reader.position())
+        } else {
+            None
+        }
+    }
 }
 
 /// XML input source that reads from a slice of bytes and can borrow from it.
@@ -3598,6 +3687,14 @@ impl<'de> XmlRead<'de> for SliceReader<'de> {
     fn has_nil_attr(&self, start: &BytesStart) -> bool {
         start.attributes().has_nil(self.reader.resolver())
     }
+
+    fn position(&self) -> Option<Position> {
+        if self.reader.config().track_position {
+            Some(self.reader.position())
+        } else {
+            None
+        }
+    }
 }
 
 #[cfg(test)]
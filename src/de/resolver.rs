@@ -1,49 +1,54 @@
 //! Entity resolver module
 
+use std::collections::HashMap;
 use std::convert::Infallible;
 use std::error::Error;
+use std::fmt;
 
-use crate::escape::resolve_predefined_entity;
+use crate::dtd::{DtdError, DtdEvent, DtdReader, EntityDef};
+use crate::encoding::EncodingError;
+use crate::escape::{parse_number, replace_char_refs, resolve_predefined_entity, EscapeError};
 use crate::events::BytesText;
 
 /// Used to resolve unknown entities while parsing
 ///
+/// [`DtdEntityResolver`] is a ready-to-use implementation that resolves entities
+/// declared in the internal DTD subset. Implement this trait yourself if you
+/// need something else, for example, to load external entities.
+///
 /// # Example
 ///
 /// ```
 /// # use serde::Deserialize;
 /// # use pretty_assertions::assert_eq;
-/// use regex::bytes::Regex;
 /// use std::collections::BTreeMap;
-/// use std::string::FromUtf8Error;
 /// use quick_xml::de::{Deserializer, EntityResolver};
+/// use quick_xml::dtd::{DtdEvent, DtdReader, EntityDef};
 /// use quick_xml::events::BytesText;
+/// use quick_xml::Error;
 ///
+/// #[derive(Default)]
 /// struct DocTypeEntityResolver {
-///     re: Regex,
 ///     map: BTreeMap<String, String>,
 /// }
 ///
-/// impl Default for DocTypeEntityResolver {
-///     fn default() -> Self {
-///         Self {
-///             // We do not focus on true parsing in this example
-///             // You should use special libraries to parse DTD
-///             re: Regex::new(r#"<!ENTITY\s+([^ \t\r\n]+)\s+"([^"]*)"\s*>"#).unwrap(),
-///             map: BTreeMap::new(),
-///         }
-///     }
-/// }
-///
 /// impl EntityResolver for DocTypeEntityResolver {
-///     type Error = FromUtf8Error;
+///     type Error = Error;
 ///
 ///     fn capture(&mut self, doctype: BytesText) -> Result<(), Self::Error> {
-///         for cap in self.re.captures_iter(&doctype) {
-///             self.map.insert(
-///                 String::from_utf8(cap[1].to_vec())?,
-///                 String::from_utf8(cap[2].to_vec())?,
-///             );
+///         let content = doctype.decode()?;
+///         for event in DtdReader::new(&content) {
+///             match event {
+///                 Ok(DtdEvent::EntityDecl(decl)) if !decl.parameter => {
+///                     // External entities are not supported in this example
+///                     if let EntityDef::Internal(value) = decl.def {
+///                         self.map.insert(decl.name.to_string(), value.to_string());
+///                     }
+///                 }
+///                 Ok(_) => {}
+///                 // Do not fail on DTD errors, just ignore the rest of DTD
+///                 Err(_) => break,
+///             }
 ///         }
 ///         Ok(())
 ///     }
@@ -113,3 +118,259 @@ impl EntityResolver for PredefinedEntityResolver {
         resolve_predefined_entity(entity)
     }
 }
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An error returned by the [`DtdEntityResolver`] when it cannot capture entities.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DtdResolverError {
+    /// DTD cannot be decoded.
+    Encoding(EncodingError),
+    /// DTD is malformed.
+    Syntax(DtdError),
+    /// Replacement text of an entity contains an invalid or unknown reference.
+    Escape(EscapeError),
+    /// An entity references itself directly or through other entities.
+    /// Contains the name of that entity.
+    RecursiveEntity(String),
+    /// Total size of replacement texts of all entities after expansion exceeds
+    /// the limit. Contains the limit.
+    LimitExceeded(usize),
+}
+
+impl fmt::Display for DtdResolverError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Encoding(e) => write!(f, "cannot decode DTD: {}", e),
+            Self::Syntax(e) => e.fmt(f),
+            Self::Escape(e) => e.fmt(f),
+            Self::RecursiveEntity(name) => write!(f, "entity `{}` references itself", name),
+            Self::LimitExceeded(limit) => {
+                write!(f, "expanded entities exceed the limit of {} bytes", limit)
+            }
+        }
+    }
+}
+
+impl Error for DtdResolverError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Encoding(e) => Some(e),
+            Self::Syntax(e) => Some(e),
+            Self::Escape(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<EncodingError> for DtdResolverError {
+    #[inline]
+    fn from(error: EncodingError) -> Self {
+        Self::Encoding(error)
+    }
+}
+
+impl From<DtdError> for DtdResolverError {
+    #[inline]
+    fn from(error: DtdError) -> Self {
+        Self::Syntax(error)
+    }
+}
+
+impl From<EscapeError> for DtdResolverError {
+    #[inline]
+    fn from(error: EscapeError) -> Self {
+        Self::Escape(error)
+    }
+}
+
+/// An [`EntityResolver`] that resolves predefined entities and internal general
+/// entities declared in the `<!DOCTYPE>`:
+///
+/// ```xml
+/// <!DOCTYPE root [
+///   <!ENTITY name "value">
+/// ]>
+/// ```
+///
+/// Entities are expanded when DTD is captured. Replacement text of an entity
+/// can reference other entities, which are expanded recursively. Entities which
+/// reference themselves are reported as [`DtdResolverError::RecursiveEntity`].
+/// To protect against exponential entity expansion ("billion laughs" attack)
+/// the total size of all expanded entities is limited, see [`with_limit`].
+///
+/// Replacement text is inserted as text, so markup inside it (for example,
+/// `<!ENTITY e "<b>bold</b>">`) is not parsed. External and parameter entities
+/// are ignored.
+///
+/// # Example
+///
+/// ```
+/// # use serde::Deserialize;
+/// # use pretty_assertions::assert_eq;
+/// use quick_xml::de::{Deserializer, DtdEntityResolver};
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Root {
+///     greeting: String,
+/// }
+///
+/// let xml = r#"
+///     <!DOCTYPE root [
+///         <!ENTITY name "world">
+///         <!ENTITY greeting "Hello, &name;!">
+///     ]>
+///     <root>
+///         <greeting>&greeting;</greeting>
+///     </root>
+/// "#;
+///
+/// let mut de = Deserializer::with_resolver(xml.as_bytes(), DtdEntityResolver::new());
+/// assert_eq!(
+///     Root::deserialize(&mut de).unwrap(),
+///     Root { greeting: "Hello, world!".to_string() }
+/// );
+/// ```
+///
+/// [`with_limit`]: Self::with_limit
+#[derive(Clone, Debug)]
+pub struct DtdEntityResolver {
+    /// Expanded replacement texts of entities
+    entities: HashMap<String, String>,
+    /// Total size of all values in `entities`
+    size: usize,
+    /// Maximum allowed value of `size`
+    limit: usize,
+}
+
+impl DtdEntityResolver {
+    /// The default limit of the total size of all expanded entities (1 MiB).
+    pub const DEFAULT_LIMIT: usize = 1024 * 1024;
+
+    /// Creates a resolver with the [default limit] of the expanded entities size.
+    ///
+    /// [default limit]: Self::DEFAULT_LIMIT
+    pub fn new() -> Self {
+        Self::with_limit(Self::DEFAULT_LIMIT)
+    }
+
+    /// Creates a resolver which allows expanding entities into at most `limit`
+    /// bytes in total.
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            entities: HashMap::new(),
+            size: 0,
+            limit,
+        }
+    }
+
+    /// Expands entity `name` and all entities referenced by it and stores
+    /// the expanded replacement text in `self.entities`.
+    ///
+    /// # Parameters
+    /// - `name`: the name of the entity to expand
+    /// - `declared`: raw values of entities declared in the current DTD
+    /// - `stack`: names of entities which expansion is in progress
+    fn expand<'d>(
+        &mut self,
+        name: &'d str,
+        declared: &HashMap<&'d str, &'d str>,
+        stack: &mut Vec<&'d str>,
+    ) -> Result<(), DtdResolverError> {
+        if self.entities.contains_key(name) {
+            return Ok(());
+        }
+        if stack.contains(&name) {
+            return Err(DtdResolverError::RecursiveEntity(name.to_string()));
+        }
+        stack.push(name);
+
+        // Character references are replaced when entity is declared, so
+        // `&#38;#60;` becomes `&#60;` here and `<` in the expanded text
+        let replacement = replace_char_refs(declared[name])?;
+        let mut value = String::new();
+        let mut rest = replacement.as_str();
+        while let Some(start) = rest.find('&') {
+            value.push_str(&rest[..start]);
+            let offset = replacement.len() - rest.len() + start;
+            let len = match rest[start..].find(';') {
+                Some(len) => len,
+                None => {
+                    return Err(EscapeError::UnterminatedEntity(offset..replacement.len()).into())
+                }
+            };
+            let reference = &rest[start + 1..start + len];
+            if let Some(num) = reference.strip_prefix('#') {
+                value.push(parse_number(num).map_err(EscapeError::InvalidCharRef)?);
+            } else if let Some(v) = resolve_predefined_entity(reference) {
+                value.push_str(v);
+            } else if let Some((&name, _)) = declared.get_key_value(reference) {
+                self.expand(name, declared, stack)?;
+                value.push_str(&self.entities[name]);
+            } else if let Some(v) = self.entities.get(reference) {
+                value.push_str(v);
+            } else {
+                return Err(EscapeError::UnrecognizedEntity(
+                    offset..offset + len + 1,
+                    reference.to_string(),
+                )
+                .into());
+            }
+            if self.size + value.len() > self.limit {
+                return Err(DtdResolverError::LimitExceeded(self.limit));
+            }
+            rest = &rest[start + len + 1..];
+        }
+        value.push_str(rest);
+        if self.size + value.len() > self.limit {
+            return Err(DtdResolverError::LimitExceeded(self.limit));
+        }
+
+        stack.pop();
+        self.size += value.len();
+        self.entities.insert(name.to_string(), value);
+        Ok(())
+    }
+}
+
+impl Default for DtdEntityResolver {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EntityResolver for DtdEntityResolver {
+    type Error = DtdResolverError;
+
+    fn capture(&mut self, doctype: BytesText) -> Result<(), Self::Error> {
+        let content = doctype.decode()?;
+
+        let mut declared = HashMap::new();
+        // Names in order of declaration, so errors are reported deterministically
+        let mut names = Vec::new();
+        for event in DtdReader::new(&content) {
+            if let DtdEvent::EntityDecl(decl) = event? {
+                if let (false, EntityDef::Internal(value)) = (decl.parameter, decl.def) {
+                    // The first declaration is binding
+                    if !self.entities.contains_key(decl.name) && !declared.contains_key(decl.name) {
+                        declared.insert(decl.name, value);
+                        names.push(decl.name);
+                    }
+                }
+            }
+        }
+
+        let mut stack = Vec::new();
+        for name in names {
+            self.expand(name, &declared, &mut stack)?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn resolve(&self, entity: &str) -> Option<&str> {
+        // Predefined entities cannot be redefined
+        resolve_predefined_entity(entity).or_else(|| self.entities.get(entity).map(|s| s.as_str()))
+    }
+}
@@ -0,0 +1,502 @@
+//! An owned tree representation of an XML document.
+//!
+//! This module contains a small DOM-like API that is built on top of the
+//! [`Reader`] events. It is useful when you need random access to a (small)
+//! document, for example to read a configuration file with mixed content or
+//! unknown elements, which is inconvenient to model with serde.
+//!
+//! The tree keeps all the markup that the reader reports: comments, processing
+//! instructions, CDATA sections, the XML declaration and the DOCTYPE. All textual
+//! content is stored in the same escaped form as it was read, so writing a
+//! [`Document`] back using [`Writer`] gives the same document (modulo things that
+//! the reader normalizes itself, such as whitespaces in end tags).
+//!
+//! # Example
+//!
+//! ```
+//! # use pretty_assertions::assert_eq;
+//! use quick_xml::dom::{Document, Element, Node};
+//! use quick_xml::writer::Writer;
+//!
+//! let xml = r#"<?xml version="1.0"?><!-- settings --><config debug="true"><name>app</name><extra/></config>"#;
+//! let mut document: Document = xml.parse().unwrap();
+//!
+//! let root = document.root_mut().unwrap();
+//! assert_eq!(root.name().as_ref(), b"config");
+//! assert_eq!(root.child("name").unwrap().text().unwrap(), "app");
+//!
+//! root.push(Element::new("added"));
+//!
+//! let mut writer = Writer::new(Vec::new());
+//! document.write_to(&mut writer).unwrap();
+//! assert_eq!(
+//!     String::from_utf8(writer.into_inner()).unwrap(),
+//!     r#"<?xml version="1.0"?><!-- settings --><config debug="true"><name>app</name><extra/><added/></config>"#
+//! );
+//! ```
+//!
+//! [`Reader`]: crate::reader::Reader
+//! [`Writer`]: crate::writer::Writer
+
+use std::borrow::Cow;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+use crate::encoding::Decoder;
+use crate::errors::{Error, IllFormedError, Result};
+use crate::escape::resolve_predefined_entity;
+use crate::events::attributes::{Attribute, Attributes};
+use crate::events::{BytesCData, BytesDecl, BytesPI, BytesRef, BytesStart, BytesText, Event};
+use crate::name::{QName, ResolveResult};
+use crate::reader::{NsReader, Reader};
+use crate::writer::Writer;
+
+/// A node of a document tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Node {
+    /// An element with its attributes and children.
+    Element(Element),
+    /// Escaped character data.
+    Text(BytesText<'static>),
+    /// Unescaped character data stored in `<![CDATA[...]]>`.
+    CData(BytesCData<'static>),
+    /// Comment `<!-- ... -->`.
+    Comment(BytesText<'static>),
+    /// XML declaration `<?xml ...?>`.
+    Decl(BytesDecl<'static>),
+    /// Processing instruction `<?...?>`.
+    PI(BytesPI<'static>),
+    /// Document type definition data (DTD) stored in `<!DOCTYPE ...>`.
+    DocType(BytesText<'static>),
+    /// General reference `&entity;` in the textual data.
+    GeneralRef(BytesRef<'static>),
+}
+
+impl Node {
+    /// Returns the element if this node is an element.
+    #[inline]
+    pub const fn as_element(&self) -> Option<&Element> {
+        match self {
+            Self::Element(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// Returns the mutable element if this node is an element.
+    #[inline]
+    pub fn as_element_mut(&mut self) -> Option<&mut Element> {
+        match self {
+            Self::Element(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// Writes this node and all its children to the `writer`.
+    pub fn write_to<W: Write>(&self, writer: &mut Writer<W>) -> std::io::Result<()> {
+        match self {
+            Self::Element(e) => e.write_to(writer),
+            Self::Text(e) => writer.write_event(Event::Text(e.borrow())),
+            Self::CData(e) => writer.write_event(Event::CData(e.borrow())),
+            Self::Comment(e) => writer.write_event(Event::Comment(e.borrow())),
+            Self::Decl(e) => writer.write_event(Event::Decl(e.borrow())),
+            Self::PI(e) => writer.write_event(Event::PI(e.borrow())),
+            Self::DocType(e) => writer.write_event(Event::DocType(e.borrow())),
+            Self::GeneralRef(e) => writer.write_event(Event::GeneralRef(e.borrow())),
+        }
+    }
+}
+
+impl From<Element> for Node {
+    #[inline]
+    fn from(element: Element) -> Self {
+        Self::Element(element)
+    }
+}
+
+impl From<BytesText<'static>> for Node {
+    #[inline]
+    fn from(text: BytesText<'static>) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<BytesCData<'static>> for Node {
+    #[inline]
+    fn from(cdata: BytesCData<'static>) -> Self {
+        Self::CData(cdata)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An XML element: a start tag with attributes, children nodes and an end tag.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Element {
+    /// The start tag, which contains the name and attributes of an element
+    start: BytesStart<'static>,
+    /// Namespace of the element, if the tree was built by the [`NsReader`] and
+    /// the element name is bound to a namespace
+    namespace: Option<Vec<u8>>,
+    /// Children nodes of an element
+    children: Vec<Node>,
+    /// If `true`, the element without children is written as `<tag></tag>`,
+    /// otherwise as `<tag/>`. Elements read from the document remember how
+    /// they were written in it
+    expanded: bool,
+}
+
+/// Builder methods
+impl Element {
+    /// Creates a new element without attributes and children.
+    ///
+    /// # Warning
+    ///
+    /// `name` must be a valid name.
+    pub fn new<N: Into<Cow<'static, str>>>(name: N) -> Self {
+        Self::from_start(BytesStart::new(name))
+    }
+
+    /// Creates a new element without children from the start tag.
+    pub const fn from_start(start: BytesStart<'static>) -> Self {
+        Self {
+            start,
+            namespace: None,
+            children: Vec::new(),
+            expanded: false,
+        }
+    }
+
+    /// Adds an attribute to this element.
+    pub fn with_attribute<'b, A: Into<Attribute<'b>>>(mut self, attr: A) -> Self {
+        self.start.push_attribute(attr);
+        self
+    }
+
+    /// Adds a child node to this element.
+    pub fn with_child<N: Into<Node>>(mut self, child: N) -> Self {
+        self.children.push(child.into());
+        self
+    }
+
+    /// Adds a text child to this element. The text will be escaped.
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.push_text(text);
+        self
+    }
+}
+
+/// Getters
+impl Element {
+    /// Returns the start tag of this element.
+    #[inline]
+    pub const fn start(&self) -> &BytesStart<'static> {
+        &self.start
+    }
+
+    /// Returns the mutable start tag of this element. It can be used to change
+    /// the name of the element and its attributes.
+    #[inline]
+    pub fn start_mut(&mut self) -> &mut BytesStart<'static> {
+        &mut self.start
+    }
+
+    /// Gets the undecoded raw name of the element, as present in the input stream.
+    #[inline]
+    pub fn name(&self) -> QName<'_> {
+        self.start.name()
+    }
+
+    /// Returns the namespace of the element. The namespace is known only if the
+    /// tree was built using [`Document::read_from_ns`], otherwise returns `None`.
+    #[inline]
+    pub fn namespace(&self) -> Option<&[u8]> {
+        self.namespace.as_deref()
+    }
+
+    /// Returns an iterator over the attributes of this element.
+    #[inline]
+    pub fn attributes(&self) -> Attributes<'_> {
+        self.start.attributes()
+    }
+
+    /// Returns the value of the attribute with the specified name or `None`
+    /// if the element does not have such attribute.
+    pub fn attribute<N: AsRef<[u8]>>(&self, name: N) -> Result<Option<Attribute<'_>>> {
+        Ok(self.start.try_get_attribute(name)?)
+    }
+
+    /// Returns all children nodes of this element.
+    #[inline]
+    pub fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    /// Returns a mutable vector of children nodes of this element.
+    #[inline]
+    pub fn children_mut(&mut self) -> &mut Vec<Node> {
+        &mut self.children
+    }
+
+    /// Returns an iterator over children elements of this element, skipping
+    /// all other nodes.
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(Node::as_element)
+    }
+
+    /// Returns a mutable iterator over children elements of this element,
+    /// skipping all other nodes.
+    pub fn elements_mut(&mut self) -> impl Iterator<Item = &mut Element> {
+        self.children.iter_mut().filter_map(Node::as_element_mut)
+    }
+
+    /// Returns the first child element with the specified qualified name.
+    pub fn child<N: AsRef<[u8]>>(&self, name: N) -> Option<&Element> {
+        let name = name.as_ref();
+        self.elements().find(|e| e.name().as_ref() == name)
+    }
+
+    /// Returns the first mutable child element with the specified qualified name.
+    pub fn child_mut<N: AsRef<[u8]>>(&mut self, name: N) -> Option<&mut Element> {
+        let name = name.as_ref();
+        self.elements_mut().find(|e| e.name().as_ref() == name)
+    }
+
+    /// Returns the decoded and unescaped textual content of the direct children
+    /// of this element. Content of the text, CDATA and reference nodes is
+    /// concatenated, all other nodes (including nested elements) are ignored.
+    ///
+    /// Only the character references and predefined entities can be resolved,
+    /// any other entity reference produces [`EscapeError::UnrecognizedEntity`].
+    ///
+    /// [`EscapeError::UnrecognizedEntity`]: crate::escape::EscapeError::UnrecognizedEntity
+    pub fn text(&self) -> Result<String> {
+        let mut text = String::new();
+        for child in &self.children {
+            match child {
+                Node::Text(e) => text.push_str(&e.xml10_content()?),
+                Node::CData(e) => text.push_str(&e.xml10_content()?),
+                Node::GeneralRef(e) => match e.resolve_char_ref()? {
+                    Some(ch) => text.push(ch),
+                    None => {
+                        let name = e.decode()?;
+                        match resolve_predefined_entity(&name) {
+                            Some(value) => text.push_str(value),
+                            None => {
+                                return Err(Error::Escape(
+                                    crate::escape::EscapeError::UnrecognizedEntity(
+                                        0..name.len(),
+                                        name.into_owned(),
+                                    ),
+                                ))
+                            }
+                        }
+                    }
+                },
+                _ => {}
+            }
+        }
+        Ok(text)
+    }
+}
+
+/// Modifiers
+impl Element {
+    /// Adds a child node to the end of the children list.
+    #[inline]
+    pub fn push<N: Into<Node>>(&mut self, child: N) {
+        self.children.push(child.into());
+    }
+
+    /// Adds a text node to the end of the children list. The text will be escaped.
+    pub fn push_text(&mut self, text: &str) {
+        self.children
+            .push(Node::Text(BytesText::new(text).into_owned()));
+    }
+
+    /// Writes this element and all its children to the `writer`.
+    pub fn write_to<W: Write>(&self, writer: &mut Writer<W>) -> std::io::Result<()> {
+        if self.children.is_empty() && !self.expanded {
+            return writer.write_event(Event::Empty(self.start.borrow()));
+        }
+        writer.write_event(Event::Start(self.start.borrow()))?;
+        for child in &self.children {
+            child.write_to(writer)?;
+        }
+        writer.write_event(Event::End(self.start.to_end()))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An XML document: a list of top-level nodes, which usually contains one root
+/// element and optional declaration, DOCTYPE, comments and processing instructions.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Document {
+    /// Top-level nodes of the document
+    children: Vec<Node>,
+}
+
+impl Document {
+    /// Creates an empty document.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            children: Vec::new(),
+        }
+    }
+
+    /// Reads all events from the `reader` until [`Event::Eof`] and builds a
+    /// document tree from them.
+    ///
+    /// The reader configuration is respected, so, for example, if you enable
+    /// [`trim_text`], whitespaces between elements would not be present in the tree.
+    ///
+    /// [`trim_text`]: crate::reader::Config::trim_text
+    pub fn read_from<R: BufRead>(reader: &mut Reader<R>) -> Result<Self> {
+        let mut builder = TreeBuilder::default();
+        let mut buf = Vec::new();
+        loop {
+            let event = reader.read_event_into(&mut buf)?;
+            if builder.feed(event, None, reader.decoder())? {
+                return Ok(builder.finish());
+            }
+            buf.clear();
+        }
+    }
+
+    /// Reads all events from the namespace-aware `reader` until [`Event::Eof`]
+    /// and builds a document tree from them. Each element in the tree will
+    /// remember its [namespace].
+    ///
+    /// [namespace]: Element::namespace
+    pub fn read_from_ns<R: BufRead>(reader: &mut NsReader<R>) -> Result<Self> {
+        let mut builder = TreeBuilder::default();
+        let mut buf = Vec::new();
+        loop {
+            let (ns, event) = reader.read_resolved_event_into(&mut buf)?;
+            let ns = match ns {
+                ResolveResult::Bound(ns) => Some(ns.into_inner().to_vec()),
+                _ => None,
+            };
+            if builder.feed(event, ns, reader.decoder())? {
+                return Ok(builder.finish());
+            }
+            buf.clear();
+        }
+    }
+
+    /// Returns all top-level nodes of the document.
+    #[inline]
+    pub fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    /// Returns a mutable vector of top-level nodes of the document.
+    #[inline]
+    pub fn children_mut(&mut self) -> &mut Vec<Node> {
+        &mut self.children
+    }
+
+    /// Returns the first top-level element of the document.
+    pub fn root(&self) -> Option<&Element> {
+        self.children.iter().find_map(Node::as_element)
+    }
+
+    /// Returns the first mutable top-level element of the document.
+    pub fn root_mut(&mut self) -> Option<&mut Element> {
+        self.children.iter_mut().find_map(Node::as_element_mut)
+    }
+
+    /// Adds a top-level node to the end of the document.
+    #[inline]
+    pub fn push<N: Into<Node>>(&mut self, node: N) {
+        self.children.push(node.into());
+    }
+
+    /// Writes the whole document to the `writer`.
+    pub fn write_to<W: Write>(&self, writer: &mut Writer<W>) -> std::io::Result<()> {
+        for child in &self.children {
+            child.write_to(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Document {
+    type Err = Error;
+
+    /// Parses a document from a string using the default reader configuration.
+    fn from_str(s: &str) -> Result<Self> {
+        Self::read_from(&mut Reader::from_str(s))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Collects events into a tree.
+#[derive(Default)]
+struct TreeBuilder {
+    /// Top-level nodes of a document
+    document: Vec<Node>,
+    /// Elements which start tag was seen, but end tag was not seen yet
+    stack: Vec<Element>,
+}
+
+impl TreeBuilder {
+    /// Returns `true` when the end of document is reached.
+    ///
+    /// # Parameters
+    /// - `event`: the next event from the reader
+    /// - `namespace`: a namespace to which element name is bound, if known
+    /// - `decoder`: a decoder, used by the reader to decode event data
+    fn feed(&mut self, event: Event, namespace: Option<Vec<u8>>, decoder: Decoder) -> Result<bool> {
+        let node = match event {
+            Event::Start(e) => {
+                let mut element = Element::from_start(e.into_owned());
+                element.namespace = namespace;
+                self.stack.push(element);
+                return Ok(false);
+            }
+            Event::End(e) => match self.stack.pop() {
+                Some(mut element) => {
+                    element.expanded = true;
+                    Node::Element(element)
+                }
+                None => {
+                    return Err(Error::IllFormed(IllFormedError::UnmatchedEndTag(
+                        decoder.decode(e.name().as_ref())?.into_owned(),
+                    )))
+                }
+            },
+            Event::Empty(e) => {
+                let mut element = Element::from_start(e.into_owned());
+                element.namespace = namespace;
+                Node::Element(element)
+            }
+            Event::Text(e) => Node::Text(e.into_owned()),
+            Event::CData(e) => Node::CData(e.into_owned()),
+            Event::Comment(e) => Node::Comment(e.into_owned()),
+            Event::Decl(e) => Node::Decl(e.into_owned()),
+            Event::PI(e) => Node::PI(e.into_owned()),
+            Event::DocType(e) => Node::DocType(e.into_owned()),
+            Event::GeneralRef(e) => Node::GeneralRef(e.into_owned()),
+            Event::Eof => {
+                return match self.stack.last() {
+                    Some(element) => Err(Error::missed_end(element.name(), decoder)),
+                    None => Ok(true),
+                };
+            }
+        };
+        match self.stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => self.document.push(node),
+        }
+        Ok(false)
+    }
+
+    fn finish(self) -> Document {
+        Document {
+            children: self.document,
+        }
+    }
+}
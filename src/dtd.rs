@@ -0,0 +1,919 @@
+//! A reader of document type definitions (DTD).
+//!
+//! [`Reader`] reports the whole `<!DOCTYPE ...>` declaration as a single
+//! [`Event::DocType`] event and does not look inside it. [`DtdReader`] parses
+//! the content of that event (or a content of an external DTD subset) and yields
+//! typed markup declarations:
+//!
+//! - [`ElementDecl`] for `<!ELEMENT ...>`,
+//! - [`AttlistDecl`] for `<!ATTLIST ...>`,
+//! - [`EntityDecl`] for `<!ENTITY ...>`,
+//! - [`NotationDecl`] for `<!NOTATION ...>`,
+//! - parameter-entity references (`%name;`) between declarations.
+//!
+//! Comments and processing instructions inside DTD are skipped. Parameter-entity
+//! references inside declarations and conditional sections (`<![INCLUDE[...]]>`)
+//! are not supported.
+//!
+//! All strings returned by the reader are slices of the input. Literal values
+//! (entity replacement text, default values of attributes) are returned as is,
+//! without expansion of any references.
+//!
+//! # Example
+//!
+//! ```
+//! # use pretty_assertions::assert_eq;
+//! use quick_xml::dtd::{DtdEvent, DtdReader, EntityDef};
+//! use quick_xml::events::Event;
+//! use quick_xml::reader::Reader;
+//!
+//! let mut reader = Reader::from_str(r#"
+//!     <!DOCTYPE root [
+//!         <!ENTITY e1 "entity 1">
+//!         <!ENTITY e2 SYSTEM "e2.xml">
+//!     ]>
+//!     <root/>
+//! "#);
+//! reader.config_mut().trim_text(true);
+//!
+//! let mut entities = Vec::new();
+//! match reader.read_event().unwrap() {
+//!     Event::DocType(e) => {
+//!         let content = e.decode().unwrap();
+//!         for event in DtdReader::new(&content) {
+//!             if let DtdEvent::EntityDecl(decl) = event.unwrap() {
+//!                 if let EntityDef::Internal(value) = decl.def {
+//!                     entities.push((decl.name.to_string(), value.to_string()));
+//!                 }
+//!             }
+//!         }
+//!     }
+//!     e => panic!("Expected `DocType`, but got `{:?}`", e),
+//! }
+//! assert_eq!(entities, vec![("e1".to_string(), "entity 1".to_string())]);
+//! ```
+//!
+//! [`Reader`]: crate::reader::Reader
+//! [`Event::DocType`]: crate::events::Event::DocType
+
+use std::fmt;
+
+use crate::utils::is_whitespace;
+
+/// An error returned by [`DtdReader`] when DTD does not correspond to the XML grammar.
+///
+/// All positions are byte offsets relative to the start of the input of the reader.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DtdError {
+    /// The input ended before the declaration was finished. Contains a description
+    /// of what was expected.
+    UnexpectedEof(&'static str),
+    /// An unexpected character was found at the specified position. Contains
+    /// a description of what was expected.
+    Unexpected(usize, &'static str),
+}
+
+impl fmt::Display for DtdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnexpectedEof(expected) => {
+                write!(f, "unexpected end of DTD, expected {}", expected)
+            }
+            Self::Unexpected(position, expected) => write!(
+                f,
+                "unexpected character at position {}, expected {}",
+                position, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DtdError {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An external identifier which points to a resource outside of the document.
+///
+/// ```text
+/// [75] ExternalID ::= 'SYSTEM' S SystemLiteral | 'PUBLIC' S PubidLiteral S SystemLiteral
+/// [83] PublicID   ::= 'PUBLIC' S PubidLiteral
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExternalId<'a> {
+    /// `SYSTEM "system-literal"`
+    System(&'a str),
+    /// `PUBLIC "pubid-literal" "system-literal"`. The system literal can be
+    /// omitted only in notation declarations.
+    Public {
+        /// The public identifier
+        public_id: &'a str,
+        /// The system identifier (URI)
+        system_id: Option<&'a str>,
+    },
+}
+
+impl<'a> ExternalId<'a> {
+    /// Returns the system identifier (URI) of the resource, if it is defined.
+    pub const fn system_id(&self) -> Option<&'a str> {
+        match self {
+            Self::System(system_id) => Some(system_id),
+            Self::Public { system_id, .. } => *system_id,
+        }
+    }
+}
+
+/// The document type declaration header: `<!DOCTYPE name ExternalID? [...]>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DocTypeDecl<'a> {
+    /// Name of the root element of the document.
+    pub name: &'a str,
+    /// Reference to the external DTD subset.
+    pub external_id: Option<ExternalId<'a>>,
+}
+
+/// How many times a content particle can occur.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Repeat {
+    /// Particle should occur exactly once (no suffix).
+    Once,
+    /// Particle can occur zero or one time (`?` suffix).
+    Optional,
+    /// Particle can occur any number of times (`*` suffix).
+    ZeroOrMore,
+    /// Particle should occur at least once (`+` suffix).
+    OneOrMore,
+}
+
+/// A kind of content particle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParticleKind<'a> {
+    /// A child element with the specified name.
+    Name(&'a str),
+    /// One of the listed particles: `(a | b | c)`.
+    Choice(Vec<ContentParticle<'a>>),
+    /// All of the listed particles in that order: `(a, b, c)`.
+    Seq(Vec<ContentParticle<'a>>),
+}
+
+/// A content particle of the element content model.
+///
+/// ```text
+/// [48] cp ::= (Name | choice | seq) ('?' | '*' | '+')?
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContentParticle<'a> {
+    /// What should occur.
+    pub kind: ParticleKind<'a>,
+    /// How many times it should occur.
+    pub repeat: Repeat,
+}
+
+/// Content model of an element.
+///
+/// ```text
+/// [46] contentspec ::= 'EMPTY' | 'ANY' | Mixed | children
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ContentSpec<'a> {
+    /// `EMPTY`: the element cannot have any content.
+    Empty,
+    /// `ANY`: the element can have any content.
+    Any,
+    /// `(#PCDATA | a | b)*`: the element can contain text and any number of
+    /// listed child elements in any order. `(#PCDATA)` is represented by an empty list.
+    Mixed(Vec<&'a str>),
+    /// The element can contain only child elements which order is defined by
+    /// the particle. The top-level particle is always [`Choice`] or [`Seq`].
+    ///
+    /// [`Choice`]: ParticleKind::Choice
+    /// [`Seq`]: ParticleKind::Seq
+    Children(ContentParticle<'a>),
+}
+
+/// An element type declaration: `<!ELEMENT name contentspec>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ElementDecl<'a> {
+    /// Name of the element.
+    pub name: &'a str,
+    /// Allowed content of the element.
+    pub content: ContentSpec<'a>,
+}
+
+/// A type of attribute.
+///
+/// ```text
+/// [54] AttType ::= StringType | TokenizedType | EnumeratedType
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AttributeType<'a> {
+    /// `CDATA`: any string.
+    CData,
+    /// `ID`: unique identifier of an element.
+    Id,
+    /// `IDREF`: reference to an identifier.
+    IdRef,
+    /// `IDREFS`: whitespace-separated list of references to identifiers.
+    IdRefs,
+    /// `ENTITY`: name of an unparsed entity.
+    Entity,
+    /// `ENTITIES`: whitespace-separated list of names of unparsed entities.
+    Entities,
+    /// `NMTOKEN`: a name token.
+    NmToken,
+    /// `NMTOKENS`: whitespace-separated list of name tokens.
+    NmTokens,
+    /// `NOTATION (a | b)`: name of one of the listed notations.
+    Notation(Vec<&'a str>),
+    /// `(a | b)`: one of the listed name tokens.
+    Enumeration(Vec<&'a str>),
+}
+
+/// A default value of an attribute.
+///
+/// ```text
+/// [60] DefaultDecl ::= '#REQUIRED' | '#IMPLIED' | (('#FIXED' S)? AttValue)
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DefaultDecl<'a> {
+    /// `#REQUIRED`: attribute must be specified.
+    Required,
+    /// `#IMPLIED`: attribute is optional and has no default value.
+    Implied,
+    /// `#FIXED "value"`: attribute is optional, but if specified, must have
+    /// the same value. Contains the value as written in the DTD.
+    Fixed(&'a str),
+    /// `"value"`: attribute is optional and has the default value. Contains
+    /// the value as written in the DTD.
+    Value(&'a str),
+}
+
+impl<'a> DefaultDecl<'a> {
+    /// Returns the default value of an attribute if it is declared.
+    pub const fn value(&self) -> Option<&'a str> {
+        match self {
+            Self::Fixed(value) | Self::Value(value) => Some(value),
+            Self::Required | Self::Implied => None,
+        }
+    }
+}
+
+/// Definition of an attribute in the [`AttlistDecl`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttributeDef<'a> {
+    /// Name of the attribute.
+    pub name: &'a str,
+    /// Type of the attribute.
+    pub ty: AttributeType<'a>,
+    /// Default value of the attribute.
+    pub default: DefaultDecl<'a>,
+}
+
+/// An attribute-list declaration: `<!ATTLIST element AttDef*>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttlistDecl<'a> {
+    /// Name of the element to which attributes belong.
+    pub element: &'a str,
+    /// Declared attributes in order of declaration.
+    pub attributes: Vec<AttributeDef<'a>>,
+}
+
+/// A definition of an entity.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EntityDef<'a> {
+    /// An internal entity with the replacement text as written in the DTD.
+    Internal(&'a str),
+    /// An external entity.
+    External {
+        /// Location of the entity content.
+        id: ExternalId<'a>,
+        /// Name of the notation of an unparsed entity (`NDATA name`).
+        /// Always `None` for parameter entities.
+        ndata: Option<&'a str>,
+    },
+}
+
+/// An entity declaration: `<!ENTITY name EntityDef>` or `<!ENTITY % name PEDef>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntityDecl<'a> {
+    /// Name of the entity.
+    pub name: &'a str,
+    /// `true` for parameter entities (`<!ENTITY % name ...>`), which could be
+    /// referenced only inside DTD, and `false` for general entities.
+    pub parameter: bool,
+    /// Value of the entity.
+    pub def: EntityDef<'a>,
+}
+
+/// A notation declaration: `<!NOTATION name ExternalID>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NotationDecl<'a> {
+    /// Name of the notation.
+    pub name: &'a str,
+    /// Identifier of the notation.
+    pub id: ExternalId<'a>,
+}
+
+/// An event produced by the [`DtdReader`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DtdEvent<'a> {
+    /// The header of the `<!DOCTYPE>` declaration. It is always the first event,
+    /// if the reader was created from the content of the [`Event::DocType`] event.
+    ///
+    /// [`Event::DocType`]: crate::events::Event::DocType
+    DocType(DocTypeDecl<'a>),
+    /// `<!ELEMENT>` declaration.
+    ElementDecl(ElementDecl<'a>),
+    /// `<!ATTLIST>` declaration.
+    AttlistDecl(AttlistDecl<'a>),
+    /// `<!ENTITY>` declaration.
+    EntityDecl(EntityDecl<'a>),
+    /// `<!NOTATION>` declaration.
+    NotationDecl(NotationDecl<'a>),
+    /// A parameter-entity reference `%name;` between declarations. Contains the
+    /// name of the entity.
+    PEReference(&'a str),
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    /// Expecting the name of the document type
+    DocType,
+    /// Inside `[...]` of the `<!DOCTYPE>`
+    InternalSubset,
+    /// Inside the external subset
+    ExternalSubset,
+    /// All input is read or an error occurred
+    Done,
+}
+
+/// An iterator over markup declarations in the DTD.
+///
+/// The reader can be created from:
+/// - content of the [`Event::DocType`] event, i.e. everything between
+///   `<!DOCTYPE` and `>`. The first event would be [`DtdEvent::DocType`];
+/// - content of an external DTD subset, which starts from a markup declaration,
+///   a comment, a processing instruction or a parameter-entity reference.
+///
+/// After the first error iterator returns `None`.
+///
+/// See the [module documentation](self) for an example.
+///
+/// [`Event::DocType`]: crate::events::Event::DocType
+#[derive(Clone, Debug)]
+pub struct DtdReader<'a> {
+    input: &'a str,
+    /// Current position in `input`
+    pos: usize,
+    state: State,
+}
+
+impl<'a> DtdReader<'a> {
+    /// Creates a new reader of the DTD.
+    pub fn new(input: &'a str) -> Self {
+        let start = input.trim_start_matches(|c: char| c.is_ascii() && is_whitespace(c as u8));
+        let state = match start.as_bytes().first() {
+            Some(b'<' | b'%') => State::ExternalSubset,
+            _ => State::DocType,
+        };
+        Self {
+            input,
+            pos: 0,
+            state,
+        }
+    }
+
+    /// Returns the byte position in the input just after the last read event.
+    #[inline]
+    pub const fn position(&self) -> usize {
+        self.pos
+    }
+
+    fn read_event(&mut self) -> Result<Option<DtdEvent<'a>>, DtdError> {
+        loop {
+            match self.state {
+                State::Done => return Ok(None),
+                State::DocType => {
+                    self.skip_space();
+                    let name = self.name()?;
+                    let external_id = if self.skip_space() && self.is_external_id() {
+                        let id = self.external_id(false)?;
+                        self.skip_space();
+                        Some(id)
+                    } else {
+                        None
+                    };
+                    self.state = if self.consume("[") {
+                        State::InternalSubset
+                    } else {
+                        self.end()?;
+                        State::Done
+                    };
+                    return Ok(Some(DtdEvent::DocType(DocTypeDecl { name, external_id })));
+                }
+                State::InternalSubset | State::ExternalSubset => {
+                    self.skip_space();
+                    if self.rest().is_empty() {
+                        if self.state == State::InternalSubset {
+                            return Err(DtdError::UnexpectedEof("`]`"));
+                        }
+                        self.state = State::Done;
+                        return Ok(None);
+                    }
+                    if self.state == State::InternalSubset && self.consume("]") {
+                        self.skip_space();
+                        self.end()?;
+                        self.state = State::Done;
+                        return Ok(None);
+                    }
+                    if self.consume("<!--") {
+                        self.skip_until("-->", "`-->`")?;
+                        continue;
+                    }
+                    if self.consume("<?") {
+                        self.skip_until("?>", "`?>`")?;
+                        continue;
+                    }
+                    return self.markup_decl().map(Some);
+                }
+            }
+        }
+    }
+
+    fn markup_decl(&mut self) -> Result<DtdEvent<'a>, DtdError> {
+        if self.consume("%") {
+            let name = self.name()?;
+            self.expect(";", "`;`")?;
+            return Ok(DtdEvent::PEReference(name));
+        }
+        let event = if self.consume("<!ELEMENT") {
+            self.space()?;
+            DtdEvent::ElementDecl(self.element_decl()?)
+        } else if self.consume("<!ATTLIST") {
+            self.space()?;
+            DtdEvent::AttlistDecl(self.attlist_decl()?)
+        } else if self.consume("<!ENTITY") {
+            self.space()?;
+            DtdEvent::EntityDecl(self.entity_decl()?)
+        } else if self.consume("<!NOTATION") {
+            self.space()?;
+            DtdEvent::NotationDecl(self.notation_decl()?)
+        } else {
+            return Err(DtdError::Unexpected(self.pos, "markup declaration"));
+        };
+        self.skip_space();
+        self.expect(">", "`>`")?;
+        Ok(event)
+    }
+
+    /// ```text
+    /// [45] elementdecl ::= '<!ELEMENT' S Name S contentspec S? '>'
+    /// ```
+    fn element_decl(&mut self) -> Result<ElementDecl<'a>, DtdError> {
+        let name = self.name()?;
+        self.space()?;
+        let content = if self.keyword("EMPTY") {
+            ContentSpec::Empty
+        } else if self.keyword("ANY") {
+            ContentSpec::Any
+        } else {
+            self.expect("(", "`EMPTY`, `ANY` or `(`")?;
+            self.skip_space();
+            if self.consume("#PCDATA") {
+                self.mixed()?
+            } else {
+                ContentSpec::Children(self.group()?)
+            }
+        };
+        Ok(ElementDecl { name, content })
+    }
+
+    /// Parses content after `(#PCDATA`.
+    ///
+    /// ```text
+    /// [51] Mixed ::= '(' S? '#PCDATA' (S? '|' S? Name)* S? ')*'
+    ///              | '(' S? '#PCDATA' S? ')'
+    /// ```
+    fn mixed(&mut self) -> Result<ContentSpec<'a>, DtdError> {
+        let mut names = Vec::new();
+        loop {
+            self.skip_space();
+            if self.consume(")") {
+                break;
+            }
+            self.expect("|", "`|` or `)`")?;
+            self.skip_space();
+            names.push(self.name()?);
+        }
+        if !self.consume("*") && !names.is_empty() {
+            return Err(DtdError::Unexpected(self.pos, "`*`"));
+        }
+        Ok(ContentSpec::Mixed(names))
+    }
+
+    /// Parses choice or sequence after the opening `(`.
+    ///
+    /// ```text
+    /// [49] choice ::= '(' S? cp ( S? '|' S? cp )+ S? ')'
+    /// [50] seq    ::= '(' S? cp ( S? ',' S? cp )* S? ')'
+    /// ```
+    fn group(&mut self) -> Result<ContentParticle<'a>, DtdError> {
+        let mut particles = vec![self.particle()?];
+        let mut separator = None;
+        loop {
+            self.skip_space();
+            if self.consume(")") {
+                break;
+            }
+            let sep = match self.rest().as_bytes().first() {
+                Some(&sep @ (b'|' | b',')) if separator.is_none() || separator == Some(sep) => sep,
+                Some(_) => {
+                    return Err(DtdError::Unexpected(
+                        self.pos,
+                        match separator {
+                            Some(b'|') => "`|` or `)`",
+                            Some(_) => "`,` or `)`",
+                            None => "`|`, `,` or `)`",
+                        },
+                    ))
+                }
+                None => return Err(DtdError::UnexpectedEof("`)`")),
+            };
+            self.pos += 1;
+            separator = Some(sep);
+            self.skip_space();
+            particles.push(self.particle()?);
+        }
+        let kind = match separator {
+            Some(b'|') => ParticleKind::Choice(particles),
+            _ => ParticleKind::Seq(particles),
+        };
+        Ok(ContentParticle {
+            kind,
+            repeat: self.repeat(),
+        })
+    }
+
+    /// ```text
+    /// [48] cp ::= (Name | choice | seq) ('?' | '*' | '+')?
+    /// ```
+    fn particle(&mut self) -> Result<ContentParticle<'a>, DtdError> {
+        if self.consume("(") {
+            self.skip_space();
+            return self.group();
+        }
+        let name = self.name()?;
+        Ok(ContentParticle {
+            kind: ParticleKind::Name(name),
+            repeat: self.repeat(),
+        })
+    }
+
+    fn repeat(&mut self) -> Repeat {
+        let repeat = match self.rest().as_bytes().first() {
+            Some(b'?') => Repeat::Optional,
+            Some(b'*') => Repeat::ZeroOrMore,
+            Some(b'+') => Repeat::OneOrMore,
+            _ => return Repeat::Once,
+        };
+        self.pos += 1;
+        repeat
+    }
+
+    /// ```text
+    /// [52] AttlistDecl ::= '<!ATTLIST' S Name AttDef* S? '>'
+    /// [53] AttDef      ::= S Name S AttType S DefaultDecl
+    /// ```
+    fn attlist_decl(&mut self) -> Result<AttlistDecl<'a>, DtdError> {
+        let element = self.name()?;
+        let mut attributes = Vec::new();
+        while self.skip_space() && !self.rest().starts_with('>') {
+            let name = self.name()?;
+            self.space()?;
+            let ty = self.attribute_type()?;
+            self.space()?;
+            let default = self.default_decl()?;
+            attributes.push(AttributeDef { name, ty, default });
+        }
+        Ok(AttlistDecl {
+            element,
+            attributes,
+        })
+    }
+
+    /// ```text
+    /// [54] AttType        ::= StringType | TokenizedType | EnumeratedType
+    /// [55] StringType     ::= 'CDATA'
+    /// [56] TokenizedType  ::= 'ID' | 'IDREF' | 'IDREFS' | 'ENTITY' | 'ENTITIES' | 'NMTOKEN' | 'NMTOKENS'
+    /// [57] EnumeratedType ::= NotationType | Enumeration
+    /// [58] NotationType   ::= 'NOTATION' S '(' S? Name (S? '|' S? Name)* S? ')'
+    /// [59] Enumeration    ::= '(' S? Nmtoken (S? '|' S? Nmtoken)* S? ')'
+    /// ```
+    fn attribute_type(&mut self) -> Result<AttributeType<'a>, DtdError> {
+        if self.consume("(") {
+            return Ok(AttributeType::Enumeration(self.enumeration()?));
+        }
+        let start = self.pos;
+        let ty = match self.name()? {
+            "CDATA" => AttributeType::CData,
+            "ID" => AttributeType::Id,
+            "IDREF" => AttributeType::IdRef,
+            "IDREFS" => AttributeType::IdRefs,
+            "ENTITY" => AttributeType::Entity,
+            "ENTITIES" => AttributeType::Entities,
+            "NMTOKEN" => AttributeType::NmToken,
+            "NMTOKENS" => AttributeType::NmTokens,
+            "NOTATION" => {
+                self.space()?;
+                self.expect("(", "`(`")?;
+                AttributeType::Notation(self.enumeration()?)
+            }
+            _ => return Err(DtdError::Unexpected(start, "attribute type")),
+        };
+        Ok(ty)
+    }
+
+    /// Parses `|`-separated list of names after the opening `(`.
+    fn enumeration(&mut self) -> Result<Vec<&'a str>, DtdError> {
+        let mut names = Vec::new();
+        loop {
+            self.skip_space();
+            names.push(self.name()?);
+            self.skip_space();
+            if self.consume(")") {
+                return Ok(names);
+            }
+            self.expect("|", "`|` or `)`")?;
+        }
+    }
+
+    /// ```text
+    /// [60] DefaultDecl ::= '#REQUIRED' | '#IMPLIED' | (('#FIXED' S)? AttValue)
+    /// ```
+    fn default_decl(&mut self) -> Result<DefaultDecl<'a>, DtdError> {
+        if self.keyword("#REQUIRED") {
+            Ok(DefaultDecl::Required)
+        } else if self.keyword("#IMPLIED") {
+            Ok(DefaultDecl::Implied)
+        } else if self.keyword("#FIXED") {
+            self.space()?;
+            Ok(DefaultDecl::Fixed(self.literal()?))
+        } else {
+            Ok(DefaultDecl::Value(self.literal()?))
+        }
+    }
+
+    /// ```text
+    /// [70] EntityDecl ::= GEDecl | PEDecl
+    /// [71] GEDecl     ::= '<!ENTITY' S Name S EntityDef S? '>'
+    /// [72] PEDecl     ::= '<!ENTITY' S '%' S Name S PEDef S? '>'
+    /// [73] EntityDef  ::= EntityValue | (ExternalID NDataDecl?)
+    /// [74] PEDef      ::= EntityValue | ExternalID
+    /// [76] NDataDecl  ::= S 'NDATA' S Name
+    /// ```
+    fn entity_decl(&mut self) -> Result<EntityDecl<'a>, DtdError> {
+        let parameter = self.consume("%");
+        if parameter {
+            self.space()?;
+        }
+        let name = self.name()?;
+        self.space()?;
+        let def = if self.is_external_id() {
+            let id = self.external_id(false)?;
+            let start = self.pos;
+            let ndata = if self.skip_space() && self.keyword("NDATA") {
+                if parameter {
+                    return Err(DtdError::Unexpected(start + 1, "`>`"));
+                }
+                self.space()?;
+                Some(self.name()?)
+            } else {
+                None
+            };
+            EntityDef::External { id, ndata }
+        } else {
+            EntityDef::Internal(self.literal()?)
+        };
+        Ok(EntityDecl {
+            name,
+            parameter,
+            def,
+        })
+    }
+
+    /// ```text
+    /// [82] NotationDecl ::= '<!NOTATION' S Name S (ExternalID | PublicID) S? '>'
+    /// ```
+    fn notation_decl(&mut self) -> Result<NotationDecl<'a>, DtdError> {
+        let name = self.name()?;
+        self.space()?;
+        if !self.is_external_id() {
+            return Err(DtdError::Unexpected(self.pos, "`SYSTEM` or `PUBLIC`"));
+        }
+        let id = self.external_id(true)?;
+        Ok(NotationDecl { name, id })
+    }
+
+    /// ```text
+    /// [75] ExternalID ::= 'SYSTEM' S SystemLiteral | 'PUBLIC' S PubidLiteral S SystemLiteral
+    /// [83] PublicID   ::= 'PUBLIC' S PubidLiteral
+    /// ```
+    ///
+    /// # Parameters
+    /// - `public_only`: if `true`, system literal after `PUBLIC` is optional
+    fn external_id(&mut self, public_only: bool) -> Result<ExternalId<'a>, DtdError> {
+        if self.keyword("SYSTEM") {
+            self.space()?;
+            return Ok(ExternalId::System(self.literal()?));
+        }
+        self.keyword("PUBLIC");
+        self.space()?;
+        let public_id = self.literal()?;
+        let start = self.pos;
+        let system_id =
+            if self.skip_space() && matches!(self.rest().as_bytes().first(), Some(b'"' | b'\'')) {
+                Some(self.literal()?)
+            } else if public_only {
+                self.pos = start;
+                None
+            } else {
+                return Err(self.unexpected("system literal"));
+            };
+        Ok(ExternalId::Public {
+            public_id,
+            system_id,
+        })
+    }
+
+    fn is_external_id(&self) -> bool {
+        let rest = self.rest();
+        let is_keyword = |keyword: &str| {
+            rest.starts_with(keyword) && !rest[keyword.len()..].starts_with(is_name_char)
+        };
+        is_keyword("SYSTEM") || is_keyword("PUBLIC")
+    }
+
+    //--------------------------------------------------------------------------------------------
+    // Low-level helpers
+
+    #[inline]
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    /// Consumes `prefix` if input starts with it.
+    fn consume(&mut self, prefix: &str) -> bool {
+        if self.rest().starts_with(prefix) {
+            self.pos += prefix.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consumes `keyword` if input starts with it and it is not a part of a longer name.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let rest = self.rest();
+        if rest.starts_with(keyword) && !rest[keyword.len()..].starts_with(is_name_char) {
+            self.pos += keyword.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, prefix: &str, expected: &'static str) -> Result<(), DtdError> {
+        if self.consume(prefix) {
+            Ok(())
+        } else {
+            Err(self.unexpected(expected))
+        }
+    }
+
+    /// Returns an error at the current position.
+    fn unexpected(&self, expected: &'static str) -> DtdError {
+        if self.rest().is_empty() {
+            DtdError::UnexpectedEof(expected)
+        } else {
+            DtdError::Unexpected(self.pos, expected)
+        }
+    }
+
+    /// Checks that nothing left after the internal subset.
+    fn end(&self) -> Result<(), DtdError> {
+        if self.rest().is_empty() {
+            Ok(())
+        } else {
+            Err(DtdError::Unexpected(self.pos, "end of DOCTYPE"))
+        }
+    }
+
+    /// Skips whitespaces and returns `true` if at least one was skipped.
+    fn skip_space(&mut self) -> bool {
+        let rest = self.rest();
+        let len = rest.len() - rest.trim_start_matches(is_space).len();
+        self.pos += len;
+        len > 0
+    }
+
+    /// Skips required whitespaces.
+    fn space(&mut self) -> Result<(), DtdError> {
+        if self.skip_space() {
+            Ok(())
+        } else {
+            Err(self.unexpected("whitespace"))
+        }
+    }
+
+    /// Skips everything up to and including `end`.
+    fn skip_until(&mut self, end: &str, expected: &'static str) -> Result<(), DtdError> {
+        match self.rest().find(end) {
+            Some(i) => {
+                self.pos += i + end.len();
+                Ok(())
+            }
+            None => {
+                self.pos = self.input.len();
+                Err(DtdError::UnexpectedEof(expected))
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<&'a str, DtdError> {
+        let rest = self.rest();
+        let len = rest.len() - rest.trim_start_matches(is_name_char).len();
+        if len == 0 {
+            return Err(self.unexpected("name"));
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    /// Reads a string in single or double quotes and returns its content.
+    fn literal(&mut self) -> Result<&'a str, DtdError> {
+        let rest = self.rest();
+        let quote = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => quote,
+            _ => return Err(self.unexpected("quoted string")),
+        };
+        match rest[1..].find(quote) {
+            Some(len) => {
+                self.pos += len + 2;
+                Ok(&rest[1..len + 1])
+            }
+            None => {
+                self.pos = self.input.len();
+                Err(DtdError::UnexpectedEof("closing quote"))
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for DtdReader<'a> {
+    type Item = Result<DtdEvent<'a>, DtdError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.read_event() {
+            Ok(event) => event.map(Ok),
+            Err(e) => {
+                self.state = State::Done;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[inline]
+const fn is_space(c: char) -> bool {
+    c.is_ascii() && is_whitespace(c as u8)
+}
+
+/// Returns `true` for characters that can be a part of a name. This is more
+/// permissive than the XML grammar: any character that is not a whitespace
+/// and not a DTD delimiter is accepted.
+#[inline]
+const fn is_name_char(c: char) -> bool {
+    !matches!(
+        c,
+        ' ' | '\t'
+            | '\r'
+            | '\n'
+            | '<'
+            | '>'
+            | '('
+            | ')'
+            | '['
+            | ']'
+            | '|'
+            | ','
+            | '?'
+            | '*'
+            | '+'
+            | '%'
+            | ';'
+            | '"'
+            | '\''
+            | '='
+    )
+}
@@ -67,12 +67,15 @@ impl std::fmt::Display for EncodingError {
 /// key is not defined or contains unknown encoding.
 ///
 /// The library supports any UTF-8 compatible encodings that crate `encoding_rs`
-/// is supported. [*UTF-16 and ISO-2022-JP are not supported at the present*][utf16].
+/// is supported. UTF-16 documents are transcoded to UTF-8 by the [`Reader`],
+/// so the decoder for them is always UTF-8 decoder. [*ISO-2022-JP is not supported
+/// at the present*][iso-2022-jp] without `DecodingReader`.
 ///
 /// If feature [`encoding`] is disabled, the decoder is always UTF-8 decoder:
 /// any XML declarations are ignored.
 ///
-/// [utf16]: https://github.com/tafia/quick-xml/issues/158
+/// [iso-2022-jp]: https://github.com/tafia/quick-xml/issues/158
+/// [`Reader`]: crate::reader::Reader
 /// [`encoding`]: ../index.html#encoding
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Decoder {
@@ -676,6 +679,255 @@ impl<R: BufRead> Read for DecodingReader<R> {
     }
 }
 
+/// State of the UTF-16 to UTF-8 transcoding, which is used by the [`Reader`]
+/// and the [`PushReader`] when they detect, that the document is UTF-16 encoded.
+///
+/// If bytes were consumed from the underlying reader to detect the encoding
+/// of a document that is not UTF-16 encoded, the transcoder returns them as is
+/// before the data of the underlying reader (see [`Transcoder::replay()`]).
+///
+/// Unlike [`DecodingReader`], this type does not own the underlying reader,
+/// so it can be stored beside it. Use [`Transcoder::reader()`] to get a [`BufRead`]
+/// that returns transcoded data.
+///
+/// [`Reader`]: crate::reader::Reader
+/// [`PushReader`]: crate::reader::PushReader
+#[cfg(feature = "encoding")]
+#[derive(Clone)]
+pub(crate) struct Transcoder {
+    /// Encoding of the source, either UTF-16 LE or UTF-16 BE, or UTF-8 if
+    /// the transcoder only replays consumed bytes
+    encoding: &'static encoding_rs::Encoding,
+    /// Bytes read from the source, that was not transcoded yet. That could be
+    /// the last byte of an incomplete code unit or a high surrogate which pair
+    /// was not read yet
+    pending: Vec<u8>,
+    /// Transcoded UTF-8 data
+    out: Vec<u8>,
+    /// Start of unconsumed data in `out`
+    out_pos: usize,
+}
+
+#[cfg(feature = "encoding")]
+impl std::fmt::Debug for Transcoder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Transcoder")
+            .field("encoding", &self.encoding)
+            .field("pending", &self.pending.len())
+            .field("out_pos", &self.out_pos)
+            .field("out_len", &self.out.len())
+            .finish()
+    }
+}
+
+#[cfg(feature = "encoding")]
+impl Transcoder {
+    /// Creates a transcoder if `encoding` is not ASCII-compatible and the document
+    /// should be transcoded in order to be parsed, otherwise returns `None`.
+    pub fn new(encoding: &'static encoding_rs::Encoding) -> Option<Self> {
+        if encoding == encoding_rs::UTF_16LE || encoding == encoding_rs::UTF_16BE {
+            Some(Self {
+                encoding,
+                pending: Vec::new(),
+                out: Vec::new(),
+                out_pos: 0,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Creates a transcoder which does not transcode anything, but returns
+    /// `consumed` bytes before the data of the underlying reader.
+    pub fn replay(consumed: Vec<u8>) -> Self {
+        Self {
+            encoding: encoding_rs::UTF_8,
+            pending: Vec::new(),
+            out: consumed,
+            out_pos: 0,
+        }
+    }
+
+    /// Sets bytes which were consumed from the underlying reader before
+    /// the transcoding was started, so they will be transcoded first.
+    pub fn with_pending(mut self, consumed: Vec<u8>) -> Self {
+        self.pending = consumed;
+        self
+    }
+
+    fn replays(&self) -> bool {
+        self.encoding == encoding_rs::UTF_8
+    }
+
+    /// Returns `true` if the transcoder only replays bytes and all of them were
+    /// consumed, so the underlying reader can be used directly.
+    pub fn is_finished(&self) -> bool {
+        self.replays() && self.out_pos == self.out.len()
+    }
+
+    /// Returns bytes read from the underlying reader that were not consumed yet.
+    /// Returns an error if data is transcoded, because the original bytes are
+    /// not available then.
+    pub fn read_ahead(&self) -> io::Result<&[u8]> {
+        if self.replays() {
+            Ok(&self.out[self.out_pos..])
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the input is transcoded from UTF-16, raw bytes are not available",
+            ))
+        }
+    }
+
+    /// Consumes bytes returned by [`Self::read_ahead()`].
+    pub fn consume(&mut self, amt: usize) {
+        self.out_pos += amt;
+    }
+
+    /// Returns the encoding of the source.
+    pub const fn encoding(&self) -> &'static encoding_rs::Encoding {
+        self.encoding
+    }
+
+    /// Transcodes `chunk` and appends the result to `output`. Used by the
+    /// [`PushReader`], which gets data by chunks instead of reading them.
+    ///
+    /// If `last` is `true`, no more data will follow. Returns `false` if malformed
+    /// data was found. Data before it is still appended to `output`.
+    ///
+    /// [`PushReader`]: crate::reader::PushReader
+    pub fn feed(&mut self, chunk: &[u8], last: bool, output: &mut Vec<u8>) -> bool {
+        self.pending.extend_from_slice(chunk);
+        let valid = self.transcode(last);
+        output.append(&mut self.out);
+        valid
+    }
+
+    /// Returns a reader that transcodes data from `inner`. If `transcoder` is
+    /// `None`, data of `inner` returned as is.
+    #[inline]
+    pub fn reader<'t, R>(inner: &'t mut R, transcoder: &'t mut Option<Self>) -> Transcoding<'t, R> {
+        Transcoding { inner, transcoder }
+    }
+
+    /// Transcodes as much as possible bytes from `pending` to `out`. Stops at
+    /// the first malformed code unit and returns `false` if it is found, so
+    /// data before it could be returned to the caller.
+    ///
+    /// If `last` is `true`, no more data will be added to `pending`, so any
+    /// remaining bytes are malformed.
+    fn transcode(&mut self, last: bool) -> bool {
+        let big_endian = self.encoding == encoding_rs::UTF_16BE;
+        let units = self.pending.chunks_exact(2).map(|unit| {
+            if big_endian {
+                u16::from_be_bytes([unit[0], unit[1]])
+            } else {
+                u16::from_le_bytes([unit[0], unit[1]])
+            }
+        });
+        let len = self.pending.len() & !1;
+        let mut used = 0;
+        let mut valid = true;
+        for ch in char::decode_utf16(units) {
+            match ch {
+                Ok(ch) => {
+                    used += ch.len_utf16() * 2;
+                    self.out
+                        .extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                // A high surrogate at the end of the available data could be
+                // paired with a low surrogate that will be read later
+                Err(e)
+                    if !last
+                        && used + 2 == len
+                        && (0xD800..0xDC00).contains(&e.unpaired_surrogate()) =>
+                {
+                    break
+                }
+                Err(_) => {
+                    valid = false;
+                    break;
+                }
+            }
+        }
+        self.pending.drain(..used);
+        valid && (!last || self.pending.is_empty())
+    }
+
+    fn error(&self) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            EncodingError::Other(self.encoding),
+        )
+    }
+}
+
+/// A [`BufRead`] which returns data of an inner reader transcoded from UTF-16
+/// to UTF-8. Created by [`Transcoder::reader()`].
+#[cfg(feature = "encoding")]
+pub(crate) struct Transcoding<'t, R> {
+    inner: &'t mut R,
+    transcoder: &'t mut Option<Transcoder>,
+}
+
+#[cfg(feature = "encoding")]
+impl<'t, R: BufRead> BufRead for Transcoding<'t, R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let transcoder = match self.transcoder {
+            Some(transcoder) => transcoder,
+            None => return self.inner.fill_buf(),
+        };
+        if transcoder.out_pos < transcoder.out.len() {
+            return Ok(&transcoder.out[transcoder.out_pos..]);
+        }
+        if transcoder.replays() {
+            return self.inner.fill_buf();
+        }
+        transcoder.out.clear();
+        transcoder.out_pos = 0;
+
+        // Loop until we produce output or hit EOF. Transcoder may consume input
+        // without producing output, for example, a lone byte of a code unit
+        while transcoder.out.is_empty() {
+            let available = self.inner.fill_buf()?;
+            let last = available.is_empty();
+            let used = available.len();
+            transcoder.pending.extend_from_slice(available);
+            self.inner.consume(used);
+
+            let valid = transcoder.transcode(last);
+            // Return data before the malformed sequence first
+            if !valid && transcoder.out.is_empty() {
+                // Do not report the same error again
+                transcoder.pending.clear();
+                return Err(transcoder.error());
+            }
+            if last || !valid {
+                break;
+            }
+        }
+        Ok(&transcoder.out)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        match self.transcoder {
+            Some(transcoder) if !transcoder.is_finished() => transcoder.out_pos += amt,
+            _ => self.inner.consume(amt),
+        }
+    }
+}
+
+#[cfg(feature = "encoding")]
+impl<'t, R: BufRead> Read for Transcoding<'t, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
 #[cfg(all(test, feature = "encoding"))]
 mod decoding_reader {
     use super::*;
@@ -1,9 +1,11 @@
 //! Error management module
 
+use crate::dtd::DtdError;
 use crate::encoding::{Decoder, EncodingError};
 use crate::escape::EscapeError;
 use crate::events::attributes::AttrError;
 use crate::name::{NamespaceError, QName};
+use crate::reader::Position;
 use std::fmt;
 use std::io::{Error as IoError, ErrorKind as IoErrorKind};
 use std::sync::Arc;
@@ -143,6 +145,89 @@ pub enum IllFormedError {
     /// The parser started to parse entity or character reference (`&...;`) in text,
     /// but the input ended before the closing `;` character was found.
     UnclosedReference,
+    /// The internal subset of a document type definition (DTD) cannot be parsed.
+    ///
+    /// This error is returned only when [expansion of entities] is enabled,
+    /// because the reader does not look inside DTD otherwise.
+    ///
+    /// [expansion of entities]: crate::reader::Config::expand_entities
+    InvalidDtd(DtdError),
+    /// The replacement text of the specified entity references that entity
+    /// directly or through other entities.
+    ///
+    /// This error is returned only when [expansion of entities] is enabled.
+    ///
+    /// [expansion of entities]: crate::reader::Config::expand_entities
+    RecursiveEntity(String),
+    /// The document contains a character that is not allowed by the [`Char`]
+    /// production of its XML version, either literally or as a character
+    /// reference. Contains the code point of the character, which may be not
+    /// a valid Unicode scalar value (for example, a surrogate).
+    ///
+    /// This error is returned only when [checks of characters] are enabled.
+    ///
+    /// [`Char`]: https://www.w3.org/TR/xml11/#NT-Char
+    /// [checks of characters]: crate::reader::Config::check_chars
+    InvalidChar(u32),
+    /// The name of an element, attribute, processing instruction or entity does
+    /// not match the [`Name`] production. Contains the invalid name.
+    ///
+    /// This error is returned only when [checks of names] are enabled.
+    ///
+    /// [`Name`]: https://www.w3.org/TR/xml11/#NT-Name
+    /// [checks of names]: crate::reader::Config::check_names
+    InvalidName(String),
+    /// The document contains more than one root element. Contains the name of
+    /// the second root element.
+    ///
+    /// This error is returned only by the [`Writer`] with enabled [checks of
+    /// well-formedness].
+    ///
+    /// [`Writer`]: crate::writer::Writer
+    /// [checks of well-formedness]: crate::writer::Config::check_well_formedness
+    MultipleRoots(String),
+    /// The document contains text, CDATA section or reference outside the root
+    /// element. Only whitespace is allowed there.
+    ///
+    /// This error is returned only by the [`Writer`] with enabled [checks of
+    /// well-formedness].
+    ///
+    /// [`Writer`]: crate::writer::Writer
+    /// [checks of well-formedness]: crate::writer::Config::check_well_formedness
+    TextOutsideRoot,
+    /// A CDATA section contains forbidden `]]>` sequence inside.
+    ///
+    /// This error is returned only by the [`Writer`] with enabled [checks of
+    /// well-formedness]. Split the text into several CDATA sections with
+    /// [`BytesCData::escaped()`] to write it.
+    ///
+    /// [`Writer`]: crate::writer::Writer
+    /// [checks of well-formedness]: crate::writer::Config::check_well_formedness
+    /// [`BytesCData::escaped()`]: crate::events::BytesCData::escaped
+    CDataEndInCData,
+    /// The `<` character in the text is not followed by a name, `!`, `?` or `/`,
+    /// so it cannot start a markup and should be escaped as `&lt;`.
+    ///
+    /// This error is reported only as a [`Diagnostic`] when [`Config::recover_errors`]
+    /// is enabled. Otherwise such `<` is read as a start of a tag.
+    ///
+    /// [`Diagnostic`]: crate::reader::Diagnostic
+    /// [`Config::recover_errors`]: crate::reader::Config::recover_errors
+    UnescapedLessThan,
+    /// The end tag of an outer element was found while an inner element was
+    /// still opened, so the inner element was closed implicitly.
+    ///
+    /// This error is reported only as a [`Diagnostic`] when [`Config::recover_errors`]
+    /// is enabled. Otherwise [`MismatchedEndTag`](Self::MismatchedEndTag) is returned.
+    ///
+    /// [`Diagnostic`]: crate::reader::Diagnostic
+    /// [`Config::recover_errors`]: crate::reader::Config::recover_errors
+    ImpliedEndTag {
+        /// Name of open tag, that was closed implicitly
+        expected: String,
+        /// Name of the end tag of the outer element
+        found: String,
+    },
 }
 
 impl fmt::Display for IllFormedError {
@@ -179,11 +264,101 @@ impl fmt::Display for IllFormedError {
             Self::UnclosedReference => f.write_str(
                 "entity or character reference not closed: `;` not found before end of input",
             ),
+            Self::InvalidDtd(e) => write!(f, "invalid DTD: {}", e),
+            Self::RecursiveEntity(name) => {
+                write!(f, "entity `{}` references itself", name)
+            }
+            Self::InvalidChar(code) => {
+                write!(f, "character U+{:04X} is not allowed in XML", code)
+            }
+            Self::InvalidName(name) => write!(f, "`{}` is not a valid XML name", name),
+            Self::MultipleRoots(name) => write!(
+                f,
+                "only one root element is allowed, but `<{}>` was found after the root element",
+                name,
+            ),
+            Self::TextOutsideRoot => {
+                f.write_str("only whitespace is allowed outside the root element")
+            }
+            Self::CDataEndInCData => {
+                f.write_str("forbidden string `]]>` was found in a CDATA section")
+            }
+            Self::UnescapedLessThan => {
+                f.write_str("`<` does not start a markup and should be escaped as `&lt;`")
+            }
+            Self::ImpliedEndTag { expected, found } => write!(
+                f,
+                "start tag not closed: `</{}>` not found before `</{}>`",
+                expected, found,
+            ),
         }
     }
 }
 
-impl std::error::Error for IllFormedError {}
+impl std::error::Error for IllFormedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidDtd(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A resource limit of the reader that was exceeded. Each variant contains
+/// the configured value of the limit.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Limit {
+    /// Total size of replacement texts of expanded entities in bytes.
+    /// See [`Config::max_entity_expansion`].
+    ///
+    /// [`Config::max_entity_expansion`]: crate::reader::Config::max_entity_expansion
+    EntityExpansion(usize),
+    /// Number of attributes in one start or empty tag.
+    /// See [`Config::max_attributes`].
+    ///
+    /// [`Config::max_attributes`]: crate::reader::Config::max_attributes
+    Attributes(usize),
+    /// Nesting depth of elements. See [`Config::max_depth`].
+    ///
+    /// [`Config::max_depth`]: crate::reader::Config::max_depth
+    Depth(usize),
+    /// Total number of events read. See [`Config::max_events`].
+    ///
+    /// [`Config::max_events`]: crate::reader::Config::max_events
+    Events(usize),
+    /// Length of element or attribute name in bytes.
+    /// See [`Config::max_name_length`].
+    ///
+    /// [`Config::max_name_length`]: crate::reader::Config::max_name_length
+    NameLength(usize),
+    /// Size of a single text, CDATA, comment or markup in bytes.
+    /// See [`Config::max_text_size`].
+    ///
+    /// [`Config::max_text_size`]: crate::reader::Config::max_text_size
+    TextSize(usize),
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::EntityExpansion(limit) => {
+                write!(f, "expanded entities exceed {} bytes", limit)
+            }
+            Self::Attributes(limit) => {
+                write!(f, "tag has more than {} attributes", limit)
+            }
+            Self::Depth(limit) => {
+                write!(f, "elements are nested deeper than {} levels", limit)
+            }
+            Self::Events(limit) => write!(f, "document has more than {} events", limit),
+            Self::NameLength(limit) => write!(f, "name is longer than {} bytes", limit),
+            Self::TextSize(limit) => write!(f, "event is larger than {} bytes", limit),
+        }
+    }
+}
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
@@ -206,9 +381,36 @@ pub enum Error {
     Escape(EscapeError),
     /// Parsed XML has some namespace-related problems
     Namespace(NamespaceError),
+    /// The document exceeds one of the resource limits set in the reader
+    /// configuration. Reading cannot be continued after this error.
+    LimitExceeded(Limit),
+    /// An error, that happened at the specified position of the input.
+    ///
+    /// Errors are wrapped into this variant only when [`Config::track_position`]
+    /// is enabled.
+    ///
+    /// [`Config::track_position`]: crate::reader::Config::track_position
+    Positioned {
+        /// Position of the error in the input
+        position: Position,
+        /// The error itself. Never contains another `Positioned` error
+        error: Box<Error>,
+    },
 }
 
 impl Error {
+    /// Returns the position of the error in the input, if it is known.
+    ///
+    /// Position is known only when [`Config::track_position`] is enabled.
+    ///
+    /// [`Config::track_position`]: crate::reader::Config::track_position
+    pub const fn position(&self) -> Option<Position> {
+        match self {
+            Self::Positioned { position, .. } => Some(*position),
+            _ => None,
+        }
+    }
+
     pub(crate) fn missed_end(name: QName, decoder: Decoder) -> Self {
         match decoder.decode(name.as_ref()) {
             Ok(name) => IllFormedError::MissingEndTag(name.into()).into(),
@@ -226,6 +428,11 @@ impl From<IoError> for Error {
                 Ok(err) => Self::Encoding(err),
                 Err(err) => Self::Io(Arc::new(err)),
             },
+            // Errors of the `Writer` with enabled checks of well-formedness
+            IoErrorKind::InvalidInput => match error.downcast::<IllFormedError>() {
+                Ok(err) => Self::IllFormed(err),
+                Err(err) => Self::Io(Arc::new(err)),
+            },
             _ => Self::Io(Arc::new(error)),
         }
     }
@@ -290,6 +497,8 @@ impl fmt::Display for Error {
             Self::Encoding(e) => e.fmt(f),
             Self::Escape(e) => e.fmt(f),
             Self::Namespace(e) => e.fmt(f),
+            Self::LimitExceeded(e) => write!(f, "limit exceeded: {}", e),
+            Self::Positioned { position, error } => write!(f, "{}: {}", position, error),
         }
     }
 }
@@ -304,6 +513,8 @@ impl std::error::Error for Error {
             Self::Encoding(e) => Some(e),
             Self::Escape(e) => Some(e),
             Self::Namespace(e) => Some(e),
+            Self::LimitExceeded(_) => None,
+            Self::Positioned { error, .. } => Some(error),
         }
     }
 }
@@ -349,6 +560,36 @@ pub mod serialize {
         /// exceeded. The limit was provided as an argument
         #[cfg(feature = "overlapped-lists")]
         TooManyEvents(NonZeroUsize),
+        /// An error, that happened when the underlying reader was at the specified
+        /// position of the input. Because deserializer reads ahead, the position
+        /// may point after the XML content that caused the error.
+        ///
+        /// Errors are wrapped into this variant only when [`Config::track_position`]
+        /// is enabled in the reader used by the deserializer. XML errors are not
+        /// wrapped, because they carry the position in the [`Error::Positioned`].
+        ///
+        /// [`Config::track_position`]: crate::reader::Config::track_position
+        Positioned {
+            /// Position of the reader when the error occurred
+            position: Position,
+            /// The error itself. Never contains another `Positioned` error
+            error: Box<DeError>,
+        },
+    }
+
+    impl DeError {
+        /// Returns the position of the error in the input, if it is known.
+        ///
+        /// Position is known only when [`Config::track_position`] is enabled.
+        ///
+        /// [`Config::track_position`]: crate::reader::Config::track_position
+        pub const fn position(&self) -> Option<Position> {
+            match self {
+                Self::InvalidXml(e) => e.position(),
+                Self::Positioned { position, .. } => Some(*position),
+                _ => None,
+            }
+        }
     }
 
     impl fmt::Display for DeError {
@@ -365,6 +606,7 @@ pub mod serialize {
                 Self::UnexpectedEof => f.write_str("unexpected `Event::Eof`"),
                 #[cfg(feature = "overlapped-lists")]
                 Self::TooManyEvents(s) => write!(f, "deserializer buffered {} events, limit exceeded", s),
+                Self::Positioned { position, error } => write!(f, "{}: {}", position, error),
             }
         }
     }
@@ -373,6 +615,7 @@ pub mod serialize {
         fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
             match self {
                 Self::InvalidXml(e) => Some(e),
+                Self::Positioned { error, .. } => Some(error),
                 _ => None,
             }
         }
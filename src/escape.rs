@@ -2346,6 +2346,25 @@ pub const fn resolve_html5_entity(entity: &str) -> Option<&'static str> {
     Some(s)
 }
 
+/// Replaces character references in the entity value literal. General entity
+/// references are left as is.
+pub(crate) fn replace_char_refs(literal: &str) -> Result<String, EscapeError> {
+    let mut result = String::with_capacity(literal.len());
+    let mut rest = literal;
+    while let Some(start) = rest.find("&#") {
+        result.push_str(&rest[..start]);
+        let offset = literal.len() - rest.len() + start;
+        let len = rest[start..]
+            .find(';')
+            .ok_or(EscapeError::UnterminatedEntity(offset..literal.len()))?;
+        let num = &rest[start + 2..start + len];
+        result.push(parse_number(num).map_err(EscapeError::InvalidCharRef)?);
+        rest = &rest[start + len + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
 pub(crate) fn parse_number(num: &str) -> Result<char, ParseCharRefError> {
     let code = if let Some(hex) = num.strip_prefix('x') {
         from_str_radix(hex, 16)?
@@ -97,6 +97,10 @@ pub struct BytesStart<'a> {
     pub(crate) name_len: usize,
     /// Encoding used for `buf`
     decoder: Decoder,
+    /// Start of the attributes in `buf` which were not specified in the tag,
+    /// but were added by the reader from the default values declared in the DTD.
+    /// Such attributes are always at the end of `buf`
+    defaulted: Option<usize>,
 }
 
 impl<'a> BytesStart<'a> {
@@ -107,6 +111,7 @@ impl<'a> BytesStart<'a> {
             buf: Cow::Borrowed(content),
             name_len,
             decoder,
+            defaulted: None,
         }
     }
 
@@ -122,6 +127,7 @@ impl<'a> BytesStart<'a> {
             name_len: buf.len(),
             buf,
             decoder: Decoder::utf8(),
+            defaulted: None,
         }
     }
 
@@ -138,6 +144,7 @@ impl<'a> BytesStart<'a> {
             buf: str_cow_to_bytes(content),
             name_len,
             decoder: Decoder::utf8(),
+            defaulted: None,
         }
     }
 
@@ -147,6 +154,7 @@ impl<'a> BytesStart<'a> {
             buf: Cow::Owned(self.buf.into_owned()),
             name_len: self.name_len,
             decoder: self.decoder,
+            defaulted: self.defaulted,
         }
     }
 
@@ -156,6 +164,7 @@ impl<'a> BytesStart<'a> {
             buf: Cow::Owned(self.buf.clone().into_owned()),
             name_len: self.name_len,
             decoder: self.decoder,
+            defaulted: self.defaulted,
         }
     }
 
@@ -189,6 +198,7 @@ impl<'a> BytesStart<'a> {
             buf: Cow::Borrowed(&self.buf),
             name_len: self.name_len,
             decoder: self.decoder,
+            defaulted: self.defaulted,
         }
     }
 
@@ -235,6 +245,9 @@ impl<'a> BytesStart<'a> {
     pub fn set_name(&mut self, name: &[u8]) -> &mut BytesStart<'a> {
         let bytes = self.buf.to_mut();
         bytes.splice(..self.name_len, name.iter().cloned());
+        if let Some(defaulted) = self.defaulted.as_mut() {
+            *defaulted = *defaulted + name.len() - self.name_len;
+        }
         self.name_len = name.len();
         self
     }
@@ -269,25 +282,97 @@ impl<'a> BytesStart<'a> {
     }
 
     /// Adds an attribute to this element.
+    ///
+    /// If the element has [defaulted attributes], the attribute is inserted
+    /// before them.
+    ///
+    /// [defaulted attributes]: Self::defaulted_attributes
     pub fn push_attribute<'b, A>(&mut self, attr: A)
     where
         A: Into<Attribute<'b>>,
     {
-        self.buf.to_mut().push(b' ');
-        self.push_attr(attr.into());
+        match self.defaulted {
+            Some(defaulted) => {
+                let defaults = self.buf.to_mut().split_off(defaulted);
+                self.buf.to_mut().push(b' ');
+                self.push_attr(attr.into());
+                self.defaulted = Some(self.buf.len());
+                self.buf.to_mut().extend(defaults);
+            }
+            None => {
+                self.buf.to_mut().push(b' ');
+                self.push_attr(attr.into());
+            }
+        }
     }
 
     /// Remove all attributes from the ByteStart
     pub fn clear_attributes(&mut self) -> &mut BytesStart<'a> {
         self.buf.to_mut().truncate(self.name_len);
+        self.defaulted = None;
         self
     }
 
     /// Returns an iterator over the attributes of this tag.
+    ///
+    /// If the [`Config::default_attributes`] option of the reader is enabled,
+    /// the iterator also returns attributes that were not specified in the tag,
+    /// but have default values declared in the DTD. They are returned after the
+    /// specified attributes. Use [`specified_attributes()`] and [`defaulted_attributes()`]
+    /// to distinguish them.
+    ///
+    /// [`Config::default_attributes`]: crate::reader::Config::default_attributes
+    /// [`specified_attributes()`]: Self::specified_attributes
+    /// [`defaulted_attributes()`]: Self::defaulted_attributes
     pub fn attributes(&self) -> Attributes<'_> {
         Attributes::wrap(&self.buf, self.name_len, false, self.decoder)
     }
 
+    /// Returns an iterator over the attributes specified in the tag, excluding
+    /// attributes added by the reader from default values declared in the DTD.
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::Event;
+    /// use quick_xml::reader::Reader;
+    ///
+    /// let mut reader = Reader::from_str(r#"<!DOCTYPE a [
+    ///     <!ATTLIST a specified CDATA "default" defaulted CDATA "default">
+    /// ]><a specified="value"/>"#);
+    /// reader.config_mut().default_attributes = true;
+    ///
+    /// assert!(matches!(reader.read_event().unwrap(), Event::DocType(_)));
+    /// match reader.read_event().unwrap() {
+    ///     Event::Empty(e) => {
+    ///         let names = |attrs: quick_xml::events::attributes::Attributes| {
+    ///             attrs.map(|a| a.unwrap().key.as_ref().to_vec()).collect::<Vec<_>>()
+    ///         };
+    ///         assert_eq!(names(e.attributes()), [b"specified".to_vec(), b"defaulted".to_vec()]);
+    ///         assert_eq!(names(e.specified_attributes()), [b"specified".to_vec()]);
+    ///         assert_eq!(names(e.defaulted_attributes()), [b"defaulted".to_vec()]);
+    ///     }
+    ///     e => panic!("Expected `Empty`, but got `{:?}`", e),
+    /// }
+    /// ```
+    pub fn specified_attributes(&self) -> Attributes<'_> {
+        let end = self.defaulted.unwrap_or(self.buf.len());
+        Attributes::wrap(&self.buf[..end], self.name_len, false, self.decoder)
+    }
+
+    /// Returns an iterator over the attributes that were not specified in the tag,
+    /// but were added by the reader from default values declared in the DTD.
+    /// Such attributes are added only when the [`Config::default_attributes`]
+    /// option is enabled.
+    ///
+    /// See [`specified_attributes()`] for an example.
+    ///
+    /// [`Config::default_attributes`]: crate::reader::Config::default_attributes
+    /// [`specified_attributes()`]: Self::specified_attributes
+    pub fn defaulted_attributes(&self) -> Attributes<'_> {
+        let start = self.defaulted.unwrap_or(self.buf.len());
+        Attributes::wrap(&self.buf, start, false, self.decoder)
+    }
+
     /// Returns an iterator over the HTML-like attributes of this tag (no mandatory quotes or `=`).
     pub fn html_attributes(&self) -> Attributes<'_> {
         Attributes::wrap(&self.buf, self.name_len, true, self.decoder)
@@ -324,6 +409,23 @@ impl<'a> BytesStart<'a> {
         bytes.push(b'"');
     }
 
+    /// Adds an attribute with the default value declared in the DTD, which was
+    /// not specified in the tag. `value` is written as is, in quotes that it
+    /// does not contain.
+    pub(crate) fn push_defaulted_attr(&mut self, key: &[u8], value: &[u8]) {
+        let quote = if value.contains(&b'"') { b'\'' } else { b'"' };
+        let bytes = self.buf.to_mut();
+        if self.defaulted.is_none() {
+            self.defaulted = Some(bytes.len());
+        }
+        bytes.push(b' ');
+        bytes.extend_from_slice(key);
+        bytes.push(b'=');
+        bytes.push(quote);
+        bytes.extend_from_slice(value);
+        bytes.push(quote);
+    }
+
     /// Adds new line in existing element
     pub(crate) fn push_newline(&mut self) {
         self.buf.to_mut().push(b'\n');
@@ -339,7 +441,11 @@ impl<'a> Debug for BytesStart<'a> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(f, "BytesStart {{ buf: ")?;
         write_cow_string(f, &self.buf)?;
-        write!(f, ", name_len: {} }}", self.name_len)
+        write!(f, ", name_len: {}", self.name_len)?;
+        if let Some(defaulted) = self.defaulted {
+            write!(f, ", defaulted: {}", defaulted)?;
+        }
+        write!(f, " }}")
     }
 }
 
@@ -1099,6 +1205,7 @@ impl<'a> BytesPI<'a> {
                 buf,
                 name_len,
                 decoder: Decoder::utf8(),
+                defaulted: None,
             },
         }
     }
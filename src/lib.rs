@@ -16,8 +16,9 @@
 //! Especially for nested XML elements, the user must keep track _where_ (how deep)
 //! in the XML document the current event is located.
 //!
-//! quick-xml contains optional support of asynchronous reading and writing using [tokio].
-//! To get it enable the [`async-tokio`](#async-tokio) feature.
+//! quick-xml contains optional support of asynchronous reading and writing using [tokio]
+//! or [futures-io] (used by `async-std` and `smol`). To get it enable the
+//! [`async-tokio`](#async-tokio) or [`async-futures`](#async-futures) feature.
 //!
 //! Furthermore, quick-xml also contains optional [Serde] support to directly
 //! serialize and deserialize from structs, without having to deal with the XML events.
@@ -36,6 +37,7 @@
 //!
 //! [StAX]: https://en.wikipedia.org/wiki/StAX
 //! [tokio]: https://tokio.rs/
+//! [futures-io]: https://docs.rs/futures-io
 //! [Serde]: https://serde.rs/
 //! [`de`]: ./de/index.html
 #![cfg_attr(
@@ -54,8 +56,13 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![warn(clippy::missing_const_for_fn)]
 
+#[cfg(any(feature = "async-tokio", feature = "async-futures"))]
+pub mod async_io;
+pub mod c14n;
 #[cfg(feature = "serialize")]
 pub mod de;
+pub mod dom;
+pub mod dtd;
 pub mod encoding;
 pub mod errors;
 pub mod escape;
@@ -70,7 +77,11 @@ pub mod serde_helpers;
 /// Not an official API, public for integration tests
 #[doc(hidden)]
 pub mod utils;
+pub mod validate;
 pub mod writer;
+pub mod xpath;
+#[cfg(feature = "xsd")]
+pub mod xsd;
 
 use std::borrow::Cow;
 
@@ -80,7 +91,7 @@ pub use crate::encoding::Decoder;
 pub use crate::errors::serialize::{DeError, SeError};
 pub use crate::errors::{Error, Result};
 pub use crate::reader::{NsReader, Reader};
-pub use crate::writer::{ElementWriter, Writer};
+pub use crate::writer::{ElementWriter, NsWriter, Writer};
 
 /// Version of XML standard
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -121,6 +132,42 @@ impl XmlVersion {
             _ => escape::normalize_xml10_attribute_value(value, depth, resolve_entity),
         }
     }
+
+    /// Returns `true` if `ch` is allowed to appear literally in the document
+    /// of this version.
+    ///
+    /// Unlike XML 1.0, XML 1.1 allows the C0 and C1 control characters (so
+    /// called [`RestrictedChar`]s) only as character references. See [`Char`]
+    /// (XML 1.0) and [`Char`][Char11] (XML 1.1).
+    ///
+    /// [`RestrictedChar`]: https://www.w3.org/TR/xml11/#NT-RestrictedChar
+    /// [`Char`]: https://www.w3.org/TR/xml/#NT-Char
+    /// [Char11]: https://www.w3.org/TR/xml11/#NT-Char
+    pub(crate) const fn is_char(&self, ch: char) -> bool {
+        match self {
+            Self::Explicit1_1 => matches!(
+                ch,
+                '\t' | '\n' | '\r' | '\u{20}'..='\u{7E}' | '\u{85}' | '\u{A0}'..='\u{D7FF}'
+                    | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..
+            ),
+            _ => matches!(
+                ch,
+                '\t' | '\n' | '\r' | '\u{20}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..
+            ),
+        }
+    }
+
+    /// Returns `true` if `ch` is allowed to be referenced by a character
+    /// reference in the document of this version.
+    pub(crate) const fn is_ref_char(&self, ch: char) -> bool {
+        match self {
+            Self::Explicit1_1 => matches!(
+                ch,
+                '\u{1}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..
+            ),
+            _ => self.is_char(ch),
+        }
+    }
 }
 
 impl Default for XmlVersion {
@@ -40,6 +40,19 @@ pub enum NamespaceError {
     ///
     /// Contains the prefix that is tried to be bound.
     InvalidPrefixForXmlns(Vec<u8>),
+    /// Two attributes of the same element have the same [expanded name], i.e.
+    /// the same local name and prefixes bound to the same namespace. For example,
+    /// `<x xmlns:a="ns" xmlns:b="ns" a:attr="1" b:attr="2"/>`.
+    ///
+    /// Contains the qualified names of both attributes.
+    ///
+    /// [expanded name]: https://www.w3.org/TR/xml-names11/#dt-expname
+    DuplicatedAttribute {
+        /// The qualified name of the first attribute
+        first: Vec<u8>,
+        /// The qualified name of the second attribute
+        second: Vec<u8>,
+    },
 }
 
 impl fmt::Display for NamespaceError {
@@ -70,6 +83,13 @@ impl fmt::Display for NamespaceError {
                 write_byte_string(f, prefix)?;
                 f.write_str("' cannot be bound to 'http://www.w3.org/2000/xmlns/'")
             }
+            Self::DuplicatedAttribute { first, second } => {
+                f.write_str("attributes '")?;
+                write_byte_string(f, first)?;
+                f.write_str("' and '")?;
+                write_byte_string(f, second)?;
+                f.write_str("' have the same namespace and local name")
+            }
         }
     }
 }
@@ -396,6 +416,18 @@ impl<'ns> Debug for ResolveResult<'ns> {
     }
 }
 
+impl<'ns> ResolveResult<'ns> {
+    /// Converts this result to an [`OwnedResolveResult`] which does not borrow
+    /// the namespace from the resolver
+    pub fn into_owned(self) -> OwnedResolveResult {
+        match self {
+            Self::Unbound => OwnedResolveResult::Unbound,
+            Self::Bound(ns) => OwnedResolveResult::Bound(ns.0.to_vec()),
+            Self::Unknown(p) => OwnedResolveResult::Unknown(p),
+        }
+    }
+}
+
 impl<'ns> TryFrom<ResolveResult<'ns>> for Option<Namespace<'ns>> {
     type Error = NamespaceError;
 
@@ -414,6 +446,46 @@ impl<'ns> TryFrom<ResolveResult<'ns>> for Option<Namespace<'ns>> {
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
+/// Owned version of [`ResolveResult`], created by [`ResolveResult::into_owned`].
+/// Returned by the [`NsEventStream`], which cannot borrow namespaces from the reader.
+///
+/// [`NsEventStream`]: crate::reader::NsEventStream
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum OwnedResolveResult {
+    /// Qualified name does not contain prefix, and resolver does not define
+    /// default namespace, so name is not bound to any namespace
+    Unbound,
+    /// [`Prefix`] resolved to the specified namespace
+    Bound(Vec<u8>),
+    /// Specified prefix was not found in scope
+    Unknown(Vec<u8>),
+}
+impl Debug for OwnedResolveResult {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Unbound => write!(f, "Unbound"),
+            Self::Bound(ns) => {
+                write!(f, "Bound(")?;
+                write_byte_string(f, ns)?;
+                write!(f, ")")
+            }
+            Self::Unknown(p) => {
+                write!(f, "Unknown(")?;
+                write_byte_string(f, p)?;
+                write!(f, ")")
+            }
+        }
+    }
+}
+impl<'ns> From<ResolveResult<'ns>> for OwnedResolveResult {
+    #[inline]
+    fn from(result: ResolveResult<'ns>) -> Self {
+        result.into_owned()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
 /// An entry that contains index into the buffer with namespace bindings.
 ///
 /// Defines a mapping from *[namespace prefix]* to *[namespace name]*.
@@ -687,6 +759,59 @@ impl NamespaceResolver {
         Ok(())
     }
 
+    /// Checks that attributes of the element have unique [expanded names], i.e.
+    /// there are no two attributes with the same local name and prefixes bound
+    /// to the same namespace. Bindings declared in the element itself should be
+    /// already [pushed].
+    ///
+    /// Only prefixed attributes with known prefixes are checked. Attributes
+    /// without prefix are not in any namespace, their uniqueness is checked by
+    /// the [`Attributes`] iterator.
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::events::BytesStart;
+    /// # use quick_xml::name::{NamespaceError, NamespaceResolver};
+    /// #
+    /// let mut resolver = NamespaceResolver::default();
+    /// let start = BytesStart::from_content(
+    ///     r#"x xmlns:a="ns" xmlns:b="ns" a:attr="1" b:attr="2""#,
+    ///     1,
+    /// );
+    /// resolver.push(&start).unwrap();
+    ///
+    /// assert_eq!(
+    ///     resolver.check_duplicates(&start),
+    ///     Err(NamespaceError::DuplicatedAttribute {
+    ///         first: b"a:attr".to_vec(),
+    ///         second: b"b:attr".to_vec(),
+    ///     }),
+    /// );
+    /// ```
+    ///
+    /// [expanded names]: https://www.w3.org/TR/xml-names11/#dt-expname
+    /// [pushed]: Self::push
+    /// [`Attributes`]: crate::events::attributes::Attributes
+    pub fn check_duplicates(&self, start: &BytesStart) -> Result<(), NamespaceError> {
+        let mut seen: Vec<(Namespace, LocalName, QName)> = Vec::new();
+        for a in start.attributes().with_checks(false) {
+            let key = match a {
+                Ok(Attribute { key, .. }) => key,
+                Err(_) => break,
+            };
+            if let (ResolveResult::Bound(ns), local) = self.resolve_attribute(key) {
+                if let Some((_, _, first)) = seen.iter().find(|(n, l, _)| *n == ns && *l == local) {
+                    return Err(NamespaceError::DuplicatedAttribute {
+                        first: first.as_ref().to_vec(),
+                        second: key.as_ref().to_vec(),
+                    });
+                }
+                seen.push((ns, local, key));
+            }
+        }
+        Ok(())
+    }
+
     /// Ends a top-most scope by popping all [namespace bindings], that was added by
     /// last call to [`Self::push()`] and [`Self::add()`].
     ///
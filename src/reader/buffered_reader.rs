@@ -6,11 +6,13 @@ use std::io::{self, BufRead, BufReader};
 use std::path::Path;
 
 use crate::encoding;
-use crate::errors::{Error, Result};
+use crate::errors::{Error, Limit, Result};
 use crate::events::{BytesText, Event};
 use crate::name::QName;
 use crate::parser::Parser;
-use crate::reader::{BangType, ReadRefResult, ReadTextResult, Reader, Span, XmlSource};
+use crate::reader::{
+    BangType, Position, RawTextParser, ReadRefResult, ReadTextResult, Reader, Span, XmlSource,
+};
 use crate::utils::is_whitespace;
 
 macro_rules! impl_buffered_source {
@@ -54,6 +56,7 @@ macro_rules! impl_buffered_source {
             &mut self,
             buf: &'b mut Vec<u8>,
             position: &mut u64,
+            limit: usize,
         ) -> ReadTextResult<'b, &'b mut Vec<u8>> {
             let mut read = 0;
             let start = buf.len();
@@ -64,12 +67,18 @@ macro_rules! impl_buffered_source {
                     Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
                     Err(e) => {
                         *position += read;
-                        return ReadTextResult::Err(e);
+                        return ReadTextResult::Err(Error::from(e));
                     }
                 };
 
                 // Search for start of markup or an entity or character reference
-                match memchr::memchr2(b'<', b'&', available) {
+                let found = memchr::memchr2(b'<', b'&', available);
+                // Do not grow the buffer beyond the limit
+                if read as usize + found.unwrap_or(available.len()) > limit {
+                    *position += read;
+                    return ReadTextResult::Err(Error::LimitExceeded(Limit::TextSize(limit)));
+                }
+                match found {
                     // Special handling is needed only on the first iteration.
                     // On next iterations we already read something and should emit Text event
                     Some(0) if read == 0 && available[0] == b'<' => return ReadTextResult::Markup(buf),
@@ -113,6 +122,7 @@ macro_rules! impl_buffered_source {
             &mut self,
             buf: &'b mut Vec<u8>,
             position: &mut u64,
+            limit: usize,
         ) -> ReadRefResult<'b> {
             let mut read = 0;
             let start = buf.len();
@@ -123,7 +133,7 @@ macro_rules! impl_buffered_source {
                     Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
                     Err(e) => {
                         *position += read;
-                        return ReadRefResult::Err(e);
+                        return ReadRefResult::Err(Error::from(e));
                     }
                 };
                 // `read_ref` called when the first character is `&`, so we
@@ -143,7 +153,14 @@ macro_rules! impl_buffered_source {
                     continue;
                 }
 
-                match memchr::memchr3(b';', b'&', b'<', available) {
+                let found = memchr::memchr3(b';', b'&', b'<', available);
+                // Do not grow the buffer beyond the limit. The end `;` is not
+                // counted here, because it is not a part of the reference name
+                if read as usize + found.unwrap_or(available.len()) > limit {
+                    *position += read;
+                    return ReadRefResult::Err(Error::LimitExceeded(Limit::TextSize(limit)));
+                }
+                match found {
                     Some(i) if available[i] == b';' => {
                         // +1 -- skip the end `;`
                         let used = i + 1;
@@ -193,6 +210,7 @@ macro_rules! impl_buffered_source {
             mut parser: P,
             buf: &'b mut Vec<u8>,
             position: &mut u64,
+            limit: usize,
         ) -> Result<&'b [u8]> {
             let mut read = 1;
             let start = buf.len();
@@ -209,7 +227,13 @@ macro_rules! impl_buffered_source {
                     }
                 };
 
-                if let Some(i) = parser.feed(available) {
+                let found = parser.feed(available);
+                // Do not grow the buffer beyond the limit
+                if read as usize + found.map_or(available.len(), |i| i + 1) > limit {
+                    *position += read;
+                    return Err(Error::LimitExceeded(Limit::TextSize(limit)));
+                }
+                if let Some(i) = found {
                     let used = i + 1; // +1 for `>`
                     buf.extend_from_slice(&available[..used]);
 
@@ -237,6 +261,7 @@ macro_rules! impl_buffered_source {
             &mut self,
             buf: &'b mut Vec<u8>,
             position: &mut u64,
+            limit: usize,
         ) -> Result<(BangType, &'b [u8])> {
             // Peeked '<!' before being called, so it's guaranteed to start with it.
             let start = buf.len();
@@ -266,7 +291,13 @@ macro_rules! impl_buffered_source {
                 };
                 // We only parse from start because we don't want to consider
                 // whatever is in the buffer before the bang element
-                if let Some(i) = bang_type.feed(&buf[start..], available) {
+                let found = bang_type.feed(&buf[start..], available);
+                // Do not grow the buffer beyond the limit
+                if read as usize + found.map_or(available.len(), |i| i + 1) > limit {
+                    *position += read;
+                    return Err(Error::LimitExceeded(Limit::TextSize(limit)));
+                }
+                if let Some(i) = found {
                     let consumed = i + 1; // +1 for `>`
                     buf.extend_from_slice(&available[..consumed]);
 
@@ -290,12 +321,13 @@ macro_rules! impl_buffered_source {
         }
 
         #[inline]
-        $($async)? fn skip_whitespace(&mut self, position: &mut u64) -> io::Result<()> {
+        $($async)? fn skip_whitespace(&mut self, position: &mut u64, tracked: &mut Position) -> io::Result<()> {
             loop {
                 break match self $(.$reader)? .fill_buf() $(.$await)? {
                     Ok(n) => {
                         let count = n.iter().position(|b| !is_whitespace(*b)).unwrap_or(n.len());
                         if count > 0 {
+                            tracked.advance(&n[..count]);
                             self $(.$reader)? .consume(count);
                             *position += count as u64;
                             continue;
@@ -323,6 +355,54 @@ macro_rules! impl_buffered_source {
             };
             Ok(available.first().cloned())
         }
+
+        #[inline]
+        fn skip_lt(&mut self, position: &mut u64) {
+            // `<` was already consumed in peek_one()
+            *position += 1;
+        }
+
+        $($async)? fn read_raw_text $(<$lf>)? (
+            &mut self,
+            parser: &mut RawTextParser,
+            buf: &'b mut Vec<u8>,
+            position: &mut u64,
+            limit: usize,
+        ) -> Result<(&'b [u8], bool)> {
+            let mut read = 0;
+            let start = buf.len();
+            loop {
+                let available = match self $(.$reader)? .fill_buf() $(.$await)? {
+                    Ok(n) if n.is_empty() => break,
+                    Ok(n) => n,
+                    Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => {
+                        *position += read;
+                        return Err(Error::from(e));
+                    }
+                };
+
+                let found = parser.feed(available);
+                // +1 for `>`
+                let used = found.map_or(available.len(), |i| i + 1);
+                // Do not grow the buffer beyond the limit
+                if read as usize + used > limit {
+                    *position += read;
+                    return Err(Error::LimitExceeded(Limit::TextSize(limit)));
+                }
+                buf.extend_from_slice(&available[..used]);
+                self $(.$reader)? .consume(used);
+                read += used as u64;
+
+                if found.is_some() {
+                    *position += read;
+                    return Ok((&buf[start..], true));
+                }
+            }
+
+            *position += read;
+            Ok((&buf[start..], false))
+        }
     };
 }
 
@@ -330,13 +410,48 @@ macro_rules! impl_buffered_source {
 // New rustc reports
 // > warning: the item `impl_buffered_source` is imported redundantly
 // so make it public only when async feature is enabled
-#[cfg(feature = "async-tokio")]
+#[cfg(any(feature = "async-tokio", feature = "async-futures"))]
 pub(super) use impl_buffered_source;
 
 /// Implementation of `XmlSource` for any `BufRead` reader using a user-given
 /// `Vec<u8>` as buffer that will be borrowed by events.
 impl<'b, R: BufRead> XmlSource<'b, &'b mut Vec<u8>> for R {
     impl_buffered_source!();
+
+    #[cfg(feature = "encoding")]
+    fn peek_encoding(
+        &mut self,
+        consumed: &mut Vec<u8>,
+    ) -> io::Result<Option<encoding::DetectedEncoding>> {
+        loop {
+            let available = match self.fill_buf() {
+                Ok(n) => n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if consumed.is_empty() && (available.len() >= 4 || available.is_empty()) {
+                return Ok(encoding::detect_encoding(available));
+            }
+            if available.is_empty() {
+                break;
+            }
+            // The underlying reader does not return more data until the available
+            // one is consumed, so collect the first bytes of input
+            let used = available.len().min(4 - consumed.len());
+            consumed.extend_from_slice(&available[..used]);
+            self.consume(used);
+            if consumed.len() == 4 {
+                break;
+            }
+        }
+        Ok(encoding::detect_encoding(consumed))
+    }
+
+    #[cfg(feature = "encoding")]
+    #[inline]
+    fn read_transcoded(reader: &mut Reader<Self>, buf: &'b mut Vec<u8>) -> Result<Event<'b>> {
+        reader.read_transcoded_event(buf)
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
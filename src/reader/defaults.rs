@@ -0,0 +1,81 @@
+//! Contains default values of attributes declared in the DTD, which are used by
+//! [`Reader`] when [`Config::default_attributes`] is enabled.
+//!
+//! [`Reader`]: crate::reader::Reader
+//! [`Config::default_attributes`]: crate::reader::Config::default_attributes
+
+use std::collections::HashMap;
+
+use crate::dtd::{DtdEvent, DtdReader};
+use crate::errors::{IllFormedError, Result};
+use crate::events::{BytesStart, BytesText};
+
+/// Holds default values of attributes declared in the DTD.
+#[derive(Clone, Debug, Default)]
+pub(super) struct Defaults {
+    /// Maps the name of an element to the names of attributes with default
+    /// values in order of their declaration. Values are stored as written in
+    /// the DTD, i.e. they can contain character and entity references
+    declared: HashMap<String, Vec<(String, String)>>,
+}
+
+impl Defaults {
+    /// Captures default values of attributes declared in the DTD.
+    ///
+    /// # Parameters
+    /// - `doctype`: content of the [`Event::DocType`] event
+    ///
+    /// [`Event::DocType`]: crate::events::Event::DocType
+    pub fn capture(&mut self, doctype: &BytesText) -> Result<()> {
+        let content = doctype.decode()?;
+        for event in DtdReader::new(&content) {
+            let event = event.map_err(IllFormedError::InvalidDtd)?;
+            if let DtdEvent::AttlistDecl(decl) = event {
+                let attributes = self.declared.entry(decl.element.to_string()).or_default();
+                for def in decl.attributes {
+                    // The first declaration is binding, and attributes without
+                    // default value cannot be redeclared with it
+                    if let Some(value) = def.default.value() {
+                        if !attributes.iter().any(|(name, _)| name == def.name) {
+                            attributes.push((def.name.to_string(), value.to_string()));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds attributes that have default values, but are not specified in
+    /// the tag, to the end of the tag.
+    pub fn apply(&self, start: &mut BytesStart) -> Result<()> {
+        let declared = {
+            let name = start.name();
+            // Names of elements and attributes are case-sensitive
+            let name = start.decoder().decode(name.as_ref())?;
+            match self.declared.get(name.as_ref()) {
+                Some(declared) => declared,
+                None => return Ok(()),
+            }
+        };
+        for (name, value) in declared {
+            let specified = start
+                .attributes()
+                .with_checks(false)
+                .flatten()
+                .any(|a| a.key.as_ref() == name.as_bytes());
+            if !specified {
+                #[cfg(feature = "encoding")]
+                {
+                    let encoding = start.decoder().encoding();
+                    let name = encoding.encode(name).0.into_owned();
+                    let value = encoding.encode(value).0.into_owned();
+                    start.push_defaulted_attr(&name, &value);
+                }
+                #[cfg(not(feature = "encoding"))]
+                start.push_defaulted_attr(name.as_bytes(), value.as_bytes());
+            }
+        }
+        Ok(())
+    }
+}
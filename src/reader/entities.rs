@@ -0,0 +1,149 @@
+//! Contains a state of expansion of internal entities, which is used by
+//! [`Reader`] when [`Config::expand_entities`] is enabled.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::Arc;
+
+use crate::dtd::{DtdEvent, DtdReader, EntityDef};
+use crate::errors::{Error, IllFormedError, Limit, Result};
+use crate::escape::replace_char_refs;
+use crate::events::{BytesRef, BytesText, Event};
+use crate::name::QName;
+use crate::reader::{Config, Reader};
+
+/// A reader of the replacement text of an entity.
+type EntityReader = Reader<Cursor<Arc<[u8]>>>;
+
+/// Holds replacement texts of declared entities and readers of entities which
+/// are expanded at the moment.
+#[derive(Clone, Debug, Default)]
+pub(super) struct Entities {
+    /// Replacement texts of internal general entities declared in the DTD.
+    /// Character references in them are already replaced
+    declared: HashMap<String, Arc<[u8]>>,
+    /// Readers of the entities which are currently expanded. The last reader
+    /// reads the innermost entity
+    stack: Vec<(String, EntityReader)>,
+    /// Total size of replacement texts of all expanded entities
+    expanded: usize,
+    /// Buffer for events of entity readers
+    buf: Vec<u8>,
+}
+
+impl Entities {
+    /// Stops expansion of all entities.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.stack.clear();
+    }
+
+    /// Captures internal general entities declared in the DTD.
+    ///
+    /// # Parameters
+    /// - `doctype`: content of the [`Event::DocType`] event
+    pub fn capture(&mut self, doctype: &BytesText) -> Result<()> {
+        let content = doctype.decode()?;
+        for event in DtdReader::new(&content) {
+            let event = event.map_err(IllFormedError::InvalidDtd)?;
+            if let DtdEvent::EntityDecl(decl) = event {
+                if let (false, EntityDef::Internal(value)) = (decl.parameter, decl.def) {
+                    // The first declaration is binding
+                    if !self.declared.contains_key(decl.name) {
+                        let value = replace_char_refs(value)?;
+                        self.declared
+                            .insert(decl.name.to_string(), value.into_bytes().into());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Starts expansion of the entity, if it is declared. Returns `false` if
+    /// entity is unknown.
+    ///
+    /// # Parameters
+    /// - `reference`: the reference to the entity
+    /// - `config`: configuration of the reader which document is read
+    /// - `depth`: number of elements opened at the place of reference
+    pub fn push(&mut self, reference: &BytesRef, config: &Config, depth: usize) -> Result<bool> {
+        let name = reference.decode()?;
+        let text = match self.declared.get(name.as_ref()) {
+            Some(text) => text,
+            None => return Ok(false),
+        };
+        if self.stack.iter().any(|(n, _)| *n == name) {
+            return Err(IllFormedError::RecursiveEntity(name.into_owned()).into());
+        }
+        self.expanded += text.len();
+        if self.expanded > config.max_entity_expansion {
+            return Err(Error::LimitExceeded(Limit::EntityExpansion(
+                config.max_entity_expansion,
+            )));
+        }
+
+        let mut reader = Reader::from_reader(Cursor::new(text.clone()));
+        // Replacement text is always UTF-8
+        #[cfg(feature = "encoding")]
+        {
+            reader.state.encoding = crate::reader::EncodingRef::Explicit(encoding_rs::UTF_8);
+        }
+        let inner = reader.config_mut();
+        *inner = config.clone();
+        // Nested references are expanded by us
+        inner.expand_entities = false;
+        // Errors are reported at the position of the reference
+        inner.track_position = false;
+        // Replacement text cannot close elements opened outside it
+        inner.allow_unmatched_ends = false;
+        // Elements opened outside the replacement text are not known to the
+        // nested reader, so it gets the remaining depth. Events are counted
+        // by the outer reader
+        inner.max_depth = config.max_depth.saturating_sub(depth);
+        inner.max_events = usize::MAX;
+
+        self.stack.push((name.into_owned(), reader));
+        Ok(true)
+    }
+
+    /// Reads the next event from the replacement text of the innermost expanded
+    /// entity. Returns `None` when all entities are fully read.
+    pub fn read_event(&mut self, config: &Config) -> Option<Result<Event<'static>>> {
+        loop {
+            let (_, reader) = self.stack.last_mut()?;
+            self.buf.clear();
+            let event = match reader.read_event_into(&mut self.buf) {
+                Ok(Event::Eof) => {
+                    let unclosed = reader
+                        .state
+                        .last_opened()
+                        .map(|name| Error::missed_end(QName(name), reader.decoder()));
+                    self.stack.pop();
+                    match unclosed {
+                        Some(error) => Err(error),
+                        None => continue,
+                    }
+                }
+                Ok(Event::GeneralRef(e)) => {
+                    let e = e.into_owned();
+                    // Depth of the outer document at the place of reference
+                    // plus depth inside the replacement text
+                    let depth = config.max_depth - reader.config().max_depth + reader.state.depth();
+                    match self.push(&e, config, depth) {
+                        Ok(true) => continue,
+                        Ok(false) => Ok(Event::GeneralRef(e)),
+                        Err(error) => Err(error),
+                    }
+                }
+                Ok(event) => Ok(event.into_owned()),
+                // Report the configured limit, not the remaining depth
+                Err(Error::LimitExceeded(Limit::Depth(_))) => {
+                    Err(Error::LimitExceeded(Limit::Depth(config.max_depth)))
+                }
+                Err(error) => Err(error),
+            };
+            return Some(event);
+        }
+    }
+}
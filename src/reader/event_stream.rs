@@ -0,0 +1,297 @@
+//! Contains [`Stream`] adapters over asynchronous [`Reader`] and [`NsReader`].
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use futures_core::stream::{FusedStream, Stream};
+
+use crate::async_io::AsyncSource;
+use crate::errors::Result;
+use crate::events::Event;
+use crate::name::OwnedResolveResult;
+use crate::reader::{NsReader, Reader};
+
+/// Output of the future which reads the next item `I` using the reader `T`:
+/// the reader and the buffer are returned back together with the item.
+type Read<T, I> = (T, Vec<u8>, Option<Result<I>>);
+
+/// An owned event with the namespace of its name, read by [`NsEventStream`].
+type Resolved = (OwnedResolveResult, Event<'static>);
+
+/// State shared by [`EventStream`] and [`NsEventStream`].
+///
+/// Reading of each item is performed by the future `F`, which takes the reader
+/// `T` and the buffer and returns them back together with the item. The future
+/// is stored in place, so the allocation is made only once for the whole stream.
+struct State<T, F> {
+    /// The reader and the buffer when no read is in progress
+    idle: Option<(T, Vec<u8>)>,
+    /// The future of the read in progress
+    future: Pin<Box<Option<F>>>,
+    /// Creates the future that reads the next item
+    read: fn(T, Vec<u8>) -> F,
+    /// `true` when `Eof` or an error was returned and the stream is exhausted
+    finished: bool,
+}
+
+impl<T, F> State<T, F> {
+    fn new(reader: T, read: fn(T, Vec<u8>) -> F) -> Self {
+        Self {
+            idle: Some((reader, Vec::new())),
+            future: Box::pin(None),
+            read,
+            finished: false,
+        }
+    }
+
+    fn poll_next<I>(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<I>>>
+    where
+        F: Future<Output = Read<T, I>>,
+    {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+        if let Some((reader, buf)) = self.idle.take() {
+            self.future.set(Some((self.read)(reader, buf)));
+        }
+        let future = self
+            .future
+            .as_mut()
+            .as_pin_mut()
+            .expect("stream polled after a panic");
+        let (reader, buf, item) = ready!(future.poll(cx));
+        self.future.set(None);
+        self.idle = Some((reader, buf));
+        self.finished = !matches!(item, Some(Ok(_)));
+        Poll::Ready(item)
+    }
+
+    fn into_inner(self) -> Option<T> {
+        self.idle.map(|(reader, _)| reader)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Reads the next event for the [`EventStream`].
+async fn read_event<R: AsyncSource>(
+    mut reader: Reader<R>,
+    mut buf: Vec<u8>,
+) -> Read<Reader<R>, Event<'static>> {
+    buf.clear();
+    let item = match reader.read_event_into_async(&mut buf).await {
+        Ok(Event::Eof) => None,
+        result => Some(result.map(Event::into_owned)),
+    };
+    (reader, buf, item)
+}
+
+/// A [`Stream`] of owned events read from an asynchronous [`Reader`].
+///
+/// The stream manages its own buffer and returns each event converted to
+/// `Event<'static>`. It ends when [`Event::Eof`] is read (`Eof` itself is not
+/// returned) or after the first error.
+///
+/// This struct is created by the [`Reader::into_event_stream`] method. `F` is
+/// the type of the future which reads the next event. It cannot be named, so
+/// use `impl Stream` when you need to refer to the type of the stream.
+/// The stream is [`Send`] if the reader is `Send`.
+pub struct EventStream<R, F> {
+    state: State<Reader<R>, F>,
+}
+
+impl<R, F> EventStream<R, F> {
+    /// Returns the underlying reader, or `None` if the reading of the next event
+    /// was started but not completed. Reading can be continued from the returned reader.
+    pub fn into_inner(self) -> Option<Reader<R>> {
+        self.state.into_inner()
+    }
+}
+
+impl<R: AsyncSource> Reader<R> {
+    /// Converts the reader into a [`Stream`] of owned events.
+    ///
+    /// The stream manages its own buffer, so it is a convenient replacement
+    /// of the loop with [`read_event_into_async()`] and [`Event::into_owned()`]
+    /// which composes with stream combinators. See [`EventStream`] for details.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(feature = "async-tokio", doc = "```")]
+    #[cfg_attr(not(feature = "async-tokio"), doc = "```ignore")]
+    /// # tokio_test::block_on(async {
+    /// # use pretty_assertions::assert_eq;
+    /// use std::future::poll_fn;
+    /// use std::pin::Pin;
+    /// use futures_core::Stream;
+    /// use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+    /// use quick_xml::reader::Reader;
+    ///
+    /// let reader = Reader::from_reader("<tag>text</tag>".as_bytes());
+    /// let mut stream = reader.into_event_stream();
+    ///
+    /// let mut events = Vec::new();
+    /// while let Some(event) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+    ///     events.push(event.unwrap());
+    /// }
+    /// assert_eq!(events, [
+    ///     Event::Start(BytesStart::new("tag")),
+    ///     Event::Text(BytesText::new("text")),
+    ///     Event::End(BytesEnd::new("tag")),
+    /// ]);
+    /// # }) // tokio_test::block_on
+    /// ```
+    ///
+    /// [`read_event_into_async()`]: Self::read_event_into_async
+    pub fn into_event_stream(
+        self,
+    ) -> EventStream<R, impl Future<Output = Read<Reader<R>, Event<'static>>>> {
+        EventStream {
+            state: State::new(self, read_event),
+        }
+    }
+}
+
+impl<R, F> Stream for EventStream<R, F>
+where
+    R: AsyncSource,
+    F: Future<Output = Read<Reader<R>, Event<'static>>>,
+{
+    type Item = Result<Event<'static>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().state.poll_next(cx)
+    }
+}
+
+impl<R, F> FusedStream for EventStream<R, F>
+where
+    R: AsyncSource,
+    F: Future<Output = Read<Reader<R>, Event<'static>>>,
+{
+    fn is_terminated(&self) -> bool {
+        self.state.finished
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Reads the next event with the namespace of its name for the [`NsEventStream`].
+async fn read_resolved_event<R: AsyncSource>(
+    mut reader: NsReader<R>,
+    mut buf: Vec<u8>,
+) -> Read<NsReader<R>, Resolved> {
+    buf.clear();
+    let item = match reader.read_resolved_event_into_async(&mut buf).await {
+        Ok((_, Event::Eof)) => None,
+        result => Some(result.map(|(ns, event)| (ns.into_owned(), event.into_owned()))),
+    };
+    (reader, buf, item)
+}
+
+/// A [`Stream`] of owned events together with namespaces of their names read
+/// from an asynchronous [`NsReader`].
+///
+/// Each item contains the [resolution] of the name of the element and the event
+/// converted to `Event<'static>`. Namespaces are resolved only for [`Start`],
+/// [`Empty`] and [`End`] events, other events are returned with
+/// [`OwnedResolveResult::Unbound`]. A name with an unknown prefix is returned
+/// with [`OwnedResolveResult::Unknown`] and does not stop the stream.
+///
+/// The stream ends when [`Event::Eof`] is read (`Eof` itself is not returned)
+/// or after the first error.
+///
+/// This struct is created by the [`NsReader::into_event_stream`] method. `F` is
+/// the type of the future which reads the next event. It cannot be named, so
+/// use `impl Stream` when you need to refer to the type of the stream.
+/// The stream is [`Send`] if the reader is `Send`.
+///
+/// [`Start`]: Event::Start
+/// [`Empty`]: Event::Empty
+/// [`End`]: Event::End
+/// [resolution]: OwnedResolveResult
+pub struct NsEventStream<R, F> {
+    state: State<NsReader<R>, F>,
+}
+
+impl<R, F> NsEventStream<R, F> {
+    /// Returns the underlying reader, or `None` if the reading of the next event
+    /// was started but not completed. Reading can be continued from the returned reader.
+    pub fn into_inner(self) -> Option<NsReader<R>> {
+        self.state.into_inner()
+    }
+}
+
+impl<R: AsyncSource> NsReader<R> {
+    /// Converts the reader into a [`Stream`] of owned events with namespaces
+    /// of their names.
+    ///
+    /// The stream manages its own buffer, so it is a convenient replacement
+    /// of the loop with [`read_resolved_event_into_async()`] and [`Event::into_owned()`]
+    /// which composes with stream combinators. See [`NsEventStream`] for details.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(feature = "async-tokio", doc = "```")]
+    #[cfg_attr(not(feature = "async-tokio"), doc = "```ignore")]
+    /// # tokio_test::block_on(async {
+    /// # use pretty_assertions::assert_eq;
+    /// use std::future::poll_fn;
+    /// use std::pin::Pin;
+    /// use futures_core::Stream;
+    /// use quick_xml::events::Event;
+    /// use quick_xml::name::OwnedResolveResult::*;
+    /// use quick_xml::reader::NsReader;
+    ///
+    /// let reader = NsReader::from_reader(r#"<a xmlns="urn:a"><b xmlns=""/><p:c/></a>"#.as_bytes());
+    /// let mut stream = reader.into_event_stream();
+    ///
+    /// let mut names = Vec::new();
+    /// while let Some(item) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+    ///     match item.unwrap() {
+    ///         (ns, Event::Start(e) | Event::Empty(e)) => {
+    ///             names.push((ns, e.local_name().as_ref().to_vec()));
+    ///         }
+    ///         _ => {}
+    ///     }
+    /// }
+    /// assert_eq!(names, [
+    ///     (Bound(b"urn:a".to_vec()), b"a".to_vec()),
+    ///     (Unbound, b"b".to_vec()),
+    ///     (Unknown(b"p".to_vec()), b"c".to_vec()),
+    /// ]);
+    /// # }) // tokio_test::block_on
+    /// ```
+    ///
+    /// [`read_resolved_event_into_async()`]: Self::read_resolved_event_into_async
+    pub fn into_event_stream(
+        self,
+    ) -> NsEventStream<R, impl Future<Output = Read<NsReader<R>, Resolved>>> {
+        NsEventStream {
+            state: State::new(self, read_resolved_event),
+        }
+    }
+}
+
+impl<R, F> Stream for NsEventStream<R, F>
+where
+    R: AsyncSource,
+    F: Future<Output = Read<NsReader<R>, Resolved>>,
+{
+    type Item = Result<Resolved>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().state.poll_next(cx)
+    }
+}
+
+impl<R, F> FusedStream for NsEventStream<R, F>
+where
+    R: AsyncSource,
+    F: Future<Output = Read<NsReader<R>, Resolved>>,
+{
+    fn is_terminated(&self) -> bool {
+        self.state.finished
+    }
+}
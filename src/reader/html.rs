@@ -0,0 +1,268 @@
+//! Contains knowledge about HTML elements used by the reader when
+//! [`Config::html`] is enabled.
+//!
+//! [`Config::html`]: crate::reader::Config::html
+
+use crate::errors::SyntaxError;
+use crate::parser::Parser;
+use crate::utils::is_whitespace;
+
+/// [Void elements] which cannot have content and the end tag.
+///
+/// [Void elements]: https://html.spec.whatwg.org/multipage/syntax.html#void-elements
+const VOID: &[&[u8]] = &[
+    b"area",
+    b"base",
+    b"basefont",
+    b"bgsound",
+    b"br",
+    b"col",
+    b"embed",
+    b"frame",
+    b"hr",
+    b"img",
+    b"input",
+    b"keygen",
+    b"link",
+    b"meta",
+    b"param",
+    b"source",
+    b"track",
+    b"wbr",
+];
+
+/// Elements which content is read as a text up to the end tag of the element.
+///
+/// See [raw text] and [escapable raw text] elements.
+///
+/// [raw text]: https://html.spec.whatwg.org/multipage/syntax.html#raw-text-elements
+/// [escapable raw text]: https://html.spec.whatwg.org/multipage/syntax.html#escapable-raw-text-elements
+const RAW_TEXT: &[&[u8]] = &[b"script", b"style", b"textarea", b"title"];
+
+/// Start tags of these elements close the opened `<p>` element.
+///
+/// See [optional tags].
+///
+/// [optional tags]: https://html.spec.whatwg.org/multipage/syntax.html#optional-tags
+const CLOSE_P: &[&[u8]] = &[
+    b"address",
+    b"article",
+    b"aside",
+    b"blockquote",
+    b"center",
+    b"dd",
+    b"details",
+    b"dialog",
+    b"dir",
+    b"div",
+    b"dl",
+    b"dt",
+    b"fieldset",
+    b"figcaption",
+    b"figure",
+    b"footer",
+    b"form",
+    b"h1",
+    b"h2",
+    b"h3",
+    b"h4",
+    b"h5",
+    b"h6",
+    b"header",
+    b"hgroup",
+    b"hr",
+    b"li",
+    b"main",
+    b"menu",
+    b"nav",
+    b"ol",
+    b"p",
+    b"pre",
+    b"section",
+    b"summary",
+    b"table",
+    b"ul",
+];
+
+/// Checks if the ASCII case-insensitive `name` is in the `list`.
+fn is_one_of(name: &[u8], list: &[&[u8]]) -> bool {
+    list.iter().any(|item| item.eq_ignore_ascii_case(name))
+}
+
+/// Returns `true` if the element with the `name` is a void element.
+pub fn is_void(name: &[u8]) -> bool {
+    is_one_of(name, VOID)
+}
+
+/// Returns `true` if the content of the element with the `name` is a raw text.
+pub fn is_raw_text(name: &[u8]) -> bool {
+    is_one_of(name, RAW_TEXT)
+}
+
+/// Returns `true` if the start tag `start` implies the end tag of the `opened` element.
+pub fn closes(start: &[u8], opened: &[u8]) -> bool {
+    let list: &[&[u8]] = match opened.to_ascii_lowercase().as_slice() {
+        b"p" => CLOSE_P,
+        b"li" => &[b"li"],
+        b"dt" | b"dd" => &[b"dt", b"dd"],
+        b"option" => &[b"option", b"optgroup"],
+        b"optgroup" => &[b"optgroup"],
+        b"rt" | b"rp" => &[b"rt", b"rp"],
+        b"td" | b"th" => &[b"td", b"th", b"tr", b"tbody", b"thead", b"tfoot"],
+        b"tr" => &[b"tr", b"tbody", b"thead", b"tfoot"],
+        b"thead" | b"tbody" | b"tfoot" => &[b"tbody", b"thead", b"tfoot"],
+        _ => return false,
+    };
+    is_one_of(start, list)
+}
+
+/// Returns `true` if the end tag of the element with the `name` can be omitted.
+///
+/// See [optional tags].
+///
+/// [optional tags]: https://html.spec.whatwg.org/multipage/syntax.html#optional-tags
+pub fn has_optional_end(name: &[u8]) -> bool {
+    is_one_of(
+        name,
+        &[
+            b"html",
+            b"head",
+            b"body",
+            b"p",
+            b"li",
+            b"dt",
+            b"dd",
+            b"option",
+            b"optgroup",
+            b"rt",
+            b"rp",
+            b"td",
+            b"th",
+            b"tr",
+            b"thead",
+            b"tbody",
+            b"tfoot",
+            b"colgroup",
+            b"caption",
+        ],
+    )
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A parser that searches the end tag of a raw text element. The end tag is
+/// `</`, the name of the element in any case, followed by a whitespace, `/`
+/// or `>`, and ends at the first `>`.
+#[derive(Clone, Debug)]
+pub struct RawTextParser {
+    /// Name of the element in the lower case
+    name: Vec<u8>,
+    /// Number of bytes of the end tag, that were seen so far
+    matched: usize,
+    /// `true` if the name of the end tag was matched and the parser searches `>`
+    in_tag: bool,
+}
+
+impl RawTextParser {
+    pub fn new(name: &[u8]) -> Self {
+        Self {
+            name: name.to_ascii_lowercase(),
+            matched: 0,
+            in_tag: false,
+        }
+    }
+
+    /// Returns the length of the end tag including `<` and `>`, after the end
+    /// tag was found.
+    pub const fn end_tag_len(&self) -> usize {
+        self.matched
+    }
+}
+
+impl Parser for RawTextParser {
+    /// Returns position of the `>` of the end tag in `bytes`.
+    fn feed(&mut self, bytes: &[u8]) -> Option<usize> {
+        let mut i = 0;
+        while i < bytes.len() {
+            let byte = bytes[i];
+            if self.in_tag {
+                self.matched += 1;
+                if byte == b'>' {
+                    return Some(i);
+                }
+            } else if self.matched == self.name.len() + 2 {
+                // `</name` was matched
+                match byte {
+                    b'>' => {
+                        self.matched += 1;
+                        return Some(i);
+                    }
+                    b'/' => {
+                        self.matched += 1;
+                        self.in_tag = true;
+                    }
+                    b if is_whitespace(b) => {
+                        self.matched += 1;
+                        self.in_tag = true;
+                    }
+                    b'<' => self.matched = 1,
+                    _ => self.matched = 0,
+                }
+            } else if self.matched == 0 {
+                // Fast skip to the next `<`
+                match memchr::memchr(b'<', &bytes[i..]) {
+                    Some(p) => {
+                        i += p;
+                        self.matched = 1;
+                    }
+                    None => return None,
+                }
+            } else {
+                let expected = match self.matched {
+                    1 => b'/',
+                    n => self.name[n - 2],
+                };
+                if byte.to_ascii_lowercase() == expected {
+                    self.matched += 1;
+                } else {
+                    self.matched = usize::from(byte == b'<');
+                }
+            }
+            i += 1;
+        }
+        None
+    }
+
+    fn eof_error(self, _content: &[u8]) -> SyntaxError {
+        // The content of a raw text element is closed by the end of input
+        SyntaxError::UnclosedTag
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn raw_text_parser() {
+        let mut parser = RawTextParser::new(b"script");
+        assert_eq!(parser.feed(b"a < b && </scripts> </"), None);
+        assert_eq!(parser.feed(b"SCRIPT "), None);
+        assert_eq!(parser.feed(b"x>rest"), Some(1));
+        assert_eq!(parser.end_tag_len(), 11);
+
+        let mut parser = RawTextParser::new(b"style");
+        assert_eq!(parser.feed(b"<</style>"), Some(8));
+        assert_eq!(parser.end_tag_len(), 8);
+    }
+
+    #[test]
+    fn implied_ends() {
+        assert!(closes(b"DIV", b"p"));
+        assert!(closes(b"li", b"LI"));
+        assert!(closes(b"tr", b"td"));
+        assert!(!closes(b"span", b"p"));
+        assert!(!closes(b"li", b"ul"));
+    }
+}
@@ -2,15 +2,19 @@
 
 #[cfg(feature = "encoding")]
 use encoding_rs::Encoding;
+use std::fmt;
 use std::io;
 use std::ops::Range;
 
 use crate::encoding::Decoder;
 #[cfg(feature = "encoding")]
-use crate::encoding::DetectedEncoding;
+use crate::encoding::{DetectedEncoding, Transcoder};
 use crate::errors::{Error, IllFormedError, SyntaxError};
-use crate::events::{BytesRef, Event};
+use crate::events::{BytesText, Event};
 use crate::parser::{DtdParser, ElementParser, Parser, PiParser};
+use crate::reader::defaults::Defaults;
+use crate::reader::entities::Entities;
+use crate::reader::html::RawTextParser;
 use crate::reader::state::ReaderState;
 
 /// A struct that holds a parser configuration.
@@ -71,6 +75,49 @@ pub struct Config {
     /// [`End`]: crate::events::Event::End
     pub allow_unmatched_ends: bool,
 
+    /// Whether the document should contain only characters allowed by the XML
+    /// [`Char`] production. If enabled, in case of forbidden character the
+    /// [`Error::IllFormed(InvalidChar)`] is returned from read methods.
+    ///
+    /// When set to `true`, text, CDATA sections, comments, processing instructions,
+    /// DOCTYPE definitions and tags (including attribute values) are checked,
+    /// as well as the characters referenced by character references such as
+    /// `&#1;`. The rules of the XML version from the [XML declaration] are
+    /// applied, XML 1.0 is assumed if declaration is missing:
+    /// - XML 1.0 forbids control characters except tab, carriage return and
+    ///   line feed both literally and in character references;
+    /// - XML 1.1 forbids the C0 and C1 control characters except tab, carriage
+    ///   return, line feed and NEL literally, but allows them in character references.
+    ///
+    /// Content that is not a valid text in the document encoding (for example,
+    /// an encoded lone surrogate in UTF-8 document) is reported as an
+    /// [`Error::Encoding`].
+    ///
+    /// Default: `false`
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::errors::{Error, IllFormedError};
+    /// use quick_xml::events::{BytesStart, Event};
+    /// use quick_xml::reader::Reader;
+    ///
+    /// let mut reader = Reader::from_str("<root>\u{1}</root>");
+    /// reader.config_mut().check_chars = true;
+    ///
+    /// assert_eq!(reader.read_event().unwrap(), Event::Start(BytesStart::new("root")));
+    /// match reader.read_event() {
+    ///     Err(Error::IllFormed(IllFormedError::InvalidChar(0x1))) => {}
+    ///     x => panic!("Expected `InvalidChar`, but got {:?}", x),
+    /// }
+    /// assert_eq!(reader.error_position(), 6);
+    /// ```
+    ///
+    /// [`Char`]: https://www.w3.org/TR/xml11/#NT-Char
+    /// [`Error::IllFormed(InvalidChar)`]: crate::errors::IllFormedError::InvalidChar
+    /// [XML declaration]: crate::events::BytesDecl::xml_version
+    /// [`Error::Encoding`]: crate::errors::Error::Encoding
+    pub check_chars: bool,
+
     /// Whether comments should be validated. If enabled, in case of invalid comment
     /// [`Error::IllFormed(DoubleHyphenInComment)`] is returned from read methods.
     ///
@@ -124,6 +171,76 @@ pub struct Config {
     /// [`expand_empty_elements`]: Self::expand_empty_elements
     pub check_end_names: bool,
 
+    /// Whether names should match the XML [`Name`] production. If enabled, in
+    /// case of invalid name the [`Error::IllFormed(InvalidName)`] is returned
+    /// from read methods.
+    ///
+    /// When set to `true`, names of elements in start and end tags, names of
+    /// attributes, targets of processing instructions and names in entity
+    /// references are checked. The rules are the same for XML 1.0 and XML 1.1.
+    ///
+    /// Default: `false`
+    ///
+    /// [`Name`]: https://www.w3.org/TR/xml11/#NT-Name
+    /// [`Error::IllFormed(InvalidName)`]: crate::errors::IllFormedError::InvalidName
+    pub check_names: bool,
+
+    /// Whether attributes with default values declared in the DTD should be
+    /// added to elements which do not specify them.
+    ///
+    /// When set to `true`, the reader captures `<!ATTLIST>` declarations from
+    /// the internal subset of the [`DocType`] event. Attributes declared with
+    /// a default value (`"value"`) or a fixed value (`#FIXED "value"`), which
+    /// are missing in a start or empty tag of the element, are added after the
+    /// specified attributes and are returned by [`BytesStart::attributes()`].
+    /// The first declaration of an attribute is binding. Values are added as
+    /// written in the DTD, so they can contain character and entity references.
+    ///
+    /// Use [`BytesStart::specified_attributes()`] and [`BytesStart::defaulted_attributes()`]
+    /// to find out whether an attribute was specified in the document or added
+    /// from its default value.
+    ///
+    /// Because the [`NsReader`] resolves namespaces using the attributes of
+    /// the events, defaulted `xmlns` attributes declare namespaces too. The same
+    /// is true for the serde [`Deserializer`] created from a configured reader.
+    ///
+    /// This option is not supported by the [`PushReader`].
+    ///
+    /// Default: `false`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::Event;
+    /// use quick_xml::name::{Namespace, ResolveResult};
+    /// use quick_xml::reader::NsReader;
+    ///
+    /// let mut reader = NsReader::from_str(r#"<!DOCTYPE book [
+    ///     <!ATTLIST book xmlns CDATA #FIXED "http://docbook.org/ns/docbook">
+    /// ]><book/>"#);
+    /// reader.config_mut().default_attributes = true;
+    ///
+    /// assert!(matches!(reader.read_event().unwrap(), Event::DocType(_)));
+    /// match reader.read_resolved_event().unwrap() {
+    ///     (ResolveResult::Bound(ns), Event::Empty(e)) => {
+    ///         assert_eq!(ns, Namespace(b"http://docbook.org/ns/docbook"));
+    ///         assert_eq!(e.specified_attributes().count(), 0);
+    ///         assert_eq!(e.defaulted_attributes().count(), 1);
+    ///     }
+    ///     e => panic!("Expected bound `Empty`, but got `{:?}`", e),
+    /// }
+    /// ```
+    ///
+    /// [`DocType`]: crate::events::Event::DocType
+    /// [`BytesStart::attributes()`]: crate::events::BytesStart::attributes
+    /// [`BytesStart::specified_attributes()`]: crate::events::BytesStart::specified_attributes
+    /// [`BytesStart::defaulted_attributes()`]: crate::events::BytesStart::defaulted_attributes
+    /// [`NsReader`]: crate::reader::NsReader
+    /// [`Deserializer`]: crate::de::Deserializer
+    /// [`PushReader`]: crate::reader::PushReader
+    pub default_attributes: bool,
+
     /// Whether empty elements should be split into an `Open` and a `Close` event.
     ///
     /// When set to `true`, all [`Empty`] events produced by a self-closing tag
@@ -144,6 +261,299 @@ pub struct Config {
     /// [`check_end_names`]: Self::check_end_names
     pub expand_empty_elements: bool,
 
+    /// Whether references to internal general entities declared in the DTD
+    /// should be expanded.
+    ///
+    /// When set to `true`, the reader captures `<!ENTITY name "value">`
+    /// declarations from the internal subset of the [`DocType`] event. When
+    /// a reference to a captured entity (`&name;`) is found in the text,
+    /// the reader parses the replacement text of the entity and returns its
+    /// events instead of the [`GeneralRef`] event. Replacement text can contain
+    /// markup and references to other entities, which are also expanded.
+    /// References to other entities (including predefined entities and character
+    /// references) are returned as [`GeneralRef`] events as usual.
+    ///
+    /// Events produced from the replacement text are always owned. Elements
+    /// inside the replacement text should be balanced; references in attribute
+    /// values are not expanded.
+    ///
+    /// If the replacement text of an entity does not produce any events (for
+    /// example, `<!ENTITY e "">` or an entity which only references such
+    /// entities), an empty [`Text`] event is returned in place of the reference.
+    /// The next event of the document cannot be read in the same call, because
+    /// the buffer is still borrowed by the reference.
+    ///
+    /// Entities which reference themselves are reported as
+    /// [`Error::IllFormed(RecursiveEntity)`]. The total size of the expanded
+    /// replacement texts is limited by [`max_entity_expansion`].
+    ///
+    /// This option is not supported by the [`PushReader`].
+    ///
+    /// Default: `false`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+    /// use quick_xml::reader::Reader;
+    ///
+    /// let mut reader = Reader::from_str(r#"<!DOCTYPE root [
+    ///     <!ENTITY bold "<b>&text;</b>">
+    ///     <!ENTITY text "bold text">
+    /// ]><root>&bold;</root>"#);
+    /// reader.config_mut().expand_entities = true;
+    ///
+    /// assert!(matches!(reader.read_event().unwrap(), Event::DocType(_)));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Start(BytesStart::new("root")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Start(BytesStart::new("b")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Text(BytesText::new("bold text")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("b")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("root")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    /// ```
+    ///
+    /// [`DocType`]: crate::events::Event::DocType
+    /// [`GeneralRef`]: crate::events::Event::GeneralRef
+    /// [`Text`]: crate::events::Event::Text
+    /// [`Error::IllFormed(RecursiveEntity)`]: crate::errors::IllFormedError::RecursiveEntity
+    /// [`max_entity_expansion`]: Self::max_entity_expansion
+    /// [`PushReader`]: crate::reader::PushReader
+    pub expand_entities: bool,
+
+    /// Whether the document should be read as HTML rather than XML.
+    ///
+    /// When enabled, the reader tolerates the following HTML constructs:
+    ///
+    /// - [void elements] such as `<br>` or `<img>` produce an [`Event::Empty`]
+    ///   event, because they never have an end tag;
+    /// - content of `<script>`, `<style>`, `<textarea>` and `<title>` elements is
+    ///   read as a single [`Event::Text`] up to their end tag, so it can contain
+    ///   `<` and `&` characters. References are not recognized in such content,
+    ///   use [`BytesText::decode()`] to get it as is;
+    /// - [end tags that can be omitted], for example, `</p>` and `</li>`, are
+    ///   implied: an [`Event::End`] is returned when the next start tag or an end
+    ///   tag of an outer element closes the element, or at the end of input;
+    /// - names in end tags are matched with names in start tags case-insensitively.
+    ///   [`Event::End`] events always contain the name as it is written in the start tag.
+    ///
+    /// Attributes without quotes and values can be read using [`BytesStart::html_attributes()`]
+    /// and HTML entities can be resolved using `escape::resolve_html5_entity()`
+    /// from the `escape-html` feature.
+    ///
+    /// Default: `false`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+    /// use quick_xml::reader::Reader;
+    ///
+    /// let mut reader = Reader::from_str("<UL><li>One<li>Two<br></ul><script>a < b</script>");
+    /// reader.config_mut().html = true;
+    ///
+    /// assert_eq!(reader.read_event().unwrap(), Event::Start(BytesStart::new("UL")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Start(BytesStart::new("li")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Text(BytesText::new("One")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("li")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Start(BytesStart::new("li")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Text(BytesText::new("Two")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Empty(BytesStart::new("br")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("li")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("UL")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Start(BytesStart::new("script")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Text(BytesText::from_escaped("a < b")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("script")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    /// ```
+    ///
+    /// [void elements]: https://html.spec.whatwg.org/multipage/syntax.html#void-elements
+    /// [`BytesText::decode()`]: crate::events::BytesText::decode
+    /// [end tags that can be omitted]: https://html.spec.whatwg.org/multipage/syntax.html#optional-tags
+    /// [`BytesStart::html_attributes()`]: crate::events::BytesStart::html_attributes
+    pub html: bool,
+
+    /// Maximum number of attributes in one start or empty tag. Malformed
+    /// attributes are counted too, because attributes are not validated when
+    /// counted.
+    ///
+    /// When the limit is exceeded, [`Error::LimitExceeded(Attributes)`] is
+    /// returned and reading stops.
+    ///
+    /// Default: unlimited (`usize::MAX`)
+    ///
+    /// [`Error::LimitExceeded(Attributes)`]: crate::errors::Limit::Attributes
+    pub max_attributes: usize,
+
+    /// Maximum nesting depth of elements. Top-level element has depth 1.
+    /// Empty elements `<element/>` also count, so with the limit of `1` only
+    /// the root element without children can be read.
+    ///
+    /// When the limit is exceeded, [`Error::LimitExceeded(Depth)`] is returned
+    /// and reading stops.
+    ///
+    /// Default: unlimited (`usize::MAX`)
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::errors::{Error, Limit};
+    /// use quick_xml::events::{BytesStart, Event};
+    /// use quick_xml::reader::Reader;
+    ///
+    /// let mut reader = Reader::from_str("<a><b><c/></b></a>");
+    /// reader.config_mut().max_depth = 2;
+    ///
+    /// assert_eq!(reader.read_event().unwrap(), Event::Start(BytesStart::new("a")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Start(BytesStart::new("b")));
+    /// match reader.read_event() {
+    ///     Err(Error::LimitExceeded(Limit::Depth(2))) => {}
+    ///     x => panic!("Expected `LimitExceeded(Depth)`, but got {:?}", x),
+    /// }
+    /// // Reading is stopped
+    /// assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    /// ```
+    ///
+    /// [`Error::LimitExceeded(Depth)`]: crate::errors::Limit::Depth
+    pub max_depth: usize,
+
+    /// Maximum total size in bytes of replacement texts of all entities expanded
+    /// in the document when [`expand_entities`] is enabled. Each reference to
+    /// an entity counts separately, so this limit protects from exponential
+    /// growth of a small document when entities reference other entities many
+    /// times (so called "billion laughs" attack).
+    ///
+    /// When the limit is exceeded, [`Error::LimitExceeded(EntityExpansion)`] is
+    /// returned and reading stops.
+    ///
+    /// Default: 1 MiB (`1048576`)
+    ///
+    /// [`expand_entities`]: Self::expand_entities
+    /// [`Error::LimitExceeded(EntityExpansion)`]: crate::errors::Limit::EntityExpansion
+    pub max_entity_expansion: usize,
+
+    /// Maximum total number of events that reader returns, excluding the final
+    /// [`Event::Eof`]. Events produced by expansion of entities are counted too.
+    ///
+    /// When the limit is exceeded, [`Error::LimitExceeded(Events)`] is returned
+    /// and reading stops.
+    ///
+    /// Default: unlimited (`usize::MAX`)
+    ///
+    /// [`Event::Eof`]: crate::events::Event::Eof
+    /// [`Error::LimitExceeded(Events)`]: crate::errors::Limit::Events
+    pub max_events: usize,
+
+    /// Maximum length in bytes of element names and attribute names, including
+    /// namespace prefixes.
+    ///
+    /// When the limit is exceeded, [`Error::LimitExceeded(NameLength)`] is
+    /// returned and reading stops.
+    ///
+    /// Default: unlimited (`usize::MAX`)
+    ///
+    /// [`Error::LimitExceeded(NameLength)`]: crate::errors::Limit::NameLength
+    pub max_name_length: usize,
+
+    /// Maximum size in bytes of a single piece of text, CDATA section, comment,
+    /// processing instruction, DOCTYPE definition, tag or general reference,
+    /// including its delimiters (`<`, `>`, `<!--` and so on). Text that is
+    /// split by references into several [`Text`] events is checked piece by
+    /// piece.
+    ///
+    /// When reading from a [`BufRead`], the internal buffer never grows beyond
+    /// this limit, so it protects from exhausting memory by, for example, an
+    /// unclosed comment in an endless stream.
+    ///
+    /// When the limit is exceeded, [`Error::LimitExceeded(TextSize)`] is
+    /// returned and reading stops.
+    ///
+    /// Default: unlimited (`usize::MAX`)
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use std::io::{BufReader, Read};
+    /// use quick_xml::errors::{Error, Limit};
+    /// use quick_xml::events::{BytesStart, Event};
+    /// use quick_xml::reader::Reader;
+    ///
+    /// // Endless unclosed comment
+    /// let input = b"<root><!--".chain(std::io::repeat(b'-'));
+    /// let mut reader = Reader::from_reader(BufReader::new(input));
+    /// reader.config_mut().max_text_size = 1024;
+    ///
+    /// let mut buf = Vec::new();
+    /// assert_eq!(
+    ///     reader.read_event_into(&mut buf).unwrap(),
+    ///     Event::Start(BytesStart::new("root")),
+    /// );
+    /// match reader.read_event_into(&mut buf) {
+    ///     Err(Error::LimitExceeded(Limit::TextSize(1024))) => {}
+    ///     x => panic!("Expected `LimitExceeded(TextSize)`, but got {:?}", x),
+    /// }
+    /// assert!(buf.len() <= 1024);
+    /// ```
+    ///
+    /// [`Text`]: crate::events::Event::Text
+    /// [`BufRead`]: std::io::BufRead
+    /// [`Error::LimitExceeded(TextSize)`]: crate::errors::Limit::TextSize
+    pub max_text_size: usize,
+
+    /// Whether the reader should recover from syntax and well-formedness errors
+    /// instead of returning them.
+    ///
+    /// When enabled, errors found in the document are recorded as [`Diagnostic`]s,
+    /// which are available via [`Reader::diagnostics()`], and the reader continues
+    /// to produce events:
+    ///
+    /// - a `<` that does not start a markup, invalid `<!...>` and `<?>` markup,
+    ///   unknown and unmatched end tags are returned as [`Event::Text`];
+    /// - an end tag which name matches one of the outer opened elements closes
+    ///   all elements opened after it: an [`Event::End`] is returned for each
+    ///   of them;
+    /// - elements that are not closed at the end of input are closed by
+    ///   [`Event::End`] events before the [`Event::Eof`];
+    /// - markup that is not closed at the end of input is dropped;
+    /// - dangling `&`, invalid characters and names, `--` in comments and missing
+    ///   DOCTYPE names are reported, but the events are returned as is.
+    ///
+    /// Errors of the underlying reader, of decoding the input and [`Error::LimitExceeded`]
+    /// are still returned.
+    ///
+    /// Default: `false`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::errors::{Error, IllFormedError};
+    /// use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+    /// use quick_xml::reader::Reader;
+    ///
+    /// let mut reader = Reader::from_str("<root><item>1 < 2</root>");
+    /// reader.config_mut().recover_errors = true;
+    ///
+    /// assert_eq!(reader.read_event().unwrap(), Event::Start(BytesStart::new("root")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Start(BytesStart::new("item")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Text(BytesText::new("1 ")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Text(BytesText::new("<")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Text(BytesText::new(" 2")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("item")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("root")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    ///
+    /// let diagnostics = reader.diagnostics();
+    /// assert_eq!(diagnostics.len(), 2);
+    /// assert!(matches!(diagnostics[0].error, Error::IllFormed(IllFormedError::UnescapedLessThan)));
+    /// assert_eq!(diagnostics[0].position.byte, 14);
+    /// assert!(matches!(diagnostics[1].error, Error::IllFormed(IllFormedError::ImpliedEndTag { .. })));
+    /// assert_eq!(diagnostics[1].position.byte, 17);
+    /// ```
+    ///
+    /// [`Reader::diagnostics()`]: crate::reader::Reader::diagnostics
+    /// [`Error::LimitExceeded`]: crate::errors::Error::LimitExceeded
+    pub recover_errors: bool,
+
     /// Whether trailing whitespace after the markup name are trimmed in closing
     /// tags `</a >`.
     ///
@@ -201,6 +611,47 @@ pub struct Config {
     /// [`BytesText::inplace_trim_start`]: crate::events::BytesText::inplace_trim_start
     /// [`BytesText::inplace_trim_end`]: crate::events::BytesText::inplace_trim_end
     pub trim_text_end: bool,
+
+    /// Whether line and column of the current position should be tracked.
+    ///
+    /// When set to `true`, the reader counts lines and columns of the consumed
+    /// data, which is available via [`Reader::position()`]. Errors returned
+    /// from read methods (except I/O errors) are wrapped into [`Error::Positioned`]
+    /// which contains the [`Position`] of the error.
+    ///
+    /// This option should be set before reading the first event, otherwise the
+    /// reported lines and columns will be counted from the place where it was set.
+    ///
+    /// Default: `false`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::errors::{Error, IllFormedError};
+    /// use quick_xml::reader::{Position, Reader};
+    ///
+    /// let mut reader = Reader::from_str("<root>\n  <child>\n</root>");
+    /// reader.config_mut().track_position = true;
+    ///
+    /// reader.read_event().unwrap(); // <root>
+    /// reader.read_event().unwrap(); // "\n  "
+    /// reader.read_event().unwrap(); // <child>
+    /// assert_eq!(reader.position(), Position { byte: 16, line: 2, column: 10 });
+    ///
+    /// reader.read_event().unwrap(); // "\n"
+    /// match reader.read_event() {
+    ///     Err(Error::Positioned { position, error }) => {
+    ///         assert_eq!(position, Position { byte: 17, line: 3, column: 1 });
+    ///         assert!(matches!(*error, Error::IllFormed(IllFormedError::MismatchedEndTag { .. })));
+    ///     }
+    ///     x => panic!("Expected `Err(Positioned {{ .. }})`, but got `{:?}`", x),
+    /// }
+    /// ```
+    ///
+    /// [`Reader::position()`]: crate::reader::Reader::position
+    /// [`Error::Positioned`]: crate::errors::Error::Positioned
+    pub track_position: bool,
 }
 
 impl Config {
@@ -240,12 +691,25 @@ impl Default for Config {
         Self {
             allow_dangling_amp: false,
             allow_unmatched_ends: false,
+            check_chars: false,
             check_comments: false,
             check_end_names: true,
+            check_names: false,
+            default_attributes: false,
             expand_empty_elements: false,
+            expand_entities: false,
+            html: false,
+            max_attributes: usize::MAX,
+            max_depth: usize::MAX,
+            max_entity_expansion: 1024 * 1024,
+            max_events: usize::MAX,
+            max_name_length: usize::MAX,
+            max_text_size: usize::MAX,
+            recover_errors: false,
             trim_markup_names_in_closing_tags: true,
             trim_text_start: false,
             trim_text_end: false,
+            track_position: false,
         }
     }
 }
@@ -282,51 +746,28 @@ macro_rules! read_event_impl {
                 },
                 ParseState::InsideRef => { // Go to InsideText
                     let start = $self.state.offset;
-                    match $reader.read_ref($buf, &mut $self.state.offset) $(.$await)? {
+                    match $reader.read_ref($buf, &mut $self.state.offset, $self.state.config.max_text_size) $(.$await)? {
                         // Emit reference, go to InsideText state
-                        ReadRefResult::Ref(bytes) => {
-                            $self.state.state = ParseState::InsideText;
-                            // +1 to skip start `&`
-                            // -1 to skip end `;`
-                            Ok(Event::GeneralRef(BytesRef::wrap(&bytes[1..bytes.len() - 1], $self.decoder())))
-                        }
+                        ReadRefResult::Ref(bytes) => $self.state.emit_complete_ref(bytes),
                         // Go to Done state
-                        ReadRefResult::UpToEof(bytes) if $self.state.config.allow_dangling_amp => {
-                            $self.state.state = ParseState::Done;
-                            Ok(Event::Text($self.state.emit_text(bytes)))
-                        }
-                        ReadRefResult::UpToEof(_) => {
-                            $self.state.state = ParseState::Done;
-                            $self.state.last_error_offset = start;
-                            Err(Error::IllFormed(IllFormedError::UnclosedReference))
-                        }
+                        ReadRefResult::UpToEof(bytes) => $self.state.emit_unclosed_ref(ParseState::Done, start, bytes),
                         // Do not change state, stay in InsideRef
-                        ReadRefResult::UpToRef(bytes) if $self.state.config.allow_dangling_amp => {
-                            Ok(Event::Text($self.state.emit_text(bytes)))
-                        }
-                        ReadRefResult::UpToRef(_) => {
-                            $self.state.last_error_offset = start;
-                            Err(Error::IllFormed(IllFormedError::UnclosedReference))
-                        }
+                        ReadRefResult::UpToRef(bytes) => $self.state.emit_unclosed_ref(ParseState::InsideRef, start, bytes),
                         // Go to InsideMarkup state
-                        ReadRefResult::UpToMarkup(bytes) if $self.state.config.allow_dangling_amp => {
-                            $self.state.state = ParseState::InsideMarkup;
-                            Ok(Event::Text($self.state.emit_text(bytes)))
+                        ReadRefResult::UpToMarkup(bytes) => $self.state.emit_unclosed_ref(ParseState::InsideMarkup, start, bytes),
+                        ReadRefResult::Err(e) => {
+                            $self.state.set_error_offset(start, &[]);
+                            Err(e)
                         }
-                        ReadRefResult::UpToMarkup(_) => {
-                            $self.state.state = ParseState::InsideMarkup;
-                            $self.state.last_error_offset = start;
-                            Err(Error::IllFormed(IllFormedError::UnclosedReference))
-                        }
-                        ReadRefResult::Err(e) => Err(Error::from(e)),
                     }
                 }
                 ParseState::InsideText => { // Go to InsideMarkup or Done state
                     if $self.state.config.trim_text_start {
-                        $reader.skip_whitespace(&mut $self.state.offset) $(.$await)? ?;
+                        $reader.skip_whitespace(&mut $self.state.offset, &mut $self.state.tracked) $(.$await)? ?;
                     }
 
-                    match $reader.read_text($buf, &mut $self.state.offset) $(.$await)? {
+                    let start = $self.state.offset;
+                    match $reader.read_text($buf, &mut $self.state.offset, $self.state.config.max_text_size) $(.$await)? {
                         ReadTextResult::Markup(buf) => {
                             $self.state.state = ParseState::InsideMarkup;
                             // Pass `buf` to the next next iteration of parsing loop
@@ -339,46 +780,39 @@ macro_rules! read_event_impl {
                             $buf = buf;
                             continue;
                         }
-                        ReadTextResult::UpToMarkup(bytes) => {
-                            $self.state.state = ParseState::InsideMarkup;
-                            // FIXME: Can produce an empty event if:
-                            // - event contains only spaces
-                            // - trim_text_start = false
-                            // - trim_text_end = true
-                            Ok(Event::Text($self.state.emit_text(bytes)))
-                        }
-                        ReadTextResult::UpToRef(bytes) => {
-                            $self.state.state = ParseState::InsideRef;
-                            // Return Text event with `bytes` content or Eof if bytes is empty
-                            Ok(Event::Text($self.state.emit_text(bytes)))
-                        }
-                        ReadTextResult::UpToEof(bytes) => {
-                            $self.state.state = ParseState::Done;
-                            // Trim bytes from end if required
-                            let event = $self.state.emit_text(bytes);
-                            if event.is_empty() {
-                                Ok(Event::Eof)
-                            } else {
-                                Ok(Event::Text(event))
-                            }
+                        ReadTextResult::UpToMarkup(bytes) => $self.state.emit_text_before(ParseState::InsideMarkup, bytes),
+                        // Return Text event with `bytes` content
+                        ReadTextResult::UpToRef(bytes) => $self.state.emit_text_before(ParseState::InsideRef, bytes),
+                        // Return Text event with `bytes` content or Eof if bytes is empty
+                        ReadTextResult::UpToEof(bytes) => $self.state.emit_text_before(ParseState::Done, bytes),
+                        ReadTextResult::Err(e) => {
+                            $self.state.set_error_offset(start, &[]);
+                            Err(e)
                         }
-                        ReadTextResult::Err(e) => Err(Error::from(e)),
                     }
                 },
                 // Go to InsideText state in next two arms
                 ParseState::InsideMarkup => $self.$read_until_close($buf) $(.$await)?,
                 ParseState::InsideEmpty => Ok(Event::End($self.state.close_expanded_empty())),
-                ParseState::Done => Ok(Event::Eof),
+                ParseState::InsideRawText => {
+                    let start = $self.state.offset;
+                    let mut parser = $self.state.raw_text_parser();
+                    match $reader.read_raw_text(&mut parser, $buf, &mut $self.state.offset, $self.state.config.max_text_size) $(.$await)? {
+                        Ok((bytes, found)) => {
+                            let end_len = if found { parser.end_tag_len() } else { 0 };
+                            $self.state.emit_raw_text(bytes, end_len)
+                        }
+                        Err(e) => {
+                            $self.state.set_error_offset(start, &[]);
+                            Err(e)
+                        }
+                    }
+                }
+                ParseState::ImpliedEnd => Ok($self.state.close_implied()),
+                ParseState::Done => Ok($self.state.eof()),
             };
         };
-        match event {
-            // #513: In case of ill-formed errors we already consume the wrong data
-            // and change the state. We can continue parsing if we wish
-            Err(Error::IllFormed(_)) => {}
-            Err(_) | Ok(Event::Eof) => $self.state.state = ParseState::Done,
-            _ => {}
-        }
-        event
+        $self.state.finish_event(event)
     }};
 }
 
@@ -414,13 +848,20 @@ macro_rules! read_until_close {
         match $reader.peek_one() $(.$await)? {
             // `<!` - comment, CDATA or DOCTYPE declaration
             Ok(Some(b'!')) => match $reader
-                .read_bang_element($buf, &mut $self.state.offset)
+                .read_bang_element($buf, &mut $self.state.offset, $self.state.config.max_text_size)
                 $(.$await)?
             {
-                Ok((bang_type, bytes)) => $self.state.emit_bang(bang_type, bytes),
+                Ok((bang_type, bytes)) => $self.state.emit_markup(Some(bang_type), bytes),
+                // Nothing was consumed, return `<` as a text and continue from the `!`
+                Err(Error::Syntax(e @ SyntaxError::InvalidBangMarkup))
+                    if $self.state.config.recover_errors =>
+                {
+                    $reader.skip_lt(&mut $self.state.offset);
+                    Ok($self.state.emit_lt(Error::Syntax(e)))
+                }
                 Err(e) => {
                     // We want to report error at `<`
-                    $self.state.last_error_offset = start;
+                    $self.state.set_error_offset(start, &[]);
                     Err(e)
                 }
             },
@@ -435,44 +876,53 @@ macro_rules! read_until_close {
             //   does. This is malformed XML, however it is tolerated by some parsers
             //   (e.g. the one used by Adobe Flash) and such documents do exist in the wild.
             Ok(Some(b'/')) => match $reader
-                .read_with(ElementParser::Outside, $buf, &mut $self.state.offset)
+                .read_with(ElementParser::Outside, $buf, &mut $self.state.offset, $self.state.config.max_text_size)
                 $(.$await)?
             {
-                Ok(bytes) => $self.state.emit_end(bytes),
+                Ok(bytes) => $self.state.emit_markup(None, bytes),
                 Err(e) => {
                     // We want to report error at `<`
-                    $self.state.last_error_offset = start;
+                    $self.state.set_error_offset(start, &[]);
                     Err(e)
                 }
             },
             // `<?` - processing instruction
             Ok(Some(b'?')) => match $reader
-                .read_with(PiParser(false), $buf, &mut $self.state.offset)
+                .read_with(PiParser(false), $buf, &mut $self.state.offset, $self.state.config.max_text_size)
                 $(.$await)?
             {
-                Ok(bytes) => $self.state.emit_question_mark(bytes),
+                Ok(bytes) => $self.state.emit_markup(None, bytes),
                 Err(e) => {
                     // We want to report error at `<`
-                    $self.state.last_error_offset = start;
+                    $self.state.set_error_offset(start, &[]);
                     Err(e)
                 }
             },
+            // `<` which does not start a markup in the recovery mode
+            Ok(Some(byte)) if $self.state.config.recover_errors && !is_markup_start(byte) => {
+                $reader.skip_lt(&mut $self.state.offset);
+                Ok($self.state.emit_lt(Error::IllFormed(IllFormedError::UnescapedLessThan)))
+            }
             // `<...` - opening or self-closed tag
             Ok(Some(_)) => match $reader
-                .read_with(ElementParser::Outside, $buf, &mut $self.state.offset)
+                .read_with(ElementParser::Outside, $buf, &mut $self.state.offset, $self.state.config.max_text_size)
                 $(.$await)?
             {
-                Ok(bytes) => Ok($self.state.emit_start(bytes)),
+                Ok(bytes) => $self.state.emit_markup(None, bytes),
                 Err(e) => {
                     // We want to report error at `<`
-                    $self.state.last_error_offset = start;
+                    $self.state.set_error_offset(start, &[]);
                     Err(e)
                 }
             },
             // `<` - syntax error, tag not closed
+            Ok(None) if $self.state.config.recover_errors => {
+                $reader.skip_lt(&mut $self.state.offset);
+                Ok($self.state.emit_lt(Error::Syntax(SyntaxError::UnclosedTag)))
+            }
             Ok(None) => {
                 // We want to report error at `<`
-                $self.state.last_error_offset = start;
+                $self.state.set_error_offset(start, &[]);
                 Err(Error::Syntax(SyntaxError::UnclosedTag))
             }
             Err(e) => Err(Error::from(e)),
@@ -527,7 +977,9 @@ macro_rules! read_to_end {
                 }
                 Ok(Event::Eof) => {
                     $self.config_mut().trim_text_start = trim;
-                    return Err(Error::missed_end($end, $self.decoder()));
+                    let error = Error::missed_end($end, $self.decoder());
+                    // Report error at the end of the input
+                    return Err($self.state.with_position(error, $self.state.position()));
                 }
                 _ => (),
             }
@@ -535,18 +987,105 @@ macro_rules! read_to_end {
     }};
 }
 
+#[cfg(feature = "async-futures")]
+mod async_futures;
+#[cfg(any(feature = "async-tokio", feature = "async-futures"))]
+mod async_io;
 #[cfg(feature = "async-tokio")]
 mod async_tokio;
 mod buffered_reader;
+mod defaults;
+mod entities;
+#[cfg(any(feature = "async-tokio", feature = "async-futures"))]
+mod event_stream;
+mod html;
 mod ns_reader;
+mod push_reader;
 mod slice_reader;
 mod state;
 
+#[cfg(any(feature = "async-tokio", feature = "async-futures"))]
+pub use event_stream::{EventStream, NsEventStream};
 pub use ns_reader::NsReader;
+pub use push_reader::{PushEvent, PushReader};
 
 /// Range of input in bytes, that corresponds to some piece of XML
 pub type Span = Range<u64>;
 
+/// A position in the input data, expressed both as a byte offset and as a
+/// line and column pair that is convenient to report to humans.
+///
+/// Lines and columns are counted from 1. Lines are delimited by `\n` characters,
+/// columns count characters assuming the input is encoded in UTF-8 (each UTF-8
+/// sequence counts as one column, so for ASCII input columns are equal to bytes).
+///
+/// Line and column are only tracked when [`Config::track_position`] is enabled.
+/// Otherwise both of them are `0`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Position {
+    /// Offset in bytes from the start of the input data. Has the same meaning as
+    /// [`Reader::buffer_position()`] and [`Reader::error_position()`]
+    pub byte: u64,
+    /// Number of the line, starting from 1
+    pub line: u64,
+    /// Number of the character in the line, starting from 1
+    pub column: u64,
+}
+
+impl Position {
+    /// Position of the first byte of the input
+    pub(crate) const START: Self = Self {
+        byte: 0,
+        line: 1,
+        column: 1,
+    };
+
+    /// Moves position to the byte after the last byte of `bytes`, which should
+    /// start at the current position.
+    pub(crate) fn advance(&mut self, bytes: &[u8]) {
+        self.byte += bytes.len() as u64;
+        let tail = match memchr::memrchr(b'\n', bytes) {
+            Some(i) => {
+                self.line += memchr::memchr_iter(b'\n', bytes).count() as u64;
+                self.column = 1;
+                &bytes[i + 1..]
+            }
+            None => bytes,
+        };
+        // Count everything except UTF-8 continuation bytes (0b10xx_xxxx)
+        self.column += tail.iter().filter(|&&b| (b as i8) >= -0x40).count() as u64;
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// An error found in the document from which the reader has recovered.
+///
+/// Diagnostics are collected only when [`Config::recover_errors`] is enabled
+/// and are available via [`Reader::diagnostics()`].
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    /// Position of the error. Line and column are only tracked when
+    /// [`Config::track_position`] is enabled, otherwise they are `0`
+    pub position: Position,
+    /// The error that would be returned if the recovery was not enabled
+    pub error: Error,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.position.line == 0 {
+            write!(f, "byte {}: {}", self.position.byte, self.error)
+        } else {
+            write!(f, "{}: {}", self.position, self.error)
+        }
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
 /// Possible reader states. The state transition diagram (`true` and `false` shows
@@ -564,6 +1103,10 @@ pub type Span = Range<u64>;
 ///   end
 ///   InsideText     -- "#lt;true#gt;"\nStart --> InsideEmpty
 ///   InsideEmpty    -- End                   --> InsideText
+///   InsideText     -- "#lt;recover#gt;"\nEnd --> ImpliedEnd
+///   ImpliedEnd     -- End                   --> InsideText
+///   InsideText     -- "#lt;html#gt;"\nStart --> InsideRawText
+///   InsideRawText  -- Text                  --> ImpliedEnd
 ///   _ -. Eof .-> Done
 /// ```
 #[derive(Clone, Debug)]
@@ -595,6 +1138,23 @@ enum ParseState {
     ///
     /// [`expand_empty_elements`]: Config::expand_empty_elements
     InsideEmpty,
+    /// This state is used only if option [`recover_errors`] is set to `true`.
+    /// Reader enters to this state when it is in a `InsideText` state and reads
+    /// an end tag which closes not the last opened element, but one of the outer
+    /// ones. Each next event emitted will be an [`Event::End`] for the next opened
+    /// element, the last one is for the element named in the end tag, after which
+    /// reader returned to the `InsideText` state.
+    ///
+    /// [`recover_errors`]: Config::recover_errors
+    ImpliedEnd,
+    /// This state is used only if option [`html`] is set to `true`. Reader enters
+    /// to this state when it is in a `InsideText` state and emits an [`Event::Start`]
+    /// event for an element which content is a raw text, for example, `<script>`.
+    /// The content up to the end tag is returned in the [`Event::Text`] event,
+    /// after which reader moves to the `ImpliedEnd` state to emit an [`Event::End`].
+    ///
+    /// [`html`]: Config::html
+    InsideRawText,
     /// Reader enters this state when `Eof` event generated or an error occurred.
     /// This is the last state, the reader stay in it forever.
     Done,
@@ -610,6 +1170,7 @@ enum ParseState {
 ///   Implicit    -- BOM            --> BomDetected
 ///   Implicit    -- "encoding=..." --> XmlDetected
 ///   BomDetected -- "encoding=..." --> XmlDetected
+///   Implicit    -- UTF-16         --> Transcoded
 /// ```
 #[cfg(feature = "encoding")]
 #[derive(Clone, Copy, Debug)]
@@ -626,6 +1187,10 @@ enum EncodingRef {
     /// Encoding was detected using XML declaration event (`<?xml encoding=... ?>`).
     /// It can no longer change
     XmlDetected(&'static Encoding),
+    /// Encoding, detected from a BOM or by the first bytes of the content, is
+    /// not ASCII-compatible (UTF-16), so input is transcoded to UTF-8 before
+    /// parsing. It can no longer change
+    Transcoded,
 }
 #[cfg(feature = "encoding")]
 impl EncodingRef {
@@ -636,13 +1201,15 @@ impl EncodingRef {
             Self::Explicit(e) => e,
             Self::BomDetected(e) => e,
             Self::XmlDetected(e) => e,
+            // Events contain transcoded data
+            Self::Transcoded => encoding_rs::UTF_8,
         }
     }
     #[inline]
     const fn can_be_refined(&self) -> bool {
         match self {
             Self::Implicit(_) | Self::BomDetected(_) => true,
-            Self::Explicit(_) | Self::XmlDetected(_) => false,
+            Self::Explicit(_) | Self::XmlDetected(_) | Self::Transcoded => false,
         }
     }
 }
@@ -656,6 +1223,9 @@ impl EncodingRef {
 pub struct BinaryStream<'r, R> {
     inner: &'r mut R,
     offset: &'r mut u64,
+    /// Transcoder of the reader, which can hold bytes already read from `inner`
+    #[cfg(feature = "encoding")]
+    transcoder: &'r mut Option<Transcoder>,
 }
 
 impl<'r, R> BinaryStream<'r, R> {
@@ -687,6 +1257,17 @@ where
 {
     #[inline]
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        #[cfg(feature = "encoding")]
+        if let Some(transcoder) = self.transcoder {
+            let ahead = transcoder.read_ahead()?;
+            if !ahead.is_empty() {
+                let amt = ahead.len().min(buf.len());
+                buf[..amt].copy_from_slice(&ahead[..amt]);
+                transcoder.consume(amt);
+                *self.offset += amt as u64;
+                return Ok(amt);
+            }
+        }
         let amt = self.inner.read(buf)?;
         *self.offset += amt as u64;
         Ok(amt)
@@ -699,13 +1280,26 @@ where
 {
     #[inline]
     fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        #[cfg(feature = "encoding")]
+        if let Some(transcoder) = self.transcoder {
+            let ahead = transcoder.read_ahead()?;
+            if !ahead.is_empty() {
+                return Ok(ahead);
+            }
+        }
         self.inner.fill_buf()
     }
 
     #[inline]
     fn consume(&mut self, amt: usize) {
-        self.inner.consume(amt);
         *self.offset += amt as u64;
+        #[cfg(feature = "encoding")]
+        if let Some(transcoder) = self.transcoder {
+            if !transcoder.is_finished() {
+                return transcoder.consume(amt);
+            }
+        }
+        self.inner.consume(amt);
     }
 }
 
@@ -771,6 +1365,16 @@ pub struct Reader<R> {
     reader: R,
     /// Configuration and current parse state
     state: ReaderState,
+    /// If the document is UTF-16 encoded, contains the transcoding state.
+    /// Data read from `reader` is transcoded to UTF-8 before parsing
+    #[cfg(feature = "encoding")]
+    transcoder: Option<Transcoder>,
+    /// Entities declared in the DTD and the state of their expansion.
+    /// Used only when [`Config::expand_entities`] is enabled
+    entities: Entities,
+    /// Default values of attributes declared in the DTD.
+    /// Used only when [`Config::default_attributes`] is enabled
+    defaults: Defaults,
 }
 
 /// Builder methods
@@ -780,6 +1384,10 @@ impl<R> Reader<R> {
         Self {
             reader,
             state: ReaderState::default(),
+            #[cfg(feature = "encoding")]
+            transcoder: None,
+            entities: Entities::default(),
+            defaults: Defaults::default(),
         }
     }
 
@@ -895,6 +1503,37 @@ impl<R> Reader<R> {
         self.state.last_error_offset
     }
 
+    /// Gets the position in the input data just after the last emitted event,
+    /// including line and column.
+    ///
+    /// Line and column are only tracked if [`Config::track_position`] is enabled,
+    /// otherwise they are always `0` and only [`Position::byte`] is meaningful.
+    /// The byte offset is the same as returned by [`buffer_position()`].
+    ///
+    /// Position of the errors is reported by the [`Error::Positioned`] error.
+    ///
+    /// [`buffer_position()`]: Self::buffer_position
+    pub fn position(&self) -> Position {
+        self.state.position()
+    }
+
+    /// Returns errors from which the reader has recovered so far, in order of
+    /// their appearance. Errors are collected only when [`Config::recover_errors`]
+    /// is enabled.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.state.diagnostics
+    }
+
+    /// Returns errors from which the reader has recovered since the last call
+    /// of this method and clears the list of them.
+    ///
+    /// See [`diagnostics()`] for details.
+    ///
+    /// [`diagnostics()`]: Self::diagnostics
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.state.diagnostics)
+    }
+
     /// Get the decoder, used to decode bytes, read by this reader, to the strings.
     ///
     /// If [`encoding`] feature is enabled, the used encoding may change after
@@ -909,6 +1548,17 @@ impl<R> Reader<R> {
         self.state.decoder()
     }
 
+    /// Returns the version of the document declared in the XML declaration,
+    /// or [`XmlVersion::Implicit1_0`] if the declaration was not read yet.
+    #[cfg(all(
+        feature = "serialize",
+        any(feature = "async-tokio", feature = "async-futures")
+    ))]
+    #[inline]
+    pub(crate) const fn xml_version(&self) -> crate::XmlVersion {
+        self.state.version
+    }
+
     /// Get the direct access to the underlying reader, but tracks the amount of
     /// read data and update [`Reader::buffer_position()`] accordingly.
     ///
@@ -916,6 +1566,11 @@ impl<R> Reader<R> {
     /// data will not be returned in any subsequent events read by `read_event`
     /// family of methods.
     ///
+    /// When the `encoding` feature is enabled and the document is UTF-16 encoded,
+    /// the reader transcodes the input to UTF-8 and keeps the transcoded data that
+    /// was read ahead. Raw bytes of the input are not available then, so reading
+    /// from the stream returns an error of the [`io::ErrorKind::Unsupported`] kind.
+    ///
     /// # Example
     ///
     /// This example demonstrates how to read stream raw bytes from an XML document.
@@ -966,6 +1621,8 @@ impl<R> Reader<R> {
         BinaryStream {
             inner: &mut self.reader,
             offset: &mut self.state.offset,
+            #[cfg(feature = "encoding")]
+            transcoder: &mut self.transcoder,
         }
     }
 }
@@ -975,13 +1632,147 @@ impl<R> Reader<R> {
     /// Read text into the given buffer, and return an event that borrows from
     /// either that buffer or from the input itself, based on the type of the
     /// reader.
-    fn read_event_impl<'i, B>(&mut self, mut buf: B) -> Result<Event<'i>, Error>
+    fn read_event_impl<'i, B>(&mut self, buf: B) -> Result<Event<'i>, Error>
+    where
+        R: XmlSource<'i, B>,
+    {
+        let event = match self.read_expanded() {
+            Some(event) => event,
+            None => {
+                let event = self.read_source_event(buf);
+                self.expand_entities(event)
+            }
+        };
+        let event = self.default_attributes(event);
+        self.count_event(event)
+    }
+
+    /// Reads the next event from the underlying source of data. Entities are
+    /// not expanded.
+    fn read_source_event<'i, B>(&mut self, mut buf: B) -> Result<Event<'i>, Error>
     where
         R: XmlSource<'i, B>,
     {
+        #[cfg(feature = "encoding")]
+        {
+            // UTF-16 documents cannot be parsed as is, so check if we need to
+            // transcode the input before the first event
+            if matches!(self.state.state, ParseState::Init) && self.state.encoding.can_be_refined()
+            {
+                let mut consumed = Vec::new();
+                let detected = self.reader.peek_encoding(&mut consumed)?;
+                match detected.and_then(|detected| Transcoder::new(detected.encoding())) {
+                    Some(transcoder) => {
+                        self.state.encoding = EncodingRef::Transcoded;
+                        self.transcoder = Some(transcoder.with_pending(consumed));
+                    }
+                    None if !consumed.is_empty() => {
+                        self.transcoder = Some(Transcoder::replay(consumed));
+                    }
+                    None => {}
+                }
+            }
+            if self
+                .transcoder
+                .as_ref()
+                .is_some_and(Transcoder::is_finished)
+            {
+                self.transcoder = None;
+            }
+            if self.transcoder.is_some() {
+                return R::read_transcoded(self, buf);
+            }
+        }
         read_event_impl!(self, buf, self.reader, read_until_close)
     }
 
+    /// Captures entities from the [`DocType`] event and starts expansion of
+    /// referenced entities if [`Config::expand_entities`] is enabled.
+    ///
+    /// [`DocType`]: Event::DocType
+    fn expand_entities<'i>(&mut self, event: Result<Event<'i>, Error>) -> Result<Event<'i>, Error> {
+        if !self.state.config.expand_entities {
+            return event;
+        }
+        match event {
+            Ok(Event::DocType(e)) => match self.entities.capture(&e) {
+                Ok(()) => Ok(Event::DocType(e)),
+                Err(error) => Err(self.expansion_error(error)),
+            },
+            Ok(Event::GeneralRef(e)) => {
+                match self
+                    .entities
+                    .push(&e, &self.state.config, self.state.depth())
+                {
+                    // We cannot read the next event from the source here, because
+                    // `buf` is already borrowed by the reference, so an entity which
+                    // expands to nothing is returned as an empty text, as documented
+                    // in `Config::expand_entities`
+                    Ok(true) => self
+                        .read_expanded()
+                        .unwrap_or_else(|| Ok(Event::Text(BytesText::from_escaped("")))),
+                    Ok(false) => Ok(Event::GeneralRef(e)),
+                    Err(error) => Err(self.expansion_error(error)),
+                }
+            }
+            event => event,
+        }
+    }
+
+    /// Captures default values of attributes from the [`DocType`] event and
+    /// adds missing attributes to elements if [`Config::default_attributes`]
+    /// is enabled.
+    ///
+    /// [`DocType`]: Event::DocType
+    fn default_attributes<'i>(
+        &mut self,
+        mut event: Result<Event<'i>, Error>,
+    ) -> Result<Event<'i>, Error> {
+        if !self.state.config.default_attributes {
+            return event;
+        }
+        let result = match event {
+            Ok(Event::DocType(ref e)) => self.defaults.capture(e),
+            Ok(Event::Start(ref mut e)) | Ok(Event::Empty(ref mut e)) => self.defaults.apply(e),
+            _ => Ok(()),
+        };
+        match result {
+            Ok(()) => event,
+            Err(error) => Err(self.state.with_position(error, self.state.position())),
+        }
+    }
+
+    /// Reads the next event from the replacement text of expanded entity.
+    /// Returns `None` if expansion is finished.
+    fn read_expanded(&mut self) -> Option<Result<Event<'static>, Error>> {
+        let event = self.entities.read_event(&self.state.config)?;
+        Some(event.map_err(|error| self.expansion_error(error)))
+    }
+
+    /// Checks that the number of read events does not exceed [`Config::max_events`].
+    fn count_event<'i>(&mut self, event: Result<Event<'i>, Error>) -> Result<Event<'i>, Error> {
+        match event {
+            Ok(Event::Eof) | Err(_) => event,
+            Ok(event) => match self.state.count_event() {
+                Ok(()) => Ok(event),
+                Err(error) => {
+                    self.entities.clear();
+                    Err(self.state.with_position(error, self.state.position()))
+                }
+            },
+        }
+    }
+
+    /// Stops reading if error is fatal and attaches the position of the current
+    /// entity reference to it.
+    fn expansion_error(&mut self, error: Error) -> Error {
+        if !matches!(error, Error::IllFormed(_)) {
+            self.entities.clear();
+            self.state.state = ParseState::Done;
+        }
+        self.state.with_position(error, self.state.position())
+    }
+
     /// Private function to read until `>` is found. This function expects that
     /// it was called just after encounter a `<` symbol.
     fn read_until_close<'i, B>(&mut self, buf: B) -> Result<Event<'i>, Error>
@@ -990,6 +1781,35 @@ impl<R> Reader<R> {
     {
         read_until_close!(self, buf, self.reader)
     }
+
+    /// Reads the next event from the input transcoded from UTF-16 to UTF-8.
+    /// The returned event borrows from `buf`.
+    #[cfg(feature = "encoding")]
+    fn read_transcoded_event<'b>(&mut self, mut buf: &'b mut Vec<u8>) -> Result<Event<'b>, Error>
+    where
+        R: io::BufRead,
+    {
+        read_event_impl!(
+            self,
+            buf,
+            Transcoder::reader(&mut self.reader, &mut self.transcoder),
+            read_transcoded_until_close
+        )
+    }
+
+    /// Same as [`Self::read_until_close()`], but reads from the input transcoded
+    /// from UTF-16 to UTF-8.
+    #[cfg(feature = "encoding")]
+    fn read_transcoded_until_close<'b>(&mut self, buf: &'b mut Vec<u8>) -> Result<Event<'b>, Error>
+    where
+        R: io::BufRead,
+    {
+        read_until_close!(
+            self,
+            buf,
+            Transcoder::reader(&mut self.reader, &mut self.transcoder)
+        )
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -1014,8 +1834,8 @@ enum ReadTextResult<'r, B> {
     /// Contains text block up to EOF, neither start of markup (`<` character)
     /// or start of reference (`&` character) was found.
     UpToEof(&'r [u8]),
-    /// IO error occurred.
-    Err(io::Error),
+    /// IO error occurred or text is too long.
+    Err(Error),
 }
 
 /// Result of an attempt to read general reference from the reader.
@@ -1034,8 +1854,8 @@ enum ReadRefResult<'r> {
     /// Contains text block up to start of markup (`<` character).
     /// Result includes start `&`.
     UpToMarkup(&'r [u8]),
-    /// IO error occurred.
-    Err(io::Error),
+    /// IO error occurred or reference is too long.
+    Err(Error),
 }
 
 /// Represents an input for a reader that can return borrowed data.
@@ -1061,6 +1881,19 @@ trait XmlSource<'r, B> {
     #[cfg(feature = "encoding")]
     fn detect_encoding(&mut self) -> io::Result<Option<DetectedEncoding>>;
 
+    /// Determines encoding from the first 4 bytes of input. Nothing is consumed
+    /// if they are available at once, otherwise the bytes consumed in order
+    /// to read them are stored in `consumed`.
+    #[cfg(feature = "encoding")]
+    fn peek_encoding(&mut self, consumed: &mut Vec<u8>) -> io::Result<Option<DetectedEncoding>>;
+
+    /// Reads the next event from the input that is transcoded to UTF-8 by the
+    /// [`Transcoder`] of the `reader`.
+    #[cfg(feature = "encoding")]
+    fn read_transcoded(reader: &mut Reader<Self>, buf: B) -> Result<Event<'r>, Error>
+    where
+        Self: Sized;
+
     /// Read input until start of markup (the `<`) is found, start of general entity
     /// reference (the `&`) is found or end of input is reached.
     ///
@@ -1068,9 +1901,12 @@ trait XmlSource<'r, B> {
     /// - `buf`: Buffer that could be filled from an input (`Self`) and
     ///   from which [events] could borrow their data
     /// - `position`: Will be increased by amount of bytes consumed
+    /// - `limit`: Maximum size of text. If text is longer, [`TextSize`] error
+    ///   is returned
     ///
     /// [events]: crate::events::Event
-    fn read_text(&mut self, buf: B, position: &mut u64) -> ReadTextResult<'r, B>;
+    /// [`TextSize`]: crate::errors::Limit::TextSize
+    fn read_text(&mut self, buf: B, position: &mut u64, limit: usize) -> ReadTextResult<'r, B>;
 
     /// Read input until end of general reference (the `;`) is found, start of
     /// another general reference (the `&`) is found or end of input is reached.
@@ -1081,9 +1917,12 @@ trait XmlSource<'r, B> {
     /// - `buf`: Buffer that could be filled from an input (`Self`) and
     ///   from which [events] could borrow their data
     /// - `position`: Will be increased by amount of bytes consumed
+    /// - `limit`: Maximum size of reference. If reference is longer,
+    ///   [`TextSize`] error is returned
     ///
     /// [events]: crate::events::Event
-    fn read_ref(&mut self, buf: B, position: &mut u64) -> ReadRefResult<'r>;
+    /// [`TextSize`]: crate::errors::Limit::TextSize
+    fn read_ref(&mut self, buf: B, position: &mut u64, limit: usize) -> ReadRefResult<'r>;
 
     /// Read input until processing instruction is finished.
     ///
@@ -1099,12 +1938,21 @@ trait XmlSource<'r, B> {
     /// - `buf`: Buffer that could be filled from an input (`Self`) and
     ///   from which [events] could borrow their data
     /// - `position`: Will be increased by amount of bytes consumed
+    /// - `limit`: Maximum size of markup including `<` and `>`. If markup is
+    ///   longer, [`TextSize`] error is returned
     ///
     /// A `P` type parameter is used to preserve state between calls to the underlying
     /// reader which provides bytes fed into the parser.
     ///
     /// [events]: crate::events::Event
-    fn read_with<P>(&mut self, parser: P, buf: B, position: &mut u64) -> Result<&'r [u8], Error>
+    /// [`TextSize`]: crate::errors::Limit::TextSize
+    fn read_with<P>(
+        &mut self,
+        parser: P,
+        buf: B,
+        position: &mut u64,
+        limit: usize,
+    ) -> Result<&'r [u8], Error>
     where
         P: Parser;
 
@@ -1121,12 +1969,16 @@ trait XmlSource<'r, B> {
     /// - `buf`: Buffer that could be filled from an input (`Self`) and
     ///   from which [events] could borrow their data
     /// - `position`: Will be increased by amount of bytes consumed
+    /// - `limit`: Maximum size of markup including `<` and `>`. If markup is
+    ///   longer, [`TextSize`] error is returned
     ///
     /// [events]: crate::events::Event
+    /// [`TextSize`]: crate::errors::Limit::TextSize
     fn read_bang_element(
         &mut self,
         buf: B,
         position: &mut u64,
+        limit: usize,
     ) -> Result<(BangType, &'r [u8]), Error>;
 
     /// Consume and discard all the whitespace until the next non-whitespace
@@ -1134,11 +1986,44 @@ trait XmlSource<'r, B> {
     ///
     /// # Parameters
     /// - `position`: Will be increased by amount of bytes consumed
-    fn skip_whitespace(&mut self, position: &mut u64) -> io::Result<()>;
+    /// - `tracked`: Will be advanced over consumed bytes
+    fn skip_whitespace(&mut self, position: &mut u64, tracked: &mut Position) -> io::Result<()>;
 
     /// Return one character without consuming it, so that future `read_*` calls
     /// will still include it. On EOF, return `None`.
     fn peek_one(&mut self) -> io::Result<Option<u8>>;
+
+    /// Consumes the `<` character after [`peek_one()`] was called. Used in the
+    /// recovery mode, when `<` does not start a markup.
+    ///
+    /// # Parameters
+    /// - `position`: Will be increased by amount of bytes consumed
+    ///
+    /// [`peek_one()`]: Self::peek_one
+    fn skip_lt(&mut self, position: &mut u64);
+
+    /// Read input until the end tag of a raw text element is found or end of
+    /// input is reached. Returns the read data, including the end tag, if it
+    /// was found, in which case the length of the end tag can be got from the
+    /// `parser`.
+    ///
+    /// # Parameters
+    /// - `parser`: Parser that searches the end tag
+    /// - `buf`: Buffer that could be filled from an input (`Self`) and
+    ///   from which [events] could borrow their data
+    /// - `position`: Will be increased by amount of bytes consumed
+    /// - `limit`: Maximum size of text including the end tag. If text is longer,
+    ///   [`TextSize`] error is returned
+    ///
+    /// [events]: crate::events::Event
+    /// [`TextSize`]: crate::errors::Limit::TextSize
+    fn read_raw_text(
+        &mut self,
+        parser: &mut RawTextParser,
+        buf: B,
+        position: &mut u64,
+        limit: usize,
+    ) -> Result<(&'r [u8], bool), Error>;
 }
 
 /// Possible elements started with `<!`
@@ -1228,6 +2113,13 @@ impl BangType {
     }
 }
 
+/// Returns `true` if the byte after `<` can start a markup: a tag name, `!`,
+/// `?` or `/`. Non-ASCII bytes are assumed to be a part of a name.
+#[inline]
+const fn is_markup_start(byte: u8) -> bool {
+    matches!(byte, b'!' | b'?' | b'/' | b':' | b'_' | b'A'..=b'Z' | b'a'..=b'z') || byte >= 0x80
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
 #[cfg(test)]
@@ -1266,7 +2158,7 @@ mod test {
                         let mut input = &b"<![]]>other content"[$skip..];
                         //                 ^= 0
 
-                        match $source(&mut input).read_bang_element(buf, &mut position) $(.$await)? {
+                        match $source(&mut input).read_bang_element(buf, &mut position, usize::MAX) $(.$await)? {
                             Err(Error::Syntax(cause)) => assert_eq!(cause, SyntaxError::UnclosedCData),
                             x => panic!(
                                 "Expected `Err(Syntax(_))`, but got `{:?}`",
@@ -1285,7 +2177,7 @@ mod test {
                         let mut input = &b"<![CDATA[other content"[$skip..];
                         //                 ^= 0                  ^= 22
 
-                        match $source(&mut input).read_bang_element(buf, &mut position) $(.$await)? {
+                        match $source(&mut input).read_bang_element(buf, &mut position, usize::MAX) $(.$await)? {
                             Err(Error::Syntax(cause)) => assert_eq!(cause, SyntaxError::UnclosedCData),
                             x => panic!(
                                 "Expected `Err(Syntax(_))`, but got `{:?}`",
@@ -1304,7 +2196,7 @@ mod test {
                         //                ^= 0        ^= 12
 
                         let (ty, bytes) = $source(&mut input)
-                            .read_bang_element(buf, &mut position)
+                            .read_bang_element(buf, &mut position, usize::MAX)
                             $(.$await)?
                             .unwrap();
                         assert_eq!(
@@ -1325,7 +2217,7 @@ mod test {
                         //                 ^= 0                         ^= 29
 
                         let (ty, bytes) = $source(&mut input)
-                            .read_bang_element(buf, &mut position)
+                            .read_bang_element(buf, &mut position, usize::MAX)
                             $(.$await)?
                             .unwrap();
                         assert_eq!(
@@ -1364,7 +2256,7 @@ mod test {
                         let mut input = &b"<!- -->other content"[$skip..];
                         //                  ^= 1
 
-                        match $source(&mut input).read_bang_element(buf, &mut position) $(.$await)? {
+                        match $source(&mut input).read_bang_element(buf, &mut position, usize::MAX) $(.$await)? {
                             Err(Error::Syntax(cause)) => assert_eq!(cause, SyntaxError::UnclosedComment),
                             x => panic!(
                                 "Expected `Err(Syntax(_))`, but got `{:?}`",
@@ -1381,7 +2273,7 @@ mod test {
                         let mut input = &b"<!->other content"[$skip..];
                         //                 ^= 0             ^= 17
 
-                        match $source(&mut input).read_bang_element(buf, &mut position) $(.$await)? {
+                        match $source(&mut input).read_bang_element(buf, &mut position, usize::MAX) $(.$await)? {
                             Err(Error::Syntax(cause)) => assert_eq!(cause, SyntaxError::UnclosedComment),
                             x => panic!(
                                 "Expected `Err(Syntax(_))`, but got `{:?}`",
@@ -1398,7 +2290,7 @@ mod test {
                         let mut input = &b"<!--other content"[$skip..];
                         //                 ^= 0             ^= 17
 
-                        match $source(&mut input).read_bang_element(buf, &mut position) $(.$await)? {
+                        match $source(&mut input).read_bang_element(buf, &mut position, usize::MAX) $(.$await)? {
                             Err(Error::Syntax(cause)) => assert_eq!(cause, SyntaxError::UnclosedComment),
                             x => panic!(
                                 "Expected `Err(Syntax(_))`, but got `{:?}`",
@@ -1415,7 +2307,7 @@ mod test {
                         let mut input = &b"<!-->other content"[$skip..];
                         //                 ^= 0              ^= 18
 
-                        match $source(&mut input).read_bang_element(buf, &mut position) $(.$await)? {
+                        match $source(&mut input).read_bang_element(buf, &mut position, usize::MAX) $(.$await)? {
                             Err(Error::Syntax(cause)) => assert_eq!(cause, SyntaxError::UnclosedComment),
                             x => panic!(
                                 "Expected `Err(Syntax(_))`, but got `{:?}`",
@@ -1432,7 +2324,7 @@ mod test {
                         let mut input = &b"<!--->other content"[$skip..];
                         //                 ^= 0               ^= 19
 
-                        match $source(&mut input).read_bang_element(buf, &mut position) $(.$await)? {
+                        match $source(&mut input).read_bang_element(buf, &mut position, usize::MAX) $(.$await)? {
                             Err(Error::Syntax(cause)) => assert_eq!(cause, SyntaxError::UnclosedComment),
                             x => panic!(
                                 "Expected `Err(Syntax(_))`, but got `{:?}`",
@@ -1450,7 +2342,7 @@ mod test {
                         //                 ^= 0   ^= 7
 
                         let (ty, bytes) = $source(&mut input)
-                            .read_bang_element(buf, &mut position)
+                            .read_bang_element(buf, &mut position, usize::MAX)
                             $(.$await)?
                             .unwrap();
                         assert_eq!(
@@ -1468,7 +2360,7 @@ mod test {
                         //                 ^= 0              ^= 18
 
                         let (ty, bytes) = $source(&mut input)
-                            .read_bang_element(buf, &mut position)
+                            .read_bang_element(buf, &mut position, usize::MAX)
                             $(.$await)?
                             .unwrap();
                         assert_eq!(
@@ -1494,7 +2386,7 @@ mod test {
                             let mut input = &b"<!D other content"[$skip..];
                             //                 ^= 0             ^= 17
 
-                            match $source(&mut input).read_bang_element(buf, &mut position) $(.$await)? {
+                            match $source(&mut input).read_bang_element(buf, &mut position, usize::MAX) $(.$await)? {
                                 Err(Error::Syntax(cause)) => assert_eq!(cause, SyntaxError::UnclosedDoctype),
                                 x => panic!(
                                     "Expected `Err(Syntax(_))`, but got `{:?}`",
@@ -1511,7 +2403,7 @@ mod test {
                             let mut input = &b"<!DOCTYPEother content"[$skip..];
                             //                 ^= 0                  ^= 22
 
-                            match $source(&mut input).read_bang_element(buf, &mut position) $(.$await)? {
+                            match $source(&mut input).read_bang_element(buf, &mut position, usize::MAX) $(.$await)? {
                                 Err(Error::Syntax(cause)) => assert_eq!(cause, SyntaxError::UnclosedDoctype),
                                 x => panic!(
                                     "Expected `Err(Syntax(_))`, but got `{:?}`",
@@ -1529,7 +2421,7 @@ mod test {
                             //                 ^= 0      ^= 10
 
                             let (ty, bytes) = $source(&mut input)
-                                .read_bang_element(buf, &mut position)
+                                .read_bang_element(buf, &mut position, usize::MAX)
                                 $(.$await)?
                                 .unwrap();
                             assert_eq!(
@@ -1546,7 +2438,7 @@ mod test {
                             let mut input = &b"<!DOCTYPE other content"[$skip..];
                             //                 ^= 0                   ^23
 
-                            match $source(&mut input).read_bang_element(buf, &mut position) $(.$await)? {
+                            match $source(&mut input).read_bang_element(buf, &mut position, usize::MAX) $(.$await)? {
                                 Err(Error::Syntax(cause)) => assert_eq!(cause, SyntaxError::UnclosedDoctype),
                                 x => panic!(
                                     "Expected `Err(Syntax(_))`, but got `{:?}`",
@@ -1568,7 +2460,7 @@ mod test {
                             let mut input = &b"<!d other content"[$skip..];
                             //                 ^= 0             ^= 17
 
-                            match $source(&mut input).read_bang_element(buf, &mut position) $(.$await)? {
+                            match $source(&mut input).read_bang_element(buf, &mut position, usize::MAX) $(.$await)? {
                                 Err(Error::Syntax(cause)) => assert_eq!(cause, SyntaxError::UnclosedDoctype),
                                 x => panic!(
                                     "Expected `Err(Syntax(_))`, but got `{:?}`",
@@ -1585,7 +2477,7 @@ mod test {
                             let mut input = &b"<!doctypeother content"[$skip..];
                             //                 ^= 0                  ^= 22
 
-                            match $source(&mut input).read_bang_element(buf, &mut position) $(.$await)? {
+                            match $source(&mut input).read_bang_element(buf, &mut position, usize::MAX) $(.$await)? {
                                 Err(Error::Syntax(cause)) => assert_eq!(cause, SyntaxError::UnclosedDoctype),
                                 x => panic!(
                                     "Expected `Err(Syntax(_))`, but got `{:?}`",
@@ -1603,7 +2495,7 @@ mod test {
                             //                 ^= 0      ^= 10
 
                             let (ty, bytes) = $source(&mut input)
-                                .read_bang_element(buf, &mut position)
+                                .read_bang_element(buf, &mut position, usize::MAX)
                                 $(.$await)?
                                 .unwrap();
                             assert_eq!(
@@ -1620,7 +2512,7 @@ mod test {
                             let mut input = &b"<!doctype other content"[$skip..];
                             //                 ^= 0                   ^= 23
 
-                            match $source(&mut input).read_bang_element(buf, &mut position) $(.$await)? {
+                            match $source(&mut input).read_bang_element(buf, &mut position, usize::MAX) $(.$await)? {
                                 Err(Error::Syntax(cause)) => assert_eq!(cause, SyntaxError::UnclosedDoctype),
                                 x => panic!(
                                     "Expected `Err(Syntax(_))`, but got `{:?}`",
@@ -1646,7 +2538,7 @@ mod test {
                     let mut input = b"".as_ref();
                     //                ^= 1
 
-                    match $source(&mut input).read_text(buf, &mut position) $(.$await)? {
+                    match $source(&mut input).read_text(buf, &mut position, usize::MAX) $(.$await)? {
                         ReadTextResult::UpToEof(bytes) => assert_eq!(Bytes(bytes), Bytes(b"")),
                         x => panic!("Expected `UpToEof(_)`, but got `{:?}`", x),
                     }
@@ -1660,7 +2552,7 @@ mod test {
                     let mut input = b"<".as_ref();
                     //                 ^= 1
 
-                    match $source(&mut input).read_text(buf, &mut position) $(.$await)? {
+                    match $source(&mut input).read_text(buf, &mut position, usize::MAX) $(.$await)? {
                         ReadTextResult::Markup(b) => assert_eq!(b, $buf),
                         x => panic!("Expected `Markup(_)`, but got `{:?}`", x),
                     }
@@ -1674,7 +2566,7 @@ mod test {
                     let mut input = b"&".as_ref();
                     //                ^= 1
 
-                    match $source(&mut input).read_text(buf, &mut position) $(.$await)? {
+                    match $source(&mut input).read_text(buf, &mut position, usize::MAX) $(.$await)? {
                         ReadTextResult::Ref(b) => assert_eq!(b, $buf),
                         x => panic!("Expected `Ref(_)`, but got `{:?}`", x),
                     }
@@ -1688,7 +2580,7 @@ mod test {
                     let mut input = b"a<".as_ref();
                     //                  ^= 2
 
-                    match $source(&mut input).read_text(buf, &mut position) $(.$await)? {
+                    match $source(&mut input).read_text(buf, &mut position, usize::MAX) $(.$await)? {
                         ReadTextResult::UpToMarkup(bytes) => assert_eq!(Bytes(bytes), Bytes(b"a")),
                         x => panic!("Expected `UpToMarkup(_)`, but got `{:?}`", x),
                     }
@@ -1702,7 +2594,7 @@ mod test {
                     let mut input = b"a&".as_ref();
                     //                 ^= 2
 
-                    match $source(&mut input).read_text(buf, &mut position) $(.$await)? {
+                    match $source(&mut input).read_text(buf, &mut position, usize::MAX) $(.$await)? {
                         ReadTextResult::UpToRef(bytes) => assert_eq!(Bytes(bytes), Bytes(b"a")),
                         x => panic!("Expected `UpToRef(_)`, but got `{:?}`", x),
                     }
@@ -1716,7 +2608,7 @@ mod test {
                     let mut input = b"a".as_ref();
                     //                 ^= 2
 
-                    match $source(&mut input).read_text(buf, &mut position) $(.$await)? {
+                    match $source(&mut input).read_text(buf, &mut position, usize::MAX) $(.$await)? {
                         ReadTextResult::UpToEof(bytes) => assert_eq!(Bytes(bytes), Bytes(b"a")),
                         x => panic!("Expected `UpToEof(_)`, but got `{:?}`", x),
                     }
@@ -1741,7 +2633,7 @@ mod test {
                     let mut input = b"&".as_ref();
                     //                 ^= 2
 
-                    match $source(&mut input).read_ref(buf, &mut position) $(.$await)? {
+                    match $source(&mut input).read_ref(buf, &mut position, usize::MAX) $(.$await)? {
                         ReadRefResult::UpToEof(bytes) => assert_eq!(Bytes(bytes), Bytes(b"&")),
                         x => panic!("Expected `UpToEof(_)`, but got `{:?}`", x),
                     }
@@ -1755,7 +2647,7 @@ mod test {
                     let mut input = b"&&".as_ref();
                     //                 ^= 2
 
-                    match $source(&mut input).read_ref(buf, &mut position) $(.$await)? {
+                    match $source(&mut input).read_ref(buf, &mut position, usize::MAX) $(.$await)? {
                         ReadRefResult::UpToRef(bytes) => assert_eq!(Bytes(bytes), Bytes(b"&")),
                         x => panic!("Expected `UpToRef(_)`, but got `{:?}`", x),
                     }
@@ -1769,7 +2661,7 @@ mod test {
                     let mut input = b"&<".as_ref();
                     //                 ^= 2
 
-                    match $source(&mut input).read_ref(buf, &mut position) $(.$await)? {
+                    match $source(&mut input).read_ref(buf, &mut position, usize::MAX) $(.$await)? {
                         ReadRefResult::UpToMarkup(bytes) => assert_eq!(Bytes(bytes), Bytes(b"&")),
                         x => panic!("Expected `UpToMarkup(_)`, but got `{:?}`", x),
                     }
@@ -1783,7 +2675,7 @@ mod test {
                     let mut input = b"&;".as_ref();
                     //                  ^= 3
 
-                    match $source(&mut input).read_ref(buf, &mut position) $(.$await)? {
+                    match $source(&mut input).read_ref(buf, &mut position, usize::MAX) $(.$await)? {
                         ReadRefResult::Ref(bytes) => assert_eq!(Bytes(bytes), Bytes(b"&;")),
                         x => panic!("Expected `Ref(_)`, but got `{:?}`", x),
                     }
@@ -1797,7 +2689,7 @@ mod test {
                     let mut input = b"&lt;".as_ref();
                     //                    ^= 5
 
-                    match $source(&mut input).read_ref(buf, &mut position) $(.$await)? {
+                    match $source(&mut input).read_ref(buf, &mut position, usize::MAX) $(.$await)? {
                         ReadRefResult::Ref(bytes) => assert_eq!(Bytes(bytes), Bytes(b"&lt;")),
                         x => panic!("Expected `Ref(_)`, but got `{:?}`", x),
                     }
@@ -1822,7 +2714,7 @@ mod test {
                     let mut input = &b"<"[$skip..];
                     //                  ^= 1
 
-                    match $source(&mut input).read_with(ElementParser::default(), buf, &mut position) $(.$await)? {
+                    match $source(&mut input).read_with(ElementParser::default(), buf, &mut position, usize::MAX) $(.$await)? {
                         Err(Error::Syntax(cause)) => assert_eq!(cause, SyntaxError::UnclosedTag),
                         x => panic!(
                             "Expected `Err(Syntax(_))`, but got `{:?}`",
@@ -1844,7 +2736,7 @@ mod test {
                         //                   ^= 2
 
                         assert_eq!(
-                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position) $(.$await)? .unwrap()),
+                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position, usize::MAX) $(.$await)? .unwrap()),
                             Bytes(b"<>")
                         );
                         assert_eq!(position, 2);
@@ -1858,7 +2750,7 @@ mod test {
                         //                      ^= 5
 
                         assert_eq!(
-                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position) $(.$await)? .unwrap()),
+                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position, usize::MAX) $(.$await)? .unwrap()),
                             Bytes(b"<tag>")
                         );
                         assert_eq!(position, 5);
@@ -1872,7 +2764,7 @@ mod test {
                         //                    ^= 3
 
                         assert_eq!(
-                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position) $(.$await)? .unwrap()),
+                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position, usize::MAX) $(.$await)? .unwrap()),
                             Bytes(b"<:>")
                         );
                         assert_eq!(position, 3);
@@ -1886,7 +2778,7 @@ mod test {
                         //                       ^= 6
 
                         assert_eq!(
-                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position) $(.$await)? .unwrap()),
+                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position, usize::MAX) $(.$await)? .unwrap()),
                             Bytes(b"<:tag>")
                         );
                         assert_eq!(position, 6);
@@ -1900,7 +2792,7 @@ mod test {
                         //                                                          ^= 39
 
                         assert_eq!(
-                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position) $(.$await)? .unwrap()),
+                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position, usize::MAX) $(.$await)? .unwrap()),
                             Bytes(br#"<tag  attr-1=">"  attr2  =  '>'  3attr>"#)
                         );
                         assert_eq!(position, 39);
@@ -1919,7 +2811,7 @@ mod test {
                         //                    ^= 3
 
                         assert_eq!(
-                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position) $(.$await)? .unwrap()),
+                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position, usize::MAX) $(.$await)? .unwrap()),
                             Bytes(b"</>")
                         );
                         assert_eq!(position, 3);
@@ -1933,7 +2825,7 @@ mod test {
                         //                       ^= 6
 
                         assert_eq!(
-                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position) $(.$await)? .unwrap()),
+                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position, usize::MAX) $(.$await)? .unwrap()),
                             Bytes(b"<tag/>")
                         );
                         assert_eq!(position, 6);
@@ -1947,7 +2839,7 @@ mod test {
                         //                     ^= 4
 
                         assert_eq!(
-                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position) $(.$await)? .unwrap()),
+                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position, usize::MAX) $(.$await)? .unwrap()),
                             Bytes(b"<:/>")
                         );
                         assert_eq!(position, 4);
@@ -1961,7 +2853,7 @@ mod test {
                         //                        ^= 7
 
                         assert_eq!(
-                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position) $(.$await)? .unwrap()),
+                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position, usize::MAX) $(.$await)? .unwrap()),
                             Bytes(b"<:tag/>")
                         );
                         assert_eq!(position, 7);
@@ -1975,7 +2867,7 @@ mod test {
                         //                                                             ^= 42
 
                         assert_eq!(
-                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position) $(.$await)? .unwrap()),
+                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position, usize::MAX) $(.$await)? .unwrap()),
                             Bytes(br#"<tag  attr-1="/>"  attr2  =  '/>'  3attr/>"#)
                         );
                         assert_eq!(position, 42);
@@ -1994,7 +2886,7 @@ mod test {
                         //                     ^= 4
 
                         assert_eq!(
-                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position) $(.$await)? .unwrap()),
+                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position, usize::MAX) $(.$await)? .unwrap()),
                             Bytes(b"</ >")
                         );
                         assert_eq!(position, 4);
@@ -2008,7 +2900,7 @@ mod test {
                         //                       ^= 6
 
                         assert_eq!(
-                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position) $(.$await)? .unwrap()),
+                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position, usize::MAX) $(.$await)? .unwrap()),
                             Bytes(b"</tag>")
                         );
                         assert_eq!(position, 6);
@@ -2022,7 +2914,7 @@ mod test {
                         //                     ^= 4
 
                         assert_eq!(
-                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position) $(.$await)? .unwrap()),
+                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position, usize::MAX) $(.$await)? .unwrap()),
                             Bytes(b"</:>")
                         );
                         assert_eq!(position, 4);
@@ -2036,7 +2928,7 @@ mod test {
                         //                        ^= 7
 
                         assert_eq!(
-                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position) $(.$await)? .unwrap()),
+                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position, usize::MAX) $(.$await)? .unwrap()),
                             Bytes(b"</:tag>")
                         );
                         assert_eq!(position, 7);
@@ -2050,7 +2942,7 @@ mod test {
                         //                                                           ^= 40
 
                         assert_eq!(
-                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position) $(.$await)? .unwrap()),
+                            Bytes($source(&mut input).read_with(ElementParser::default(), buf, &mut position, usize::MAX) $(.$await)? .unwrap()),
                             Bytes(br#"</tag  attr-1=">"  attr2  =  '>'  3attr>"#)
                         );
                         assert_eq!(position, 40);
@@ -10,9 +10,9 @@ use std::ops::Deref;
 use std::path::Path;
 
 use crate::errors::Result;
-use crate::events::{BytesText, Event};
+use crate::events::{BytesStart, BytesText, Event};
 use crate::name::{NamespaceResolver, QName, ResolveResult};
-use crate::reader::{Config, Reader, Span, XmlSource};
+use crate::reader::{Config, Diagnostic, Reader, Span, XmlSource};
 
 /// A low level encoding-agnostic XML event reader that performs namespace resolution.
 ///
@@ -48,6 +48,15 @@ impl<R> NsReader<R> {
     pub fn config_mut(&mut self) -> &mut Config {
         self.reader.config_mut()
     }
+
+    /// Returns errors from which the reader has recovered since the last call
+    /// of this method and clears the list of them.
+    ///
+    /// See [`Reader::take_diagnostics()`] for details.
+    #[inline]
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        self.reader.take_diagnostics()
+    }
 }
 
 /// Private methods
@@ -77,17 +86,31 @@ impl<R> NsReader<R> {
         }
     }
 
+    /// Opens a new namespace scope and checks that attributes of the element
+    /// are unique. If the scope cannot be opened or attributes are duplicated,
+    /// the error is reported at the position just after the start tag.
+    fn push(&mut self, start: &BytesStart) -> Result<()> {
+        let resolver = &mut self.ns_resolver;
+        resolver
+            .push(start)
+            .and_then(|()| resolver.check_duplicates(start))
+            .map_err(|e| {
+                let state = &self.reader.state;
+                state.with_position(e.into(), state.position())
+            })
+    }
+
     pub(super) fn process_event<'i>(&mut self, event: Result<Event<'i>>) -> Result<Event<'i>> {
         match event {
             Ok(Event::Start(e)) => {
-                self.ns_resolver.push(&e)?;
+                self.push(&e)?;
                 Ok(Event::Start(e))
             }
             Ok(Event::Empty(e)) => {
-                self.ns_resolver.push(&e)?;
                 // notify next `read_event_impl()` invocation that it needs to pop this
-                // namespace scope
+                // namespace scope. The scope is opened even if `push` fails
                 self.pending_pop = true;
+                self.push(&e)?;
                 Ok(Event::Empty(e))
             }
             Ok(Event::End(e)) => {
@@ -0,0 +1,612 @@
+//! Contains a push-based XML reader, which is fed with chunks of data by the
+//! caller instead of pulling them from the underlying source.
+
+#[cfg(feature = "encoding")]
+use crate::encoding::{detect_encoding, EncodingError, Transcoder, UTF16_BE_BOM, UTF16_LE_BOM};
+use crate::encoding::{Decoder, UTF8_BOM};
+use crate::errors::{Error, IllFormedError, Limit, Result, SyntaxError};
+use crate::events::Event;
+use crate::parser::{ElementParser, Parser, PiParser};
+use crate::reader::html::RawTextParser;
+use crate::reader::state::ReaderState;
+use crate::reader::{is_markup_start, BangType, Config, Diagnostic, ParseState, Position};
+use crate::utils::is_whitespace;
+
+/// Returns [`PushEvent::NeedMoreData`] from the [`PushReader::read_event()`]. If
+/// the transcoder found malformed data and all data before it was read, breaks
+/// the parsing loop with an error instead.
+macro_rules! need_more_data {
+    ($self:ident) => {{
+        #[cfg(feature = "encoding")]
+        if let Some(transcoder) = $self.transcoder.as_ref().filter(|_| $self.malformed) {
+            let available = &$self.input[$self.start..];
+            let offset = $self.state.offset + available.len() as u64;
+            $self.state.set_error_offset(offset, available);
+            break Err(Error::Encoding(EncodingError::Other(transcoder.encoding())));
+        }
+        return Ok(PushEvent::NeedMoreData);
+    }};
+}
+
+/// A result of the [`PushReader::read_event()`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum PushEvent<'a> {
+    /// The next event was completely read
+    Event(Event<'a>),
+    /// The data fed to the reader so far ends in the middle of an event.
+    /// Call [`PushReader::feed()`] with the next chunk of data (or
+    /// [`PushReader::finish()`] if there is no more data) and try again.
+    NeedMoreData,
+}
+
+/// Parser of a markup which end was not found in the fed data yet
+#[derive(Debug)]
+enum Markup {
+    /// `<!...>`: comment, CDATA or DOCTYPE
+    Bang(BangType),
+    /// `<...>` or `</...>`: start, empty or end tag
+    Element(ElementParser),
+    /// `<?...?>`: processing instruction or XML declaration
+    Pi(PiParser),
+}
+
+/// A low level encoding-agnostic XML event reader, that is fed with chunks of
+/// data instead of reading them from an underlying source.
+///
+/// This reader is useful when data arrives in fragments of arbitrary size, for
+/// example, from a network socket in a non-blocking fashion, and it is not possible
+/// to provide a [`BufRead`] to the [`Reader`]. Each portion of data is passed
+/// to the [`feed()`] method, and then events are retrieved by calling
+/// [`read_event()`] until it returns [`PushEvent::NeedMoreData`]. Events are
+/// returned only when they are completely read, so each event has the same
+/// content as it would have when the whole document is read by the [`Reader`].
+///
+/// When the input is over, call [`finish()`] to get the rest events. After that
+/// text at the end of the document is returned and unclosed markup is reported
+/// as an error.
+///
+/// The reader keeps all data of the event being read in the internal buffer.
+/// Processed data is dropped from it on the next call to `feed()`.
+///
+/// With the `encoding` feature UTF-16 documents are detected by the BOM or by
+/// the first bytes of the XML declaration and fed data is transcoded to UTF-8,
+/// as the [`Reader`] does. Reported positions are positions in the transcoded
+/// data then. If the data is not a valid UTF-16, [`Error::Encoding`] is returned
+/// after all events before the malformed data.
+///
+/// [`Error::Encoding`]: crate::errors::Error::Encoding
+///
+/// # Example
+///
+/// ```
+/// # use pretty_assertions::assert_eq;
+/// use quick_xml::events::{BytesStart, BytesText, Event};
+/// use quick_xml::reader::{PushEvent, PushReader};
+///
+/// let mut reader = PushReader::new();
+///
+/// reader.feed(b"<stream><message to='ro");
+/// assert_eq!(
+///     reader.read_event().unwrap(),
+///     PushEvent::Event(Event::Start(BytesStart::new("stream")))
+/// );
+/// assert_eq!(reader.read_event().unwrap(), PushEvent::NeedMoreData);
+///
+/// reader.feed(b"meo'>Hello");
+/// assert_eq!(
+///     reader.read_event().unwrap(),
+///     PushEvent::Event(Event::Start(BytesStart::from_content("message to='romeo'", 7)))
+/// );
+/// // The text may continue in the next chunk
+/// assert_eq!(reader.read_event().unwrap(), PushEvent::NeedMoreData);
+///
+/// reader.feed(b", world!</message>");
+/// assert_eq!(
+///     reader.read_event().unwrap(),
+///     PushEvent::Event(Event::Text(BytesText::new("Hello, world!")))
+/// );
+/// assert!(matches!(reader.read_event().unwrap(), PushEvent::Event(Event::End(_))));
+/// assert_eq!(reader.read_event().unwrap(), PushEvent::NeedMoreData);
+///
+/// reader.finish();
+/// // `<stream>` was not closed, but `check_end_names` does not check that
+/// assert_eq!(reader.read_event().unwrap(), PushEvent::Event(Event::Eof));
+/// ```
+///
+/// [`BufRead`]: std::io::BufRead
+/// [`Reader`]: crate::reader::Reader
+/// [`feed()`]: Self::feed
+/// [`read_event()`]: Self::read_event
+/// [`finish()`]: Self::finish
+#[derive(Debug)]
+pub struct PushReader {
+    /// Parser state and configuration
+    state: ReaderState,
+    /// Data fed to the reader. Data before `start` is already processed and
+    /// will be dropped on the next call to `feed()`
+    input: Vec<u8>,
+    /// Index in `input` of the first byte of the incomplete piece of XML
+    start: usize,
+    /// Number of bytes after `start` that were already examined when searching
+    /// for the end of the incomplete piece of XML
+    scanned: usize,
+    /// Parser of the incomplete markup. Holds its state between chunks
+    markup: Option<Markup>,
+    /// Parser of the incomplete content of a raw text element in the HTML mode.
+    /// Holds its state between chunks
+    raw_text: Option<RawTextParser>,
+    /// `true` if no more data is expected. Stays `false` after [`Self::finish()`]
+    /// if the transcoder found malformed data, so an error will be returned
+    /// after all data before it will be read
+    eof: bool,
+    /// Transcoder of the fed data if the document is UTF-16 encoded
+    #[cfg(feature = "encoding")]
+    transcoder: Option<Transcoder>,
+    /// `true` if the transcoder found malformed data
+    #[cfg(feature = "encoding")]
+    malformed: bool,
+}
+
+impl PushReader {
+    /// Creates a reader without any data fed.
+    pub fn new() -> Self {
+        Self {
+            state: ReaderState::default(),
+            input: Vec::new(),
+            start: 0,
+            scanned: 0,
+            markup: None,
+            raw_text: None,
+            eof: false,
+            #[cfg(feature = "encoding")]
+            transcoder: None,
+            #[cfg(feature = "encoding")]
+            malformed: false,
+        }
+    }
+
+    /// Returns reference to the parser configuration
+    pub const fn config(&self) -> &Config {
+        &self.state.config
+    }
+
+    /// Returns mutable reference to the parser configuration
+    pub fn config_mut(&mut self) -> &mut Config {
+        &mut self.state.config
+    }
+
+    /// Gets the byte position in the input data just after the last emitted event
+    /// (i.e. this is position where data of last event ends).
+    ///
+    /// See [`Reader::buffer_position()`] for details.
+    ///
+    /// [`Reader::buffer_position()`]: crate::reader::Reader::buffer_position
+    pub const fn buffer_position(&self) -> u64 {
+        self.state.offset
+    }
+
+    /// Gets the last error byte position in the input data. If there is no errors
+    /// yet, returns `0`.
+    ///
+    /// See [`Reader::error_position()`] for details.
+    ///
+    /// [`Reader::error_position()`]: crate::reader::Reader::error_position
+    pub const fn error_position(&self) -> u64 {
+        self.state.last_error_offset
+    }
+
+    /// Gets the position in the input data just after the last emitted event,
+    /// including line and column.
+    ///
+    /// See [`Reader::position()`] for details.
+    ///
+    /// [`Reader::position()`]: crate::reader::Reader::position
+    pub fn position(&self) -> Position {
+        self.state.position()
+    }
+
+    /// Returns errors from which the reader has recovered so far.
+    ///
+    /// See [`Reader::diagnostics()`] for details.
+    ///
+    /// [`Reader::diagnostics()`]: crate::reader::Reader::diagnostics
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.state.diagnostics
+    }
+
+    /// Returns errors from which the reader has recovered since the last call
+    /// of this method and clears the list of them.
+    ///
+    /// See [`Reader::take_diagnostics()`] for details.
+    ///
+    /// [`Reader::take_diagnostics()`]: crate::reader::Reader::take_diagnostics
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.state.diagnostics)
+    }
+
+    /// Get the decoder, used to decode bytes, read by this reader, to the strings.
+    ///
+    /// See [`Reader::decoder()`] for details.
+    ///
+    /// [`Reader::decoder()`]: crate::reader::Reader::decoder
+    pub const fn decoder(&self) -> Decoder {
+        self.state.decoder()
+    }
+
+    /// Appends the next chunk of the document to the internal buffer. Chunks
+    /// can be split at arbitrary places, even inside of multi-byte characters.
+    ///
+    /// Feeding data after calling [`finish()`] is a logic error. Such data will
+    /// be read as if it was fed before the `finish()` call.
+    ///
+    /// [`finish()`]: Self::finish
+    pub fn feed(&mut self, chunk: &[u8]) {
+        // Drop already processed data
+        if self.start > 0 {
+            self.input.drain(..self.start);
+            self.start = 0;
+        }
+        #[cfg(feature = "encoding")]
+        if self.transcoder.is_some() {
+            return self.transcode(chunk, false);
+        }
+        self.input.extend_from_slice(chunk);
+    }
+
+    /// Signals that all data was fed to the reader. After that [`read_event()`]
+    /// will never return [`PushEvent::NeedMoreData`].
+    ///
+    /// [`read_event()`]: Self::read_event
+    pub fn finish(&mut self) {
+        self.eof = true;
+        #[cfg(feature = "encoding")]
+        if self.transcoder.is_some() {
+            self.transcode(&[], true);
+        }
+    }
+
+    /// Transcodes `chunk` of UTF-16 data and appends the result to the input.
+    #[cfg(feature = "encoding")]
+    fn transcode(&mut self, chunk: &[u8], last: bool) {
+        if let Some(transcoder) = self.transcoder.as_mut() {
+            if !transcoder.feed(chunk, last, &mut self.input) {
+                self.malformed = true;
+                // Data after the malformed sequence cannot be read, so do not
+                // treat data before it as the end of the document
+                self.eof = false;
+            }
+        }
+    }
+
+    /// Skips the BOM and detects the encoding of the document by it or by
+    /// the first bytes of the XML declaration. Data of UTF-16 documents is
+    /// transcoded to UTF-8 from that moment.
+    fn skip_bom(&mut self) {
+        let available = &self.input[self.start..];
+
+        #[cfg(feature = "encoding")]
+        if let Some(encoding) = detect_encoding(available) {
+            self.start += encoding.bom_len();
+            if self.state.encoding.can_be_refined() {
+                match Transcoder::new(encoding.encoding()) {
+                    // UTF-16 documents cannot be parsed as is, so transcode data
+                    // fed so far and all following chunks
+                    Some(transcoder) => {
+                        self.state.encoding = crate::reader::EncodingRef::Transcoded;
+                        self.transcoder = Some(transcoder);
+                        let fed = self.input.split_off(self.start);
+                        self.transcode(&fed, self.eof);
+                    }
+                    None => {
+                        self.state.encoding =
+                            crate::reader::EncodingRef::BomDetected(encoding.encoding());
+                    }
+                }
+            }
+        }
+
+        #[cfg(not(feature = "encoding"))]
+        if available.starts_with(UTF8_BOM) {
+            self.start += UTF8_BOM.len();
+        }
+    }
+
+    /// Reads the next event from the data fed so far.
+    ///
+    /// Returns [`PushEvent::NeedMoreData`] if the fed data ends before the end
+    /// of the next event. Errors are reported in the same way as by
+    /// [`Reader::read_event()`].
+    ///
+    /// [`Reader::read_event()`]: crate::reader::Reader::read_event
+    pub fn read_event(&mut self) -> Result<PushEvent<'_>> {
+        if let ParseState::Init = self.state.state {
+            // Wait until we will be able to recognize BOM
+            if !self.eof && is_incomplete_bom(&self.input[self.start..]) {
+                return Ok(PushEvent::NeedMoreData);
+            }
+            self.skip_bom();
+            self.state.state = ParseState::InsideText;
+        }
+        let event = loop {
+            // Data of the incomplete piece of XML
+            let available = &self.input[self.start..];
+            break match self.state.state {
+                ParseState::Init => unreachable!("start of the document is processed before"),
+                ParseState::InsideText => {
+                    // Skip whitespaces only if we didn't start to read the text yet
+                    if self.state.config.trim_text_start && self.scanned == 0 {
+                        let count = available
+                            .iter()
+                            .position(|b| !is_whitespace(*b))
+                            .unwrap_or(available.len());
+                        self.state.tracked.advance(&available[..count]);
+                        self.state.offset += count as u64;
+                        self.start += count;
+                        if count == available.len() && !self.eof {
+                            need_more_data!(self)
+                        }
+                    }
+                    let available = &self.input[self.start..];
+
+                    let found = memchr::memchr2(b'<', b'&', &available[self.scanned..])
+                        .map(|i| i + self.scanned);
+                    // Do not accumulate data beyond the limit
+                    if found.unwrap_or(available.len()) > self.state.config.max_text_size {
+                        break Err(text_too_large(&mut self.state));
+                    }
+                    match found {
+                        Some(0) if available[0] == b'<' => {
+                            self.state.state = ParseState::InsideMarkup;
+                            continue;
+                        }
+                        Some(0) => {
+                            self.state.state = ParseState::InsideRef;
+                            continue;
+                        }
+                        Some(i) => {
+                            let next = if available[i] == b'<' {
+                                ParseState::InsideMarkup
+                            } else {
+                                ParseState::InsideRef
+                            };
+                            let bytes = &available[..i];
+                            self.start += i;
+                            self.scanned = 0;
+                            self.state.offset += i as u64;
+                            self.state.emit_text_before(next, bytes)
+                        }
+                        None if self.eof => {
+                            self.start += available.len();
+                            self.scanned = 0;
+                            self.state.offset += available.len() as u64;
+                            self.state.emit_text_before(ParseState::Done, available)
+                        }
+                        None => {
+                            self.scanned = available.len();
+                            need_more_data!(self)
+                        }
+                    }
+                }
+                ParseState::InsideRef => {
+                    let start = self.state.offset;
+                    // Skip start `&`
+                    let from = self.scanned.max(1);
+                    let found =
+                        memchr::memchr3(b';', b'&', b'<', &available[from..]).map(|i| i + from);
+                    // Do not accumulate data beyond the limit
+                    if found.unwrap_or(available.len()) > self.state.config.max_text_size {
+                        break Err(text_too_large(&mut self.state));
+                    }
+                    let (bytes, end) = match found {
+                        // Emit reference, go to InsideText state
+                        Some(i) if available[i] == b';' => {
+                            let bytes = &available[..i + 1];
+                            self.start += bytes.len();
+                            self.scanned = 0;
+                            self.state.offset += bytes.len() as u64;
+                            break self.state.emit_complete_ref(bytes);
+                        }
+                        Some(i) => (&available[..i], Some(available[i])),
+                        None if self.eof => (available, None),
+                        None => {
+                            self.scanned = available.len();
+                            need_more_data!(self)
+                        }
+                    };
+                    self.start += bytes.len();
+                    self.scanned = 0;
+                    self.state.offset += bytes.len() as u64;
+                    let next = match end {
+                        // Do not change state, stay in InsideRef
+                        Some(b'&') => ParseState::InsideRef,
+                        Some(_) => ParseState::InsideMarkup,
+                        None => ParseState::Done,
+                    };
+                    self.state.emit_unclosed_ref(next, start, bytes)
+                }
+                ParseState::InsideMarkup => {
+                    let markup = match self.markup.as_mut() {
+                        Some(markup) => markup,
+                        None => {
+                            let markup = match available.get(1) {
+                                Some(b'!') => match available.get(2) {
+                                    None if !self.eof => need_more_data!(self),
+                                    byte => match BangType::new(byte.copied()) {
+                                        Ok(bang_type) => Markup::Bang(bang_type),
+                                        // Return `<` as a text and continue from the `!`
+                                        Err(e) if self.state.config.recover_errors => {
+                                            break Ok(skip_lt(
+                                                &mut self.start,
+                                                &mut self.state,
+                                                Error::Syntax(e),
+                                            ));
+                                        }
+                                        Err(e) => {
+                                            // We want to report error at `<`
+                                            self.state.state = ParseState::InsideText;
+                                            self.state.set_error_offset(self.state.offset, &[]);
+                                            break Err(Error::Syntax(e));
+                                        }
+                                    },
+                                },
+                                Some(b'?') => Markup::Pi(PiParser(false)),
+                                Some(&byte)
+                                    if self.state.config.recover_errors
+                                        && !is_markup_start(byte) =>
+                                {
+                                    break Ok(skip_lt(
+                                        &mut self.start,
+                                        &mut self.state,
+                                        Error::IllFormed(IllFormedError::UnescapedLessThan),
+                                    ));
+                                }
+                                Some(_) => Markup::Element(ElementParser::Outside),
+                                None if self.eof && self.state.config.recover_errors => {
+                                    break Ok(skip_lt(
+                                        &mut self.start,
+                                        &mut self.state,
+                                        Error::Syntax(SyntaxError::UnclosedTag),
+                                    ));
+                                }
+                                None if self.eof => {
+                                    // We want to report error at `<`
+                                    self.state.state = ParseState::InsideText;
+                                    self.state.set_error_offset(self.state.offset, &[]);
+                                    break Err(Error::Syntax(SyntaxError::UnclosedTag));
+                                }
+                                None => need_more_data!(self),
+                            };
+                            self.markup.insert(markup)
+                        }
+                    };
+                    // Continue search from the place where we stopped last time
+                    let (buf, chunk) = available.split_at(self.scanned);
+                    let found = match markup {
+                        // Parsers remember the last bytes of the previous chunk,
+                        // so do not feed them with empty chunks
+                        _ if chunk.is_empty() => None,
+                        Markup::Bang(bang_type) => bang_type.feed(buf, chunk),
+                        Markup::Element(parser) => parser.feed(chunk),
+                        Markup::Pi(parser) => parser.feed(chunk),
+                    };
+                    // +1 for `>`
+                    let len = found.map_or(available.len(), |i| self.scanned + i + 1);
+                    // Do not accumulate data beyond the limit
+                    if len > self.state.config.max_text_size {
+                        break Err(text_too_large(&mut self.state));
+                    }
+                    let bytes = match found {
+                        Some(_) => &available[..len],
+                        None if self.eof => available,
+                        None => {
+                            self.scanned = available.len();
+                            need_more_data!(self)
+                        }
+                    };
+                    let start = self.state.offset;
+                    self.start += bytes.len();
+                    self.scanned = 0;
+                    self.state.offset += bytes.len() as u64;
+                    self.state.state = ParseState::InsideText;
+
+                    let markup = self.markup.take();
+                    if found.is_none() {
+                        // We want to report error at `<`
+                        self.state.set_error_offset(start, &[]);
+                        break Err(Error::Syntax(match markup {
+                            Some(Markup::Bang(bang_type)) => bang_type.to_err(),
+                            Some(Markup::Element(parser)) => parser.eof_error(bytes),
+                            Some(Markup::Pi(parser)) => parser.eof_error(bytes),
+                            None => unreachable!("markup parser is always created"),
+                        }));
+                    }
+                    let bang_type = match markup {
+                        Some(Markup::Bang(bang_type)) => Some(bang_type),
+                        _ => None,
+                    };
+                    self.state.emit_markup(bang_type, bytes)
+                }
+                ParseState::InsideRawText => {
+                    let parser = match self.raw_text.as_mut() {
+                        Some(parser) => parser,
+                        None => self.raw_text.insert(self.state.raw_text_parser()),
+                    };
+                    // Continue search from the place where we stopped last time
+                    let found = parser.feed(&available[self.scanned..]);
+                    // +1 for `>`
+                    let len = found.map_or(available.len(), |i| self.scanned + i + 1);
+                    // Do not accumulate data beyond the limit
+                    if len > self.state.config.max_text_size {
+                        break Err(text_too_large(&mut self.state));
+                    }
+                    if found.is_none() && !self.eof {
+                        self.scanned = available.len();
+                        need_more_data!(self)
+                    }
+                    let bytes = &available[..len];
+                    let end_len = match self.raw_text.take() {
+                        Some(parser) if found.is_some() => parser.end_tag_len(),
+                        _ => 0,
+                    };
+                    self.start += len;
+                    self.scanned = 0;
+                    self.state.offset += len as u64;
+                    self.state.emit_raw_text(bytes, end_len)
+                }
+                ParseState::InsideEmpty => Ok(Event::End(self.state.close_expanded_empty())),
+                ParseState::ImpliedEnd => Ok(self.state.close_implied()),
+                ParseState::Done => Ok(self.state.eof()),
+            };
+        };
+        let event = self.state.finish_event(event)?;
+        if !matches!(event, Event::Eof) {
+            if let Err(error) = self.state.count_event() {
+                return Err(self.state.with_position(error, self.state.position()));
+            }
+        }
+        Ok(PushEvent::Event(event))
+    }
+}
+
+impl Default for PushReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns an error when the currently read event exceeds
+/// [`Config::max_text_size`]. The error is reported at the start of the event.
+///
+/// [`Config::max_text_size`]: crate::reader::Config::max_text_size
+fn text_too_large(state: &mut ReaderState) -> Error {
+    state.set_error_offset(state.offset, &[]);
+    Error::LimitExceeded(Limit::TextSize(state.config.max_text_size))
+}
+
+/// Consumes `<` which does not start a markup and returns it as a text.
+/// Used in the recovery mode.
+fn skip_lt(start: &mut usize, state: &mut ReaderState, error: Error) -> Event<'static> {
+    *start += 1;
+    state.offset += 1;
+    state.state = ParseState::InsideText;
+    state.emit_lt(error)
+}
+
+/// Checks if `bytes` are the beginning of the byte sequence that can be used to
+/// detect an encoding of the document, but are not enough to make a decision.
+fn is_incomplete_bom(bytes: &[u8]) -> bool {
+    #[cfg(feature = "encoding")]
+    const PATTERNS: &[&[u8]] = &[
+        UTF16_BE_BOM,
+        UTF16_LE_BOM,
+        UTF8_BOM,
+        &[0x00, b'<', 0x00, b'?'],
+        &[b'<', 0x00, b'?', 0x00],
+    ];
+    #[cfg(not(feature = "encoding"))]
+    const PATTERNS: &[&[u8]] = &[UTF8_BOM];
+
+    PATTERNS
+        .iter()
+        .any(|pattern| bytes.len() < pattern.len() && pattern.starts_with(bytes))
+}
@@ -11,11 +11,13 @@ use crate::reader::EncodingRef;
 #[cfg(feature = "encoding")]
 use encoding_rs;
 
-use crate::errors::{Error, Result};
+use crate::errors::{Error, Limit, Result};
 use crate::events::{BytesText, Event};
 use crate::name::QName;
 use crate::parser::Parser;
-use crate::reader::{BangType, ReadRefResult, ReadTextResult, Reader, Span, XmlSource};
+use crate::reader::{
+    BangType, Position, RawTextParser, ReadRefResult, ReadTextResult, Reader, Span, XmlSource,
+};
 use crate::utils::is_whitespace;
 
 /// This is an implementation for reading from a `&[u8]` as underlying byte stream.
@@ -228,6 +230,15 @@ impl<'a> Reader<&'a [u8]> {
     /// [`Start`]: Event::Start
     /// [`decoder()`]: Self::decoder()
     pub fn read_text(&mut self, end: QName) -> Result<BytesText<'a>> {
+        // Transcoded data is not a part of the input, so text cannot borrow from it
+        #[cfg(feature = "encoding")]
+        if self.transcoder.is_some() {
+            let mut buf = Vec::new();
+            return self
+                .read_text_into(end, &mut buf)
+                .map(BytesText::into_owned);
+        }
+
         // self.reader will be changed, so store original reference
         let buffer = self.reader;
         let span = self.read_to_end(end)?;
@@ -263,10 +274,29 @@ impl<'a> XmlSource<'a, ()> for &'a [u8] {
         Ok(None)
     }
 
+    #[cfg(feature = "encoding")]
+    #[inline]
+    fn peek_encoding(&mut self, _consumed: &mut Vec<u8>) -> io::Result<Option<DetectedEncoding>> {
+        Ok(crate::encoding::detect_encoding(self))
+    }
+
+    #[cfg(feature = "encoding")]
+    fn read_transcoded(reader: &mut Reader<Self>, _buf: ()) -> Result<Event<'a>> {
+        // Transcoded data is not a part of the input, so events cannot borrow from it
+        let mut buf = Vec::new();
+        reader
+            .read_transcoded_event(&mut buf)
+            .map(Event::into_owned)
+    }
+
     #[inline]
-    fn read_text(&mut self, _buf: (), position: &mut u64) -> ReadTextResult<'a, ()> {
+    fn read_text(&mut self, _buf: (), position: &mut u64, limit: usize) -> ReadTextResult<'a, ()> {
         // Search for start of markup or an entity or character reference
-        match memchr::memchr2(b'<', b'&', self) {
+        let found = memchr::memchr2(b'<', b'&', self);
+        if found.unwrap_or(self.len()) > limit {
+            return ReadTextResult::Err(Error::LimitExceeded(Limit::TextSize(limit)));
+        }
+        match found {
             Some(0) if self[0] == b'<' => ReadTextResult::Markup(()),
             // Do not consume `&` because it may be lone and we would be need to
             // return it as part of Text event
@@ -293,14 +323,19 @@ impl<'a> XmlSource<'a, ()> for &'a [u8] {
     }
 
     #[inline]
-    fn read_ref(&mut self, _buf: (), position: &mut u64) -> ReadRefResult<'a> {
+    fn read_ref(&mut self, _buf: (), position: &mut u64, limit: usize) -> ReadRefResult<'a> {
         debug_assert!(
             self.starts_with(b"&"),
             "`read_ref` must be called at `&`:\n{:?}",
             crate::utils::Bytes(self)
         );
         // Search for the end of reference or a start of another reference or a markup
-        match memchr::memchr3(b';', b'&', b'<', &self[1..]) {
+        let found = memchr::memchr3(b';', b'&', b'<', &self[1..]);
+        // +1 for the start `&`
+        if found.map_or(self.len(), |i| i + 1) > limit {
+            return ReadRefResult::Err(Error::LimitExceeded(Limit::TextSize(limit)));
+        }
+        match found {
             Some(i) if self[i + 1] == b';' => {
                 // +1 for the start `&`
                 // +1 for the end `;`
@@ -336,11 +371,22 @@ impl<'a> XmlSource<'a, ()> for &'a [u8] {
     }
 
     #[inline]
-    fn read_with<P>(&mut self, mut parser: P, _buf: (), position: &mut u64) -> Result<&'a [u8]>
+    fn read_with<P>(
+        &mut self,
+        mut parser: P,
+        _buf: (),
+        position: &mut u64,
+        limit: usize,
+    ) -> Result<&'a [u8]>
     where
         P: Parser,
     {
-        if let Some(i) = parser.feed(self) {
+        let found = parser.feed(self);
+        // +1 for `>`
+        if found.map_or(self.len(), |i| i + 1) > limit {
+            return Err(Error::LimitExceeded(Limit::TextSize(limit)));
+        }
+        if let Some(i) = found {
             let used = i + 1; // +1 for `>`
             *position += used as u64;
             let (bytes, rest) = self.split_at(used);
@@ -353,7 +399,12 @@ impl<'a> XmlSource<'a, ()> for &'a [u8] {
     }
 
     #[inline]
-    fn read_bang_element(&mut self, _buf: (), position: &mut u64) -> Result<(BangType, &'a [u8])> {
+    fn read_bang_element(
+        &mut self,
+        _buf: (),
+        position: &mut u64,
+        limit: usize,
+    ) -> Result<(BangType, &'a [u8])> {
         // Peeked one bang ('!') before being called, so it's guaranteed to
         // start with it.
         debug_assert!(
@@ -364,7 +415,12 @@ impl<'a> XmlSource<'a, ()> for &'a [u8] {
 
         let mut bang_type = BangType::new(self.get(2).copied())?;
 
-        if let Some(i) = bang_type.feed(&[], self) {
+        let found = bang_type.feed(&[], self);
+        // +1 for `>`
+        if found.map_or(self.len(), |i| i + 1) > limit {
+            return Err(Error::LimitExceeded(Limit::TextSize(limit)));
+        }
+        if let Some(i) = found {
             let consumed = i + 1; // +1 for `>`
             *position += consumed as u64;
             let (bytes, rest) = self.split_at(consumed);
@@ -377,12 +433,13 @@ impl<'a> XmlSource<'a, ()> for &'a [u8] {
     }
 
     #[inline]
-    fn skip_whitespace(&mut self, position: &mut u64) -> io::Result<()> {
+    fn skip_whitespace(&mut self, position: &mut u64, tracked: &mut Position) -> io::Result<()> {
         let whitespaces = self
             .iter()
             .position(|b| !is_whitespace(*b))
             .unwrap_or(self.len());
         *position += whitespaces as u64;
+        tracked.advance(&self[..whitespaces]);
         *self = &self[whitespaces..];
         Ok(())
     }
@@ -396,6 +453,31 @@ impl<'a> XmlSource<'a, ()> for &'a [u8] {
         );
         Ok(self.get(1).copied())
     }
+
+    #[inline]
+    fn skip_lt(&mut self, position: &mut u64) {
+        *self = &self[1..];
+        *position += 1;
+    }
+
+    fn read_raw_text(
+        &mut self,
+        parser: &mut RawTextParser,
+        _buf: (),
+        position: &mut u64,
+        limit: usize,
+    ) -> Result<(&'a [u8], bool)> {
+        let found = parser.feed(self);
+        // +1 for `>`
+        let used = found.map_or(self.len(), |i| i + 1);
+        if used > limit {
+            return Err(Error::LimitExceeded(Limit::TextSize(limit)));
+        }
+        *position += used as u64;
+        let (bytes, rest) = self.split_at(used);
+        *self = rest;
+        Ok((bytes, found.is_some()))
+    }
 }
 
 #[cfg(test)]
@@ -1,16 +1,22 @@
+use std::borrow::Cow;
 use std::fmt::Debug;
 
 #[cfg(feature = "encoding")]
-use encoding_rs::UTF_8;
+use encoding_rs::{UTF_16BE, UTF_16LE, UTF_8};
 
-use crate::encoding::Decoder;
-use crate::errors::{Error, IllFormedError, Result};
-use crate::events::{BytesCData, BytesDecl, BytesEnd, BytesPI, BytesStart, BytesText, Event};
+use crate::encoding::{Decoder, EncodingError};
+use crate::errors::{Error, IllFormedError, Limit, Result, SyntaxError};
+use crate::escape::{EscapeError, ParseCharRefError};
+use crate::events::{
+    BytesCData, BytesDecl, BytesEnd, BytesPI, BytesRef, BytesStart, BytesText, Event,
+};
 use crate::parser::{Parser, PiParser};
+use crate::reader::html::{self, RawTextParser};
 #[cfg(feature = "encoding")]
 use crate::reader::EncodingRef;
-use crate::reader::{BangType, Config, DtdParser, ParseState};
-use crate::utils::{is_whitespace, name_len, Bytes};
+use crate::reader::{BangType, Config, Diagnostic, DtdParser, ParseState, Position};
+use crate::utils::{is_whitespace, is_xml_name, name_len, Bytes};
+use crate::XmlVersion;
 
 /// A struct that holds a current reader state and a parser configuration.
 /// It is independent on a way of reading data: the reader feed data into it and
@@ -24,6 +30,16 @@ pub(super) struct ReaderState {
     /// and changing `offset` is not possible, because `Error::IllFormed` errors
     /// are recoverable.
     pub last_error_offset: u64,
+    /// Line and column of the byte at `tracked.byte` offset. Usually `tracked.byte`
+    /// is equal to `offset`, but it can lag behind when the data was consumed
+    /// without tracking, for example, after a fatal error.
+    ///
+    /// Updated only when [`Config::track_position`] is set.
+    pub tracked: Position,
+    /// Line and column of the `last_error_offset`.
+    ///
+    /// Updated only when [`Config::track_position`] is set.
+    pub last_error_position: Position,
     /// Defines how to process next byte
     pub state: ParseState,
     /// User-defined settings that affect parsing
@@ -49,6 +65,22 @@ pub(super) struct ReaderState {
     /// Opened name start indexes into [`Self::opened_buffer`]. See documentation
     /// for that field for details
     opened_starts: Vec<usize>,
+    /// Number of events returned by the reader. Used to check
+    /// [`Config::max_events`]
+    events: usize,
+    /// Version of XML from the XML declaration. Used to check characters
+    /// when [`Config::check_chars`] is enabled
+    pub version: XmlVersion,
+    /// Errors from which the reader has recovered. Collected only when
+    /// [`Config::recover_errors`] is enabled
+    pub diagnostics: Vec<Diagnostic>,
+    /// Number of [`Event::End`] events that should be emitted in the
+    /// [`ParseState::ImpliedEnd`] state
+    implied_ends: usize,
+    /// The start tag which implied the end tags of the opened elements in
+    /// the HTML mode, and the flag whether it is a void or self-closed element.
+    /// Emitted in the [`ParseState::ImpliedEnd`] state after all implied end tags
+    implied_by: Option<(BytesStart<'static>, bool)>,
 
     #[cfg(feature = "encoding")]
     /// Reference to the encoding used to read an XML
@@ -56,11 +88,301 @@ pub(super) struct ReaderState {
 }
 
 impl ReaderState {
+    /// Updates tracked line and column after consuming `bytes` from the input.
+    ///
+    /// # Parameters
+    /// - `bytes`: data that was read starting from the tracked position. Bytes
+    ///   between the end of `bytes` and `offset` (markup delimiters that was
+    ///   consumed, but not returned) are counted as columns
+    pub fn track(&mut self, bytes: &[u8]) {
+        if self.config.track_position {
+            self.tracked = self.position_at(self.offset, bytes);
+        }
+    }
+
+    /// Remembers the position of the last error.
+    ///
+    /// # Parameters
+    /// - `offset`: the offset of the error
+    /// - `bytes`: data that was read starting from the tracked position. `offset`
+    ///   is expected to point inside that data or just after it
+    pub fn set_error_offset(&mut self, offset: u64, bytes: &[u8]) {
+        self.last_error_offset = offset;
+        if self.config.track_position {
+            self.last_error_position = self.position_at(offset, bytes);
+        }
+    }
+
+    /// Calculates line and column of the `offset`. `bytes` should start at the
+    /// tracked position.
+    fn position_at(&self, offset: u64, bytes: &[u8]) -> Position {
+        let mut position = self.tracked;
+        let len = offset.saturating_sub(position.byte);
+        match bytes.get(..len as usize) {
+            Some(bytes) => position.advance(bytes),
+            None => {
+                position.advance(bytes);
+                position.column += offset - position.byte;
+                position.byte = offset;
+            }
+        }
+        position
+    }
+
+    /// Returns the current position of the reader.
+    pub fn position(&self) -> Position {
+        if self.config.track_position {
+            self.position_at(self.offset, &[])
+        } else {
+            Position {
+                byte: self.offset,
+                line: 0,
+                column: 0,
+            }
+        }
+    }
+
+    /// Attaches the `position` to the error, if position tracking is enabled.
+    /// I/O errors are returned as is, because they are not related to any place
+    /// in the document.
+    pub fn with_position(&self, error: Error, position: Position) -> Error {
+        match error {
+            Error::Io(_) | Error::Positioned { .. } => error,
+            error if self.config.track_position => Error::Positioned {
+                position,
+                error: Box::new(error),
+            },
+            error => error,
+        }
+    }
+
+    /// Records `error` as a diagnostic reported at the position of the last error.
+    fn diagnose(&mut self, error: Error) {
+        let position = if self.config.track_position {
+            self.last_error_position
+        } else {
+            Position {
+                byte: self.last_error_offset,
+                line: 0,
+                column: 0,
+            }
+        };
+        self.diagnostics.push(Diagnostic { position, error });
+    }
+
+    /// Returns `error`, or records it as a diagnostic and returns `Ok(())` if
+    /// [`Config::recover_errors`] is enabled. The position of the error should
+    /// be set before.
+    fn recoverable(&mut self, error: Error) -> Result<()> {
+        if self.config.recover_errors {
+            self.diagnose(error);
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    /// Returns `true` if an `&` without the paired `;` should be returned as text.
+    /// In the recovery mode records the [`IllFormedError::UnclosedReference`] diagnostic.
+    ///
+    /// # Parameters
+    /// - `start`: the offset of the `&`
+    /// - `bytes`: data that was read starting from `&`
+    fn dangling_amp(&mut self, start: u64, bytes: &[u8]) -> bool {
+        if self.config.allow_dangling_amp {
+            return true;
+        }
+        if self.config.recover_errors {
+            self.set_error_offset(start, bytes);
+            self.diagnose(Error::IllFormed(IllFormedError::UnclosedReference));
+            return true;
+        }
+        false
+    }
+
+    /// Returns the escaped `<` as a text event. Used in the recovery mode, when
+    /// `<` does not start a markup. `<` should be already consumed.
+    pub fn emit_lt(&mut self, error: Error) -> Event<'static> {
+        self.set_error_offset(self.offset - 1, b"<");
+        self.diagnose(error);
+        self.track(b"<");
+        Event::Text(BytesText::from_escaped("&lt;"))
+    }
+
+    /// Records a syntax error about markup that is not closed before the end of
+    /// input and returns an event that follows the end of input. Used in the
+    /// recovery mode. The position of the error should be set before.
+    pub fn skip_unclosed(&mut self, error: SyntaxError) -> Event<'static> {
+        self.diagnose(Error::Syntax(error));
+        self.state = ParseState::Done;
+        self.eof()
+    }
+
+    /// Completes reading of an event by a reader. Syntax errors are recovered
+    /// in the recovery mode, fatal errors and the end of input stop reading,
+    /// and the position of the error is attached to errors.
+    pub fn finish_event<'b>(&mut self, event: Result<Event<'b>>) -> Result<Event<'b>> {
+        let event = match event {
+            // Syntax errors are reported only for markup which is not closed before
+            // the end of input
+            Err(Error::Syntax(error)) if self.config.recover_errors => {
+                Ok(self.skip_unclosed(error))
+            }
+            event => event,
+        };
+        match event {
+            // #513: In case of ill-formed errors we already consume the wrong data
+            // and change the state. We can continue parsing if we wish
+            Err(Error::IllFormed(_)) => {}
+            Err(_) | Ok(Event::Eof) => self.state = ParseState::Done,
+            _ => {}
+        }
+        event.map_err(|e| self.with_position(e, self.last_error_position))
+    }
+
+    /// Returns an event that follows the end of input. In the recovery mode the
+    /// elements that are still opened are closed one by one before the `Eof`.
+    pub fn eof(&mut self) -> Event<'static> {
+        if !self.config.recover_errors && !self.config.html {
+            return Event::Eof;
+        }
+        match self.opened_starts.pop() {
+            Some(start) => {
+                let name = self.opened_buffer.split_off(start);
+                if self.is_missed_end(&name) {
+                    self.set_error_offset(self.offset, &[]);
+                    self.diagnose(Error::IllFormed(IllFormedError::MissingEndTag(
+                        self.decoder()
+                            .decode(&name)
+                            .unwrap_or_default()
+                            .into_owned(),
+                    )));
+                }
+                Event::End(BytesEnd::wrap(name.into()))
+            }
+            None => Event::Eof,
+        }
+    }
+
+    /// Returns `true` if the omitted end tag of the element `name` should be
+    /// reported as a diagnostic. In the HTML mode end tags of some elements
+    /// can be omitted.
+    fn is_missed_end(&self, name: &[u8]) -> bool {
+        self.config.recover_errors && !(self.config.html && html::has_optional_end(name))
+    }
+
+    /// Returns a markup as an escaped text. Used in the recovery mode to return
+    /// markup which cannot be recognized.
+    fn markup_as_text(&self, buf: &[u8]) -> Event<'static> {
+        let text = match self.decoder().decode(buf) {
+            Ok(text) => text,
+            Err(_) => String::from_utf8_lossy(buf),
+        };
+        Event::Text(BytesText::new(&text).into_owned())
+    }
+
+    /// Checks that `buf` contains only characters allowed by the XML version of
+    /// the document, if [`Config::check_chars`] is enabled.
+    ///
+    /// # Parameters
+    /// - `buf`: data of an event that was read just before the current offset
+    fn check_chars(&mut self, buf: &[u8]) -> Result<()> {
+        if !self.config.check_chars {
+            return Ok(());
+        }
+        let start = self.offset - buf.len() as u64;
+        let text = match self.decoder().decode(buf) {
+            Ok(text) => text,
+            Err(error) => {
+                let offset = match error {
+                    EncodingError::Utf8(ref e) => e.valid_up_to(),
+                    // Position of an error is known only for UTF-8
+                    #[cfg(feature = "encoding")]
+                    EncodingError::Other(encoding) if encoding == UTF_8 => std::str::from_utf8(buf)
+                        .err()
+                        .map_or(0, |e| e.valid_up_to()),
+                    #[cfg(feature = "encoding")]
+                    EncodingError::Other(_) => 0,
+                };
+                self.set_error_offset(start + offset as u64, buf);
+                return Err(Error::Encoding(error));
+            }
+        };
+        let version = self.version;
+        if let Some((i, ch)) = text.char_indices().find(|(_, ch)| !version.is_char(*ch)) {
+            // Decoded text has the same offsets as `buf` only if it was borrowed,
+            // otherwise report the error at start of the event
+            let offset = match text {
+                Cow::Borrowed(_) => i,
+                Cow::Owned(_) => 0,
+            };
+            self.set_error_offset(start + offset as u64, buf);
+            return self.recoverable(Error::IllFormed(IllFormedError::InvalidChar(ch as u32)));
+        }
+        Ok(())
+    }
+
+    /// Checks that `name` matches the `Name` production, if [`Config::check_names`]
+    /// is enabled.
+    ///
+    /// # Parameters
+    /// - `name`: the name to check. Must be a subslice of `buf`
+    /// - `buf`: data of an event that was read just before the current offset
+    fn check_name(&mut self, name: &[u8], buf: &[u8]) -> Result<()> {
+        if !self.config.check_names {
+            return Ok(());
+        }
+        let offset = name.as_ptr() as usize - buf.as_ptr() as usize;
+        let start = self.offset - buf.len() as u64;
+        let error = match self.decoder().decode(name) {
+            Ok(name) if is_xml_name(&name) => return Ok(()),
+            Ok(name) => Error::IllFormed(IllFormedError::InvalidName(name.into_owned())),
+            Err(error) => {
+                self.set_error_offset(start + offset as u64, buf);
+                return Err(Error::Encoding(error));
+            }
+        };
+        self.set_error_offset(start + offset as u64, buf);
+        self.recoverable(error)
+    }
+
     /// Trims end whitespaces from `bytes`, if required, and returns a text event.
     ///
     /// # Parameters
     /// - `bytes`: data from the start of stream to the first `<` or from `>` to `<`
-    pub fn emit_text<'b>(&mut self, bytes: &'b [u8]) -> BytesText<'b> {
+    fn emit_text<'b>(&mut self, bytes: &'b [u8]) -> Result<BytesText<'b>> {
+        self.check_chars(bytes)?;
+        Ok(self.text(bytes))
+    }
+
+    /// Returns a text event with the content of `bytes` and moves the parser to
+    /// the `next` state. If the text is at the end of input and becomes empty
+    /// after trimming, returns an event that follows the end of input instead.
+    ///
+    /// # Parameters
+    /// - `next`: the state after the text: `InsideMarkup`, `InsideRef` or `Done`
+    /// - `bytes`: data from the start of stream to the first `<` or `&`, from `>`
+    ///   or `;` to `<` or `&`, or up to the end of input
+    pub fn emit_text_before<'b>(&mut self, next: ParseState, bytes: &'b [u8]) -> Result<Event<'b>> {
+        let at_eof = matches!(next, ParseState::Done);
+        self.state = next;
+        // FIXME: Can produce an empty event if:
+        // - event contains only spaces
+        // - trim_text_start = false
+        // - trim_text_end = true
+        let event = self.emit_text(bytes).map(|event| {
+            if at_eof && event.is_empty() {
+                self.eof()
+            } else {
+                Event::Text(event)
+            }
+        });
+        self.track(bytes);
+        event
+    }
+
+    /// Trims end whitespaces from `bytes`, if required, and wraps them into a text.
+    fn text<'b>(&self, bytes: &'b [u8]) -> BytesText<'b> {
         let mut content = bytes;
 
         if self.config.trim_text_end {
@@ -74,6 +396,141 @@ impl ReaderState {
         BytesText::wrap(content, self.decoder())
     }
 
+    /// Returns a parser that searches the end tag of the last opened element,
+    /// which content is a raw text.
+    pub fn raw_text_parser(&self) -> RawTextParser {
+        RawTextParser::new(self.last_opened().unwrap_or_default())
+    }
+
+    /// Returns a text event with the content of a raw text element. If the end
+    /// tag of the element was found, the [`Event::End`] will be emitted next,
+    /// otherwise the input is over.
+    ///
+    /// # Parameters
+    /// - `bytes`: data from `>` of the start tag up to the end of the end tag
+    ///   or up to the end of input
+    /// - `end_len`: length of the end tag at the end of `bytes`, or `0` if
+    ///   the end tag was not found
+    pub fn emit_raw_text<'b>(&mut self, bytes: &'b [u8], end_len: usize) -> Result<Event<'b>> {
+        let event = self.raw_text(bytes, end_len);
+        self.track(bytes);
+        event
+    }
+
+    /// Implementation of the [`Self::emit_raw_text`] that does not track the position.
+    fn raw_text<'b>(&mut self, bytes: &'b [u8], end_len: usize) -> Result<Event<'b>> {
+        let content = &bytes[..bytes.len() - end_len];
+        if end_len > 0 {
+            self.implied_ends = 1;
+            self.state = ParseState::ImpliedEnd;
+        } else {
+            self.state = ParseState::Done;
+        }
+        if content.is_empty() {
+            return Ok(if end_len > 0 {
+                self.close_implied()
+            } else {
+                self.eof()
+            });
+        }
+        self.check_chars(bytes)?;
+        Ok(Event::Text(self.text(content)))
+    }
+
+    /// Returns a `GeneralRef` event and moves the parser to the `InsideText` state.
+    ///
+    /// `bytes` contains the whole reference including `&` and `;`.
+    pub fn emit_complete_ref<'b>(&mut self, bytes: &'b [u8]) -> Result<Event<'b>> {
+        self.state = ParseState::InsideText;
+        let event = self.emit_ref(bytes);
+        self.track(bytes);
+        event
+    }
+
+    /// Handles `&` without the paired `;` and moves the parser to the `next` state.
+    /// Returns the data as a text if [`Self::dangling_amp`] allows that, otherwise
+    /// returns an [`IllFormedError::UnclosedReference`] error.
+    ///
+    /// # Parameters
+    /// - `next`: the state after the data: `InsideRef` if the next `&` was found,
+    ///   `InsideMarkup` if `<` was found and `Done` at the end of input
+    /// - `start`: the offset of the `&`
+    /// - `bytes`: data that was read starting from `&`
+    pub fn emit_unclosed_ref<'b>(
+        &mut self,
+        next: ParseState,
+        start: u64,
+        bytes: &'b [u8],
+    ) -> Result<Event<'b>> {
+        self.state = next;
+        let event = if self.dangling_amp(start, bytes) {
+            self.emit_text(bytes).map(Event::Text)
+        } else {
+            self.set_error_offset(start, bytes);
+            Err(Error::IllFormed(IllFormedError::UnclosedReference))
+        };
+        self.track(bytes);
+        event
+    }
+
+    /// Returns a `GeneralRef` event. Checks the name of the entity or the
+    /// referenced character, if required.
+    ///
+    /// `buf` contains the whole reference including `&` and `;`.
+    fn emit_ref<'b>(&mut self, buf: &'b [u8]) -> Result<Event<'b>> {
+        debug_assert!(
+            buf.starts_with(b"&") && buf.ends_with(b";"),
+            "reference must start from '&' and end with ';':\n{:?}",
+            crate::utils::Bytes(buf)
+        );
+        // +1 to skip start `&`
+        // -1 to skip end `;`
+        let name = &buf[1..buf.len() - 1];
+        let event = BytesRef::wrap(name, self.decoder());
+        if !event.is_char_ref() {
+            self.check_name(name, buf)?;
+        } else if self.config.check_chars {
+            // Malformed references are reported when the user resolves them
+            let code = match event.resolve_char_ref() {
+                Ok(Some(ch)) if self.version.is_ref_char(ch) => None,
+                Ok(Some(ch)) => Some(ch as u32),
+                Err(Error::Escape(EscapeError::InvalidCharRef(
+                    ParseCharRefError::InvalidCodepoint(code)
+                    | ParseCharRefError::IllegalCharacter(code),
+                ))) => Some(code),
+                _ => None,
+            };
+            if let Some(code) = code {
+                self.set_error_offset(self.offset - buf.len() as u64, buf);
+                self.recoverable(Error::IllFormed(IllFormedError::InvalidChar(code)))?;
+            }
+        }
+        Ok(Event::GeneralRef(event))
+    }
+
+    /// Returns an event for the markup that was completely read. The kind of
+    /// the event is determined by the `bang_type` or by the first bytes of markup.
+    ///
+    /// # Parameters
+    /// - `bang_type`: the kind of markup started with `<!`, or `None` for other markup
+    /// - `bytes`: data between `<` and up to, including, `>`
+    pub fn emit_markup<'b>(
+        &mut self,
+        bang_type: Option<BangType>,
+        bytes: &'b [u8],
+    ) -> Result<Event<'b>> {
+        let event = match bang_type {
+            Some(bang_type) => self.emit_bang(bang_type, bytes),
+            None => match bytes.get(1) {
+                Some(b'/') => self.emit_end(bytes),
+                Some(b'?') => self.emit_question_mark(bytes),
+                _ => self.emit_start(bytes),
+            },
+        };
+        self.track(bytes);
+        event
+    }
+
     /// Returns `Comment`, `CData` or `DocType` event.
     ///
     /// `buf` contains data between `<` and `>`:
@@ -81,7 +538,7 @@ impl ReaderState {
     /// - Comment: `<!--...--`
     /// - Doctype (uppercase): `<!D...`
     /// - Doctype (lowercase): `<!d...`
-    pub fn emit_bang<'b>(&mut self, bang_type: BangType, buf: &'b [u8]) -> Result<Event<'b>> {
+    fn emit_bang<'b>(&mut self, bang_type: BangType, buf: &'b [u8]) -> Result<Event<'b>> {
         debug_assert!(
             buf.starts_with(b"<!"),
             "CDATA, comment or DOCTYPE must start from '<!':\n{:?}",
@@ -105,6 +562,7 @@ impl ReaderState {
                     "comment must end with '-->':\n{:?}",
                     crate::utils::Bytes(buf)
                 );
+                self.check_chars(buf)?;
                 if self.config.check_comments {
                     // search if '--' not in comments
                     let mut haystack = &buf[4..len - 3];
@@ -127,8 +585,11 @@ impl ReaderState {
                             //  ^  :   :           - self.offset - len
                             //     ^   :           - self.offset - len + 4
                             //         ^           - self.offset - len + 4 + p
-                            self.last_error_offset = self.offset - len as u64 + 4 + p as u64;
-                            return Err(Error::IllFormed(IllFormedError::DoubleHyphenInComment));
+                            self.set_error_offset(self.offset - len as u64 + 4 + p as u64, buf);
+                            self.recoverable(Error::IllFormed(
+                                IllFormedError::DoubleHyphenInComment,
+                            ))?;
+                            break;
                         }
                         // Continue search after single `-` (+1 to skip it)
                         haystack = &haystack[p + 1..];
@@ -150,6 +611,7 @@ impl ReaderState {
                     "CDATA must end with ']]>':\n{:?}",
                     crate::utils::Bytes(buf)
                 );
+                self.check_chars(buf)?;
                 Ok(Event::CData(BytesCData::wrap(
                     // Cut of `<![CDATA[` and `]]>` from start and end
                     &buf[9..len - 3],
@@ -161,6 +623,7 @@ impl ReaderState {
             // HTML5 allows mixed case for doctype declarations:
             // https://html.spec.whatwg.org/multipage/parsing.html#markup-declaration-open-state
             BangType::DocType(DtdParser::Finished) if uncased_starts_with(buf, b"<!DOCTYPE") => {
+                self.check_chars(buf)?;
                 match buf[9..len - 1].iter().position(|&b| !is_whitespace(b)) {
                     Some(start) => Ok(Event::DocType(BytesText::wrap(
                         // Cut of `<!DOCTYPE` and any number of spaces from start and `>` from the end
@@ -171,8 +634,12 @@ impl ReaderState {
                         // Because we here, we at least read `<!DOCTYPE>` and offset after `>`.
                         // We want report error at place where name is expected - this is just
                         // before `>`
-                        self.last_error_offset = self.offset - 1;
-                        Err(Error::IllFormed(IllFormedError::MissingDoctypeName))
+                        self.set_error_offset(self.offset - 1, buf);
+                        self.recoverable(Error::IllFormed(IllFormedError::MissingDoctypeName))?;
+                        Ok(Event::DocType(BytesText::wrap(
+                            &buf[len - 1..len - 1],
+                            self.decoder(),
+                        )))
                     }
                 }
             }
@@ -180,7 +647,11 @@ impl ReaderState {
                 // <!....>
                 // ~~~~~~~- `buf` contains that and `self.offset` is after `>`.
                 // ^------- We report error at that position, so we need to subtract buf len
-                self.last_error_offset = self.offset - len as u64;
+                self.set_error_offset(self.offset - len as u64, buf);
+                if self.config.recover_errors {
+                    self.diagnose(Error::Syntax(bang_type.to_err()));
+                    return Ok(self.markup_as_text(buf));
+                }
                 Err(Error::Syntax(bang_type.to_err()))
             }
         }
@@ -190,7 +661,7 @@ impl ReaderState {
     /// end name matches the last opened start name if `self.config.check_end_names` is set.
     ///
     /// `buf` contains data between `<` and up to, including, `>`, for example `</tag>`.
-    pub fn emit_end<'b>(&mut self, buf: &'b [u8]) -> Result<Event<'b>> {
+    fn emit_end<'b>(&mut self, buf: &'b [u8]) -> Result<Event<'b>> {
         debug_assert!(
             buf.starts_with(b"</"),
             "end tag must start from '</':\n{:?}",
@@ -216,33 +687,64 @@ impl ReaderState {
             content
         };
 
+        if name.len() > self.config.max_name_length {
+            // Report error at start of the end tag at `<` character
+            self.set_error_offset(self.offset - buf.len() as u64, buf);
+            return Err(Error::LimitExceeded(Limit::NameLength(
+                self.config.max_name_length,
+            )));
+        }
+        self.check_chars(buf)?;
+        self.check_name(name, buf)?;
+
         let decoder = self.decoder();
 
         // Get the index in self.opened_buffer of the name of the last opened tag
         match self.opened_starts.pop() {
             Some(start) => {
-                if self.config.check_end_names {
-                    let expected = &self.opened_buffer[start..];
-                    if name != expected {
-                        let expected = decoder.decode(expected).unwrap_or_default().into_owned();
-                        // #513: In order to allow error recovery we should drop content of the buffer
-                        self.opened_buffer.truncate(start);
-
-                        // Report error at start of the end tag at `<` character
-                        self.last_error_offset = self.offset - buf.len() as u64;
-                        return Err(Error::IllFormed(IllFormedError::MismatchedEndTag {
-                            expected,
-                            found: decoder.decode(name).unwrap_or_default().into_owned(),
-                        }));
+                if self.config.check_end_names
+                    && !self.is_same_name(name, &self.opened_buffer[start..])
+                {
+                    if self.config.html || self.config.recover_errors {
+                        self.opened_starts.push(start);
+                        if let Some(event) = self.close_until(name, buf) {
+                            return Ok(event);
+                        }
+                        if self.config.recover_errors {
+                            return Ok(self.unmatched_end_as_text(name, buf));
+                        }
+                        self.opened_starts.pop();
                     }
+                    let expected = decoder
+                        .decode(&self.opened_buffer[start..])
+                        .unwrap_or_default()
+                        .into_owned();
+                    // #513: In order to allow error recovery we should drop content of the buffer
+                    self.opened_buffer.truncate(start);
+
+                    // Report error at start of the end tag at `<` character
+                    self.set_error_offset(self.offset - buf.len() as u64, buf);
+                    return Err(Error::IllFormed(IllFormedError::MismatchedEndTag {
+                        expected,
+                        found: decoder.decode(name).unwrap_or_default().into_owned(),
+                    }));
                 }
 
+                // In the HTML mode names are matched case-insensitively, so
+                // use the spelling of the start tag, as for implied end tags
+                if self.config.html {
+                    let name = self.opened_buffer.split_off(start);
+                    return Ok(Event::End(BytesEnd::wrap(name.into())));
+                }
                 self.opened_buffer.truncate(start);
             }
             None => {
+                if !self.config.allow_unmatched_ends && self.config.recover_errors {
+                    return Ok(self.unmatched_end_as_text(name, buf));
+                }
                 if !self.config.allow_unmatched_ends {
                     // Report error at start of the end tag at `<` character
-                    self.last_error_offset = self.offset - buf.len() as u64;
+                    self.set_error_offset(self.offset - buf.len() as u64, buf);
                     return Err(Error::IllFormed(IllFormedError::UnmatchedEndTag(
                         decoder.decode(name).unwrap_or_default().into_owned(),
                     )));
@@ -253,11 +755,82 @@ impl ReaderState {
         Ok(Event::End(BytesEnd::wrap(name.into())))
     }
 
+    /// Compares names of the start and end tags. In the HTML mode names are
+    /// compared case-insensitively.
+    fn is_same_name(&self, name: &[u8], opened: &[u8]) -> bool {
+        if self.config.html {
+            name.eq_ignore_ascii_case(opened)
+        } else {
+            name == opened
+        }
+    }
+
+    /// Returns the name of the opened element at the specified depth.
+    fn opened(&self, depth: usize) -> &[u8] {
+        let end = self
+            .opened_starts
+            .get(depth + 1)
+            .map_or(self.opened_buffer.len(), |&end| end);
+        &self.opened_buffer[self.opened_starts[depth]..end]
+    }
+
+    /// Handles the end tag `name` that does not match the last opened element,
+    /// but matches one of the outer elements. All elements opened after it are
+    /// closed, and the first `End` event is returned. If no opened element has
+    /// that name, returns `None`. Used in the recovery and HTML modes.
+    ///
+    /// `buf` contains data between `<` and up to, including, `>`, for example `</tag>`.
+    fn close_until(&mut self, name: &[u8], buf: &[u8]) -> Option<Event<'static>> {
+        let depth = self.opened_starts.len();
+        let index = (0..depth).rposition(|i| self.is_same_name(name, self.opened(i)))?;
+
+        // Report errors at start of the end tag at `<` character
+        self.set_error_offset(self.offset - buf.len() as u64, buf);
+        let decoder = self.decoder();
+        let decode = |name: &[u8]| decoder.decode(name).unwrap_or_default().into_owned();
+        let found = decode(name);
+        let missed: Vec<_> = (index + 1..depth)
+            .rev()
+            .map(|i| self.opened(i))
+            .filter(|name| self.is_missed_end(name))
+            .map(decode)
+            .collect();
+        for expected in missed {
+            self.diagnose(Error::IllFormed(IllFormedError::ImpliedEndTag {
+                expected,
+                found: found.clone(),
+            }));
+        }
+        self.implied_ends = depth - index;
+        Some(self.close_implied())
+    }
+
+    /// Returns the end tag `name` that does not match any opened element as
+    /// a text. Used in the recovery mode.
+    ///
+    /// `buf` contains data between `<` and up to, including, `>`, for example `</tag>`.
+    fn unmatched_end_as_text(&mut self, name: &[u8], buf: &[u8]) -> Event<'static> {
+        // Report error at start of the end tag at `<` character
+        self.set_error_offset(self.offset - buf.len() as u64, buf);
+
+        let decoder = self.decoder();
+        let decode = |name: &[u8]| decoder.decode(name).unwrap_or_default().into_owned();
+        let error = match self.last_opened() {
+            Some(expected) => IllFormedError::MismatchedEndTag {
+                expected: decode(expected),
+                found: decode(name),
+            },
+            None => IllFormedError::UnmatchedEndTag(decode(name)),
+        };
+        self.diagnose(Error::IllFormed(error));
+        self.markup_as_text(buf)
+    }
+
     /// `buf` contains data between `<` and `>` and the first byte is `?`.
     /// `self.offset` already after the `>`
     ///
     /// Returns `Decl` or `PI` event
-    pub fn emit_question_mark<'b>(&mut self, buf: &'b [u8]) -> Result<Event<'b>> {
+    fn emit_question_mark<'b>(&mut self, buf: &'b [u8]) -> Result<Event<'b>> {
         debug_assert!(
             buf.starts_with(b"<?"),
             "processing instruction or XML declaration must start from '<?':\n{:?}",
@@ -280,19 +853,33 @@ impl ReaderState {
             if content.starts_with(b"xml") && (len == 3 || is_whitespace(content[3])) {
                 let event = BytesDecl::from_start(BytesStart::wrap(content, 3, self.decoder()));
 
+                // Characters are checked according to the declared version
+                if let Ok(version) = event.xml_version() {
+                    self.version = version;
+                }
+                self.check_chars(buf)?;
+
                 // Try getting encoding from the declaration event
                 #[cfg(feature = "encoding")]
                 if self.encoding.can_be_refined() {
                     if let Some(encoding) = event.encoder() {
-                        self.encoding = EncodingRef::XmlDetected(encoding);
+                        // The declaration was read as ASCII, so the document cannot
+                        // be in UTF-16 whatever the declaration says. UTF-16 documents
+                        // are detected by their first bytes and transcoded
+                        if encoding != UTF_16LE && encoding != UTF_16BE {
+                            self.encoding = EncodingRef::XmlDetected(encoding);
+                        }
                     }
                 }
 
                 Ok(Event::Decl(event))
             } else {
+                self.check_chars(buf)?;
+                let target = &content[..name_len(content)];
+                self.check_name(target, buf)?;
                 Ok(Event::PI(BytesPI::wrap(
                     content,
-                    name_len(content),
+                    target.len(),
                     self.decoder(),
                 )))
             }
@@ -300,8 +887,13 @@ impl ReaderState {
             // <?...?>
             // ~~~~~~~- `buf` contains that and `self.offset` is after `>`.
             // ^------- We report error at that position, so we need to subtract buf len
-            self.last_error_offset = self.offset - len as u64;
-            Err(Error::Syntax(PiParser(false).eof_error(buf)))
+            self.set_error_offset(self.offset - len as u64, buf);
+            let error = Error::Syntax(PiParser(false).eof_error(buf));
+            if self.config.recover_errors {
+                self.diagnose(error);
+                return Ok(self.markup_as_text(buf));
+            }
+            Err(error)
         }
     }
 
@@ -309,7 +901,7 @@ impl ReaderState {
     ///
     /// # Parameters
     /// - `content`: Content of a tag between `<` and `>`
-    pub fn emit_start<'b>(&mut self, content: &'b [u8]) -> Event<'b> {
+    fn emit_start<'b>(&mut self, content: &'b [u8]) -> Result<Event<'b>> {
         debug_assert!(
             content.starts_with(b"<"),
             "start or empty tag must start from '<':\n{:?}",
@@ -321,32 +913,133 @@ impl ReaderState {
             crate::utils::Bytes(content)
         );
 
+        let buf = content;
         // strip `<`
         let content = &content[1..];
-        if let Some(content) = content.strip_suffix(b"/>") {
+        let (content, self_closed) = match content.strip_suffix(b"/>") {
             // This is self-closed tag `<something/>`
-            let event = BytesStart::wrap(content, name_len(content), self.decoder());
+            Some(content) => (content, true),
+            // strip `>`
+            None => (&content[..content.len() - 1], false),
+        };
+        let event = BytesStart::wrap(content, name_len(content), self.decoder());
+        if let Err(error) = self.check_limits(&event) {
+            // Report error at start of the tag at `<` character
+            self.set_error_offset(self.offset - buf.len() as u64, buf);
+            return Err(error);
+        }
+        self.check_start(&event, buf)?;
 
-            if self.config.expand_empty_elements {
-                self.state = ParseState::InsideEmpty;
-                self.opened_starts.push(self.opened_buffer.len());
-                self.opened_buffer.extend(event.name().as_ref());
-                Event::Start(event)
-            } else {
-                Event::Empty(event)
+        if self.config.html {
+            let name = event.name().into_inner();
+            let self_closed = self_closed || html::is_void(name);
+            let depth = self.opened_starts.len();
+            let implied = (0..depth)
+                .rev()
+                .take_while(|&i| html::closes(name, self.opened(i)))
+                .count();
+            if implied > 0 {
+                // The start tag will be emitted after the implied end tags
+                self.implied_ends = implied;
+                self.implied_by = Some((event.into_owned(), self_closed));
+                return Ok(self.close_implied());
             }
-        } else {
-            // strip `>`
-            let content = &content[..content.len() - 1];
-            let event = BytesStart::wrap(content, name_len(content), self.decoder());
+            return Ok(self.open(event, self_closed));
+        }
+        Ok(self.open(event, self_closed))
+    }
+
+    /// Opens an element and returns a `Start` event, or an `Empty` event for
+    /// a self-closed element, if it is not expanded.
+    fn open<'b>(&mut self, event: BytesStart<'b>, self_closed: bool) -> Event<'b> {
+        if self_closed && !self.config.expand_empty_elements {
+            return Event::Empty(event);
+        }
+        // #514: Always store names event when .check_end_names == false,
+        // because checks can be temporary disabled and when they would be
+        // enabled, we should have that information
+        self.opened_starts.push(self.opened_buffer.len());
+        self.opened_buffer.extend(event.name().as_ref());
+        if self_closed {
+            self.state = ParseState::InsideEmpty;
+        } else if self.config.html && html::is_raw_text(event.name().as_ref()) {
+            self.state = ParseState::InsideRawText;
+        }
+        Event::Start(event)
+    }
+
+    /// Checks that the start or empty tag does not exceed the depth, attributes
+    /// count and names length limits.
+    fn check_limits(&self, event: &BytesStart) -> Result<()> {
+        let config = &self.config;
+        if self.opened_starts.len() >= config.max_depth {
+            return Err(Error::LimitExceeded(Limit::Depth(config.max_depth)));
+        }
+        if event.name().as_ref().len() > config.max_name_length {
+            return Err(Error::LimitExceeded(Limit::NameLength(
+                config.max_name_length,
+            )));
+        }
+        if config.max_attributes == usize::MAX && config.max_name_length == usize::MAX {
+            return Ok(());
+        }
+        let mut count = 0;
+        // Duplicated and malformed attributes are reported when the user reads
+        // them, here we only count them
+        for attr in event.attributes().with_checks(false) {
+            count += 1;
+            if count > config.max_attributes {
+                return Err(Error::LimitExceeded(Limit::Attributes(
+                    config.max_attributes,
+                )));
+            }
+            let len = attr.map_or(0, |attr| attr.key.as_ref().len());
+            if len > config.max_name_length {
+                return Err(Error::LimitExceeded(Limit::NameLength(
+                    config.max_name_length,
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks characters of the start or empty tag and names of the element and
+    /// its attributes, if required.
+    fn check_start(&mut self, event: &BytesStart, buf: &[u8]) -> Result<()> {
+        self.check_chars(buf)?;
+        if self.config.check_names {
+            self.check_name(event.name().into_inner(), buf)?;
+            // Malformed attributes are reported when the user reads them
+            for attr in event.attributes().with_checks(false).flatten() {
+                self.check_name(attr.key.into_inner(), buf)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the number of elements that are opened, but not closed yet.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.opened_starts.len()
+    }
 
-            // #514: Always store names event when .check_end_names == false,
-            // because checks can be temporary disabled and when they would be
-            // enabled, we should have that information
-            self.opened_starts.push(self.opened_buffer.len());
-            self.opened_buffer.extend(event.name().as_ref());
-            Event::Start(event)
+    /// Counts the event returned by the reader. Returns an error and stops
+    /// reading if the number of events exceeds [`Config::max_events`].
+    pub fn count_event(&mut self) -> Result<()> {
+        self.events += 1;
+        if self.events > self.config.max_events {
+            self.state = ParseState::Done;
+            return Err(Error::LimitExceeded(Limit::Events(self.config.max_events)));
         }
+        Ok(())
+    }
+
+    /// Returns the name of the last opened element which is not closed yet.
+    #[inline]
+    pub fn last_opened(&self) -> Option<&[u8]> {
+        self.opened_starts
+            .last()
+            .map(|&start| &self.opened_buffer[start..])
     }
 
     #[inline]
@@ -358,6 +1051,30 @@ impl ReaderState {
         BytesEnd::wrap(name.into())
     }
 
+    /// Closes the next element which end tag was implied by an end tag of an outer
+    /// element, or by a start tag in the HTML mode. After all implied end tags
+    /// returns the start tag that implied them. Used in the recovery and HTML modes.
+    pub fn close_implied(&mut self) -> Event<'static> {
+        if self.implied_ends == 0 {
+            self.state = ParseState::InsideText;
+            let (event, self_closed) = self
+                .implied_by
+                .take()
+                .expect("implied end tags are emitted only when something implies them");
+            return self.open(event, self_closed);
+        }
+        self.implied_ends -= 1;
+        self.state = if self.implied_ends == 0 && self.implied_by.is_none() {
+            ParseState::InsideText
+        } else {
+            ParseState::ImpliedEnd
+        };
+        let name = self
+            .opened_buffer
+            .split_off(self.opened_starts.pop().unwrap());
+        Event::End(BytesEnd::wrap(name.into()))
+    }
+
     /// Get the decoder, used to decode bytes, read by this reader, to the strings.
     ///
     /// If [`encoding`] feature is enabled, the used encoding may change after
@@ -380,10 +1097,17 @@ impl Default for ReaderState {
         Self {
             offset: 0,
             last_error_offset: 0,
+            tracked: Position::START,
+            last_error_position: Position::START,
             state: ParseState::Init,
             config: Config::default(),
             opened_buffer: Vec::new(),
             opened_starts: Vec::new(),
+            events: 0,
+            version: XmlVersion::Implicit1_0,
+            diagnostics: Vec::new(),
+            implied_ends: 0,
+            implied_by: None,
 
             #[cfg(feature = "encoding")]
             encoding: EncodingRef::Implicit(UTF_8),
@@ -397,10 +1121,15 @@ impl Debug for ReaderState {
 
         d.field("offset", &self.offset);
         d.field("last_error_offset", &self.last_error_offset);
+        d.field("tracked", &self.tracked);
+        d.field("last_error_position", &self.last_error_position);
         d.field("state", &self.state);
         d.field("config", &self.config);
         d.field("opened_buffer", &Bytes(&self.opened_buffer));
         d.field("opened_starts", &self.opened_starts);
+        d.field("diagnostics", &self.diagnostics);
+        d.field("implied_ends", &self.implied_ends);
+        d.field("implied_by", &self.implied_by);
 
         #[cfg(feature = "encoding")]
         d.field("encoding", &self.encoding);
@@ -80,6 +80,7 @@ mod text;
 use self::content::ContentSerializer;
 use self::element::{ElementSerializer, Map, Struct, Tuple};
 use crate::de::TEXT_KEY;
+use crate::utils::{is_xml11_name_char, is_xml11_name_start_char};
 use crate::writer::{Indentation, ToFmtWrite};
 use serde::ser::{self, Serialize};
 use std::fmt::Write;
@@ -180,6 +181,129 @@ where
     value.serialize(Serializer::new(&mut ToFmtWrite(writer)))
 }
 
+/// Serialize struct into an [`AsyncSink`] restricted to utf-8 encoding.
+/// Async version of [`to_utf8_io_writer`].
+///
+/// Serde serializers are synchronous, so the whole value is serialized into
+/// a `String` which is then written to the `writer`. Memory usage is therefore
+/// proportional to the size of the output. The output is the same as the output
+/// of [`to_writer`], that is, the default settings of the [`Serializer`] are used.
+/// Use [`to_async_writer_with`] to change indentation, [`QuoteLevel`] or other
+/// settings.
+///
+/// Returns the classification of the last written type.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "async-tokio", doc = "```")]
+#[cfg_attr(not(feature = "async-tokio"), doc = "```ignore")]
+/// # use quick_xml::se::to_async_writer;
+/// # use serde::Serialize;
+/// # use pretty_assertions::assert_eq;
+/// # #[tokio::main(flavor = "current_thread")] async fn main() {
+/// #[derive(Serialize)]
+/// struct Root<'a> {
+///     #[serde(rename = "@attribute")]
+///     attribute: &'a str,
+///     element: &'a str,
+///     #[serde(rename = "$text")]
+///     text: &'a str,
+/// }
+///
+/// let data = Root {
+///     attribute: "attribute content",
+///     element: "element content",
+///     text: "text content",
+/// };
+///
+/// let mut buffer = Vec::new();
+/// to_async_writer(&mut buffer, &data).await.unwrap();
+///
+/// assert_eq!(
+///     std::str::from_utf8(&buffer).unwrap(),
+///     "<Root attribute=\"attribute content\">\
+///         <element>element content</element>\
+///         text content\
+///     </Root>"
+/// );
+/// # }
+/// ```
+///
+/// [`AsyncSink`]: crate::async_io::AsyncSink
+#[cfg(any(feature = "async-tokio", feature = "async-futures"))]
+pub async fn to_async_writer<W, T>(writer: &mut W, value: &T) -> Result<WriteResult, SeError>
+where
+    W: crate::async_io::AsyncSink,
+    T: ?Sized + Serialize,
+{
+    let mut buffer = String::new();
+    let result = to_writer(&mut buffer, value)?;
+    writer.write_all(buffer.as_bytes()).await?;
+    Ok(result)
+}
+
+/// Serialize struct into an [`AsyncSink`] using a [`Serializer`] configured by
+/// the `configure` function. If `root_tag` is defined, it is used as a name of
+/// the root element, as in [`Serializer::with_root`]. `root_tag` should be valid
+/// [XML name], otherwise error is returned.
+///
+/// Serde serializers are synchronous, so the whole value is serialized into
+/// a `String` which is then written to the `writer`. The output is the same as
+/// the output of the [`Serializer`] with the same settings.
+///
+/// Returns the classification of the last written type.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "async-tokio", doc = "```")]
+#[cfg_attr(not(feature = "async-tokio"), doc = "```ignore")]
+/// # use quick_xml::se::{to_async_writer_with, QuoteLevel};
+/// # use serde::Serialize;
+/// # use pretty_assertions::assert_eq;
+/// # #[tokio::main(flavor = "current_thread")] async fn main() {
+/// #[derive(Serialize)]
+/// struct Root<'a> {
+///     element: &'a str,
+/// }
+///
+/// let data = Root { element: "'quoted'" };
+///
+/// let mut buffer = Vec::new();
+/// to_async_writer_with(&mut buffer, Some("top-level"), &data, |serializer| {
+///     serializer.indent(' ', 2).set_quote_level(QuoteLevel::Minimal);
+/// })
+/// .await
+/// .unwrap();
+///
+/// assert_eq!(
+///     std::str::from_utf8(&buffer).unwrap(),
+///     "<top-level>\n  <element>'quoted'</element>\n</top-level>"
+/// );
+/// # }
+/// ```
+///
+/// [XML name]: https://www.w3.org/TR/xml11/#NT-Name
+/// [`AsyncSink`]: crate::async_io::AsyncSink
+#[cfg(any(feature = "async-tokio", feature = "async-futures"))]
+pub async fn to_async_writer_with<W, T, F>(
+    writer: &mut W,
+    root_tag: Option<&str>,
+    value: &T,
+    configure: F,
+) -> Result<WriteResult, SeError>
+where
+    W: crate::async_io::AsyncSink,
+    T: ?Sized + Serialize,
+    F: FnOnce(&mut Serializer<'_, '_, String>),
+{
+    let mut buffer = String::new();
+    let mut serializer = Serializer::with_root(&mut buffer, root_tag)?;
+    configure(&mut serializer);
+    let result = value.serialize(serializer)?;
+    writer.write_all(buffer.as_bytes()).await?;
+    Ok(result)
+}
+
 /// Serialize struct into a `String`.
 ///
 /// # Examples
@@ -272,6 +396,66 @@ where
     value.serialize(Serializer::with_root(&mut writer, Some(root_tag))?)
 }
 
+/// Serialize struct into an [`AsyncSink`] using specified root tag name.
+/// Async version of [`to_writer_with_root`].
+/// `root_tag` should be valid [XML name], otherwise error is returned.
+///
+/// Serde serializers are synchronous, so the whole value is serialized into
+/// a `String` which is then written to the `writer`. The default settings of
+/// the [`Serializer`] are used, use [`to_async_writer_with`] to change them.
+///
+/// Returns the classification of the last written type.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "async-tokio", doc = "```")]
+#[cfg_attr(not(feature = "async-tokio"), doc = "```ignore")]
+/// # use quick_xml::se::to_async_writer_with_root;
+/// # use serde::Serialize;
+/// # use pretty_assertions::assert_eq;
+/// # #[tokio::main(flavor = "current_thread")] async fn main() {
+/// #[derive(Serialize)]
+/// struct Root<'a> {
+///     #[serde(rename = "@attribute")]
+///     attribute: &'a str,
+///     element: &'a str,
+/// }
+///
+/// let data = Root {
+///     attribute: "attribute content",
+///     element: "element content",
+/// };
+///
+/// let mut buffer = Vec::new();
+/// to_async_writer_with_root(&mut buffer, "top-level", &data).await.unwrap();
+/// assert_eq!(
+///     std::str::from_utf8(&buffer).unwrap(),
+///     "<top-level attribute=\"attribute content\">\
+///         <element>element content</element>\
+///     </top-level>"
+/// );
+/// # }
+/// ```
+///
+/// [XML name]: https://www.w3.org/TR/xml11/#NT-Name
+///
+/// [`AsyncSink`]: crate::async_io::AsyncSink
+#[cfg(any(feature = "async-tokio", feature = "async-futures"))]
+pub async fn to_async_writer_with_root<W, T>(
+    writer: &mut W,
+    root_tag: &str,
+    value: &T,
+) -> Result<WriteResult, SeError>
+where
+    W: crate::async_io::AsyncSink,
+    T: ?Sized + Serialize,
+{
+    let mut buffer = String::new();
+    let result = to_writer_with_root(&mut buffer, root_tag, value)?;
+    writer.write_all(buffer.as_bytes()).await?;
+    Ok(result)
+}
+
 /// Serialize struct into a `String` using specified root tag name.
 /// `root_tag` should be valid [XML name], otherwise error is returned.
 ///
@@ -441,49 +625,6 @@ macro_rules! forward {
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
-/// Almost all characters can form a name. Citation from <https://www.w3.org/TR/xml11/#sec-xml11>:
-///
-/// > The overall philosophy of names has changed since XML 1.0. Whereas XML 1.0
-/// > provided a rigid definition of names, wherein everything that was not permitted
-/// > was forbidden, XML 1.1 names are designed so that everything that is not
-/// > forbidden (for a specific reason) is permitted. Since Unicode will continue
-/// > to grow past version 4.0, further changes to XML can be avoided by allowing
-/// > almost any character, including those not yet assigned, in names.
-///
-/// <https://www.w3.org/TR/xml11/#NT-NameStartChar>
-const fn is_xml11_name_start_char(ch: char) -> bool {
-    // Not need to use macro when core primitives is enough
-    #[allow(clippy::match_like_matches_macro)]
-    match ch {
-        ':'
-        | 'A'..='Z'
-        | '_'
-        | 'a'..='z'
-        | '\u{00C0}'..='\u{00D6}'
-        | '\u{00D8}'..='\u{00F6}'
-        | '\u{00F8}'..='\u{02FF}'
-        | '\u{0370}'..='\u{037D}'
-        | '\u{037F}'..='\u{1FFF}'
-        | '\u{200C}'..='\u{200D}'
-        | '\u{2070}'..='\u{218F}'
-        | '\u{2C00}'..='\u{2FEF}'
-        | '\u{3001}'..='\u{D7FF}'
-        | '\u{F900}'..='\u{FDCF}'
-        | '\u{FDF0}'..='\u{FFFD}'
-        | '\u{10000}'..='\u{EFFFF}' => true,
-        _ => false,
-    }
-}
-/// <https://www.w3.org/TR/xml11/#NT-NameChar>
-const fn is_xml11_name_char(ch: char) -> bool {
-    match ch {
-        '-' | '.' | '0'..='9' | '\u{00B7}' | '\u{0300}'..='\u{036F}' | '\u{203F}'..='\u{2040}' => {
-            true
-        }
-        _ => is_xml11_name_start_char(ch),
-    }
-}
-
 /// Helper struct to self-defense from errors
 #[derive(Clone, Copy, Debug, PartialEq)]
 struct XmlName<'n>(&'n str);
@@ -322,6 +322,61 @@ pub const fn is_whitespace(b: u8) -> bool {
     matches!(b, b' ' | b'\r' | b'\n' | b'\t')
 }
 
+/// Almost all characters can form a name. Citation from <https://www.w3.org/TR/xml11/#sec-xml11>:
+///
+/// > The overall philosophy of names has changed since XML 1.0. Whereas XML 1.0
+/// > provided a rigid definition of names, wherein everything that was not permitted
+/// > was forbidden, XML 1.1 names are designed so that everything that is not
+/// > forbidden (for a specific reason) is permitted. Since Unicode will continue
+/// > to grow past version 4.0, further changes to XML can be avoided by allowing
+/// > almost any character, including those not yet assigned, in names.
+///
+/// <https://www.w3.org/TR/xml11/#NT-NameStartChar>
+pub const fn is_xml11_name_start_char(ch: char) -> bool {
+    // Not need to use macro when core primitives is enough
+    #[allow(clippy::match_like_matches_macro)]
+    match ch {
+        ':'
+        | 'A'..='Z'
+        | '_'
+        | 'a'..='z'
+        | '\u{00C0}'..='\u{00D6}'
+        | '\u{00D8}'..='\u{00F6}'
+        | '\u{00F8}'..='\u{02FF}'
+        | '\u{0370}'..='\u{037D}'
+        | '\u{037F}'..='\u{1FFF}'
+        | '\u{200C}'..='\u{200D}'
+        | '\u{2070}'..='\u{218F}'
+        | '\u{2C00}'..='\u{2FEF}'
+        | '\u{3001}'..='\u{D7FF}'
+        | '\u{F900}'..='\u{FDCF}'
+        | '\u{FDF0}'..='\u{FFFD}'
+        | '\u{10000}'..='\u{EFFFF}' => true,
+        _ => false,
+    }
+}
+/// <https://www.w3.org/TR/xml11/#NT-NameChar>
+pub const fn is_xml11_name_char(ch: char) -> bool {
+    match ch {
+        '-' | '.' | '0'..='9' | '\u{00B7}' | '\u{0300}'..='\u{036F}' | '\u{203F}'..='\u{2040}' => {
+            true
+        }
+        _ => is_xml11_name_start_char(ch),
+    }
+}
+
+/// Checks that `name` matches the [`Name`] production. The rules are the same
+/// for XML 1.0 (since the Fifth Edition) and XML 1.1.
+///
+/// [`Name`]: https://www.w3.org/TR/xml11/#NT-Name
+pub fn is_xml_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(ch) if is_xml11_name_start_char(ch) => chars.all(is_xml11_name_char),
+        _ => false,
+    }
+}
+
 /// Calculates name from an element-like content. Name is the first word in `content`,
 /// where word boundaries is XML whitespace characters.
 ///
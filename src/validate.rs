@@ -0,0 +1,865 @@
+//! Validation of documents against their document type definition (DTD).
+//!
+//! [`Dtd`] is a compiled set of element type and attribute-list declarations,
+//! and [`Validator`] checks events read by the [`Reader`] against it. The DTD
+//! is captured from the [`Event::DocType`] event of the document, and can also
+//! be provided explicitly, for example, when it is read from the external subset.
+//!
+//! The validator checks:
+//!
+//! - that the root element has the name declared in the `<!DOCTYPE>`;
+//! - that all elements are declared and their content matches the content model
+//!   of the `<!ELEMENT>` declaration: `EMPTY`, `ANY`, mixed content with
+//!   `#PCDATA` and element content built from sequences, choices and `?`, `*`
+//!   and `+` repetitions;
+//! - that attributes are declared in `<!ATTLIST>` declarations, `#REQUIRED`
+//!   attributes are present, values of `#FIXED` attributes match the declared
+//!   values and values match their types, including enumerations;
+//! - that `ID` attributes are unique within the document and `IDREF` and `IDREFS`
+//!   attributes reference existing identifiers.
+//!
+//! Validity errors do not stop the validation. They are collected as [`Violation`]s
+//! which contain the position where the offending event starts.
+//!
+//! Validation is not aware of namespaces: names of elements and attributes are
+//! compared as written, including prefixes.
+//!
+//! # Example
+//!
+//! ```
+//! # use pretty_assertions::assert_eq;
+//! use quick_xml::reader::Reader;
+//! use quick_xml::validate::{validate, ValidityError};
+//!
+//! let mut reader = Reader::from_str(r#"<!DOCTYPE list [
+//!     <!ELEMENT list (item+)>
+//!     <!ELEMENT item (#PCDATA)>
+//!     <!ATTLIST item id ID #REQUIRED>
+//! ]>
+//! <list><item id="a">1</item><item id="a">2</item></list>"#);
+//!
+//! let violations = validate(&mut reader).unwrap();
+//! let errors: Vec<_> = violations.into_iter().map(|v| (v.position.byte, v.error)).collect();
+//! assert_eq!(errors, [(141, ValidityError::DuplicateId("a".into()))]);
+//! ```
+//!
+//! [`Reader`]: crate::reader::Reader
+//! [`Event::DocType`]: crate::events::Event::DocType
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::BufRead;
+
+use crate::dtd::{
+    AttributeType, ContentParticle, ContentSpec, DefaultDecl, DtdError, DtdEvent, DtdReader,
+    EntityDef, ParticleKind, Repeat,
+};
+use crate::errors::{IllFormedError, Result};
+use crate::escape::resolve_predefined_entity;
+use crate::events::attributes::Attribute;
+use crate::events::{BytesStart, BytesText, Event};
+use crate::name::QName;
+use crate::reader::{Position, Reader};
+use crate::utils::{is_whitespace, is_xml11_name_char, is_xml_name};
+use crate::XmlVersion;
+
+/// Maximum number of nested entities that are expanded in attribute values.
+const MAX_ENTITY_DEPTH: usize = 32;
+
+/// A reason why the document is not valid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidityError {
+    /// The document does not have a `<!DOCTYPE>` declaration and no DTD was
+    /// provided to the [`Validator`].
+    MissingDtd,
+    /// The name of the root element differs from the name declared in the `<!DOCTYPE>`.
+    RootMismatch {
+        /// Name declared in the `<!DOCTYPE>`
+        expected: String,
+        /// Name of the root element
+        found: String,
+    },
+    /// The element is not declared in the DTD.
+    UndeclaredElement(String),
+    /// The content model of the parent element does not allow the child element
+    /// at this place.
+    UnexpectedElement {
+        /// Name of the parent element
+        parent: String,
+        /// Name of the child element
+        child: String,
+    },
+    /// The element contains text, but its content model does not allow it.
+    /// Contains the name of the element.
+    UnexpectedText(String),
+    /// The element was closed before all child elements required by its content
+    /// model were found. Contains the name of the element.
+    IncompleteContent(String),
+    /// The attribute is not declared for the element.
+    UndeclaredAttribute {
+        /// Name of the element
+        element: String,
+        /// Name of the attribute
+        attribute: String,
+    },
+    /// The `#REQUIRED` attribute is not specified.
+    MissingAttribute {
+        /// Name of the element
+        element: String,
+        /// Name of the attribute
+        attribute: String,
+    },
+    /// The value of the `#FIXED` attribute differs from the declared value.
+    FixedAttributeMismatch {
+        /// Name of the element
+        element: String,
+        /// Name of the attribute
+        attribute: String,
+        /// Declared value of the attribute
+        expected: String,
+        /// Value of the attribute in the document
+        found: String,
+    },
+    /// The value of the attribute does not match its type, for example, it is
+    /// not one of the enumerated values.
+    InvalidAttributeValue {
+        /// Name of the element
+        element: String,
+        /// Name of the attribute
+        attribute: String,
+        /// Normalized value of the attribute
+        value: String,
+    },
+    /// The value of an `ID` attribute was already used by another element.
+    DuplicateId(String),
+    /// An `IDREF` or `IDREFS` attribute references an identifier which is not
+    /// defined in the document.
+    UnknownId(String),
+}
+
+impl fmt::Display for ValidityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingDtd => f.write_str("document does not have a DTD"),
+            Self::RootMismatch { expected, found } => write!(
+                f,
+                "root element `{}` does not match the document type `{}`",
+                found, expected
+            ),
+            Self::UndeclaredElement(name) => write!(f, "element `{}` is not declared", name),
+            Self::UnexpectedElement { parent, child } => write!(
+                f,
+                "element `{}` is not allowed here by the content model of `{}`",
+                child, parent
+            ),
+            Self::UnexpectedText(name) => {
+                write!(f, "text is not allowed in the content of `{}`", name)
+            }
+            Self::IncompleteContent(name) => write!(
+                f,
+                "content of `{}` ended before all required elements were found",
+                name
+            ),
+            Self::UndeclaredAttribute { element, attribute } => write!(
+                f,
+                "attribute `{}` is not declared for element `{}`",
+                attribute, element
+            ),
+            Self::MissingAttribute { element, attribute } => write!(
+                f,
+                "required attribute `{}` of element `{}` is not specified",
+                attribute, element
+            ),
+            Self::FixedAttributeMismatch {
+                element,
+                attribute,
+                expected,
+                found,
+            } => write!(
+                f,
+                "attribute `{}` of element `{}` must have fixed value `{}`, but `{}` was found",
+                attribute, element, expected, found
+            ),
+            Self::InvalidAttributeValue {
+                element,
+                attribute,
+                value,
+            } => write!(
+                f,
+                "value `{}` is not valid for attribute `{}` of element `{}`",
+                value, attribute, element
+            ),
+            Self::DuplicateId(id) => write!(f, "ID `{}` is already defined", id),
+            Self::UnknownId(id) => write!(f, "referenced ID `{}` is not defined", id),
+        }
+    }
+}
+
+impl std::error::Error for ValidityError {}
+
+/// A validity error together with the position of the event where it was found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    /// Position of the start of the event that violates the DTD. Line and column
+    /// are known only when [`Config::track_position`] is enabled, otherwise they are `0`.
+    ///
+    /// [`Config::track_position`]: crate::reader::Config::track_position
+    pub position: Position,
+    /// The violated rule
+    pub error: ValidityError,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.position.line == 0 {
+            write!(f, "byte {}: {}", self.position.byte, self.error)
+        } else {
+            write!(f, "{}: {}", self.position, self.error)
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A content model of an element with element content, compiled into
+/// a non-deterministic finite automaton.
+///
+/// Each node either matches a child element with the specified name or just
+/// passes to the next nodes. Node `0` is the final node.
+#[derive(Clone, Debug)]
+struct ContentModel {
+    nodes: Vec<Node>,
+    /// Index of the initial node
+    start: usize,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    /// Name of the element matched by the node, or `None` if the node does not
+    /// consume an element
+    name: Option<String>,
+    /// Nodes reachable from this node
+    next: Vec<usize>,
+}
+
+impl ContentModel {
+    fn new(particle: &ContentParticle) -> Self {
+        let mut model = Self {
+            nodes: vec![Node {
+                name: None,
+                next: Vec::new(),
+            }],
+            start: 0,
+        };
+        model.start = model.particle(particle, 0);
+        model
+    }
+
+    fn add(&mut self, name: Option<String>, next: Vec<usize>) -> usize {
+        self.nodes.push(Node { name, next });
+        self.nodes.len() - 1
+    }
+
+    /// Adds nodes of the particle which continue to the node `out` and returns
+    /// the entry node of the particle.
+    fn particle(&mut self, particle: &ContentParticle, out: usize) -> usize {
+        match particle.repeat {
+            Repeat::Once => self.kind(&particle.kind, out),
+            Repeat::Optional => {
+                let entry = self.kind(&particle.kind, out);
+                self.add(None, vec![entry, out])
+            }
+            Repeat::ZeroOrMore | Repeat::OneOrMore => {
+                let repeat = self.add(None, Vec::new());
+                let entry = self.kind(&particle.kind, repeat);
+                self.nodes[repeat].next = vec![entry, out];
+                if particle.repeat == Repeat::ZeroOrMore {
+                    repeat
+                } else {
+                    entry
+                }
+            }
+        }
+    }
+
+    fn kind(&mut self, kind: &ParticleKind, out: usize) -> usize {
+        match kind {
+            ParticleKind::Name(name) => self.add(Some(name.to_string()), vec![out]),
+            ParticleKind::Seq(particles) => particles
+                .iter()
+                .rev()
+                .fold(out, |out, particle| self.particle(particle, out)),
+            ParticleKind::Choice(particles) => {
+                let next = particles
+                    .iter()
+                    .map(|particle| self.particle(particle, out))
+                    .collect();
+                self.add(None, next)
+            }
+        }
+    }
+
+    /// Returns nodes that match elements and the final node which are reachable
+    /// from `nodes` without matching an element.
+    fn closure(&self, nodes: impl IntoIterator<Item = usize>) -> Vec<usize> {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack: Vec<_> = nodes.into_iter().collect();
+        let mut states = Vec::new();
+        while let Some(index) = stack.pop() {
+            if std::mem::replace(&mut visited[index], true) {
+                continue;
+            }
+            let node = &self.nodes[index];
+            if node.name.is_some() || index == 0 {
+                states.push(index);
+            } else {
+                stack.extend(&node.next);
+            }
+        }
+        states
+    }
+
+    fn initial(&self) -> Vec<usize> {
+        self.closure([self.start])
+    }
+
+    /// Returns states after matching the child element `name` in the `states`.
+    /// Returns an empty vector if the element is not allowed.
+    fn step(&self, states: &[usize], name: &str) -> Vec<usize> {
+        self.closure(
+            states
+                .iter()
+                .map(|&index| &self.nodes[index])
+                .filter(|node| node.name.as_deref() == Some(name))
+                .flat_map(|node| node.next.iter().copied()),
+        )
+    }
+}
+
+/// Allowed content of an element.
+#[derive(Clone, Debug)]
+enum Content {
+    Empty,
+    Any,
+    /// Text and listed elements
+    Mixed(Vec<String>),
+    /// Only elements
+    Children(ContentModel),
+}
+
+/// A type of an attribute. Notation types are checked as enumerations.
+#[derive(Clone, Debug)]
+enum AttrType {
+    CData,
+    Id,
+    IdRef,
+    IdRefs,
+    Entity,
+    Entities,
+    NmToken,
+    NmTokens,
+    Enumeration(Vec<String>),
+}
+
+#[derive(Clone, Debug)]
+enum AttrDefault {
+    Required,
+    Implied,
+    /// Contains the value as written in the DTD
+    Fixed(String),
+    /// The attribute has a default value, which is not checked
+    Value,
+}
+
+/// Declaration of an attribute in the `<!ATTLIST>`.
+#[derive(Clone, Debug)]
+struct AttributeRule {
+    name: String,
+    ty: AttrType,
+    default: AttrDefault,
+}
+
+/// Element type and attribute-list declarations of a DTD used by the [`Validator`].
+///
+/// `Dtd` can be created from the content of an [`Event::DocType`] event or from
+/// the content of an external DTD subset.
+///
+/// [`Event::DocType`]: crate::events::Event::DocType
+#[derive(Clone, Debug, Default)]
+pub struct Dtd {
+    /// Name of the root element declared in the `<!DOCTYPE>`
+    root: Option<String>,
+    elements: HashMap<String, Content>,
+    attributes: HashMap<String, Vec<AttributeRule>>,
+    /// Replacement texts of internal general entities, used to expand references
+    /// in attribute values
+    entities: HashMap<String, String>,
+    /// Names of unparsed entities, which are allowed values of `ENTITY` attributes
+    unparsed: HashSet<String>,
+}
+
+impl Dtd {
+    /// Parses the DTD. `input` is either the content of an [`Event::DocType`]
+    /// event, or the content of an external DTD subset.
+    ///
+    /// As required by the XML specification, the first declaration of an attribute
+    /// or an entity is binding and subsequent declarations are ignored.
+    ///
+    /// [`Event::DocType`]: crate::events::Event::DocType
+    pub fn parse(input: &str) -> std::result::Result<Self, DtdError> {
+        let mut dtd = Self::default();
+        for event in DtdReader::new(input) {
+            match event? {
+                DtdEvent::DocType(decl) => dtd.root = Some(decl.name.to_string()),
+                DtdEvent::ElementDecl(decl) => {
+                    let content = match &decl.content {
+                        ContentSpec::Empty => Content::Empty,
+                        ContentSpec::Any => Content::Any,
+                        ContentSpec::Mixed(names) => {
+                            Content::Mixed(names.iter().map(|name| name.to_string()).collect())
+                        }
+                        ContentSpec::Children(particle) => {
+                            Content::Children(ContentModel::new(particle))
+                        }
+                    };
+                    dtd.elements.entry(decl.name.to_string()).or_insert(content);
+                }
+                DtdEvent::AttlistDecl(decl) => {
+                    let rules = dtd.attributes.entry(decl.element.to_string()).or_default();
+                    for def in decl.attributes {
+                        if rules.iter().any(|rule| rule.name == def.name) {
+                            continue;
+                        }
+                        let names =
+                            |names: Vec<&str>| names.into_iter().map(String::from).collect();
+                        let ty = match def.ty {
+                            AttributeType::CData => AttrType::CData,
+                            AttributeType::Id => AttrType::Id,
+                            AttributeType::IdRef => AttrType::IdRef,
+                            AttributeType::IdRefs => AttrType::IdRefs,
+                            AttributeType::Entity => AttrType::Entity,
+                            AttributeType::Entities => AttrType::Entities,
+                            AttributeType::NmToken => AttrType::NmToken,
+                            AttributeType::NmTokens => AttrType::NmTokens,
+                            AttributeType::Notation(values)
+                            | AttributeType::Enumeration(values) => {
+                                AttrType::Enumeration(names(values))
+                            }
+                        };
+                        let default = match def.default {
+                            DefaultDecl::Required => AttrDefault::Required,
+                            DefaultDecl::Implied => AttrDefault::Implied,
+                            DefaultDecl::Fixed(value) => AttrDefault::Fixed(value.to_string()),
+                            DefaultDecl::Value(_) => AttrDefault::Value,
+                        };
+                        rules.push(AttributeRule {
+                            name: def.name.to_string(),
+                            ty,
+                            default,
+                        });
+                    }
+                }
+                DtdEvent::EntityDecl(decl) if !decl.parameter => match decl.def {
+                    EntityDef::Internal(value) => {
+                        dtd.entities
+                            .entry(decl.name.to_string())
+                            .or_insert_with(|| value.to_string());
+                    }
+                    EntityDef::External { ndata: Some(_), .. } => {
+                        dtd.unparsed.insert(decl.name.to_string());
+                    }
+                    EntityDef::External { .. } => {}
+                },
+                _ => {}
+            }
+        }
+        Ok(dtd)
+    }
+
+    /// Returns the name of the root element declared in the `<!DOCTYPE>`.
+    pub fn root(&self) -> Option<&str> {
+        self.root.as_deref()
+    }
+
+    /// Adds declarations from `other` which are not declared in this DTD.
+    fn merge(&mut self, other: Self) {
+        if self.root.is_none() {
+            self.root = other.root;
+        }
+        for (name, content) in other.elements {
+            self.elements.entry(name).or_insert(content);
+        }
+        for (element, rules) in other.attributes {
+            let existing = self.attributes.entry(element).or_default();
+            for rule in rules {
+                if !existing.iter().any(|r| r.name == rule.name) {
+                    existing.push(rule);
+                }
+            }
+        }
+        for (name, value) in other.entities {
+            self.entities.entry(name).or_insert(value);
+        }
+        self.unparsed.extend(other.unparsed);
+    }
+
+    /// Normalizes the attribute value as required by its type: references are
+    /// replaced, whitespace characters are replaced by spaces and, for all types
+    /// except `CDATA`, leading and trailing spaces are removed and sequences of
+    /// spaces are replaced by a single space.
+    fn normalize(&self, attr: &Attribute, ty: &AttrType, version: XmlVersion) -> Result<String> {
+        let value = attr.normalized_value_with(version, MAX_ENTITY_DEPTH, |name| {
+            resolve_predefined_entity(name).or_else(|| self.entities.get(name).map(String::as_str))
+        })?;
+        Ok(match ty {
+            AttrType::CData => value.into_owned(),
+            _ => value
+                .split(' ')
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+                .join(" "),
+        })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An opened element.
+#[derive(Debug)]
+struct Frame {
+    name: String,
+    /// States of the content model of the element, if it has element content
+    states: Vec<usize>,
+}
+
+/// Checks events of a document against the DTD.
+///
+/// Feed each event read by the [`Reader`] to the [`feed()`] method, including
+/// the final [`Event::Eof`], and then check [`violations()`]. The DTD is taken
+/// from the [`Event::DocType`] event; if the DTD was provided to [`with_dtd()`],
+/// declarations from the internal subset take precedence over it.
+///
+/// See the [module documentation](self) for the list of checks and the [`validate()`]
+/// function which validates the whole document.
+///
+/// [`feed()`]: Self::feed
+/// [`violations()`]: Self::violations
+/// [`with_dtd()`]: Self::with_dtd
+#[derive(Debug)]
+pub struct Validator {
+    dtd: Option<Dtd>,
+    /// Opened elements
+    stack: Vec<Frame>,
+    /// `true` if the root element was already opened
+    root_seen: bool,
+    /// Defined identifiers
+    ids: HashSet<String>,
+    /// Referenced identifiers with positions of the references
+    idrefs: Vec<(String, Position)>,
+    /// Version of the document, used to normalize attribute values
+    version: XmlVersion,
+    /// Position just after the last fed event, i.e. the start of the next event
+    position: Position,
+    violations: Vec<Violation>,
+}
+
+impl Validator {
+    /// Creates a validator which takes the DTD from the document.
+    pub fn new() -> Self {
+        Self {
+            dtd: None,
+            stack: Vec::new(),
+            root_seen: false,
+            ids: HashSet::new(),
+            idrefs: Vec::new(),
+            version: XmlVersion::Implicit1_0,
+            position: Position::START,
+            violations: Vec::new(),
+        }
+    }
+
+    /// Creates a validator with the DTD provided explicitly, for example,
+    /// parsed from the external DTD subset. It is used when the document does
+    /// not have a `<!DOCTYPE>`, and is extended with the declarations from
+    /// the internal subset otherwise.
+    pub fn with_dtd(dtd: Dtd) -> Self {
+        Self {
+            dtd: Some(dtd),
+            ..Self::new()
+        }
+    }
+
+    /// Returns validity errors found so far, in order of their appearance.
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
+    /// Returns validity errors found since the last call of this method and
+    /// clears the list of them.
+    pub fn take_violations(&mut self) -> Vec<Violation> {
+        std::mem::take(&mut self.violations)
+    }
+
+    /// Updates the validator with the event that was just read by the `reader`.
+    ///
+    /// All events of the document should be fed in order, because positions of
+    /// violations are computed from the positions of the `reader` after the
+    /// previous event. Unresolved `IDREF`s are reported when [`Event::Eof`] is fed.
+    ///
+    /// Returns an error if the DTD or a value of an attribute cannot be parsed.
+    /// Validity errors are collected and returned by [`Self::violations`].
+    pub fn feed<R>(&mut self, event: &Event, reader: &Reader<R>) -> Result<()> {
+        let position = std::mem::replace(&mut self.position, reader.position());
+        match event {
+            Event::Decl(e) => self.version = e.xml_version()?,
+            Event::DocType(e) => self.capture(e)?,
+            Event::Start(e) => self.start(e, position)?,
+            Event::Empty(e) => {
+                self.start(e, position)?;
+                self.end(position);
+            }
+            Event::End(_) => self.end(position),
+            Event::Text(e) => self.text(e.iter().all(|b| is_whitespace(*b)), position),
+            Event::CData(_) | Event::GeneralRef(_) => self.text(false, position),
+            Event::Eof => {
+                let unknown: Vec<_> = self
+                    .idrefs
+                    .drain(..)
+                    .filter(|(id, _)| !self.ids.contains(id))
+                    .collect();
+                for (id, position) in unknown {
+                    self.report(position, ValidityError::UnknownId(id));
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn report(&mut self, position: Position, error: ValidityError) {
+        self.violations.push(Violation { position, error });
+    }
+
+    /// Parses the internal subset of the DTD.
+    fn capture(&mut self, doctype: &BytesText) -> Result<()> {
+        let content = doctype.decode()?;
+        let mut dtd = Dtd::parse(&content).map_err(IllFormedError::InvalidDtd)?;
+        if let Some(external) = self.dtd.take() {
+            dtd.merge(external);
+        }
+        self.dtd = Some(dtd);
+        Ok(())
+    }
+
+    fn start(&mut self, start: &BytesStart, position: Position) -> Result<()> {
+        let decoder = start.decoder();
+        let decode =
+            |name: QName| -> Result<String> { Ok(decoder.decode(name.as_ref())?.into_owned()) };
+        let name = decode(start.name())?;
+        let dtd = match &self.dtd {
+            Some(dtd) => dtd,
+            None => {
+                if !std::mem::replace(&mut self.root_seen, true) {
+                    self.report(position, ValidityError::MissingDtd);
+                }
+                self.stack.push(Frame {
+                    name,
+                    states: Vec::new(),
+                });
+                return Ok(());
+            }
+        };
+        let mut errors = Vec::new();
+
+        match self.stack.last_mut() {
+            None => {
+                self.root_seen = true;
+                match &dtd.root {
+                    Some(root) if *root != name => errors.push(ValidityError::RootMismatch {
+                        expected: root.clone(),
+                        found: name.clone(),
+                    }),
+                    _ => {}
+                }
+            }
+            Some(parent) => {
+                let allowed = match dtd.elements.get(&parent.name) {
+                    Some(Content::Empty) => false,
+                    Some(Content::Mixed(names)) => names.contains(&name),
+                    Some(Content::Children(model)) => {
+                        let states = model.step(&parent.states, &name);
+                        // Skip the unexpected element and continue with the same states
+                        let allowed = !states.is_empty();
+                        if allowed {
+                            parent.states = states;
+                        }
+                        allowed
+                    }
+                    Some(Content::Any) | None => true,
+                };
+                if !allowed {
+                    errors.push(ValidityError::UnexpectedElement {
+                        parent: parent.name.clone(),
+                        child: name.clone(),
+                    });
+                }
+            }
+        }
+
+        let states = match dtd.elements.get(&name) {
+            Some(Content::Children(model)) => model.initial(),
+            Some(_) => Vec::new(),
+            None => {
+                errors.push(ValidityError::UndeclaredElement(name.clone()));
+                Vec::new()
+            }
+        };
+
+        // Attributes of undeclared elements are not checked
+        if dtd.elements.contains_key(&name) {
+            let rules = dtd.attributes.get(&name).map_or(&[][..], Vec::as_slice);
+            let mut specified = Vec::new();
+            for attr in start.attributes() {
+                let attr = attr?;
+                let attribute = decode(attr.key)?;
+                let rule = match rules.iter().find(|rule| rule.name == attribute) {
+                    Some(rule) => rule,
+                    None => {
+                        errors.push(ValidityError::UndeclaredAttribute {
+                            element: name.clone(),
+                            attribute,
+                        });
+                        continue;
+                    }
+                };
+                let value = dtd.normalize(&attr, &rule.ty, self.version)?;
+                let tokens = || value.split(' ');
+                let valid = match &rule.ty {
+                    AttrType::CData => true,
+                    AttrType::Id => {
+                        if is_xml_name(&value) && !self.ids.insert(value.clone()) {
+                            errors.push(ValidityError::DuplicateId(value.clone()));
+                        }
+                        is_xml_name(&value)
+                    }
+                    AttrType::IdRef => {
+                        self.idrefs.push((value.clone(), position));
+                        is_xml_name(&value)
+                    }
+                    AttrType::IdRefs => {
+                        for id in tokens() {
+                            self.idrefs.push((id.to_string(), position));
+                        }
+                        tokens().all(is_xml_name)
+                    }
+                    AttrType::Entity => dtd.unparsed.contains(&value),
+                    AttrType::Entities => tokens().all(|name| dtd.unparsed.contains(name)),
+                    AttrType::NmToken => is_nmtoken(&value),
+                    AttrType::NmTokens => tokens().all(is_nmtoken),
+                    AttrType::Enumeration(values) => values.contains(&value),
+                };
+                if !valid {
+                    errors.push(ValidityError::InvalidAttributeValue {
+                        element: name.clone(),
+                        attribute: attribute.clone(),
+                        value: value.clone(),
+                    });
+                }
+                if let AttrDefault::Fixed(fixed) = &rule.default {
+                    let fixed = Attribute {
+                        key: QName(attribute.as_bytes()),
+                        value: Cow::Borrowed(fixed.as_bytes()),
+                    };
+                    let expected = dtd.normalize(&fixed, &rule.ty, self.version)?;
+                    if expected != value {
+                        errors.push(ValidityError::FixedAttributeMismatch {
+                            element: name.clone(),
+                            attribute: attribute.clone(),
+                            expected,
+                            found: value,
+                        });
+                    }
+                }
+                specified.push(attribute);
+            }
+            for rule in rules {
+                if matches!(rule.default, AttrDefault::Required) && !specified.contains(&rule.name)
+                {
+                    errors.push(ValidityError::MissingAttribute {
+                        element: name.clone(),
+                        attribute: rule.name.clone(),
+                    });
+                }
+            }
+        }
+
+        for error in errors {
+            self.report(position, error);
+        }
+        self.stack.push(Frame { name, states });
+        Ok(())
+    }
+
+    fn end(&mut self, position: Position) {
+        let (frame, dtd) = match (self.stack.pop(), &self.dtd) {
+            (Some(frame), Some(dtd)) => (frame, dtd),
+            _ => return,
+        };
+        if let Some(Content::Children(_)) = dtd.elements.get(&frame.name) {
+            if !frame.states.contains(&0) {
+                self.report(position, ValidityError::IncompleteContent(frame.name));
+            }
+        }
+    }
+
+    /// Checks that text is allowed in the current element.
+    ///
+    /// # Parameters
+    /// - `whitespace`: `true` if the text consists only of whitespace characters
+    fn text(&mut self, whitespace: bool, position: Position) {
+        let (frame, dtd) = match (self.stack.last(), &self.dtd) {
+            (Some(frame), Some(dtd)) => (frame, dtd),
+            _ => return,
+        };
+        let allowed = match dtd.elements.get(&frame.name) {
+            Some(Content::Empty) => false,
+            Some(Content::Children(_)) => whitespace,
+            Some(Content::Any | Content::Mixed(_)) | None => true,
+        };
+        if !allowed {
+            let error = ValidityError::UnexpectedText(frame.name.clone());
+            self.report(position, error);
+        }
+    }
+}
+
+impl Default for Validator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Checks that `value` matches the [`Nmtoken`] production.
+///
+/// [`Nmtoken`]: https://www.w3.org/TR/xml11/#NT-Nmtoken
+fn is_nmtoken(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(is_xml11_name_char)
+}
+
+/// Reads the whole document from the `reader` and validates it against its DTD.
+///
+/// Returns all found validity errors, or an error if the document is not
+/// well-formed. See the [module documentation](self) for an example.
+pub fn validate<R: BufRead>(reader: &mut Reader<R>) -> Result<Vec<Violation>> {
+    let mut validator = Validator::new();
+    let mut buf = Vec::new();
+    loop {
+        let event = reader.read_event_into(&mut buf)?;
+        validator.feed(&event, reader)?;
+        if let Event::Eof = event {
+            return Ok(validator.take_violations());
+        }
+        buf.clear();
+    }
+}
@@ -4,10 +4,15 @@ use std::borrow::Cow;
 use std::io::{self, Write};
 
 use crate::encoding::UTF8_BOM;
+use crate::errors::IllFormedError;
 use crate::events::{attributes::Attribute, BytesCData, BytesPI, BytesStart, BytesText, Event};
+use crate::utils::{is_whitespace, is_xml_name};
 
-#[cfg(feature = "async-tokio")]
-mod async_tokio;
+#[cfg(any(feature = "async-tokio", feature = "async-futures"))]
+mod async_io;
+mod ns_writer;
+
+pub use ns_writer::NsWriter;
 
 /// XML writer. Writes XML [`Event`]s to a [`std::io::Write`] or [`tokio::io::AsyncWrite`] implementor.
 #[cfg(feature = "serialize")]
@@ -57,6 +62,75 @@ pub struct Config {
     ///
     /// [W3C guidelines]: https://www.w3.org/TR/xhtml1/#guidelines
     pub add_space_before_slash_in_empty_elements: bool,
+
+    /// Whether the writer should check that written events form a [well-formed]
+    /// document. When enabled, the writer keeps a stack of opened elements and
+    /// refuses to write:
+    /// - an [`End`] event without matching [`Start`] event
+    ///   ([`UnmatchedEndTag`] and [`MismatchedEndTag`] errors);
+    /// - the second root element ([`MultipleRoots`] error);
+    /// - a non-whitespace text, CDATA section or reference outside the root
+    ///   element ([`TextOutsideRoot`] error);
+    /// - a comment containing `--` or ending with `-` ([`DoubleHyphenInComment`] error);
+    /// - a CDATA section containing `]]>` ([`CDataEndInCData`] error);
+    /// - an element with an invalid name ([`InvalidName`] error).
+    ///
+    /// Errors are returned as [`std::io::Error`] of kind [`InvalidInput`] which
+    /// wraps an [`IllFormedError`]. The conversion of such error into
+    /// [`Error`] returns [`Error::IllFormed`]. Nothing is written when an error
+    /// is returned.
+    ///
+    /// Use [`Writer::finish()`] to check that all elements are closed.
+    ///
+    /// This option should be set before writing the first event.
+    ///
+    /// Default: `false`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::errors::{Error, IllFormedError};
+    /// use quick_xml::events::{BytesEnd, BytesStart, Event};
+    /// use quick_xml::writer::Writer;
+    ///
+    /// let mut writer = Writer::new(Vec::new());
+    /// writer.config_mut().check_well_formedness = true;
+    ///
+    /// writer.write_event(Event::Start(BytesStart::new("root")))?;
+    /// let error = writer.write_event(Event::End(BytesEnd::new("other"))).unwrap_err();
+    /// match Error::from(error) {
+    ///     Error::IllFormed(IllFormedError::MismatchedEndTag { expected, found }) => {
+    ///         assert_eq!(expected, "root");
+    ///         assert_eq!(found, "other");
+    ///     }
+    ///     e => panic!("Unexpected error {:?}", e),
+    /// }
+    /// match Error::from(writer.finish().unwrap_err()) {
+    ///     Error::IllFormed(IllFormedError::MissingEndTag(name)) => assert_eq!(name, "root"),
+    ///     e => panic!("Unexpected error {:?}", e),
+    /// }
+    ///
+    /// writer.write_event(Event::End(BytesEnd::new("root")))?;
+    /// writer.finish()?;
+    /// assert_eq!(writer.into_inner(), b"<root></root>");
+    /// # std::io::Result::Ok(())
+    /// ```
+    ///
+    /// [well-formed]: https://www.w3.org/TR/xml11/#dt-wellformed
+    /// [`Start`]: crate::events::Event::Start
+    /// [`End`]: crate::events::Event::End
+    /// [`UnmatchedEndTag`]: IllFormedError::UnmatchedEndTag
+    /// [`MismatchedEndTag`]: IllFormedError::MismatchedEndTag
+    /// [`MultipleRoots`]: IllFormedError::MultipleRoots
+    /// [`TextOutsideRoot`]: IllFormedError::TextOutsideRoot
+    /// [`DoubleHyphenInComment`]: IllFormedError::DoubleHyphenInComment
+    /// [`CDataEndInCData`]: IllFormedError::CDataEndInCData
+    /// [`InvalidName`]: IllFormedError::InvalidName
+    /// [`InvalidInput`]: std::io::ErrorKind::InvalidInput
+    /// [`Error`]: crate::errors::Error
+    /// [`Error::IllFormed`]: crate::errors::Error::IllFormed
+    pub check_well_formedness: bool,
 }
 
 /// XML writer. Writes XML [`Event`]s to a [`std::io::Write`] implementor.
@@ -115,6 +189,11 @@ pub struct Writer<W> {
     /// indentation configuration and state; stored separately from
     /// other configuration since it also tracks writer state
     indent: Option<Indentation>,
+
+    /// Names of opened elements. Used only when well-formedness is checked
+    opened: Vec<Vec<u8>>,
+    /// Whether the root element was written. Used only when well-formedness is checked
+    root_written: bool,
 }
 
 impl<W> Writer<W> {
@@ -124,8 +203,11 @@ impl<W> Writer<W> {
             writer: inner,
             config: Config {
                 add_space_before_slash_in_empty_elements: false,
+                check_well_formedness: false,
             },
             indent: None,
+            opened: Vec::new(),
+            root_written: false,
         }
     }
 
@@ -135,8 +217,11 @@ impl<W> Writer<W> {
             writer: inner,
             config: Config {
                 add_space_before_slash_in_empty_elements: false,
+                check_well_formedness: false,
             },
             indent: Some(Indentation::new(indent_char, indent_size)),
+            opened: Vec::new(),
+            root_written: false,
         }
     }
 
@@ -220,6 +305,84 @@ impl<W> Writer<W> {
             spaces: Vec::new(),
         }
     }
+
+    /// Checks that all written elements are closed, if [checks of well-formedness]
+    /// are enabled. Returns [`IllFormedError::MissingEndTag`] with the name of
+    /// the innermost unclosed element otherwise.
+    ///
+    /// See [`Config::check_well_formedness`] for an example.
+    ///
+    /// [checks of well-formedness]: Config::check_well_formedness
+    pub fn finish(&self) -> io::Result<()> {
+        match self.opened.last() {
+            Some(name) => Err(ill_formed(IllFormedError::MissingEndTag(
+                String::from_utf8_lossy(name).into_owned(),
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that the event can be written at the current place of the document,
+    /// if [checks of well-formedness] are enabled, and updates the stack of
+    /// opened elements.
+    ///
+    /// [checks of well-formedness]: Config::check_well_formedness
+    pub(crate) fn check(&mut self, event: &Event) -> Result<(), IllFormedError> {
+        if !self.config.check_well_formedness {
+            return Ok(());
+        }
+        match event {
+            Event::Start(e) | Event::Empty(e) => {
+                let name = e.name();
+                let decoded = String::from_utf8_lossy(name.as_ref());
+                if !is_xml_name(&decoded) {
+                    return Err(IllFormedError::InvalidName(decoded.into_owned()));
+                }
+                if self.opened.is_empty() {
+                    if self.root_written {
+                        return Err(IllFormedError::MultipleRoots(decoded.into_owned()));
+                    }
+                    self.root_written = true;
+                }
+                if let Event::Start(_) = event {
+                    self.opened.push(name.as_ref().to_vec());
+                }
+            }
+            Event::End(e) => {
+                let found = e.name();
+                match self.opened.last() {
+                    Some(expected) if expected.as_slice() == found.as_ref() => {
+                        self.opened.pop();
+                    }
+                    Some(expected) => {
+                        return Err(IllFormedError::MismatchedEndTag {
+                            expected: String::from_utf8_lossy(expected).into_owned(),
+                            found: String::from_utf8_lossy(found.as_ref()).into_owned(),
+                        })
+                    }
+                    None => {
+                        return Err(IllFormedError::UnmatchedEndTag(
+                            String::from_utf8_lossy(found.as_ref()).into_owned(),
+                        ))
+                    }
+                }
+            }
+            Event::Text(e) if self.opened.is_empty() && !e.iter().copied().all(is_whitespace) => {
+                return Err(IllFormedError::TextOutsideRoot);
+            }
+            Event::CData(_) | Event::GeneralRef(_) if self.opened.is_empty() => {
+                return Err(IllFormedError::TextOutsideRoot);
+            }
+            Event::Comment(e) if e.windows(2).any(|w| w == b"--") || e.last() == Some(&b'-') => {
+                return Err(IllFormedError::DoubleHyphenInComment);
+            }
+            Event::CData(e) if e.windows(3).any(|w| w == b"]]>") => {
+                return Err(IllFormedError::CDataEndInCData);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
 }
 
 impl<W: Write> Writer<W> {
@@ -258,9 +421,16 @@ impl<W: Write> Writer<W> {
     }
 
     /// Writes the given event to the underlying writer.
+    ///
+    /// If [checks of well-formedness] are enabled, events that would make the
+    /// document ill-formed are not written and an error is returned.
+    ///
+    /// [checks of well-formedness]: Config::check_well_formedness
     pub fn write_event<'a, E: Into<Event<'a>>>(&mut self, event: E) -> io::Result<()> {
+        let event = event.into();
+        self.check(&event).map_err(ill_formed)?;
         let mut next_should_line_break = true;
-        let result = match event.into() {
+        let result = match event {
             Event::Start(e) => {
                 let result = self.write_wrapped(b"<", &e, b">");
                 if let Some(i) = self.indent.as_mut() {
@@ -398,6 +568,8 @@ impl<W: Write> Writer<W> {
     ) -> Result<(), SeError> {
         use crate::se::{Indent, Serializer};
 
+        self.check(&Event::Empty(BytesStart::new(tag_name)))
+            .map_err(ill_formed)?;
         self.write_indent()?;
         let mut fmt = ToFmtWrite(&mut self.writer);
         let mut serializer = Serializer::with_root(&mut fmt, Some(tag_name))?;
@@ -653,6 +825,13 @@ impl<'a, W: Write> ElementWriter<'a, W> {
         Ok(self.writer)
     }
 }
+/// Wraps an error of the check of well-formedness into [`io::Error`] of kind
+/// [`io::ErrorKind::InvalidInput`]. Such errors are converted back by the
+/// `From<io::Error>` implementation for [`Error`](crate::errors::Error).
+fn ill_formed(error: IllFormedError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, error)
+}
+
 #[cfg(feature = "serialize")]
 pub(crate) struct ToFmtWrite<T>(pub T);
 
@@ -1,17 +1,21 @@
 use std::future::Future;
 use std::result::Result as StdResult;
 
-use tokio::io::{AsyncWrite, AsyncWriteExt};
-
+use crate::async_io::AsyncSink;
 use crate::errors::{Error, Result};
 use crate::events::{BytesCData, BytesPI, BytesText, Event};
 use crate::{ElementWriter, Writer};
 
-impl<W: AsyncWrite + Unpin> Writer<W> {
+#[cfg(feature = "serialize")]
+use {crate::events::BytesStart, crate::se::SeError, serde::Serialize};
+
+impl<W: AsyncSink> Writer<W> {
     /// Writes the given event to the underlying writer. Async version of [`Writer::write_event`].
     pub async fn write_event_async<'a, E: Into<Event<'a>>>(&mut self, event: E) -> Result<()> {
+        let event = event.into();
+        self.check(&event)?;
         let mut next_should_line_break = true;
-        let result = match event.into() {
+        let result = match event {
             Event::Start(e) => {
                 let result = self.write_wrapped_async(b"<", &e, b">").await;
                 if let Some(i) = self.indent.as_mut() {
@@ -61,6 +65,77 @@ impl<W: AsyncWrite + Unpin> Writer<W> {
         Ok(())
     }
 
+    /// Write an arbitrary serializable type. Async version of [`Writer::write_serializable`].
+    ///
+    /// Serde serializers are synchronous, so the whole value is serialized into
+    /// a `String` which is then written to the underlying writer. Indentation
+    /// of this writer is used in the same way as in the sync version.
+    ///
+    #[cfg_attr(feature = "async-tokio", doc = "```")]
+    #[cfg_attr(not(feature = "async-tokio"), doc = "```ignore")]
+    /// # use pretty_assertions::assert_eq;
+    /// # use serde::Serialize;
+    /// # use quick_xml::events::{BytesStart, Event};
+    /// # use quick_xml::writer::Writer;
+    /// # #[tokio::main(flavor = "current_thread")] async fn main() {
+    /// #[derive(Debug, PartialEq, Serialize)]
+    /// struct MyData {
+    ///     question: String,
+    ///     answer: u32,
+    /// }
+    ///
+    /// let data = MyData {
+    ///     question: "The Ultimate Question of Life, the Universe, and Everything".into(),
+    ///     answer: 42,
+    /// };
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut writer = Writer::new_with_indent(&mut buffer, b' ', 4);
+    ///
+    /// let start = BytesStart::new("root");
+    /// let end = start.to_end();
+    ///
+    /// writer.write_event_async(Event::Start(start.clone())).await.unwrap();
+    /// writer.write_serializable_async("my_data", &data).await.unwrap();
+    /// writer.write_event_async(Event::End(end)).await.unwrap();
+    ///
+    /// assert_eq!(
+    ///     std::str::from_utf8(&buffer).unwrap(),
+    ///     r#"<root>
+    ///     <my_data>
+    ///         <question>The Ultimate Question of Life, the Universe, and Everything</question>
+    ///         <answer>42</answer>
+    ///     </my_data>
+    /// </root>"#
+    /// );
+    /// # }
+    /// ```
+    #[cfg(feature = "serialize")]
+    pub async fn write_serializable_async<T: Serialize>(
+        &mut self,
+        tag_name: &str,
+        content: &T,
+    ) -> StdResult<(), SeError> {
+        use crate::se::{Indent, Serializer};
+
+        self.check(&Event::Empty(BytesStart::new(tag_name)))
+            .map_err(super::ill_formed)?;
+        self.write_indent_async().await.map_err(|e| match e {
+            Error::Io(e) => SeError::Io(e),
+            e => SeError::Custom(e.to_string()),
+        })?;
+
+        let mut buffer = String::new();
+        let mut serializer = Serializer::with_root(&mut buffer, Some(tag_name))?;
+        if let Some(indent) = &mut self.indent {
+            serializer.set_indent(Indent::Borrow(indent));
+        }
+        content.serialize(serializer)?;
+
+        self.writer.write_all(buffer.as_bytes()).await?;
+        Ok(())
+    }
+
     #[inline]
     async fn write_async(&mut self, value: &[u8]) -> Result<()> {
         self.writer.write_all(value).await.map_err(Into::into)
@@ -86,12 +161,13 @@ impl<W: AsyncWrite + Unpin> Writer<W> {
     }
 }
 
-impl<'a, W: AsyncWrite + Unpin> ElementWriter<'a, W> {
+impl<'a, W: AsyncSink> ElementWriter<'a, W> {
     /// Write some text inside the current element.
     ///
     /// # Example
     ///
-    /// ```
+    #[cfg_attr(feature = "async-tokio", doc = "```")]
+    #[cfg_attr(not(feature = "async-tokio"), doc = "```ignore")]
     /// # use quick_xml::writer::Writer;
     /// # use quick_xml::events::BytesText;
     /// # use tokio::io::AsyncWriteExt;
@@ -130,7 +206,8 @@ impl<'a, W: AsyncWrite + Unpin> ElementWriter<'a, W> {
     ///
     /// # Example
     ///
-    /// ```
+    #[cfg_attr(feature = "async-tokio", doc = "```")]
+    #[cfg_attr(not(feature = "async-tokio"), doc = "```ignore")]
     /// # use quick_xml::writer::Writer;
     /// # use quick_xml::events::BytesCData;
     /// # use tokio::io::AsyncWriteExt;
@@ -172,7 +249,8 @@ impl<'a, W: AsyncWrite + Unpin> ElementWriter<'a, W> {
     ///
     /// # Example
     ///
-    /// ```
+    #[cfg_attr(feature = "async-tokio", doc = "```")]
+    #[cfg_attr(not(feature = "async-tokio"), doc = "```ignore")]
     /// # use quick_xml::writer::Writer;
     /// # use quick_xml::events::BytesPI;
     /// # use tokio::io::AsyncWriteExt;
@@ -213,7 +291,8 @@ impl<'a, W: AsyncWrite + Unpin> ElementWriter<'a, W> {
     ///
     /// # Example
     ///
-    /// ```
+    #[cfg_attr(feature = "async-tokio", doc = "```")]
+    #[cfg_attr(not(feature = "async-tokio"), doc = "```ignore")]
     /// # use quick_xml::writer::Writer;
     /// # use quick_xml::events::BytesText;
     /// # use tokio::io::AsyncWriteExt;
@@ -248,7 +327,8 @@ impl<'a, W: AsyncWrite + Unpin> ElementWriter<'a, W> {
     ///
     /// # Example
     ///
-    /// ```
+    #[cfg_attr(feature = "async-tokio", doc = "```")]
+    #[cfg_attr(not(feature = "async-tokio"), doc = "```ignore")]
     /// # use quick_xml::writer::Writer;
     /// # use quick_xml::events::BytesText;
     /// # use tokio::io::AsyncWriteExt;
@@ -317,7 +397,7 @@ impl<'a, W: AsyncWrite + Unpin> ElementWriter<'a, W> {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "async-tokio"))]
 mod tests {
     use super::*;
     use crate::events::*;
@@ -389,9 +469,36 @@ mod tests {
             r#"<tag>inner text</tag>"#
         );
     }
+
+    #[tokio::test]
+    async fn check_well_formedness() {
+        use crate::errors::IllFormedError;
+
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new(&mut buffer);
+        writer.config_mut().check_well_formedness = true;
+
+        writer
+            .write_event_async(Event::Start(BytesStart::new("tag")))
+            .await
+            .expect("write start tag failed");
+        match writer
+            .write_event_async(Event::End(BytesEnd::new("other")))
+            .await
+        {
+            Err(Error::IllFormed(IllFormedError::MismatchedEndTag { expected, found })) => {
+                assert_eq!(expected, "tag");
+                assert_eq!(found, "other");
+            }
+            x => panic!("Expected `Err(IllFormed(_))`, but got `{:?}`", x),
+        }
+        assert!(writer.finish().is_err());
+
+        assert_eq!(std::str::from_utf8(&buffer).unwrap(), r#"<tag>"#);
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "async-tokio"))]
 mod indentation_async {
     use super::*;
     use crate::events::*;
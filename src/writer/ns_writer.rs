@@ -0,0 +1,367 @@
+//! A writer that writes elements and attributes with [expanded names] and
+//! declares [namespace prefixes] for them automatically.
+//!
+//! [expanded names]: https://www.w3.org/TR/xml-names11/#dt-expname
+//! [namespace prefixes]: https://www.w3.org/TR/xml-names11/#dt-prefix
+
+use std::io::Write;
+use std::ops::Deref;
+
+use crate::encoding::Decoder;
+use crate::errors::{Error, IllFormedError, Result};
+use crate::escape::escape;
+use crate::events::{BytesEnd, BytesStart, Event};
+use crate::name::{LocalName, Namespace, NamespaceResolver, PrefixDeclaration, ResolveResult};
+use crate::writer::{Config, Writer};
+
+/// The namespace which is always bound to the `xml` prefix.
+const XML: &[u8] = b"http://www.w3.org/XML/1998/namespace";
+/// The namespace which is always bound to the `xmlns` prefix.
+const XMLNS: &[u8] = b"http://www.w3.org/2000/xmlns/";
+
+/// A namespace declaration which should be written in the start tag: the prefix
+/// (`None` for the default namespace) and the namespace name.
+type Declaration = (Option<Vec<u8>>, Vec<u8>);
+
+/// XML writer which writes elements and attributes with names in namespaces.
+///
+/// Elements and attributes are given as _(namespace, local name)_ pairs.
+/// The writer keeps track of the namespace bindings in scope and:
+/// - reuses prefixes already bound to the namespace;
+/// - declares [preferred] prefixes for namespaces not in scope;
+/// - declares fresh `ns0`, `ns1`, ... prefixes if there is no preferred prefix
+///   or it is already bound to another namespace.
+///
+/// An element in the empty namespace is written without prefix. If the default
+/// namespace is in scope at that place, it is undeclared with `xmlns=""`.
+/// Attributes in the empty namespace are written without prefix.
+///
+/// Namespace declarations in events written by [`write_event`] are tracked
+/// too, so you can declare some namespaces on the root element yourself.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_assertions::assert_eq;
+/// use quick_xml::events::{BytesText, Event};
+/// use quick_xml::name::{Namespace, PrefixDeclaration, QName};
+/// use quick_xml::writer::NsWriter;
+///
+/// let mut writer = NsWriter::new(Vec::new());
+/// writer.prefer_prefix(Namespace(b"urn:books"), PrefixDeclaration::Default);
+/// writer.prefer_prefix(Namespace(b"urn:isbn"), PrefixDeclaration::Named(b"isbn"));
+///
+/// let books = Namespace(b"urn:books");
+/// writer.write_start((books, QName(b"book").into()), [])?;
+/// writer.write_start(
+///     (books, QName(b"title").into()),
+///     [((Namespace(b"urn:isbn"), QName(b"id").into()), "0-395-36341-6")],
+/// )?;
+/// writer.write_event(Event::Text(BytesText::new("Cheaper by the Dozen")))?;
+/// writer.write_end()?;
+/// writer.write_empty((Namespace(b"urn:other"), QName(b"notes").into()), [])?;
+/// writer.write_end()?;
+///
+/// assert_eq!(
+///     String::from_utf8(writer.into_inner()).unwrap(),
+///     "<book xmlns=\"urn:books\">\
+///         <title xmlns:isbn=\"urn:isbn\" isbn:id=\"0-395-36341-6\">Cheaper by the Dozen</title>\
+///         <ns0:notes xmlns:ns0=\"urn:other\"/>\
+///     </book>"
+/// );
+/// # quick_xml::Result::Ok(())
+/// ```
+///
+/// [preferred]: Self::prefer_prefix
+/// [`write_event`]: Self::write_event
+#[derive(Clone)]
+pub struct NsWriter<W> {
+    /// An XML writer
+    writer: Writer<W>,
+    /// Namespace bindings in scope
+    ns_resolver: NamespaceResolver,
+    /// Qualified names of opened elements
+    opened: Vec<Vec<u8>>,
+    /// Prefixes which should be used for namespaces if they are not in scope
+    preferred: Vec<Declaration>,
+}
+
+/// Builder methods
+impl<W> NsWriter<W> {
+    /// Creates a `NsWriter` from a generic writer.
+    pub fn new(inner: W) -> Self {
+        Self::from_writer(Writer::new(inner))
+    }
+
+    /// Creates a `NsWriter` with configured indents from a generic writer.
+    pub fn new_with_indent(inner: W, indent_char: u8, indent_size: usize) -> Self {
+        Self::from_writer(Writer::new_with_indent(inner, indent_char, indent_size))
+    }
+
+    /// Creates a `NsWriter` which writes using the specified `Writer`.
+    pub fn from_writer(writer: Writer<W>) -> Self {
+        Self {
+            writer,
+            ns_resolver: NamespaceResolver::default(),
+            opened: Vec::new(),
+            preferred: Vec::new(),
+        }
+    }
+
+    /// Returns mutable reference to the writer configuration
+    pub fn config_mut(&mut self) -> &mut Config {
+        self.writer.config_mut()
+    }
+
+    /// Sets the prefix which should be declared for the namespace when it is
+    /// not in scope. The later call for the same namespace replaces the prefix.
+    ///
+    /// [`PrefixDeclaration::Default`] makes the namespace the default namespace
+    /// for elements. Attributes in such namespace still get a named prefix,
+    /// because default namespace does not apply to attributes.
+    ///
+    /// If the preferred prefix is already bound to another namespace, a fresh
+    /// prefix is generated.
+    pub fn prefer_prefix(&mut self, namespace: Namespace, prefix: PrefixDeclaration) {
+        let prefix = match prefix {
+            PrefixDeclaration::Default => None,
+            PrefixDeclaration::Named(prefix) => Some(prefix.to_vec()),
+        };
+        match self.preferred.iter_mut().find(|(_, ns)| ns == namespace.0) {
+            Some(entry) => entry.0 = prefix,
+            None => self.preferred.push((prefix, namespace.0.to_vec())),
+        }
+    }
+}
+
+/// Getters
+impl<W> NsWriter<W> {
+    /// Consumes this `NsWriter`, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner()
+    }
+
+    /// Get a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.writer.get_mut()
+    }
+
+    /// Returns a storage of namespace bindings in scope.
+    pub const fn resolver(&self) -> &NamespaceResolver {
+        &self.ns_resolver
+    }
+}
+
+/// Private methods
+impl<W> NsWriter<W> {
+    /// Returns the named prefix bound to the namespace in scope, including
+    /// bindings declared in the element being written.
+    fn find_prefix(&self, decls: &[Declaration], namespace: &[u8]) -> Option<Vec<u8>> {
+        if namespace == XML {
+            return Some(b"xml".to_vec());
+        }
+        if namespace == XMLNS {
+            return Some(b"xmlns".to_vec());
+        }
+        if let Some((prefix, _)) = decls.iter().find(|(p, ns)| p.is_some() && ns == namespace) {
+            return prefix.clone();
+        }
+        self.ns_resolver
+            .bindings()
+            .find_map(|(prefix, ns)| match prefix {
+                PrefixDeclaration::Named(prefix) if ns.0 == namespace => Some(prefix.to_vec()),
+                _ => None,
+            })
+    }
+
+    /// Checks that the prefix is not bound to any namespace in scope, including
+    /// bindings declared in the element being written.
+    fn is_free(&self, decls: &[Declaration], prefix: &[u8]) -> bool {
+        prefix != b"xml"
+            && prefix != b"xmlns"
+            && !decls.iter().any(|(p, _)| p.as_deref() == Some(prefix))
+            && !self
+                .ns_resolver
+                .bindings()
+                .any(|(p, _)| p == PrefixDeclaration::Named(prefix))
+    }
+
+    /// Declares a new prefix for the namespace and returns it. Returns `None`
+    /// if the namespace is declared as the default namespace.
+    fn declare(
+        &self,
+        decls: &mut Vec<Declaration>,
+        namespace: &[u8],
+        allow_default: bool,
+    ) -> Option<Vec<u8>> {
+        let preferred = self.preferred.iter().find(|(_, ns)| ns == namespace);
+        let prefix = match preferred {
+            Some((None, _)) if allow_default => None,
+            Some((Some(prefix), _)) if self.is_free(decls, prefix) => Some(prefix.clone()),
+            _ => (0..)
+                .map(|i| format!("ns{}", i).into_bytes())
+                .find(|prefix| self.is_free(decls, prefix)),
+        };
+        decls.push((prefix.clone(), namespace.to_vec()));
+        prefix
+    }
+
+    /// Returns the prefix for the element in the namespace, declaring it if necessary.
+    fn element_prefix(&self, decls: &mut Vec<Declaration>, namespace: &[u8]) -> Option<Vec<u8>> {
+        let default = self.ns_resolver.resolve_prefix(None, true);
+        if namespace.is_empty() {
+            if let ResolveResult::Bound(_) = default {
+                decls.push((None, Vec::new()));
+            }
+            return None;
+        }
+        if default == ResolveResult::Bound(Namespace(namespace)) {
+            return None;
+        }
+        self.find_prefix(decls, namespace)
+            .or_else(|| self.declare(decls, namespace, true))
+    }
+
+    /// Returns the prefix for the attribute in the namespace, declaring it if necessary.
+    fn attribute_prefix(&self, decls: &mut Vec<Declaration>, namespace: &[u8]) -> Option<Vec<u8>> {
+        if namespace.is_empty() {
+            return None;
+        }
+        self.find_prefix(decls, namespace)
+            .or_else(|| self.declare(decls, namespace, false))
+    }
+
+    /// Builds content of the start tag, declaring all necessary namespaces.
+    /// Returns the content and the length of the qualified name of the element.
+    fn start_tag<'a, I>(&self, name: (Namespace, LocalName), attributes: I) -> (Vec<u8>, usize)
+    where
+        I: IntoIterator<Item = ((Namespace<'a>, LocalName<'a>), &'a str)>,
+    {
+        let mut decls = Vec::new();
+        let mut buf = Vec::new();
+        push_qname(&mut buf, self.element_prefix(&mut decls, name.0 .0), name.1);
+        let name_len = buf.len();
+
+        let mut attrs = Vec::new();
+        for ((ns, local), value) in attributes {
+            attrs.push((self.attribute_prefix(&mut decls, ns.0), local, value));
+        }
+
+        for (prefix, ns) in decls {
+            buf.extend_from_slice(b" xmlns");
+            if let Some(prefix) = prefix {
+                buf.push(b':');
+                buf.extend_from_slice(&prefix);
+            }
+            buf.extend_from_slice(b"=\"");
+            buf.extend_from_slice(escape(String::from_utf8_lossy(&ns)).as_bytes());
+            buf.push(b'"');
+        }
+        for (prefix, local, value) in attrs {
+            buf.push(b' ');
+            push_qname(&mut buf, prefix, local);
+            buf.extend_from_slice(b"=\"");
+            buf.extend_from_slice(escape(value).as_bytes());
+            buf.push(b'"');
+        }
+        (buf, name_len)
+    }
+
+    /// Opens a new namespace scope for the start tag.
+    fn push(&mut self, start: &BytesStart) -> Result<()> {
+        self.ns_resolver.push(start)?;
+        self.opened.push(start.name().as_ref().to_vec());
+        Ok(())
+    }
+}
+
+impl<W: Write> NsWriter<W> {
+    /// Writes a start tag of the element with the attributes and opens a new
+    /// namespace scope. Namespaces not in scope are declared in the start tag.
+    ///
+    /// The element should be closed by [`write_end`].
+    ///
+    /// Attribute values are escaped. Namespace declarations should not be
+    /// passed as attributes, they are written automatically.
+    ///
+    /// [`write_end`]: Self::write_end
+    pub fn write_start<'a, I>(&mut self, name: (Namespace, LocalName), attributes: I) -> Result<()>
+    where
+        I: IntoIterator<Item = ((Namespace<'a>, LocalName<'a>), &'a str)>,
+    {
+        let (buf, name_len) = self.start_tag(name, attributes);
+        let start = BytesStart::wrap(&buf, name_len, Decoder::utf8());
+        self.push(&start)?;
+        Ok(self.writer.write_event(Event::Start(start))?)
+    }
+
+    /// Writes an empty element with the attributes. Namespaces not in scope are
+    /// declared in the element.
+    ///
+    /// Attribute values are escaped. Namespace declarations should not be
+    /// passed as attributes, they are written automatically.
+    pub fn write_empty<'a, I>(&mut self, name: (Namespace, LocalName), attributes: I) -> Result<()>
+    where
+        I: IntoIterator<Item = ((Namespace<'a>, LocalName<'a>), &'a str)>,
+    {
+        let (buf, name_len) = self.start_tag(name, attributes);
+        let start = BytesStart::wrap(&buf, name_len, Decoder::utf8());
+        Ok(self.writer.write_event(Event::Empty(start))?)
+    }
+
+    /// Writes an end tag of the innermost opened element and closes its namespace
+    /// scope.
+    ///
+    /// Returns [`IllFormedError::UnmatchedEndTag`] with an empty name if there
+    /// is no opened element.
+    pub fn write_end(&mut self) -> Result<()> {
+        let name = self
+            .opened
+            .pop()
+            .ok_or_else(|| Error::IllFormed(IllFormedError::UnmatchedEndTag(String::new())))?;
+        self.ns_resolver.pop();
+        Ok(self
+            .writer
+            .write_event(Event::End(BytesEnd::wrap(name.into())))?)
+    }
+
+    /// Writes the given event to the underlying writer.
+    ///
+    /// Namespace declarations of [`Start`] events open a new namespace scope,
+    /// which is closed by the [`End`] event or by [`write_end`]. [`End`] events
+    /// are written as is, their names are not checked.
+    ///
+    /// [`Start`]: Event::Start
+    /// [`End`]: Event::End
+    /// [`write_end`]: Self::write_end
+    pub fn write_event<'a, E: Into<Event<'a>>>(&mut self, event: E) -> Result<()> {
+        let event = event.into();
+        match &event {
+            Event::Start(e) => self.push(e)?,
+            Event::End(_) => {
+                self.opened.pop();
+                self.ns_resolver.pop();
+            }
+            _ => {}
+        }
+        Ok(self.writer.write_event(event)?)
+    }
+}
+
+impl<W> Deref for NsWriter<W> {
+    type Target = Writer<W>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.writer
+    }
+}
+
+/// Appends `prefix:local` or `local` name to the buffer.
+fn push_qname(buf: &mut Vec<u8>, prefix: Option<Vec<u8>>, local: LocalName) {
+    if let Some(prefix) = prefix {
+        buf.extend_from_slice(&prefix);
+        buf.push(b':');
+    }
+    buf.extend_from_slice(local.as_ref());
+}
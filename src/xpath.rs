@@ -0,0 +1,716 @@
+//! Streaming evaluation of a subset of [XPath 1.0] location paths.
+//!
+//! [`XPath`] is a compiled location path and [`Matcher`] tracks the position
+//! of the [`NsReader`] in the document and reports which events start and end
+//! the matched elements. Because matching works on events, it does not need to
+//! build a tree of the document and can be used on documents of any size.
+//!
+//! The supported subset of the syntax is:
+//!
+//! - `/a/b` -- an absolute path of child steps. Relative paths (`a/b`) are
+//!   evaluated relative to the document, so they are the same as absolute ones;
+//! - `//item`, `/a//b` -- descendant steps;
+//! - `*` and `p:*` -- any element or any element in the namespace;
+//! - `p:name` -- an element in the namespace bound to the prefix `p`. Prefixes
+//!   are bound with [`XPath::with_namespaces`]. As in XPath, names without
+//!   a prefix match only elements without a namespace, even if a default
+//!   namespace is declared in the document;
+//! - `[@attr]`, `[@attr = 'value']` and `[@attr != 'value']` -- predicates
+//!   on attributes;
+//! - `[2]` -- position predicates. Positions are counted among sibling elements
+//!   that pass the name test and the predicates before the position predicate;
+//! - `/@attr`, `//@attr`, `/@*` -- a final attribute step, which selects attributes
+//!   of the matched elements. Use [`Matcher::attributes`] to get them.
+//!
+//! Functions, other axes, `last()` and predicates that depend on the content
+//! of an element are not supported because they cannot be evaluated when the
+//! start tag is read.
+//!
+//! # Example
+//!
+//! ```
+//! # use pretty_assertions::assert_eq;
+//! use quick_xml::events::Event;
+//! use quick_xml::reader::NsReader;
+//! use quick_xml::xpath::{Match, Matcher, XPath};
+//!
+//! let xml = r#"
+//!     <feed xmlns="http://www.w3.org/2005/Atom">
+//!         <title>Feed</title>
+//!         <entry><title>First</title></entry>
+//!         <entry><title>Second</title></entry>
+//!     </feed>
+//! "#;
+//! let path = XPath::with_namespaces(
+//!     "//atom:entry/atom:title",
+//!     [("atom", "http://www.w3.org/2005/Atom")],
+//! ).unwrap();
+//! let mut matcher = Matcher::new(path);
+//! let mut reader = NsReader::from_str(xml);
+//!
+//! let mut titles = Vec::new();
+//! loop {
+//!     let event = reader.read_event().unwrap();
+//!     match matcher.feed(&event, &reader).unwrap() {
+//!         Some(Match::Start) => titles.push(String::new()),
+//!         _ if matcher.in_match() => {
+//!             if let Event::Text(e) = &event {
+//!                 titles.last_mut().unwrap().push_str(&e.decode().unwrap());
+//!             }
+//!         }
+//!         _ => {}
+//!     }
+//!     if let Event::Eof = event {
+//!         break;
+//!     }
+//! }
+//! assert_eq!(titles, ["First", "Second"]);
+//! ```
+//!
+//! [XPath 1.0]: https://www.w3.org/TR/1999/REC-xpath-19991116/
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::errors::Result;
+use crate::events::attributes::Attribute;
+use crate::events::{BytesStart, Event};
+use crate::name::{NamespaceResolver, ResolveResult};
+use crate::reader::NsReader;
+use crate::XmlVersion;
+
+/// An error returned by [`XPath`] when an expression cannot be compiled.
+///
+/// All positions are byte offsets relative to the start of the expression.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum XPathError {
+    /// The expression ended before the location path was finished. Contains
+    /// a description of what was expected.
+    UnexpectedEof(&'static str),
+    /// An unexpected character was found at the specified position. Contains
+    /// a description of what was expected.
+    Unexpected(usize, &'static str),
+    /// The prefix used in the expression was not bound to a namespace.
+    UnboundPrefix(String),
+}
+
+impl fmt::Display for XPathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnexpectedEof(expected) => {
+                write!(
+                    f,
+                    "unexpected end of XPath expression, expected {}",
+                    expected
+                )
+            }
+            Self::Unexpected(position, expected) => write!(
+                f,
+                "unexpected character at position {}, expected {}",
+                position, expected
+            ),
+            Self::UnboundPrefix(prefix) => {
+                write!(f, "prefix '{}' is not bound to a namespace", prefix)
+            }
+        }
+    }
+}
+
+impl std::error::Error for XPathError {}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A test of a name of an element or an attribute.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum NameTest {
+    /// `*`
+    Any,
+    /// `p:*`. Contains the namespace bound to `p`
+    AnyIn(Vec<u8>),
+    /// `name` or `p:name`. Contains the namespace bound to `p` (if any) and the local name
+    Name(Option<Vec<u8>>, Vec<u8>),
+}
+
+impl NameTest {
+    fn matches(&self, ns: &ResolveResult, local: &[u8]) -> bool {
+        match (self, ns) {
+            (Self::Any, _) => true,
+            (Self::AnyIn(uri), ResolveResult::Bound(ns)) => ns.as_ref() == uri.as_slice(),
+            (Self::Name(None, name), ResolveResult::Unbound) => local == name.as_slice(),
+            (Self::Name(Some(uri), name), ResolveResult::Bound(ns)) => {
+                ns.as_ref() == uri.as_slice() && local == name.as_slice()
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns attributes of the element that pass this test. Namespace
+    /// declarations are not attributes in XPath and are never returned.
+    fn select<'a>(
+        &self,
+        start: &'a BytesStart,
+        resolver: &NamespaceResolver,
+    ) -> Result<Vec<Attribute<'a>>> {
+        let mut selected = Vec::new();
+        for attr in start.attributes() {
+            let attr = attr?;
+            if attr.key.as_namespace_binding().is_some() {
+                continue;
+            }
+            let (ns, local) = resolver.resolve_attribute(attr.key);
+            if self.matches(&ns, local.as_ref()) {
+                selected.push(attr);
+            }
+        }
+        Ok(selected)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Predicate {
+    /// `[2]`. `counter` is an index of the counter of matched siblings in the [`Frame`]
+    Position { counter: usize, position: usize },
+    /// `[@attr]`
+    Attribute(NameTest),
+    /// `[@attr = 'value']` or `[@attr != 'value']`
+    Value {
+        name: NameTest,
+        value: String,
+        equal: bool,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Step {
+    /// `true` for steps after `//`, `false` for steps after `/`
+    descendant: bool,
+    test: NameTest,
+    predicates: Vec<Predicate>,
+}
+
+/// A compiled location path. See the [module documentation](self) for
+/// the supported syntax.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct XPath {
+    steps: Vec<Step>,
+    /// The final attribute step. The flag is `true` if the step follows `//`
+    attribute: Option<(bool, NameTest)>,
+    /// Number of position predicates in all steps
+    counters: usize,
+}
+
+impl XPath {
+    /// Compiles an expression without namespace prefixes.
+    ///
+    /// Only the `xml` prefix is bound. Use [`Self::with_namespaces`] to use
+    /// other prefixes.
+    pub fn new(expr: &str) -> std::result::Result<Self, XPathError> {
+        Self::with_namespaces(expr, std::iter::empty::<(&str, &str)>())
+    }
+
+    /// Compiles an expression where prefixes are bound to the namespaces
+    /// according to the `namespaces` map from prefix to namespace URI.
+    ///
+    /// The `xml` prefix is always bound to `http://www.w3.org/XML/1998/namespace`.
+    ///
+    /// ```
+    /// # use std::collections::HashMap;
+    /// use quick_xml::xpath::{XPath, XPathError};
+    ///
+    /// let mut namespaces = HashMap::new();
+    /// namespaces.insert("a", "urn:a");
+    ///
+    /// assert!(XPath::with_namespaces("/a:root/xml:item", &namespaces).is_ok());
+    /// assert_eq!(
+    ///     XPath::with_namespaces("/b:root", &namespaces),
+    ///     Err(XPathError::UnboundPrefix("b".to_string())),
+    /// );
+    /// ```
+    pub fn with_namespaces<I, P, U>(
+        expr: &str,
+        namespaces: I,
+    ) -> std::result::Result<Self, XPathError>
+    where
+        I: IntoIterator<Item = (P, U)>,
+        P: AsRef<str>,
+        U: AsRef<str>,
+    {
+        let namespaces = namespaces
+            .into_iter()
+            .map(|(p, u)| (p.as_ref().to_string(), u.as_ref().as_bytes().to_vec()))
+            .collect();
+        Parser {
+            input: expr,
+            offset: 0,
+            namespaces,
+        }
+        .parse()
+    }
+}
+
+impl FromStr for XPath {
+    type Err = XPathError;
+
+    #[inline]
+    fn from_str(expr: &str) -> std::result::Result<Self, XPathError> {
+        Self::new(expr)
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    offset: usize,
+    namespaces: Vec<(String, Vec<u8>)>,
+}
+
+impl<'a> Parser<'a> {
+    fn parse(mut self) -> std::result::Result<XPath, XPathError> {
+        let mut steps = Vec::new();
+        let mut counters = 0;
+
+        self.skip_whitespaces();
+        let mut descendant = if self.eat("//") {
+            true
+        } else {
+            self.eat("/");
+            false
+        };
+        loop {
+            self.skip_whitespaces();
+            if self.eat("@") {
+                let test = self.name_test()?;
+                self.skip_whitespaces();
+                if !self.rest().is_empty() {
+                    return Err(XPathError::Unexpected(self.offset, "end of expression"));
+                }
+                return Ok(XPath {
+                    steps,
+                    attribute: Some((descendant, test)),
+                    counters,
+                });
+            }
+            let test = self.name_test()?;
+            let predicates = self.predicates(&mut counters)?;
+            steps.push(Step {
+                descendant,
+                test,
+                predicates,
+            });
+
+            self.skip_whitespaces();
+            if self.rest().is_empty() {
+                return Ok(XPath {
+                    steps,
+                    attribute: None,
+                    counters,
+                });
+            }
+            descendant = if self.eat("//") {
+                true
+            } else if self.eat("/") {
+                false
+            } else {
+                return Err(XPathError::Unexpected(self.offset, "`/`, `//` or `[`"));
+            };
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.offset..]
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        if self.rest().starts_with(s) {
+            self.offset += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_whitespaces(&mut self) {
+        let rest = self.rest();
+        self.offset += rest.len() - rest.trim_start().len();
+    }
+
+    fn expect(&mut self, s: &'static str) -> std::result::Result<(), XPathError> {
+        if self.eat(s) {
+            Ok(())
+        } else if self.rest().is_empty() {
+            Err(XPathError::UnexpectedEof(s))
+        } else {
+            Err(XPathError::Unexpected(self.offset, s))
+        }
+    }
+
+    fn name(&mut self) -> std::result::Result<&'a str, XPathError> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || "/[]@=!'\"*:()|,".contains(c))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(if rest.is_empty() {
+                XPathError::UnexpectedEof("name")
+            } else {
+                XPathError::Unexpected(self.offset, "name")
+            });
+        }
+        self.offset += len;
+        Ok(&rest[..len])
+    }
+
+    fn name_test(&mut self) -> std::result::Result<NameTest, XPathError> {
+        if self.eat("*") {
+            return Ok(NameTest::Any);
+        }
+        let name = self.name()?;
+        if self.rest().starts_with("::") {
+            return Err(XPathError::Unexpected(self.offset, "`/`, `//` or `[`"));
+        }
+        if !self.eat(":") {
+            return Ok(NameTest::Name(None, name.as_bytes().to_vec()));
+        }
+        let namespace = self.namespace(name)?;
+        if self.eat("*") {
+            return Ok(NameTest::AnyIn(namespace));
+        }
+        let local = self.name()?;
+        Ok(NameTest::Name(Some(namespace), local.as_bytes().to_vec()))
+    }
+
+    fn namespace(&self, prefix: &str) -> std::result::Result<Vec<u8>, XPathError> {
+        if prefix == "xml" {
+            return Ok(b"http://www.w3.org/XML/1998/namespace".to_vec());
+        }
+        self.namespaces
+            .iter()
+            .find(|(p, _)| p == prefix)
+            .map(|(_, uri)| uri.clone())
+            .ok_or_else(|| XPathError::UnboundPrefix(prefix.to_string()))
+    }
+
+    fn predicates(
+        &mut self,
+        counters: &mut usize,
+    ) -> std::result::Result<Vec<Predicate>, XPathError> {
+        let mut predicates = Vec::new();
+        loop {
+            self.skip_whitespaces();
+            if !self.eat("[") {
+                return Ok(predicates);
+            }
+            self.skip_whitespaces();
+            let predicate = if self.eat("@") {
+                let name = self.name_test()?;
+                self.skip_whitespaces();
+                let equal = if self.eat("=") {
+                    true
+                } else if self.eat("!=") {
+                    false
+                } else {
+                    predicates.push(Predicate::Attribute(name));
+                    self.expect("]")?;
+                    continue;
+                };
+                self.skip_whitespaces();
+                let value = self.literal()?;
+                Predicate::Value { name, value, equal }
+            } else {
+                let start = self.offset;
+                let rest = self.rest();
+                let len = rest
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(rest.len());
+                self.offset += len;
+                match rest[..len].parse() {
+                    Ok(0) | Err(_) if rest.is_empty() => {
+                        return Err(XPathError::UnexpectedEof("`@` or position"))
+                    }
+                    Ok(0) | Err(_) => return Err(XPathError::Unexpected(start, "`@` or position")),
+                    Ok(position) => {
+                        *counters += 1;
+                        Predicate::Position {
+                            counter: *counters - 1,
+                            position,
+                        }
+                    }
+                }
+            };
+            predicates.push(predicate);
+            self.skip_whitespaces();
+            self.expect("]")?;
+        }
+    }
+
+    fn literal(&mut self) -> std::result::Result<String, XPathError> {
+        let quote = if self.eat("'") {
+            '\''
+        } else if self.eat("\"") {
+            '"'
+        } else if self.rest().is_empty() {
+            return Err(XPathError::UnexpectedEof("literal"));
+        } else {
+            return Err(XPathError::Unexpected(self.offset, "literal"));
+        };
+        let rest = self.rest();
+        match rest.find(quote) {
+            Some(len) => {
+                self.offset += len + 1;
+                Ok(rest[..len].to_string())
+            }
+            None => Err(XPathError::UnexpectedEof("closing quote")),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A kind of event of a matched element, returned by [`Matcher::feed`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Match {
+    /// [`Event::Start`] of the matched element.
+    Start,
+    /// [`Event::Empty`] of the matched element.
+    Empty,
+    /// [`Event::End`] of the matched element.
+    End,
+}
+
+/// The state of an opened element.
+#[derive(Clone, Debug)]
+struct Frame {
+    /// Indexes of steps that children of this element are tested against.
+    /// An index equal to the number of steps means that this element matched
+    /// the path
+    states: Vec<usize>,
+    /// Counters of children that passed the tests before each position predicate
+    counters: Vec<usize>,
+    /// `true` if [`Matcher::feed`] returned [`Match::Start`] for this element
+    matched: bool,
+}
+
+/// Tracks the events read by [`NsReader`] and reports which of them start
+/// and end the elements matched by an [`XPath`].
+///
+/// The matcher should be fed with all events read by the reader, in order,
+/// starting from the beginning of the document.
+#[derive(Clone, Debug)]
+pub struct Matcher {
+    path: XPath,
+    /// Opened elements, starting from the document itself
+    stack: Vec<Frame>,
+    /// Number of opened matched elements
+    opened: usize,
+    /// Version of the document used to normalize attribute values
+    version: XmlVersion,
+}
+
+impl Matcher {
+    /// Creates a matcher of the specified path.
+    pub fn new(path: XPath) -> Self {
+        let root = Frame {
+            states: vec![0],
+            counters: vec![0; path.counters],
+            matched: false,
+        };
+        Self {
+            path,
+            stack: vec![root],
+            opened: 0,
+            version: XmlVersion::Implicit1_0,
+        }
+    }
+
+    /// Returns the path matched by this matcher.
+    #[inline]
+    pub const fn path(&self) -> &XPath {
+        &self.path
+    }
+
+    /// Returns `true` if the last fed event is inside a matched element
+    /// (including its start tag, but not its end tag).
+    ///
+    /// Use this method to select the content of the matched elements.
+    #[inline]
+    pub const fn in_match(&self) -> bool {
+        self.opened > 0
+    }
+
+    /// Resets the matcher to the state at the beginning of a document.
+    pub fn reset(&mut self) {
+        self.stack.truncate(1);
+        self.stack[0].counters.iter_mut().for_each(|c| *c = 0);
+        self.opened = 0;
+        self.version = XmlVersion::Implicit1_0;
+    }
+
+    /// Updates the matcher with the event that was just read by the `reader`.
+    ///
+    /// Returns the kind of the event if it is a start, an end or an empty tag
+    /// of the matched element. For paths ending with an attribute step elements
+    /// are matched when they have at least one selected attribute.
+    ///
+    /// The event should be fed before the next event is read, because the
+    /// namespace bindings of the `reader` are used to resolve names.
+    pub fn feed<R>(&mut self, event: &Event, reader: &NsReader<R>) -> Result<Option<Match>> {
+        match event {
+            Event::Start(e) => {
+                let frame = self.enter(e, reader)?;
+                let matched = frame.matched;
+                self.stack.push(frame);
+                if matched {
+                    self.opened += 1;
+                    return Ok(Some(Match::Start));
+                }
+            }
+            Event::Empty(e) => {
+                let frame = self.enter(e, reader)?;
+                if frame.matched {
+                    return Ok(Some(Match::Empty));
+                }
+            }
+            Event::Decl(e) => self.version = e.xml_version()?,
+            Event::End(_) if self.stack.len() > 1 => {
+                let frame = self.stack.pop().expect("checked in the guard");
+                if frame.matched {
+                    self.opened -= 1;
+                    return Ok(Some(Match::End));
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    /// Returns attributes of the element selected by the final attribute step
+    /// of the path. Returns an empty vector if the path does not have an attribute
+    /// step.
+    ///
+    /// This method should be called for the elements for which [`Self::feed`]
+    /// returned [`Match::Start`] or [`Match::Empty`], before the next event
+    /// is read.
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::Event;
+    /// use quick_xml::reader::NsReader;
+    /// use quick_xml::xpath::{Matcher, XPath};
+    /// use quick_xml::XmlVersion;
+    ///
+    /// let mut reader = NsReader::from_str(r#"<list><item id="1"/><item/><item id="3"/></list>"#);
+    /// let mut matcher = Matcher::new("/list/item/@id".parse().unwrap());
+    /// let mut ids = Vec::new();
+    /// loop {
+    ///     match reader.read_event().unwrap() {
+    ///         Event::Eof => break,
+    ///         event => {
+    ///             if matcher.feed(&event, &reader).unwrap().is_some() {
+    ///                 if let Event::Empty(e) = event {
+    ///                     for attr in matcher.attributes(&e, &reader).unwrap() {
+    ///                         ids.push(attr.normalized_value(XmlVersion::Implicit1_0).unwrap().into_owned());
+    ///                     }
+    ///                 }
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    /// assert_eq!(ids, ["1", "3"]);
+    /// ```
+    pub fn attributes<'a, R>(
+        &self,
+        start: &'a BytesStart,
+        reader: &NsReader<R>,
+    ) -> Result<Vec<Attribute<'a>>> {
+        match &self.path.attribute {
+            Some((_, test)) => test.select(start, reader.resolver()),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Computes the state of the child element of the current element.
+    fn enter<R>(&mut self, start: &BytesStart, reader: &NsReader<R>) -> Result<Frame> {
+        let resolver = reader.resolver();
+        let last = self.path.steps.len();
+        let parent = self
+            .stack
+            .last_mut()
+            .expect("document frame is never popped");
+
+        let (ns, local) = resolver.resolve_element(start.name());
+        let mut states = Vec::new();
+        for &state in &parent.states {
+            let step = match self.path.steps.get(state) {
+                Some(step) => step,
+                // `//@attr` selects attributes of all descendants of the matched element
+                None => {
+                    if let Some((true, _)) = self.path.attribute {
+                        states.push(state);
+                    }
+                    continue;
+                }
+            };
+            if step.descendant {
+                states.push(state);
+            }
+            if step.test.matches(&ns, local.as_ref())
+                && check(
+                    &step.predicates,
+                    start,
+                    reader,
+                    self.version,
+                    &mut parent.counters,
+                )?
+            {
+                states.push(state + 1);
+            }
+        }
+        states.sort_unstable();
+        states.dedup();
+
+        let matched = states.contains(&last)
+            && match &self.path.attribute {
+                Some((_, test)) => !test.select(start, resolver)?.is_empty(),
+                None => true,
+            };
+        Ok(Frame {
+            states,
+            counters: vec![0; self.path.counters],
+            matched,
+        })
+    }
+}
+
+/// Checks the predicates of the step against the element. `counters` are
+/// the counters of the parent element.
+fn check<R>(
+    predicates: &[Predicate],
+    start: &BytesStart,
+    reader: &NsReader<R>,
+    version: XmlVersion,
+    counters: &mut [usize],
+) -> Result<bool> {
+    for predicate in predicates {
+        let passed = match predicate {
+            Predicate::Position { counter, position } => {
+                counters[*counter] += 1;
+                counters[*counter] == *position
+            }
+            Predicate::Attribute(name) => !name.select(start, reader.resolver())?.is_empty(),
+            Predicate::Value { name, value, equal } => {
+                let mut passed = false;
+                for attr in name.select(start, reader.resolver())? {
+                    let actual = attr.decoded_and_normalized_value(version, reader.decoder())?;
+                    if (actual == value.as_str()) == *equal {
+                        passed = true;
+                        break;
+                    }
+                }
+                passed
+            }
+        };
+        if !passed {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
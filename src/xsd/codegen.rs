@@ -0,0 +1,735 @@
+//! Generation of Rust types which follow the [serde mapping] of quick-xml from
+//! a [`Schema`].
+//!
+//! [`generate()`] returns the source code of structs and enums with `Deserialize`
+//! and `Serialize` implementations derived, which can be written to a file by
+//! a build script or by the `xsd_codegen` example:
+//!
+//! ```text
+//! cargo run --example xsd_codegen --features xsd -- order.xsd common.xsd > src/order.rs
+//! ```
+//!
+//! Schema components are mapped to Rust types as follows:
+//!
+//! - a complex type becomes a struct. A complex type defined inside of a global
+//!   element declaration is named after the element and is renamed to it, so
+//!   it is serialized with the right tag name;
+//! - a global element declaration with a named or built-in type becomes a newtype
+//!   struct, which wraps that type and is renamed to the element;
+//! - attributes become fields renamed to `@name`, which are wrapped into [`Option`]
+//!   unless they are declared with `use="required"`;
+//! - simple content becomes the `$text` field;
+//! - child elements of sequences and `xs:all` groups become fields, which are
+//!   wrapped into [`Option`] if they can be omitted and into [`Vec`] if they can
+//!   be repeated;
+//! - a choice of elements becomes an enum with a variant for each element,
+//!   stored in the `$value` field. If the content model cannot be represented by
+//!   fields, for example, it contains nested choices or the same element in
+//!   several places, and for mixed content, all child elements and text are
+//!   collected into a [`Vec`] of such enum in the `$value` field;
+//! - a simple type with the `enumeration` facet becomes an enum with unit variants,
+//!   other named simple types become type aliases;
+//! - built-in datatypes become [`String`], [`bool`], integer and float types
+//!   according to their value space, lists become [`Vec`]s, and `xs:anyType`
+//!   becomes `()`. `xs:decimal`, `xs:integer` and its subtypes without bounds,
+//!   such as `xs:positiveInteger`, become [`String`], because their values can
+//!   have any number of digits, which would be lost by Rust numeric types.
+//!
+//! Types that contain themselves are wrapped into a [`Box`]. Wildcards, facets
+//! except `enumeration` and the `fixed` values are not represented in the
+//! generated types.
+//!
+//! # Example
+//!
+//! ```
+//! # use pretty_assertions::assert_eq;
+//! use quick_xml::xsd::{codegen, Schema};
+//!
+//! let schema = Schema::parse(r#"
+//!     <xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+//!         <xs:element name="item">
+//!             <xs:complexType>
+//!                 <xs:sequence>
+//!                     <xs:element name="tag" type="xs:string" maxOccurs="unbounded"/>
+//!                 </xs:sequence>
+//!                 <xs:attribute name="id" type="xs:unsignedInt" use="required"/>
+//!             </xs:complexType>
+//!         </xs:element>
+//!     </xs:schema>"#).unwrap();
+//!
+//! assert_eq!(codegen::generate(&schema), r#"// Generated by quick-xml from an XML Schema.
+//!
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+//! #[serde(rename = "item")]
+//! pub struct Item {
+//!     #[serde(rename = "@id")]
+//!     pub id: u32,
+//!     #[serde(default)]
+//!     pub tag: Vec<String>,
+//! }
+//! "#);
+//! ```
+//!
+//! [serde mapping]: crate::de
+//! [`Schema`]: crate::xsd::Schema
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::xsd::model::{Group, Particle, Term};
+use crate::xsd::schema::{ComplexType, Content, Schema, Type, TypeId};
+use crate::xsd::types::{Builtin, SimpleType};
+
+/// Derives of all generated types.
+const DERIVE: &str = "#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]\n";
+
+/// Names that should not be used for generated types, because they are used
+/// in the generated code.
+const RESERVED: &[&str] = &[
+    "Box",
+    "Deserialize",
+    "Option",
+    "Self",
+    "Serialize",
+    "String",
+    "Vec",
+];
+
+/// Rust keywords, which are written as raw identifiers.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+/// Generates Rust types for global elements and named types of the `schema`.
+/// See the [module documentation](self) for the description of the mapping.
+pub fn generate(schema: &Schema) -> String {
+    let mut generator = Generator {
+        schema,
+        names: RESERVED.iter().map(|name| name.to_string()).collect(),
+        types: HashMap::new(),
+        expanding: HashSet::new(),
+        items: Vec::new(),
+    };
+    let mut globals: Vec<_> = schema.globals.values().copied().collect();
+    globals.sort_unstable();
+    for index in globals {
+        let decl = &schema.elements[index];
+        let local = &decl.name.local;
+        match decl.ty {
+            TypeId::Defined(ty) if !schema.type_names.contains_key(&ty) => {
+                match &schema.types[ty] {
+                    Type::Complex(complex) => {
+                        generator.complex_type(ty, complex, local, Some(local));
+                    }
+                    Type::Simple(_) => generator.global_element(decl.ty, local),
+                }
+            }
+            ty => generator.global_element(ty, local),
+        }
+    }
+    let mut named: Vec<_> = schema.type_names.keys().copied().collect();
+    named.sort_unstable();
+    for index in named {
+        generator.defined(index, "");
+    }
+
+    let mut code = String::from(
+        "// Generated by quick-xml from an XML Schema.\n\nuse serde::{Deserialize, Serialize};\n",
+    );
+    for item in generator.items {
+        code.push('\n');
+        code.push_str(&item);
+    }
+    code
+}
+
+/// How many times an element can occur.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Occurs {
+    One,
+    Optional,
+    Many,
+}
+
+impl Occurs {
+    const fn of(particle: &Particle) -> Self {
+        match particle.max {
+            Some(1) if particle.min == 0 => Self::Optional,
+            Some(1) => Self::One,
+            _ => Self::Many,
+        }
+    }
+
+    /// Returns occurrence of a particle inside a group that occurs `self` times.
+    const fn nested(self, particle: &Particle) -> Self {
+        match (self, Self::of(particle)) {
+            (Self::Many, _) | (_, Self::Many) => Self::Many,
+            (Self::Optional, _) | (_, Self::Optional) => Self::Optional,
+            _ => Self::One,
+        }
+    }
+
+    /// Wraps the type and returns it with additional serde attributes of the field.
+    fn wrap(self, ty: String) -> (String, Option<&'static str>) {
+        match self {
+            Self::One => (ty, None),
+            Self::Optional => (
+                format!("Option<{}>", ty),
+                Some("skip_serializing_if = \"Option::is_none\""),
+            ),
+            Self::Many => (format!("Vec<{}>", ty), Some("default")),
+        }
+    }
+}
+
+/// A field of a generated struct.
+struct Field {
+    /// Name of the attribute or element, or a special name like `$text`
+    xml: String,
+    ident: String,
+    ty: String,
+    /// Additional serde attributes
+    extra: Option<&'static str>,
+}
+
+struct Generator<'s> {
+    schema: &'s Schema,
+    /// Names of generated types
+    names: HashSet<String>,
+    /// Rust types for types of the schema
+    types: HashMap<usize, String>,
+    /// Complex types which structs are generated at the moment
+    expanding: HashSet<usize>,
+    /// Generated structs, enums and type aliases
+    items: Vec<String>,
+}
+
+impl<'s> Generator<'s> {
+    /// Returns a name of a type which is not used yet.
+    fn reserve(&mut self, name: &str) -> String {
+        let mut base = pascal(name);
+        if base.is_empty() {
+            base.push_str("Type");
+        } else if base.starts_with(|c: char| c.is_ascii_digit()) {
+            base.insert(0, 'T');
+        }
+        unique(&mut self.names, base)
+    }
+
+    /// Returns the Rust type for the type of an element or an attribute.
+    ///
+    /// # Parameters
+    /// - `ty`: the type
+    /// - `hint`: the name of the element or attribute, used to name anonymous types
+    fn rust_type(&mut self, ty: TypeId, hint: &str) -> String {
+        match ty {
+            TypeId::Builtin(builtin) => builtin_type(builtin).to_string(),
+            TypeId::Defined(index) => self.defined(index, hint),
+        }
+    }
+
+    /// Returns the Rust type for the type defined in the schema, generating
+    /// it if it is not generated yet.
+    fn defined(&mut self, index: usize, hint: &str) -> String {
+        if let Some(ty) = self.types.get(&index) {
+            return ty.clone();
+        }
+        let schema = self.schema;
+        let name = schema
+            .type_names
+            .get(&index)
+            .map_or(hint, |name| name.local.as_str());
+        match &schema.types[index] {
+            Type::Complex(complex) => self.complex_type(index, complex, name, None),
+            Type::Simple(simple) => {
+                let ty = match simple {
+                    SimpleType::Restriction { facets, .. } if !facets.enumeration.is_empty() => {
+                        return self.enumeration(index, &facets.enumeration, name);
+                    }
+                    SimpleType::Restriction { base, .. } => self.rust_type(*base, name),
+                    SimpleType::List(item) => format!("Vec<{}>", self.rust_type(*item, name)),
+                    SimpleType::Union(_) => "String".to_string(),
+                };
+                let ty = if schema.type_names.contains_key(&index) {
+                    let alias = self.reserve(name);
+                    self.items.push(format!("pub type {} = {};\n", alias, ty));
+                    alias
+                } else {
+                    ty
+                };
+                self.types.insert(index, ty.clone());
+                ty
+            }
+        }
+    }
+
+    /// Generates a newtype struct for a global element with a named, built-in
+    /// or anonymous simple type. The struct is renamed to the element, so it is
+    /// serialized with the right tag name.
+    fn global_element(&mut self, ty: TypeId, local: &str) {
+        let ty = self.rust_type(ty, local);
+        let name = if self.names.contains(&pascal(local)) {
+            self.reserve(&format!("{}-element", local))
+        } else {
+            self.reserve(local)
+        };
+        self.items.push(format!(
+            "{}#[serde(rename = {:?})]\npub struct {}(pub {});\n",
+            DERIVE, local, name, ty
+        ));
+    }
+
+    /// Generates an enum with unit variants for a simple type with the `enumeration` facet.
+    fn enumeration(&mut self, index: usize, values: &[String], name: &str) -> String {
+        let name = self.reserve(name);
+        self.types.insert(index, name.clone());
+        let white_space = self.schema.white_space(TypeId::Defined(index));
+
+        let mut variants = HashSet::new();
+        let mut code = format!("{}pub enum {} {{\n", DERIVE, name);
+        for value in values {
+            let value = white_space.apply(value);
+            let mut variant = pascal(&value);
+            if variant.is_empty() {
+                variant.push_str("Empty");
+            } else if variant.starts_with(|c: char| c.is_ascii_digit()) {
+                variant.insert(0, 'V');
+            }
+            let variant = unique(&mut variants, variant);
+            if variant != value {
+                let _ = writeln!(code, "    #[serde(rename = {:?})]", value);
+            }
+            let _ = writeln!(code, "    {},", variant);
+        }
+        code.push_str("}\n");
+        self.items.push(code);
+        name
+    }
+
+    /// Generates a struct for the complex type.
+    ///
+    /// # Parameters
+    /// - `index`: index of the type in the schema
+    /// - `complex`: the type
+    /// - `name`: the name of the type, or the name of the element with
+    ///   the anonymous type
+    /// - `rename`: name of the global element with the anonymous type
+    fn complex_type(
+        &mut self,
+        index: usize,
+        complex: &'s ComplexType,
+        name: &str,
+        rename: Option<&str>,
+    ) -> String {
+        let name = self.reserve(name);
+        self.types.insert(index, name.clone());
+        self.expanding.insert(index);
+        // Reserve the place for the struct, so it is placed before the types of its fields
+        let slot = self.items.len();
+        self.items.push(String::new());
+
+        let mut fields = Vec::new();
+        for attribute in &complex.attributes {
+            let local = &attribute.name.local;
+            let ty = self.rust_type(attribute.ty, local);
+            let (ty, extra) = if attribute.required {
+                Occurs::One.wrap(ty)
+            } else {
+                Occurs::Optional.wrap(ty)
+            };
+            let xml = match attribute.name.ns.as_deref() {
+                Some("http://www.w3.org/XML/1998/namespace") => format!("@xml:{}", local),
+                _ => format!("@{}", local),
+            };
+            fields.push(Field {
+                xml,
+                ident: snake(local),
+                ty,
+                extra,
+            });
+        }
+        match (&complex.content, &complex.particle) {
+            (Content::Empty, _) => {}
+            (Content::Simple(ty), _) => {
+                let ty = self.rust_type(*ty, &name);
+                fields.push(Field {
+                    xml: "$text".to_string(),
+                    ident: "text".to_string(),
+                    ty,
+                    extra: None,
+                });
+            }
+            (Content::Elements { mixed: true, .. }, None) => fields.push(Field {
+                xml: "$text".to_string(),
+                ident: "text".to_string(),
+                ty: "String".to_string(),
+                extra: Some("default"),
+            }),
+            (Content::Elements { mixed, .. }, Some(particle)) => {
+                self.elements(&name, particle, *mixed, &mut fields);
+            }
+            (Content::Elements { .. }, None) => {}
+        }
+
+        let mut code = String::from(DERIVE);
+        if let Some(rename) = rename {
+            let _ = writeln!(code, "#[serde(rename = {:?})]", rename);
+        }
+        if fields.is_empty() {
+            let _ = writeln!(code, "pub struct {} {{}}", name);
+        } else {
+            let _ = writeln!(code, "pub struct {} {{", name);
+            let mut idents = HashSet::new();
+            for field in fields {
+                let ident = unique(&mut idents, field.ident);
+                let rename = if ident == field.xml {
+                    None
+                } else {
+                    Some(format!("rename = {:?}", field.xml))
+                };
+                let attributes: Vec<_> = rename
+                    .into_iter()
+                    .chain(field.extra.map(String::from))
+                    .collect();
+                if !attributes.is_empty() {
+                    let _ = writeln!(code, "    #[serde({})]", attributes.join(", "));
+                }
+                let _ = writeln!(code, "    pub {}: {},", raw(ident), field.ty);
+            }
+            code.push_str("}\n");
+        }
+        self.items[slot] = code;
+        self.expanding.remove(&index);
+        name
+    }
+
+    /// Adds fields for child elements.
+    ///
+    /// # Parameters
+    /// - `name`: the name of the struct, used to name enums
+    /// - `particle`: the content model
+    /// - `mixed`: whether text is allowed between elements
+    /// - `fields`: the fields of the struct
+    fn elements(
+        &mut self,
+        name: &str,
+        particle: &'s Particle,
+        mixed: bool,
+        fields: &mut Vec<Field>,
+    ) {
+        let mut parts = Vec::new();
+        let ordered = collect(particle, Occurs::One, &mut parts);
+
+        let schema = self.schema;
+        let mut names = Vec::new();
+        let mut choices = 0;
+        // A choice is represented by the `$value` field, so only one choice is
+        // possible, and it can contain only elements
+        let representable = !mixed
+            && ordered
+            && parts.iter().all(|part| match part {
+                Part::Element(index, _) => {
+                    names.push(&schema.elements[*index].name);
+                    true
+                }
+                Part::Choice(particles, _) => {
+                    choices += 1;
+                    let mut branches = 0;
+                    let plain = particles.iter().all(|particle| {
+                        match (&particle.group, Occurs::of(particle)) {
+                            (Group::Term(Term::Element(index)), Occurs::One | Occurs::Optional) => {
+                                names.push(&schema.elements[*index].name);
+                                branches += 1;
+                                true
+                            }
+                            (Group::Term(Term::Any(_)), _) => true,
+                            _ => false,
+                        }
+                    });
+                    plain && branches > 0
+                }
+            })
+            && choices <= 1
+            && names
+                .iter()
+                .enumerate()
+                .all(|(i, name)| !names[..i].contains(name));
+
+        if !representable {
+            let mut all = Vec::new();
+            all_elements(schema, particle, &mut all);
+            let ty = self.content(&format!("{}Content", name), &all, mixed);
+            fields.push(Field {
+                xml: "$value".to_string(),
+                ident: "content".to_string(),
+                ty: format!("Vec<{}>", ty),
+                extra: Some("default"),
+            });
+            return;
+        }
+        for part in parts {
+            match part {
+                Part::Element(index, occurs) => {
+                    let decl = &schema.elements[index];
+                    let ty = self.element_type(decl.ty, &decl.name.local, occurs);
+                    let (ty, extra) = occurs.wrap(ty);
+                    fields.push(Field {
+                        xml: decl.name.local.clone(),
+                        ident: snake(&decl.name.local),
+                        ty,
+                        extra,
+                    });
+                }
+                Part::Choice(particles, occurs) => {
+                    let branches: Vec<_> = particles
+                        .iter()
+                        .filter_map(|particle| match particle.group {
+                            Group::Term(Term::Element(index)) => Some(index),
+                            _ => None,
+                        })
+                        .collect();
+                    let ty = self.content(&format!("{}Choice", name), &branches, false);
+                    let (ty, extra) = occurs.wrap(ty);
+                    fields.push(Field {
+                        xml: "$value".to_string(),
+                        ident: "choice".to_string(),
+                        ty,
+                        extra,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Returns the Rust type of an element, boxed if it contains itself.
+    fn element_type(&mut self, ty: TypeId, hint: &str, occurs: Occurs) -> String {
+        let rust = self.rust_type(ty, hint);
+        match ty {
+            TypeId::Defined(index) if occurs != Occurs::Many && self.expanding.contains(&index) => {
+                format!("Box<{}>", rust)
+            }
+            _ => rust,
+        }
+    }
+
+    /// Generates an enum with a variant for each element, and for text if `mixed`.
+    fn content(&mut self, name: &str, elements: &[usize], mixed: bool) -> String {
+        let name = self.reserve(name);
+        let slot = self.items.len();
+        self.items.push(String::new());
+
+        let mut variants = HashSet::new();
+        let mut code = format!("{}pub enum {} {{\n", DERIVE, name);
+        for index in elements {
+            let decl = &self.schema.elements[*index];
+            let local = &decl.name.local;
+            let mut variant = pascal(local);
+            if variant.is_empty() || variant.starts_with(|c: char| c.is_ascii_digit()) {
+                variant.insert(0, 'E');
+            }
+            let variant = unique(&mut variants, variant);
+            if variant != *local {
+                let _ = writeln!(code, "    #[serde(rename = {:?})]", local);
+            }
+            match self.element_type(decl.ty, local, Occurs::One).as_str() {
+                "()" => {
+                    let _ = writeln!(code, "    {},", variant);
+                }
+                ty => {
+                    let _ = writeln!(code, "    {}({}),", variant, ty);
+                }
+            }
+        }
+        if mixed {
+            let variant = unique(&mut variants, "Text".to_string());
+            let _ = writeln!(
+                code,
+                "    #[serde(rename = \"$text\")]\n    {}(String),",
+                variant
+            );
+        }
+        code.push_str("}\n");
+        self.items[slot] = code;
+        name
+    }
+}
+
+/// An element or a choice inside of sequences of a content model.
+enum Part<'s> {
+    Element(usize, Occurs),
+    Choice(&'s [Particle], Occurs),
+}
+
+/// Collects elements and choices of sequences with their occurrences.
+///
+/// Returns `false` if a repeated sequence contains several parts. Separate fields
+/// for them would lose the order of their occurrences, for example, `<a/><b/><a/><b/>`
+/// would be serialized as `<a/><a/><b/><b/>`.
+fn collect<'s>(particle: &'s Particle, outer: Occurs, parts: &mut Vec<Part<'s>>) -> bool {
+    let occurs = outer.nested(particle);
+    match &particle.group {
+        Group::Term(Term::Element(index)) => parts.push(Part::Element(*index, occurs)),
+        Group::Term(Term::Any(_)) => {}
+        Group::Sequence(particles) | Group::All(particles) => {
+            let start = parts.len();
+            let mut ordered = true;
+            for particle in particles {
+                ordered &= collect(particle, occurs, parts);
+            }
+            return ordered && (occurs != Occurs::Many || parts.len() - start <= 1);
+        }
+        Group::Choice(particles) => parts.push(Part::Choice(particles, occurs)),
+    }
+    true
+}
+
+/// Collects all distinct elements of the content model.
+fn all_elements(schema: &Schema, particle: &Particle, elements: &mut Vec<usize>) {
+    match &particle.group {
+        Group::Term(Term::Element(index)) => {
+            let name = &schema.elements[*index].name;
+            if !elements.iter().any(|i| schema.elements[*i].name == *name) {
+                elements.push(*index);
+            }
+        }
+        Group::Term(Term::Any(_)) => {}
+        Group::Sequence(particles) | Group::Choice(particles) | Group::All(particles) => {
+            for particle in particles {
+                all_elements(schema, particle, elements);
+            }
+        }
+    }
+}
+
+/// Returns the Rust type for values of the built-in datatype.
+const fn builtin_type(builtin: Builtin) -> &'static str {
+    match builtin {
+        Builtin::AnyType => "()",
+        Builtin::NmTokens | Builtin::IdRefs | Builtin::Entities => "Vec<String>",
+        Builtin::Boolean => "bool",
+        Builtin::Double => "f64",
+        Builtin::Float => "f32",
+        Builtin::Long => "i64",
+        Builtin::Int => "i32",
+        Builtin::Short => "i16",
+        Builtin::Byte => "i8",
+        Builtin::UnsignedLong => "u64",
+        Builtin::UnsignedInt => "u32",
+        Builtin::UnsignedShort => "u16",
+        Builtin::UnsignedByte => "u8",
+        _ => "String",
+    }
+}
+
+/// Adds a number to the name if it is already used, and marks the result as used.
+fn unique(used: &mut HashSet<String>, name: String) -> String {
+    let mut unique = name.clone();
+    let mut counter = 1;
+    while used.contains(&unique) {
+        counter += 1;
+        unique = format!("{}{}", name, counter);
+    }
+    used.insert(unique.clone());
+    unique
+}
+
+/// Splits a name into words on non-alphanumeric characters and on changes of case.
+fn words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &ch) in chars.iter().enumerate() {
+        if !ch.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if ch.is_uppercase() && !word.is_empty() {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+            // `orderId` -> `order`, `Id`; `XMLHttp` -> `XML`, `Http`
+            if !prev.is_uppercase() || next_lower {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.push(ch);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Converts the name to `PascalCase`.
+fn pascal(name: &str) -> String {
+    let mut result = String::new();
+    for word in words(name) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            result.extend(first.to_uppercase());
+            result.extend(chars.flat_map(char::to_lowercase));
+        }
+    }
+    result
+}
+
+/// Converts the name to a `snake_case` identifier.
+fn snake(name: &str) -> String {
+    let mut result = words(name)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_");
+    if result.is_empty() || result.starts_with(|c: char| c.is_ascii_digit()) {
+        result.insert(0, '_');
+    }
+    if let "self" | "super" | "crate" | "_" = result.as_str() {
+        result.push('_');
+    }
+    result
+}
+
+/// Returns the identifier, written as a raw identifier if it is a keyword.
+fn raw(ident: String) -> String {
+    if KEYWORDS.contains(&ident.as_str()) {
+        format!("r#{}", ident)
+    } else {
+        ident
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn names() {
+        assert_eq!(pascal("order-item"), "OrderItem");
+        assert_eq!(pascal("XMLHttpRequest"), "XmlHttpRequest");
+        assert_eq!(pascal("dark blue"), "DarkBlue");
+        assert_eq!(snake("orderID"), "order_id");
+        assert_eq!(snake("ID"), "id");
+        assert_eq!(snake("first.name"), "first_name");
+        assert_eq!(snake("type"), "type");
+        assert_eq!(raw(snake("type")), "r#type");
+        assert_eq!(snake("self"), "self_");
+        assert_eq!(snake("1st"), "_1st");
+        assert_eq!(snake("-"), "__");
+    }
+
+    #[test]
+    fn unique_names() {
+        let mut used = HashSet::new();
+        assert_eq!(unique(&mut used, "Item".into()), "Item");
+        assert_eq!(unique(&mut used, "Item".into()), "Item2");
+        assert_eq!(unique(&mut used, "Item".into()), "Item3");
+    }
+}
@@ -0,0 +1,745 @@
+//! Validation of namespace-aware documents against a subset of [XML Schema 1.0].
+//!
+//! [`Schema`] is loaded from one or several schema documents, which are read by
+//! quick-xml itself, and [`Validator`] checks events read by the [`NsReader`]
+//! against it. The following parts of XML Schema are supported:
+//!
+//! - global and local element declarations, element references, named and
+//!   anonymous types and `nillable` elements;
+//! - complex types with `<xs:sequence>`, `<xs:choice>`, `<xs:all>`, named model
+//!   groups and `<xs:any>` wildcards, with `minOccurs` and `maxOccurs` constraints;
+//! - mixed, empty and simple content, including `<xs:simpleContent>` and
+//!   `<xs:complexContent>` derived by extension or restriction;
+//! - attribute declarations with `use="required"`, `use="prohibited"` and `fixed`
+//!   values, attribute groups and `<xs:anyAttribute>` wildcards;
+//! - simple types derived by restriction, list and union from the built-in
+//!   datatypes, which include strings and names, numeric types, dates and times,
+//!   durations, `xs:boolean`, `xs:anyURI`, `xs:QName`, `xs:hexBinary` and
+//!   `xs:base64Binary`;
+//! - facets `pattern`, `enumeration`, `length`, `minLength`, `maxLength`,
+//!   `minInclusive`, `minExclusive`, `maxInclusive`, `maxExclusive`, `totalDigits`,
+//!   `fractionDigits` and `whiteSpace`.
+//!
+//! Identity constraints, substitution groups, default values and the `xsi:type`
+//! attribute are ignored. `<xs:import>` and `<xs:include>` are ignored too, so all
+//! schema documents should be passed to [`Schema::parse_all`]. Other constructs
+//! which are not supported produce [`SchemaError::Unsupported`] when the schema
+//! is loaded. So do content models which are too large after bounded `minOccurs`
+//! and `maxOccurs` are expanded, for example, nested groups with `maxOccurs="5000"`.
+//!
+//! Validity errors do not stop the validation. They are collected as [`Violation`]s
+//! which contain the path to the offending element and the position where the
+//! offending event starts.
+//!
+//! The [`codegen`] module generates Rust types for the serde deserializer and
+//! serializer from the schema.
+//!
+//! # Example
+//!
+//! ```
+//! # use pretty_assertions::assert_eq;
+//! use quick_xml::reader::NsReader;
+//! use quick_xml::xsd::{validate, Schema};
+//!
+//! let schema = Schema::parse(r#"
+//!     <xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+//!         <xs:element name="order">
+//!             <xs:complexType>
+//!                 <xs:sequence>
+//!                     <xs:element name="item" maxOccurs="unbounded">
+//!                         <xs:complexType>
+//!                             <xs:attribute name="sku" use="required">
+//!                                 <xs:simpleType>
+//!                                     <xs:restriction base="xs:token">
+//!                                         <xs:pattern value="[A-Z]{3}-\d+"/>
+//!                                     </xs:restriction>
+//!                                 </xs:simpleType>
+//!                             </xs:attribute>
+//!                             <xs:attribute name="qty" type="xs:positiveInteger"/>
+//!                         </xs:complexType>
+//!                     </xs:element>
+//!                 </xs:sequence>
+//!             </xs:complexType>
+//!         </xs:element>
+//!     </xs:schema>"#).unwrap();
+//!
+//! let mut reader = NsReader::from_str(
+//!     r#"<order><item sku="ABC-1" qty="2"/><item sku="abc" qty="0"/></order>"#
+//! );
+//! let violations = validate(&schema, &mut reader).unwrap();
+//! let errors: Vec<_> = violations.iter().map(|v| v.to_string()).collect();
+//! assert_eq!(errors, [
+//!     r"byte 34: /order/item[2]: value `abc` is not valid for attribute `sku`: value does not match pattern `[A-Z]{3}-\d+`",
+//!     "byte 34: /order/item[2]: value `0` is not valid for attribute `qty`: value is not a valid `xs:positiveInteger`",
+//! ]);
+//! ```
+//!
+//! [XML Schema 1.0]: https://www.w3.org/TR/xmlschema-1/
+//! [`NsReader`]: crate::reader::NsReader
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::BufRead;
+
+use crate::errors::{Error, Result};
+use crate::escape::{resolve_predefined_entity, EscapeError};
+use crate::events::{BytesStart, Event};
+use crate::name::{QName, ResolveResult};
+use crate::reader::{NsReader, Position};
+use crate::utils::is_whitespace;
+use crate::XmlVersion;
+
+pub mod codegen;
+mod model;
+mod pattern;
+mod schema;
+mod types;
+
+use self::model::{Model, Process, State, Term};
+use self::schema::{Content, ElementDecl, Type, TypeId, XSI};
+pub use self::schema::{Schema, SchemaError};
+use self::types::Builtin;
+
+/// A reason why a value does not belong to its simple type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValueError {
+    /// The value is not in the lexical space of the built-in type. Contains
+    /// the name of the type without prefix.
+    Invalid(&'static str),
+    /// The value does not match the `pattern` facet. Contains the patterns of
+    /// the facet separated by `|`.
+    Pattern(String),
+    /// The value is not one of the values of the `enumeration` facet.
+    Enumeration(Vec<String>),
+    /// The length of the value differs from the `length` facet.
+    Length {
+        /// Required length
+        expected: usize,
+        /// Length of the value
+        found: usize,
+    },
+    /// The value is shorter than the `minLength` facet.
+    MinLength {
+        /// Minimal length
+        min: usize,
+        /// Length of the value
+        found: usize,
+    },
+    /// The value is longer than the `maxLength` facet.
+    MaxLength {
+        /// Maximal length
+        max: usize,
+        /// Length of the value
+        found: usize,
+    },
+    /// The value is less than the `minInclusive` facet.
+    MinInclusive(String),
+    /// The value is less than or equal to the `minExclusive` facet.
+    MinExclusive(String),
+    /// The value is greater than the `maxInclusive` facet.
+    MaxInclusive(String),
+    /// The value is greater than or equal to the `maxExclusive` facet.
+    MaxExclusive(String),
+    /// The value has more digits than the `totalDigits` facet allows.
+    TotalDigits(usize),
+    /// The value has more fraction digits than the `fractionDigits` facet allows.
+    FractionDigits(usize),
+    /// The value does not belong to any member type of the union.
+    Union,
+    /// The value differs from the `fixed` value of the attribute.
+    Fixed(String),
+}
+
+impl fmt::Display for ValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Invalid(ty) => write!(f, "value is not a valid `xs:{}`", ty),
+            Self::Pattern(pattern) => write!(f, "value does not match pattern `{}`", pattern),
+            Self::Enumeration(values) => {
+                f.write_str("value is not one of ")?;
+                write_list(f, values)
+            }
+            Self::Length { expected, found } => {
+                write!(f, "length must be {}, but is {}", expected, found)
+            }
+            Self::MinLength { min, found } => {
+                write!(f, "length must be at least {}, but is {}", min, found)
+            }
+            Self::MaxLength { max, found } => {
+                write!(f, "length must be at most {}, but is {}", max, found)
+            }
+            Self::MinInclusive(min) => write!(f, "value must be greater than or equal to {}", min),
+            Self::MinExclusive(min) => write!(f, "value must be greater than {}", min),
+            Self::MaxInclusive(max) => write!(f, "value must be less than or equal to {}", max),
+            Self::MaxExclusive(max) => write!(f, "value must be less than {}", max),
+            Self::TotalDigits(max) => write!(f, "value must have at most {} digits", max),
+            Self::FractionDigits(max) => {
+                write!(f, "value must have at most {} fraction digits", max)
+            }
+            Self::Union => f.write_str("value does not match any member type of the union"),
+            Self::Fixed(value) => write!(f, "value must be `{}`", value),
+        }
+    }
+}
+
+impl std::error::Error for ValueError {}
+
+/// Writes names or values as a list: `` `a`, `b` or `c` ``.
+fn write_list(f: &mut fmt::Formatter, items: &[String]) -> fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        match i {
+            0 => {}
+            _ if i + 1 == items.len() => f.write_str(" or ")?,
+            _ => f.write_str(", ")?,
+        }
+        write!(f, "`{}`", item)?;
+    }
+    Ok(())
+}
+
+/// A reason why the document is not valid.
+///
+/// Names of elements and attributes from the document are written as in
+/// the document, with prefixes. Names of expected elements are written as
+/// `{namespace}local`, or as `local` for elements without namespace. The
+/// wildcard `<xs:any>` is written as `*`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidityError {
+    /// The element is not declared in the schema. Reported for the root
+    /// element and for elements matched by a strict wildcard.
+    UnknownElement(String),
+    /// The content model of the parent element does not allow the element at
+    /// this place. The element and its content are not validated.
+    UnexpectedElement {
+        /// Name of the element
+        element: String,
+        /// Elements allowed at this place
+        expected: Vec<String>,
+    },
+    /// The element was closed before all child elements required by its content
+    /// model were found.
+    IncompleteContent {
+        /// Elements that could continue the content
+        expected: Vec<String>,
+    },
+    /// The element contains text, but its type does not allow it.
+    UnexpectedText,
+    /// The attribute is not declared for the element.
+    UndeclaredAttribute(String),
+    /// The attribute with `use="required"` is not specified. Contains the name
+    /// of the attribute as declared in the schema.
+    MissingAttribute(String),
+    /// The value of the attribute does not belong to its type.
+    InvalidAttributeValue {
+        /// Name of the attribute
+        attribute: String,
+        /// Normalized value of the attribute
+        value: String,
+        /// Why the value is invalid
+        error: ValueError,
+    },
+    /// The text content of the element does not belong to its type.
+    InvalidValue {
+        /// Normalized content of the element
+        value: String,
+        /// Why the value is invalid
+        error: ValueError,
+    },
+    /// The element has `xsi:nil="true"`, but its declaration is not `nillable`.
+    NotNillable,
+    /// The element has `xsi:nil="true"`, but has content.
+    NilContent,
+}
+
+impl fmt::Display for ValidityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownElement(name) => write!(f, "element `{}` is not declared", name),
+            Self::UnexpectedElement { element, expected } => {
+                write!(f, "element `{}` is not allowed here", element)?;
+                if !expected.is_empty() {
+                    f.write_str(", expected ")?;
+                    write_list(f, expected)?;
+                }
+                Ok(())
+            }
+            Self::IncompleteContent { expected } => {
+                f.write_str("content ended before all required elements were found, expected ")?;
+                write_list(f, expected)
+            }
+            Self::UnexpectedText => f.write_str("text is not allowed here"),
+            Self::UndeclaredAttribute(name) => write!(f, "attribute `{}` is not declared", name),
+            Self::MissingAttribute(name) => {
+                write!(f, "required attribute `{}` is not specified", name)
+            }
+            Self::InvalidAttributeValue {
+                attribute,
+                value,
+                error,
+            } => write!(
+                f,
+                "value `{}` is not valid for attribute `{}`: {}",
+                value, attribute, error
+            ),
+            Self::InvalidValue { value, error } => {
+                write!(f, "value `{}` is not valid: {}", value, error)
+            }
+            Self::NotNillable => f.write_str("`xsi:nil` is specified, but element is not nillable"),
+            Self::NilContent => f.write_str("element with `xsi:nil=\"true\"` must be empty"),
+        }
+    }
+}
+
+impl std::error::Error for ValidityError {}
+
+/// A validity error together with the element and the position of the event
+/// where it was found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    /// Path to the element, which content or attributes violate the schema,
+    /// for example, `/order/item[2]/price[1]`. Each step except the root contains
+    /// the index of the element among siblings with the same name, starting from 1.
+    pub path: String,
+    /// Position of the start of the event that violates the schema. Line and column
+    /// are known only when [`Config::track_position`] is enabled, otherwise they are `0`.
+    ///
+    /// [`Config::track_position`]: crate::reader::Config::track_position
+    pub position: Position,
+    /// The violated rule
+    pub error: ValidityError,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.position.line == 0 {
+            write!(f, "byte {}: ", self.position.byte)?;
+        } else {
+            write!(f, "{}: ", self.position)?;
+        }
+        write!(f, "{}: {}", self.path, self.error)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// What is expected in the content of an opened element.
+#[derive(Debug)]
+enum Expect<'s> {
+    /// Content is not validated
+    Skip,
+    /// Text of the simple type, collected until the end of the element
+    Text { ty: TypeId, text: String },
+    /// Neither elements nor text
+    Empty,
+    /// Elements matched by the content model
+    Elements {
+        model: &'s Model,
+        state: State,
+        mixed: bool,
+    },
+    /// Nothing, because the element has `xsi:nil="true"`
+    Nil,
+}
+
+#[derive(Debug)]
+struct Frame<'s> {
+    path: String,
+    /// Number of already opened children with each name
+    children: HashMap<String, usize>,
+    expect: Expect<'s>,
+}
+
+/// Checks events of a document read by the [`NsReader`] against a [`Schema`].
+///
+/// Events are passed to [`feed()`] one by one, and found validity errors are
+/// available from [`violations()`]. The root element of the document must be
+/// declared as a global element of the schema.
+///
+/// See the [module documentation](self) for the list of supported constructs
+/// and the [`validate()`] function which validates the whole document.
+///
+/// [`NsReader`]: crate::reader::NsReader
+/// [`feed()`]: Self::feed
+/// [`violations()`]: Self::violations
+#[derive(Debug)]
+pub struct Validator<'s> {
+    schema: &'s Schema,
+    /// Opened elements
+    stack: Vec<Frame<'s>>,
+    /// Version of the document, used to normalize attribute values and text
+    version: XmlVersion,
+    /// Position just after the last fed event, i.e. the start of the next event
+    position: Position,
+    violations: Vec<Violation>,
+}
+
+impl<'s> Validator<'s> {
+    /// Creates a validator which checks the document against the `schema`.
+    pub const fn new(schema: &'s Schema) -> Self {
+        Self {
+            schema,
+            stack: Vec::new(),
+            version: XmlVersion::Implicit1_0,
+            position: Position::START,
+            violations: Vec::new(),
+        }
+    }
+
+    /// Returns validity errors found so far, in order of their appearance.
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
+    /// Returns validity errors found since the last call of this method and
+    /// clears the list of them.
+    pub fn take_violations(&mut self) -> Vec<Violation> {
+        std::mem::take(&mut self.violations)
+    }
+
+    /// Updates the validator with the event that was just read by the `reader`.
+    ///
+    /// All events of the document should be fed in order, because positions of
+    /// violations are computed from the positions of the `reader` after the
+    /// previous event, and namespaces of elements and attributes are resolved
+    /// by the `reader`.
+    ///
+    /// Returns an error if a text or a value of an attribute cannot be decoded.
+    /// Validity errors are collected and returned by [`Self::violations`].
+    pub fn feed<R>(&mut self, event: &Event, reader: &NsReader<R>) -> Result<()> {
+        let position = std::mem::replace(&mut self.position, reader.position());
+        match event {
+            Event::Decl(e) => self.version = e.xml_version()?,
+            Event::Start(e) => self.start(e, reader, position)?,
+            Event::Empty(e) => {
+                self.start(e, reader, position)?;
+                self.end(position);
+            }
+            Event::End(_) => self.end(position),
+            Event::Text(e) => {
+                let whitespace = e.iter().all(|b| is_whitespace(*b));
+                self.text(&e.xml_content(self.version)?, whitespace, position);
+            }
+            Event::CData(e) => self.text(&e.xml_content(self.version)?, false, position),
+            Event::GeneralRef(e) => match e.resolve_char_ref()? {
+                Some(ch) => self.text(ch.encode_utf8(&mut [0; 4]), false, position),
+                None => {
+                    let name = e.decode()?;
+                    match resolve_predefined_entity(&name) {
+                        Some(value) => self.text(value, false, position),
+                        None => {
+                            return Err(Error::Escape(EscapeError::UnrecognizedEntity(
+                                0..name.len(),
+                                name.into_owned(),
+                            )))
+                        }
+                    }
+                }
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn report(&mut self, path: &str, position: Position, error: ValidityError) {
+        self.violations.push(Violation {
+            path: path.to_string(),
+            position,
+            error,
+        });
+    }
+
+    fn start<R>(
+        &mut self,
+        start: &BytesStart,
+        reader: &NsReader<R>,
+        position: Position,
+    ) -> Result<()> {
+        let decoder = reader.decoder();
+        let name = decoder.decode(start.name().as_ref())?.into_owned();
+        let (ns, local) = reader.resolver().resolve_element(start.name());
+        let ns = match ns {
+            ResolveResult::Bound(ns) => Some(decoder.decode(ns.as_ref())?.into_owned()),
+            _ => None,
+        };
+        let local = decoder.decode(local.as_ref())?;
+        let schema = self.schema;
+
+        let (path, decl, error) = match self.stack.last_mut() {
+            None => {
+                let path = format!("/{}", name);
+                match schema.global(ns.as_deref(), &local) {
+                    Some(index) => (path, Some(&schema.elements[index]), None),
+                    None => (
+                        path,
+                        None,
+                        Some(ValidityError::UnknownElement(name.clone())),
+                    ),
+                }
+            }
+            Some(parent) => {
+                let count = parent.children.entry(name.clone()).or_insert(0);
+                *count += 1;
+                let path = format!("{}/{}[{}]", parent.path, name, count);
+                let (decl, error) = match &mut parent.expect {
+                    Expect::Skip => (None, None),
+                    Expect::Nil => (None, Some(ValidityError::NilContent)),
+                    Expect::Text { .. } | Expect::Empty => (
+                        None,
+                        Some(ValidityError::UnexpectedElement {
+                            element: name.clone(),
+                            expected: Vec::new(),
+                        }),
+                    ),
+                    Expect::Elements { model, state, .. } => {
+                        let model: &'s Model = model;
+                        let matches = |term: &Term| match term {
+                            Term::Element(index) => {
+                                schema.elements[*index].name.matches(ns.as_deref(), &local)
+                            }
+                            Term::Any(wildcard) => wildcard.allows(ns.as_deref()),
+                        };
+                        let expected = expected(schema, model, state);
+                        match model.step(state, matches) {
+                            Some(Term::Element(index)) => (Some(&schema.elements[*index]), None),
+                            Some(Term::Any(wildcard)) => {
+                                match (wildcard.process, schema.global(ns.as_deref(), &local)) {
+                                    (Process::Skip, _) | (Process::Lax, None) => (None, None),
+                                    (_, Some(index)) => (Some(&schema.elements[index]), None),
+                                    (Process::Strict, None) => {
+                                        (None, Some(ValidityError::UnknownElement(name.clone())))
+                                    }
+                                }
+                            }
+                            None => (
+                                None,
+                                Some(ValidityError::UnexpectedElement {
+                                    element: name.clone(),
+                                    expected,
+                                }),
+                            ),
+                        }
+                    }
+                };
+                (path, decl, error)
+            }
+        };
+        if let Some(error) = error {
+            self.report(&path, position, error);
+        }
+        let expect = match decl {
+            Some(decl) => self.element(decl, start, reader, &path, position)?,
+            None => Expect::Skip,
+        };
+        self.stack.push(Frame {
+            path,
+            children: HashMap::new(),
+            expect,
+        });
+        Ok(())
+    }
+
+    /// Checks attributes of the element and returns what is expected in its content.
+    fn element<R>(
+        &mut self,
+        decl: &ElementDecl,
+        start: &BytesStart,
+        reader: &NsReader<R>,
+        path: &str,
+        position: Position,
+    ) -> Result<Expect<'s>> {
+        let schema = self.schema;
+        let decoder = reader.decoder();
+        let (complex, mut expect) = match decl.ty {
+            TypeId::Builtin(Builtin::AnyType) => (None, Expect::Skip),
+            TypeId::Builtin(_) => (
+                None,
+                Expect::Text {
+                    ty: decl.ty,
+                    text: String::new(),
+                },
+            ),
+            TypeId::Defined(index) => match &schema.types[index] {
+                Type::Simple(_) => (
+                    None,
+                    Expect::Text {
+                        ty: decl.ty,
+                        text: String::new(),
+                    },
+                ),
+                Type::Complex(complex) => {
+                    let expect = match &complex.content {
+                        Content::Empty => Expect::Empty,
+                        Content::Simple(ty) => Expect::Text {
+                            ty: *ty,
+                            text: String::new(),
+                        },
+                        Content::Elements { model, mixed } => Expect::Elements {
+                            model,
+                            state: model.initial(),
+                            mixed: *mixed,
+                        },
+                    };
+                    (Some(complex), expect)
+                }
+            },
+        };
+        // Any attributes are allowed in `xs:anyType`
+        let any_type = matches!(expect, Expect::Skip);
+
+        let mut errors = Vec::new();
+        let mut specified = Vec::new();
+        for attr in start.attributes() {
+            let attr = attr?;
+            let key = attr.key.as_ref();
+            if key == b"xmlns" || key.starts_with(b"xmlns:") {
+                continue;
+            }
+            let attribute = decoder.decode(key)?.into_owned();
+            let (ns, local) = reader.resolver().resolve_attribute(QName(key));
+            let ns = match ns {
+                ResolveResult::Bound(ns) => Some(decoder.decode(ns.as_ref())?.into_owned()),
+                _ => None,
+            };
+            let local = decoder.decode(local.as_ref())?;
+            let value = attr.normalized_value(self.version)?;
+            if ns.as_deref() == Some(XSI) {
+                if local == "nil" && matches!(value.trim(), "true" | "1") {
+                    if decl.nillable {
+                        expect = Expect::Nil;
+                    } else {
+                        errors.push(ValidityError::NotNillable);
+                    }
+                }
+                continue;
+            }
+            if any_type {
+                continue;
+            }
+            let declared = complex.and_then(|complex| {
+                complex
+                    .attributes
+                    .iter()
+                    .find(|a| a.name.matches(ns.as_deref(), &local))
+            });
+            let declared = match declared {
+                Some(declared) => declared,
+                None => {
+                    let wildcard = complex.and_then(|complex| complex.any_attribute.as_ref());
+                    if !wildcard.is_some_and(|w| w.allows(ns.as_deref())) {
+                        errors.push(ValidityError::UndeclaredAttribute(attribute));
+                    }
+                    continue;
+                }
+            };
+            specified.push(&declared.name);
+            let invalid = |error| ValidityError::InvalidAttributeValue {
+                attribute: attribute.clone(),
+                value: schema.white_space(declared.ty).apply(&value).into_owned(),
+                error,
+            };
+            if let Err(error) = schema.check_value(declared.ty, &value) {
+                errors.push(invalid(error));
+            } else if let Some(fixed) = &declared.fixed {
+                if !schema.values_equal(declared.ty, fixed, &value) {
+                    errors.push(invalid(ValueError::Fixed(fixed.clone())));
+                }
+            }
+        }
+        if let Some(complex) = complex {
+            for declared in &complex.attributes {
+                if declared.required && !specified.contains(&&declared.name) {
+                    errors.push(ValidityError::MissingAttribute(declared.name.to_string()));
+                }
+            }
+        }
+        for error in errors {
+            self.report(path, position, error);
+        }
+        Ok(expect)
+    }
+
+    fn end(&mut self, position: Position) {
+        let frame = match self.stack.pop() {
+            Some(frame) => frame,
+            None => return,
+        };
+        let error = match frame.expect {
+            Expect::Text { ty, text } => match self.schema.check_value(ty, &text) {
+                Ok(()) => None,
+                Err(error) => Some(ValidityError::InvalidValue {
+                    value: self.schema.white_space(ty).apply(&text).into_owned(),
+                    error,
+                }),
+            },
+            Expect::Elements { model, state, .. } if !model.is_final(&state) => {
+                Some(ValidityError::IncompleteContent {
+                    expected: expected(self.schema, model, &state),
+                })
+            }
+            _ => None,
+        };
+        if let Some(error) = error {
+            self.report(&frame.path, position, error);
+        }
+    }
+
+    /// Checks that text is allowed in the current element and collects it if
+    /// the element has simple content.
+    ///
+    /// # Parameters
+    /// - `whitespace`: `true` if the text consists only of whitespace characters
+    fn text(&mut self, content: &str, whitespace: bool, position: Position) {
+        let frame = match self.stack.last_mut() {
+            Some(frame) => frame,
+            None => return,
+        };
+        let error = match &mut frame.expect {
+            Expect::Text { text, .. } => {
+                text.push_str(content);
+                None
+            }
+            Expect::Elements { mixed: false, .. } if !whitespace => {
+                Some(ValidityError::UnexpectedText)
+            }
+            Expect::Empty => Some(ValidityError::UnexpectedText),
+            Expect::Nil if !whitespace => Some(ValidityError::NilContent),
+            _ => None,
+        };
+        if let Some(error) = error {
+            let path = frame.path.clone();
+            self.report(&path, position, error);
+        }
+    }
+}
+
+/// Returns names of elements that are allowed in the state of the content model.
+fn expected(schema: &Schema, model: &Model, state: &State) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for term in model.expected(state) {
+        let name = match term {
+            Term::Element(index) => schema.elements[*index].name.to_string(),
+            Term::Any(_) => "*".to_string(),
+        };
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Reads the whole document from the `reader` and validates it against the `schema`.
+///
+/// Returns all found validity errors, or an error if the document is not
+/// well-formed. See the [module documentation](self) for an example.
+pub fn validate<R: BufRead>(schema: &Schema, reader: &mut NsReader<R>) -> Result<Vec<Violation>> {
+    let mut validator = Validator::new(schema);
+    let mut buf = Vec::new();
+    loop {
+        let event = reader.read_event_into(&mut buf)?;
+        validator.feed(&event, reader)?;
+        if let Event::Eof = event {
+            return Ok(validator.take_violations());
+        }
+        buf.clear();
+    }
+}
@@ -0,0 +1,327 @@
+//! Content models of complex types: particles with occurrence constraints and
+//! automata compiled from them.
+
+use std::collections::HashSet;
+
+/// Which namespaces are allowed by a wildcard (`<xs:any>` or `<xs:anyAttribute>`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) enum Namespaces {
+    /// `##any`
+    Any,
+    /// `##other`: any namespace except the target namespace of the schema and
+    /// no namespace
+    Not(Option<String>),
+    /// A list of namespaces, where `None` means no namespace (`##local`)
+    List(Vec<Option<String>>),
+}
+
+/// How the content matched by a wildcard is validated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Process {
+    /// A declaration must be found and the content is validated
+    Strict,
+    /// The content is validated if a declaration is found
+    Lax,
+    /// The content is not validated
+    Skip,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Wildcard {
+    pub namespaces: Namespaces,
+    pub process: Process,
+}
+
+impl Wildcard {
+    /// Returns `true` if an element or an attribute in the namespace `ns` is allowed.
+    pub fn allows(&self, ns: Option<&str>) -> bool {
+        match &self.namespaces {
+            Namespaces::Any => true,
+            Namespaces::Not(target) => ns.is_some() && ns != target.as_deref(),
+            Namespaces::List(list) => list.iter().any(|n| n.as_deref() == ns),
+        }
+    }
+}
+
+/// A term which matches one child element.
+#[derive(Clone, Debug)]
+pub(super) enum Term {
+    /// Index of the element declaration in the schema
+    Element(usize),
+    Any(Wildcard),
+}
+
+#[derive(Clone, Debug)]
+pub(super) enum Group {
+    Term(Term),
+    Sequence(Vec<Particle>),
+    Choice(Vec<Particle>),
+    /// Elements in any order. Can only be the top-level group of a content model
+    All(Vec<Particle>),
+}
+
+/// A group or a term with occurrence constraints.
+#[derive(Clone, Debug)]
+pub(super) struct Particle {
+    pub min: u32,
+    /// Maximum number of occurrences, `None` if unbounded
+    pub max: Option<u32>,
+    pub group: Group,
+}
+
+impl Particle {
+    /// A particle which is matched exactly once.
+    pub const fn once(group: Group) -> Self {
+        Self {
+            min: 1,
+            max: Some(1),
+            group,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Maximum number of nodes of an automaton. Bounded occurrences are unrolled,
+/// so nested particles with large `minOccurs` or `maxOccurs` multiply the size
+/// of the automaton.
+pub(super) const MAX_NODES: usize = 100_000;
+
+#[derive(Clone, Debug)]
+struct Node {
+    /// A term matched by the node, or `None` if the node does not consume an element
+    term: Option<Term>,
+    /// Nodes reachable from this node
+    next: Vec<usize>,
+}
+
+/// A content model of element content, compiled into a non-deterministic finite
+/// automaton. Node `0` is the final node.
+#[derive(Clone, Debug)]
+pub(super) struct Automaton {
+    nodes: Vec<Node>,
+    /// Index of the initial node
+    start: usize,
+}
+
+impl Automaton {
+    /// Compiles the particle. Returns `None` if the automaton would have more
+    /// than [`MAX_NODES`] nodes.
+    fn new(particle: Option<&Particle>) -> Option<Self> {
+        let mut automaton = Self {
+            nodes: vec![Node {
+                term: None,
+                next: Vec::new(),
+            }],
+            start: 0,
+        };
+        if let Some(particle) = particle {
+            automaton.start = automaton.particle(particle, 0);
+        }
+        (automaton.nodes.len() <= MAX_NODES).then_some(automaton)
+    }
+
+    /// Returns `true` if the automaton is too large. Unrolling of occurrences
+    /// stops then, and the automaton is discarded.
+    fn overflowed(&self) -> bool {
+        self.nodes.len() > MAX_NODES
+    }
+
+    fn add(&mut self, term: Option<Term>, next: Vec<usize>) -> usize {
+        self.nodes.push(Node { term, next });
+        self.nodes.len() - 1
+    }
+
+    /// Adds nodes of the particle which continue to the node `out` and returns
+    /// the entry node of the particle. Repetitions are unrolled: required
+    /// occurrences are followed by optional ones, each of which can be skipped
+    /// to the `out` node.
+    fn particle(&mut self, particle: &Particle, out: usize) -> usize {
+        let mut next = out;
+        match particle.max {
+            None => {
+                let repeat = self.add(None, Vec::new());
+                let entry = self.group(&particle.group, repeat);
+                self.nodes[repeat].next = vec![entry, out];
+                next = repeat;
+            }
+            Some(max) => {
+                for _ in particle.min..max {
+                    if self.overflowed() {
+                        return next;
+                    }
+                    let entry = self.group(&particle.group, next);
+                    next = self.add(None, vec![entry, out]);
+                }
+            }
+        }
+        for _ in 0..particle.min {
+            if self.overflowed() {
+                break;
+            }
+            next = self.group(&particle.group, next);
+        }
+        next
+    }
+
+    fn group(&mut self, group: &Group, out: usize) -> usize {
+        match group {
+            Group::Term(term) => self.add(Some(term.clone()), vec![out]),
+            Group::Sequence(particles) | Group::All(particles) => particles
+                .iter()
+                .rev()
+                .fold(out, |out, particle| self.particle(particle, out)),
+            Group::Choice(particles) => {
+                let next = particles
+                    .iter()
+                    .map(|particle| self.particle(particle, out))
+                    .collect();
+                self.add(None, next)
+            }
+        }
+    }
+
+    /// Returns nodes that match elements and the final node which are reachable
+    /// from `nodes` without matching an element.
+    fn closure(&self, nodes: impl IntoIterator<Item = usize>) -> Vec<usize> {
+        // Only a few nodes are usually reachable, so track them instead of
+        // allocating a flag for each node of the automaton
+        let mut visited = HashSet::new();
+        let mut stack: Vec<_> = nodes.into_iter().collect();
+        // Visit nodes in order of declaration of particles
+        stack.reverse();
+        let mut states = Vec::new();
+        while let Some(index) = stack.pop() {
+            if !visited.insert(index) {
+                continue;
+            }
+            let node = &self.nodes[index];
+            if node.term.is_some() || index == 0 {
+                states.push(index);
+            } else {
+                stack.extend(node.next.iter().rev());
+            }
+        }
+        states
+    }
+}
+
+/// A compiled content model.
+#[derive(Clone, Debug)]
+pub(super) enum Model {
+    Automaton(Automaton),
+    /// An `<xs:all>` group: elements with flags whether they are required.
+    /// If the group is optional, it can be empty
+    All {
+        elements: Vec<(Term, bool)>,
+        optional: bool,
+    },
+}
+
+/// A state of matching of children elements against a [`Model`].
+#[derive(Clone, Debug)]
+pub(super) enum State {
+    /// Current nodes of an automaton
+    Automaton(Vec<usize>),
+    /// Flags which elements of the `<xs:all>` group were already seen
+    All(Vec<bool>),
+}
+
+impl Model {
+    /// Compiles the content model. Returns `None` if it is too large, see [`MAX_NODES`].
+    pub fn new(particle: Option<&Particle>) -> Option<Self> {
+        Some(match particle {
+            Some(Particle {
+                min,
+                group: Group::All(particles),
+                ..
+            }) => Self::All {
+                elements: particles
+                    .iter()
+                    .filter_map(|p| match &p.group {
+                        Group::Term(term) => Some((term.clone(), p.min > 0)),
+                        _ => None,
+                    })
+                    .collect(),
+                optional: *min == 0,
+            },
+            _ => Self::Automaton(Automaton::new(particle)?),
+        })
+    }
+
+    pub fn initial(&self) -> State {
+        match self {
+            Self::Automaton(automaton) => State::Automaton(automaton.closure([automaton.start])),
+            Self::All { elements, .. } => State::All(vec![false; elements.len()]),
+        }
+    }
+
+    /// Finds a term that matches the child element in the current state and
+    /// updates the state. If the element is not allowed, the state is not changed
+    /// and `None` is returned.
+    pub fn step<'m>(
+        &'m self,
+        state: &mut State,
+        matches: impl Fn(&Term) -> bool,
+    ) -> Option<&'m Term> {
+        match (self, state) {
+            (Self::Automaton(automaton), State::Automaton(states)) => {
+                let node = states
+                    .iter()
+                    .map(|&index| &automaton.nodes[index])
+                    .find(|node| node.term.as_ref().is_some_and(&matches))?;
+                let term = node.term.as_ref();
+                // Several nodes can match the same element, continue with all of them
+                let next: Vec<_> = states
+                    .iter()
+                    .map(|&index| &automaton.nodes[index])
+                    .filter(|node| node.term.as_ref().is_some_and(&matches))
+                    .flat_map(|node| node.next.iter().copied())
+                    .collect();
+                *states = automaton.closure(next);
+                term
+            }
+            (Self::All { elements, .. }, State::All(seen)) => {
+                let index = elements
+                    .iter()
+                    .zip(seen.iter())
+                    .position(|((term, _), seen)| !seen && matches(term))?;
+                seen[index] = true;
+                Some(&elements[index].0)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the content can end in this state.
+    pub fn is_final(&self, state: &State) -> bool {
+        match (self, state) {
+            (Self::Automaton(_), State::Automaton(states)) => states.contains(&0),
+            (Self::All { elements, optional }, State::All(seen)) => {
+                (*optional && !seen.contains(&true))
+                    || elements
+                        .iter()
+                        .zip(seen)
+                        .all(|((_, required), seen)| *seen || !required)
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns terms which are allowed in this state.
+    pub fn expected<'m>(&'m self, state: &State) -> Vec<&'m Term> {
+        match (self, state) {
+            (Self::Automaton(automaton), State::Automaton(states)) => states
+                .iter()
+                .filter_map(|&index| automaton.nodes[index].term.as_ref())
+                .collect(),
+            (Self::All { elements, .. }, State::All(seen)) => elements
+                .iter()
+                .zip(seen)
+                .filter(|(_, seen)| !**seen)
+                .map(|((term, _), _)| term)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
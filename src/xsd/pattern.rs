@@ -0,0 +1,235 @@
+//! Translation of [regular expressions] of XML Schema into the syntax of the
+//! [`regex`] crate.
+//!
+//! XML Schema regular expressions are always anchored at both ends, do not
+//! have special meaning for `^` and `$` and have several additional escapes
+//! for XML names. Character class subtraction `[a-z-[aeiou]]` is translated
+//! into the set difference `[a-z--[aeiou]]`. Unicode block escapes like
+//! `\p{IsBasicLatin}` are not supported.
+//!
+//! [regular expressions]: https://www.w3.org/TR/xmlschema-2/#regexs
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+use regex::Regex;
+
+/// [`NameStartChar`] of XML 1.1, used for the `\i` escape.
+///
+/// [`NameStartChar`]: https://www.w3.org/TR/xml11/#NT-NameStartChar
+const NAME_START_CHAR: &str = r":A-Z_a-z\x{C0}-\x{D6}\x{D8}-\x{F6}\x{F8}-\x{2FF}\x{370}-\x{37D}\x{37F}-\x{1FFF}\x{200C}-\x{200D}\x{2070}-\x{218F}\x{2C00}-\x{2FEF}\x{3001}-\x{D7FF}\x{F900}-\x{FDCF}\x{FDF0}-\x{FFFD}\x{10000}-\x{EFFFF}";
+
+/// Characters that are allowed in [`NameChar`] of XML 1.1 in addition to
+/// [`NAME_START_CHAR`], used for the `\c` escape.
+///
+/// [`NameChar`]: https://www.w3.org/TR/xml11/#NT-NameChar
+const NAME_CHAR: &str = r"\-.0-9\x{B7}\x{300}-\x{36F}\x{203F}-\x{2040}";
+
+/// A compiled `pattern` facet.
+#[derive(Clone, Debug)]
+pub(super) struct Pattern {
+    /// The pattern as written in the schema
+    pub source: String,
+    regex: Regex,
+}
+
+impl Pattern {
+    /// Compiles the XML Schema regular expression. Returns the reason of
+    /// the failure if the expression is invalid or not supported.
+    pub fn new(source: &str) -> Result<Self, String> {
+        let translated = translate(source)?;
+        let regex = Regex::new(&format!("^(?:{})$", translated)).map_err(|e| e.to_string())?;
+        Ok(Self {
+            source: source.to_string(),
+            regex,
+        })
+    }
+
+    /// Returns `true` if the whole `value` matches the pattern.
+    pub fn is_match(&self, value: &str) -> bool {
+        self.regex.is_match(value)
+    }
+}
+
+/// Translates an XML Schema regular expression into the syntax of the `regex` crate.
+fn translate(pattern: &str) -> Result<String, String> {
+    let mut chars = pattern.chars().peekable();
+    let mut out = String::with_capacity(pattern.len());
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => escape(&mut chars, &mut out)?,
+            '[' => class(&mut chars, &mut out)?,
+            // Any character except line breaks
+            '.' => out.push_str(r"[^\n\r]"),
+            // Ordinary characters in XML Schema
+            '^' | '$' => {
+                out.push('\\');
+                out.push(ch);
+            }
+            '(' if chars.peek() == Some(&'?') => {
+                return Err("groups with `(?` are not allowed".to_string())
+            }
+            _ => out.push(ch),
+        }
+    }
+    Ok(out)
+}
+
+/// Translates a character class after the opening `[`, including the closing `]`.
+fn class(chars: &mut Peekable<Chars>, out: &mut String) -> Result<(), String> {
+    out.push('[');
+    if chars.peek() == Some(&'^') {
+        chars.next();
+        out.push('^');
+    }
+    loop {
+        match chars.next() {
+            None => return Err("unclosed character class".to_string()),
+            Some(']') => break,
+            Some('\\') => escape(chars, out)?,
+            // Subtraction: `-[...]` must be the last part of a class
+            Some('-') if chars.peek() == Some(&'[') => {
+                chars.next();
+                out.push_str("--");
+                class(chars, out)?;
+                if chars.next() != Some(']') {
+                    return Err(
+                        "subtraction must be the last part of a character class".to_string()
+                    );
+                }
+                break;
+            }
+            Some('[') => return Err("`[` must be escaped in a character class".to_string()),
+            // Operators of set expressions of the `regex` crate
+            Some(ch @ ('&' | '~')) => {
+                out.push('\\');
+                out.push(ch);
+            }
+            Some(ch) => out.push(ch),
+        }
+    }
+    out.push(']');
+    Ok(())
+}
+
+/// Translates an escape after the `\`. Multi-character escapes are translated
+/// to classes, which can be nested in other classes.
+fn escape(chars: &mut Peekable<Chars>, out: &mut String) -> Result<(), String> {
+    let ch = chars
+        .next()
+        .ok_or_else(|| "pattern ends with `\\`".to_string())?;
+    match ch {
+        'n' | 'r' | 't' | '\\' | '|' | '.' | '?' | '*' | '+' | '(' | ')' | '{' | '}' | '-'
+        | '[' | ']' | '^' | '$' => {
+            out.push('\\');
+            out.push(ch);
+        }
+        's' => out.push_str(r"[ \t\n\r]"),
+        'S' => out.push_str(r"[^ \t\n\r]"),
+        'd' => out.push_str(r"[\p{Nd}]"),
+        'D' => out.push_str(r"[^\p{Nd}]"),
+        'w' => out.push_str(r"[^\p{P}\p{Z}\p{C}]"),
+        'W' => out.push_str(r"[\p{P}\p{Z}\p{C}]"),
+        'i' => {
+            out.push('[');
+            out.push_str(NAME_START_CHAR);
+            out.push(']');
+        }
+        'I' => {
+            out.push_str("[^");
+            out.push_str(NAME_START_CHAR);
+            out.push(']');
+        }
+        'c' => {
+            out.push('[');
+            out.push_str(NAME_START_CHAR);
+            out.push_str(NAME_CHAR);
+            out.push(']');
+        }
+        'C' => {
+            out.push_str("[^");
+            out.push_str(NAME_START_CHAR);
+            out.push_str(NAME_CHAR);
+            out.push(']');
+        }
+        'p' | 'P' => {
+            if chars.next() != Some('{') {
+                return Err(format!("`\\{}` must be followed by `{{`", ch));
+            }
+            let mut name = String::new();
+            loop {
+                match chars.next() {
+                    Some('}') => break,
+                    Some(c) => name.push(c),
+                    None => return Err(format!("unclosed `\\{}{{`", ch)),
+                }
+            }
+            if name.starts_with("Is") {
+                return Err(format!(
+                    "block escape `\\{}{{{}}}` is not supported",
+                    ch, name
+                ));
+            }
+            out.push('\\');
+            out.push(ch);
+            out.push('{');
+            out.push_str(&name);
+            out.push('}');
+        }
+        _ => return Err(format!("unknown escape `\\{}`", ch)),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn anchored() {
+        let pattern = Pattern::new("[A-Z]{3}").unwrap();
+        assert!(pattern.is_match("EUR"));
+        assert!(!pattern.is_match("EURO"));
+        assert!(!pattern.is_match(" EUR"));
+    }
+
+    #[test]
+    fn ordinary_characters() {
+        let pattern = Pattern::new("^a$.").unwrap();
+        assert!(pattern.is_match("^a$b"));
+        assert!(!pattern.is_match("^a$\n"));
+    }
+
+    #[test]
+    fn subtraction() {
+        let pattern = Pattern::new("[a-z-[aeiou]]+").unwrap();
+        assert!(pattern.is_match("xyz"));
+        assert!(!pattern.is_match("abc"));
+    }
+
+    #[test]
+    fn name_escapes() {
+        let pattern = Pattern::new(r"\i\c*").unwrap();
+        assert!(pattern.is_match("_a-1.b"));
+        assert!(!pattern.is_match("1a"));
+
+        let pattern = Pattern::new(r"[\s\d]+").unwrap();
+        assert!(pattern.is_match(" 1\t2"));
+        assert!(!pattern.is_match("\u{A0}"));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            translate(r"\p{IsBasicLatin}"),
+            Err("block escape `\\p{IsBasicLatin}` is not supported".to_string())
+        );
+        assert_eq!(translate(r"\b"), Err("unknown escape `\\b`".to_string()));
+        assert_eq!(
+            translate("(?i)a"),
+            Err("groups with `(?` are not allowed".to_string())
+        );
+        assert_eq!(translate("[a"), Err("unclosed character class".to_string()));
+    }
+}
@@ -0,0 +1,1166 @@
+//! Loading of schema documents into a [`Schema`].
+
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::dom::{Document, Element};
+use crate::errors::Error;
+use crate::reader::NsReader;
+use crate::xsd::model::{Group, Model, Namespaces, Particle, Process, Term, Wildcard, MAX_NODES};
+use crate::xsd::pattern::Pattern;
+use crate::xsd::types::{Builtin, Facets, SimpleType, WhiteSpace};
+use crate::XmlVersion;
+
+/// Namespace of XML Schema.
+pub(super) const XS: &str = "http://www.w3.org/2001/XMLSchema";
+/// Namespace of attributes of XML Schema used in instance documents.
+pub(super) const XSI: &str = "http://www.w3.org/2001/XMLSchema-instance";
+/// Namespace bound to the `xml` prefix.
+const XML: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// An error of loading a schema document.
+#[derive(Clone, Debug)]
+pub enum SchemaError {
+    /// The schema document is not a well-formed XML document.
+    Xml(Error),
+    /// The root element of the schema document is not `<xs:schema>`. Contains
+    /// the name of the root element, or an empty string if the document is empty.
+    NotSchema(String),
+    /// A required attribute of a schema component is not specified.
+    MissingAttribute {
+        /// Name of the element of the schema component
+        element: String,
+        /// Name of the attribute
+        attribute: String,
+    },
+    /// An attribute of a schema component has invalid value.
+    InvalidAttribute {
+        /// Name of the element of the schema component
+        element: String,
+        /// Name of the attribute
+        attribute: String,
+        /// Value of the attribute
+        value: String,
+    },
+    /// A schema component does not contain the required child element.
+    MissingContent(String),
+    /// A prefix of a qualified name is not bound to a namespace.
+    UnboundPrefix(String),
+    /// A referenced schema component is not defined.
+    UnknownReference {
+        /// Kind of the component: `element`, `type`, `group`, `attribute group`
+        /// or `attribute`
+        kind: &'static str,
+        /// Name of the component in the `{namespace}local` form
+        name: String,
+    },
+    /// A schema component with the same kind and name is defined twice.
+    Duplicate {
+        /// Kind of the component: `element`, `type`, `group`, `attribute group`
+        /// or `attribute`
+        kind: &'static str,
+        /// Name of the component in the `{namespace}local` form
+        name: String,
+    },
+    /// A type is derived from itself, or a group references itself. Contains
+    /// the name of the component in the `{namespace}local` form.
+    Circular(String),
+    /// A complex type is used where a simple type is required. Contains the
+    /// name of the type in the `{namespace}local` form.
+    NotSimpleType(String),
+    /// A simple type is used as a base of complex content. Contains the name
+    /// of the type in the `{namespace}local` form.
+    NotComplexType(String),
+    /// A regular expression of the `pattern` facet is invalid or not supported.
+    InvalidPattern {
+        /// The regular expression
+        pattern: String,
+        /// Description of the error
+        reason: String,
+    },
+    /// The schema uses a construct which is not supported. Contains the name
+    /// of the element of the construct.
+    Unsupported(String),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Xml(e) => write!(f, "malformed schema document: {}", e),
+            Self::NotSchema(name) => {
+                write!(f, "root element `{}` is not `xs:schema`", name)
+            }
+            Self::MissingAttribute { element, attribute } => write!(
+                f,
+                "required attribute `{}` of `{}` is not specified",
+                attribute, element
+            ),
+            Self::InvalidAttribute {
+                element,
+                attribute,
+                value,
+            } => write!(
+                f,
+                "value `{}` is not valid for attribute `{}` of `{}`",
+                value, attribute, element
+            ),
+            Self::MissingContent(element) => {
+                write!(f, "`{}` does not contain a required element", element)
+            }
+            Self::UnboundPrefix(prefix) => {
+                write!(f, "prefix `{}` is not bound to a namespace", prefix)
+            }
+            Self::UnknownReference { kind, name } => {
+                write!(f, "{} `{}` is not defined", kind, name)
+            }
+            Self::Duplicate { kind, name } => write!(f, "{} `{}` is already defined", kind, name),
+            Self::Circular(name) => write!(f, "`{}` has a circular definition", name),
+            Self::NotSimpleType(name) => write!(f, "type `{}` is not a simple type", name),
+            Self::NotComplexType(name) => write!(f, "type `{}` is not a complex type", name),
+            Self::InvalidPattern { pattern, reason } => {
+                write!(f, "invalid pattern `{}`: {}", pattern, reason)
+            }
+            Self::Unsupported(element) => write!(f, "`{}` is not supported", element),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Xml(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for SchemaError {
+    #[inline]
+    fn from(error: Error) -> Self {
+        Self::Xml(error)
+    }
+}
+
+type Result<T> = std::result::Result<T, SchemaError>;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An expanded name of a schema component or of an element in a document.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(super) struct Name {
+    pub ns: Option<String>,
+    pub local: String,
+}
+
+impl Name {
+    pub fn matches(&self, ns: Option<&str>, local: &str) -> bool {
+        self.local == local && self.ns.as_deref() == ns
+    }
+}
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.ns {
+            Some(ns) => write!(f, "{{{}}}{}", ns, self.local),
+            None => f.write_str(&self.local),
+        }
+    }
+}
+
+/// A reference to a type of an element or of an attribute.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum TypeId {
+    Builtin(Builtin),
+    /// Index of the type defined in the schema
+    Defined(usize),
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct ElementDecl {
+    pub name: Name,
+    pub ty: TypeId,
+    /// Whether the element can be marked as `xsi:nil="true"`
+    pub nillable: bool,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct AttributeDecl {
+    pub name: Name,
+    pub ty: TypeId,
+    pub required: bool,
+    pub fixed: Option<String>,
+}
+
+/// Allowed content of an element of a complex type.
+#[derive(Clone, Debug)]
+pub(super) enum Content {
+    Empty,
+    /// Text of the simple type
+    Simple(TypeId),
+    /// Elements and, if `mixed`, text between them
+    Elements {
+        model: Model,
+        mixed: bool,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct ComplexType {
+    /// Declarations of attributes, including inherited from the base type
+    pub attributes: Vec<AttributeDecl>,
+    /// Wildcard for attributes which are not declared
+    pub any_attribute: Option<Wildcard>,
+    pub content: Content,
+    /// The particle of the content model, used by types derived by extension
+    /// and by the code generator
+    pub particle: Option<Particle>,
+}
+
+#[derive(Clone, Debug)]
+pub(super) enum Type {
+    Simple(SimpleType),
+    Complex(ComplexType),
+}
+
+/// A compiled set of schema documents used by the [`Validator`].
+///
+/// [`Validator`]: crate::xsd::Validator
+#[derive(Clone, Debug)]
+pub struct Schema {
+    /// All element declarations, global and local
+    pub(super) elements: Vec<ElementDecl>,
+    /// Indexes of global element declarations, which can be roots of documents
+    pub(super) globals: HashMap<Name, usize>,
+    /// Types defined in the schema, named and anonymous
+    pub(super) types: Vec<Type>,
+    /// Names of named types by their indexes
+    pub(super) type_names: HashMap<usize, Name>,
+}
+
+impl Schema {
+    /// Loads a schema from a single schema document.
+    ///
+    /// ```
+    /// use quick_xml::xsd::Schema;
+    ///
+    /// let schema = Schema::parse(r#"
+    ///     <xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+    ///         <xs:element name="root" type="xs:string"/>
+    ///     </xs:schema>"#);
+    /// assert!(schema.is_ok());
+    /// ```
+    pub fn parse(xsd: &str) -> Result<Self> {
+        Self::parse_all([xsd])
+    }
+
+    /// Loads a schema from several schema documents, usually one per target
+    /// namespace. Components defined in any document can be referenced from
+    /// the others. `<xs:import>` and `<xs:include>` elements are ignored, so
+    /// all imported and included documents should be passed to this method.
+    pub fn parse_all<'a, I>(documents: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let documents = documents
+            .into_iter()
+            .map(|xsd| Document::read_from_ns(&mut NsReader::from_str(xsd)))
+            .collect::<std::result::Result<Vec<_>, Error>>()?;
+        let mut loader = Loader::default();
+        for document in &documents {
+            loader.register(document)?;
+        }
+        loader.finish()
+    }
+
+    /// Returns the index of the global element declaration with the name.
+    pub(super) fn global(&self, ns: Option<&str>, local: &str) -> Option<usize> {
+        self.globals
+            .iter()
+            .find(|(name, _)| name.matches(ns, local))
+            .map(|(_, index)| *index)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Kind of a global schema component.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Kind {
+    Element,
+    Type,
+    Group,
+    AttributeGroup,
+    Attribute,
+}
+
+impl Kind {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Element => "element",
+            Self::Type => "type",
+            Self::Group => "group",
+            Self::AttributeGroup => "attribute group",
+            Self::Attribute => "attribute",
+        }
+    }
+}
+
+/// Properties of a schema document and namespace bindings in scope.
+#[derive(Clone, Debug, Default)]
+struct Context {
+    /// Target namespace of the schema document
+    target: Option<String>,
+    /// Whether local elements are in the target namespace by default
+    qualified_elements: bool,
+    /// Whether local attributes are in the target namespace by default
+    qualified_attributes: bool,
+    /// Bound prefixes, the last binding of a prefix wins. The default namespace
+    /// has an empty prefix
+    namespaces: Vec<(String, String)>,
+}
+
+impl Context {
+    /// Returns the context of the element `e` with the namespace bindings declared in it.
+    fn nested(&self, e: &Element) -> Result<Self> {
+        let mut context = self.clone();
+        for attr in e.attributes() {
+            let attr = attr.map_err(Error::from)?;
+            let key = attr.key.as_ref();
+            let prefix = match key.strip_prefix(b"xmlns") {
+                Some(b"") => "",
+                Some(rest) if rest.starts_with(b":") => std::str::from_utf8(&rest[1..])
+                    .map_err(|e| Error::from(crate::encoding::EncodingError::from(e)))?,
+                _ => continue,
+            };
+            let ns = attr.normalized_value(XmlVersion::Implicit1_0)?;
+            context
+                .namespaces
+                .push((prefix.to_string(), ns.into_owned()));
+        }
+        Ok(context)
+    }
+
+    /// Resolves the qualified name written in an attribute value.
+    fn resolve(&self, qname: &str) -> Result<Name> {
+        let (prefix, local) = qname.split_once(':').unwrap_or(("", qname));
+        let ns = if prefix == "xml" {
+            Some(XML)
+        } else {
+            self.namespaces
+                .iter()
+                .rev()
+                .find(|(p, _)| p == prefix)
+                .map(|(_, ns)| ns.as_str())
+        };
+        let ns = match ns {
+            // `xmlns=""` removes the default namespace
+            Some("") => None,
+            Some(ns) => Some(ns.to_string()),
+            None if prefix.is_empty() => None,
+            None => return Err(SchemaError::UnboundPrefix(prefix.to_string())),
+        };
+        Ok(Name {
+            ns,
+            local: local.to_string(),
+        })
+    }
+
+    /// Returns the name of a local element or attribute declaration.
+    ///
+    /// # Parameters
+    /// - `e`: the declaration
+    /// - `qualified`: the default qualification of names of the declaration
+    fn local_name(&self, e: &Element, qualified: bool) -> Result<Name> {
+        let qualified = match attribute(e, "form")? {
+            Some(form) => form.trim() == "qualified",
+            None => qualified,
+        };
+        Ok(Name {
+            ns: if qualified { self.target.clone() } else { None },
+            local: required(e, "name")?.trim().to_string(),
+        })
+    }
+}
+
+/// Returns the qualified name of the element as written in the schema document.
+fn tag(e: &Element) -> String {
+    String::from_utf8_lossy(e.name().as_ref()).into_owned()
+}
+
+/// Returns the local name of the element if it is in the XML Schema namespace.
+fn xs_name(e: &Element) -> Option<&[u8]> {
+    if e.namespace() == Some(XS.as_bytes()) {
+        Some(e.name().local_name().into_inner())
+    } else {
+        None
+    }
+}
+
+/// Returns children elements of `e` from the XML Schema namespace, except
+/// annotations, together with their local names.
+fn children(e: &Element) -> impl Iterator<Item = (&[u8], &Element)> {
+    e.elements()
+        .filter_map(|child| xs_name(child).map(|name| (name, child)))
+        .filter(|(name, _)| *name != b"annotation")
+}
+
+fn unsupported(kind: &[u8]) -> SchemaError {
+    SchemaError::Unsupported(format!("xs:{}", String::from_utf8_lossy(kind)))
+}
+
+/// Returns the normalized value of the attribute of `e`, if it is specified.
+fn attribute(e: &Element, name: &str) -> Result<Option<String>> {
+    match e.attribute(name)? {
+        Some(attr) => Ok(Some(
+            attr.normalized_value(XmlVersion::Implicit1_0)?.into_owned(),
+        )),
+        None => Ok(None),
+    }
+}
+
+/// Returns the normalized value of the required attribute of `e`.
+fn required(e: &Element, name: &str) -> Result<String> {
+    attribute(e, name)?.ok_or_else(|| SchemaError::MissingAttribute {
+        element: tag(e),
+        attribute: name.to_string(),
+    })
+}
+
+fn invalid(e: &Element, attribute: &str, value: &str) -> SchemaError {
+    SchemaError::InvalidAttribute {
+        element: tag(e),
+        attribute: attribute.to_string(),
+        value: value.to_string(),
+    }
+}
+
+/// Parses an attribute of type `xs:boolean`, which is `false` by default.
+fn boolean(e: &Element, name: &str) -> Result<bool> {
+    match attribute(e, name)?.as_deref().map(str::trim) {
+        None | Some("false" | "0") => Ok(false),
+        Some("true" | "1") => Ok(true),
+        Some(value) => Err(invalid(e, name, value)),
+    }
+}
+
+/// Parses an attribute with a non-negative number.
+fn number(e: &Element, name: &str) -> Result<Option<u32>> {
+    match attribute(e, name)? {
+        Some(value) => match value.trim().parse() {
+            Ok(number) => Ok(Some(number)),
+            Err(_) => Err(invalid(e, name, &value)),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Compiles schema documents into a [`Schema`]. Global components are compiled
+/// when they are referenced for the first time, which allows references to
+/// components defined later and recursive definitions.
+#[derive(Default)]
+struct Loader<'d> {
+    /// Definitions of global components with contexts of their schema documents
+    globals: HashMap<(Kind, Name), (&'d Element, Rc<Context>)>,
+    /// Global components in order of definition
+    order: Vec<(Kind, Name)>,
+    elements: Vec<ElementDecl>,
+    /// Indexes of compiled global element declarations
+    element_ids: HashMap<Name, usize>,
+    /// Compiled types. Types that are compiled at the moment are `None`
+    types: Vec<Option<Type>>,
+    /// Indexes of named types
+    type_ids: HashMap<Name, usize>,
+    /// Groups and attribute groups which are expanded at the moment
+    expanding: Vec<(Kind, Name)>,
+}
+
+impl<'d> Loader<'d> {
+    /// Registers global components of the schema document.
+    fn register(&mut self, document: &'d Document) -> Result<()> {
+        let root = match document.root() {
+            Some(root) if xs_name(root) == Some(b"schema") => root,
+            Some(root) => return Err(SchemaError::NotSchema(tag(root))),
+            None => return Err(SchemaError::NotSchema(String::new())),
+        };
+        let qualified = |name| -> Result<bool> {
+            Ok(attribute(root, name)?.as_deref().map(str::trim) == Some("qualified"))
+        };
+        let context = Context {
+            target: attribute(root, "targetNamespace")?,
+            qualified_elements: qualified("elementFormDefault")?,
+            qualified_attributes: qualified("attributeFormDefault")?,
+            namespaces: Vec::new(),
+        };
+        let context = Rc::new(context.nested(root)?);
+        for (kind, child) in children(root) {
+            let kind = match kind {
+                b"element" => Kind::Element,
+                b"complexType" | b"simpleType" => Kind::Type,
+                b"group" => Kind::Group,
+                b"attributeGroup" => Kind::AttributeGroup,
+                b"attribute" => Kind::Attribute,
+                b"import" | b"include" | b"notation" => continue,
+                _ => return Err(unsupported(kind)),
+            };
+            let name = Name {
+                ns: context.target.clone(),
+                local: required(child, "name")?.trim().to_string(),
+            };
+            let key = (kind, name);
+            if self.globals.contains_key(&key) {
+                return Err(SchemaError::Duplicate {
+                    kind: kind.name(),
+                    name: key.1.to_string(),
+                });
+            }
+            self.globals.insert(key.clone(), (child, context.clone()));
+            self.order.push(key);
+        }
+        Ok(())
+    }
+
+    /// Compiles all global components, so errors in them are reported even
+    /// when they are not referenced.
+    fn finish(mut self) -> Result<Schema> {
+        for (kind, name) in std::mem::take(&mut self.order) {
+            match kind {
+                Kind::Element => {
+                    self.global_element(&name)?;
+                }
+                Kind::Type => {
+                    self.named_type(&name)?;
+                }
+                Kind::Group => {
+                    self.group(&name)?;
+                }
+                Kind::AttributeGroup => {
+                    self.attribute_group(&name)?;
+                }
+                Kind::Attribute => {
+                    self.global_attribute(&name)?;
+                }
+            }
+        }
+        let schema = Schema {
+            elements: self.elements,
+            globals: self.element_ids,
+            types: self
+                .types
+                .into_iter()
+                .map(|ty| ty.expect("all types are compiled"))
+                .collect(),
+            type_names: self
+                .type_ids
+                .into_iter()
+                .map(|(name, index)| (index, name))
+                .collect(),
+        };
+        if let Some((facet, value)) = schema.invalid_bound() {
+            return Err(SchemaError::InvalidAttribute {
+                element: format!("xs:{}", facet),
+                attribute: "value".to_string(),
+                value: value.to_string(),
+            });
+        }
+        Ok(schema)
+    }
+
+    fn global(&self, kind: Kind, name: &Name) -> Result<(&'d Element, Rc<Context>)> {
+        match self.globals.get(&(kind, name.clone())) {
+            Some((e, context)) => Ok((e, context.clone())),
+            None => Err(SchemaError::UnknownReference {
+                kind: kind.name(),
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    /// Marks the group as expanded and returns an error if it is already expanded.
+    fn enter(&mut self, kind: Kind, name: &Name) -> Result<()> {
+        let key = (kind, name.clone());
+        if self.expanding.contains(&key) {
+            return Err(SchemaError::Circular(name.to_string()));
+        }
+        self.expanding.push(key);
+        Ok(())
+    }
+
+    //----------------------------------------------------------------------------------------------
+
+    fn global_element(&mut self, name: &Name) -> Result<usize> {
+        if let Some(index) = self.element_ids.get(name) {
+            return Ok(*index);
+        }
+        let (e, context) = self.global(Kind::Element, name)?;
+        let index = self.declare(name.clone());
+        self.element_ids.insert(name.clone(), index);
+        self.element_type(index, e, &context)?;
+        Ok(index)
+    }
+
+    /// Adds an element declaration, which type is not known yet.
+    fn declare(&mut self, name: Name) -> usize {
+        self.elements.push(ElementDecl {
+            name,
+            ty: TypeId::Builtin(Builtin::AnyType),
+            nillable: false,
+        });
+        self.elements.len() - 1
+    }
+
+    /// Sets the type of the element declaration with the `index`.
+    fn element_type(&mut self, index: usize, e: &'d Element, context: &Context) -> Result<()> {
+        let context = context.nested(e)?;
+        let ty = if let Some(ty) = attribute(e, "type")? {
+            self.type_ref(&context.resolve(ty.trim())?)?
+        } else if let Some((kind, child)) =
+            children(e).find(|(kind, _)| matches!(*kind, b"complexType" | b"simpleType"))
+        {
+            self.anonymous_type(kind, child, &context)?
+        } else {
+            TypeId::Builtin(Builtin::AnyType)
+        };
+        let decl = &mut self.elements[index];
+        decl.ty = ty;
+        decl.nillable = boolean(e, "nillable")?;
+        Ok(())
+    }
+
+    /// Returns the built-in type or the named type defined in the schema.
+    fn type_ref(&mut self, name: &Name) -> Result<TypeId> {
+        if name.ns.as_deref() == Some(XS) {
+            if let Some(builtin) = Builtin::from_name(&name.local) {
+                return Ok(TypeId::Builtin(builtin));
+            }
+        }
+        self.named_type(name)
+    }
+
+    fn named_type(&mut self, name: &Name) -> Result<TypeId> {
+        if let Some(index) = self.type_ids.get(name) {
+            return Ok(TypeId::Defined(*index));
+        }
+        let (e, context) = self.global(Kind::Type, name)?;
+        let index = self.types.len();
+        self.types.push(None);
+        self.type_ids.insert(name.clone(), index);
+        let ty = self.type_definition(xs_name(e).unwrap_or_default(), e, &context)?;
+        self.types[index] = Some(ty);
+        Ok(TypeId::Defined(index))
+    }
+
+    fn anonymous_type(&mut self, kind: &[u8], e: &'d Element, context: &Context) -> Result<TypeId> {
+        let index = self.types.len();
+        self.types.push(None);
+        let ty = self.type_definition(kind, e, context)?;
+        self.types[index] = Some(ty);
+        Ok(TypeId::Defined(index))
+    }
+
+    fn type_definition(&mut self, kind: &[u8], e: &'d Element, context: &Context) -> Result<Type> {
+        match kind {
+            b"complexType" => Ok(Type::Complex(self.complex_type(e, context)?)),
+            _ => Ok(Type::Simple(self.simple_type(e, context)?)),
+        }
+    }
+
+    /// Checks that the type is a simple type. The type should be already compiled.
+    fn simple(&self, ty: TypeId, name: &Name) -> Result<TypeId> {
+        match ty {
+            TypeId::Builtin(Builtin::AnyType) => Err(SchemaError::NotSimpleType(name.to_string())),
+            TypeId::Builtin(_) => Ok(ty),
+            TypeId::Defined(index) => match &self.types[index] {
+                Some(Type::Simple(_)) => Ok(ty),
+                Some(Type::Complex(_)) => Err(SchemaError::NotSimpleType(name.to_string())),
+                None => Err(SchemaError::Circular(name.to_string())),
+            },
+        }
+    }
+
+    /// Returns the simple type referenced by the attribute `attr` of `e`, or
+    /// the anonymous simple type defined in `e`.
+    fn simple_type_ref(&mut self, e: &'d Element, attr: &str, context: &Context) -> Result<TypeId> {
+        if let Some(name) = attribute(e, attr)? {
+            let name = context.resolve(name.trim())?;
+            let ty = self.type_ref(&name)?;
+            return self.simple(ty, &name);
+        }
+        match children(e).find(|(kind, _)| *kind == b"simpleType") {
+            Some((kind, child)) => self.anonymous_type(kind, child, context),
+            None => Err(SchemaError::MissingAttribute {
+                element: tag(e),
+                attribute: attr.to_string(),
+            }),
+        }
+    }
+
+    //----------------------------------------------------------------------------------------------
+
+    fn simple_type(&mut self, e: &'d Element, context: &Context) -> Result<SimpleType> {
+        let context = context.nested(e)?;
+        let (kind, child) = children(e)
+            .next()
+            .ok_or_else(|| SchemaError::MissingContent(tag(e)))?;
+        match kind {
+            b"restriction" => {
+                let base = self.simple_type_ref(child, "base", &context)?;
+                let facets = Box::new(facets(child)?);
+                Ok(SimpleType::Restriction { base, facets })
+            }
+            b"list" => Ok(SimpleType::List(
+                self.simple_type_ref(child, "itemType", &context)?,
+            )),
+            b"union" => {
+                let mut members = Vec::new();
+                for name in attribute(child, "memberTypes")?
+                    .unwrap_or_default()
+                    .split_whitespace()
+                {
+                    let name = context.resolve(name)?;
+                    let ty = self.type_ref(&name)?;
+                    members.push(self.simple(ty, &name)?);
+                }
+                for (kind, member) in children(child) {
+                    members.push(self.anonymous_type(kind, member, &context)?);
+                }
+                if members.is_empty() {
+                    return Err(SchemaError::MissingContent(tag(child)));
+                }
+                Ok(SimpleType::Union(members))
+            }
+            _ => Err(unsupported(kind)),
+        }
+    }
+
+    //----------------------------------------------------------------------------------------------
+
+    fn complex_type(&mut self, e: &'d Element, context: &Context) -> Result<ComplexType> {
+        let context = context.nested(e)?;
+        let mut mixed = boolean(e, "mixed")?;
+        let mut ty = ComplexType {
+            attributes: Vec::new(),
+            any_attribute: None,
+            content: Content::Empty,
+            particle: None,
+        };
+        let mut simple = None;
+        for (kind, child) in children(e) {
+            match kind {
+                b"sequence" | b"choice" | b"all" | b"group" => {
+                    ty.particle = self.particle(kind, child, &context)?;
+                }
+                b"attribute" | b"attributeGroup" | b"anyAttribute" => {
+                    self.attribute_item(kind, child, &context, &mut ty)?;
+                }
+                b"simpleContent" => simple = Some(self.simple_content(child, &context, &mut ty)?),
+                b"complexContent" => {
+                    mixed |= boolean(child, "mixed")?;
+                    self.complex_content(child, &context, &mut ty)?;
+                }
+                _ => return Err(unsupported(kind)),
+            }
+        }
+        ty.content = match simple {
+            Some(simple) => Content::Simple(simple),
+            None if ty.particle.is_none() && !mixed => Content::Empty,
+            None => Content::Elements {
+                model: Model::new(ty.particle.as_ref()).ok_or_else(|| {
+                    SchemaError::Unsupported(format!(
+                        "content model with more than {} states",
+                        MAX_NODES
+                    ))
+                })?,
+                mixed,
+            },
+        };
+        Ok(ty)
+    }
+
+    /// Returns the compiled complex type `ty`, or `None` if it is `xs:anyType`.
+    fn complex_base(&self, ty: TypeId, name: &Name) -> Result<Option<ComplexType>> {
+        match ty {
+            TypeId::Builtin(Builtin::AnyType) => Ok(None),
+            TypeId::Builtin(_) => Err(SchemaError::NotComplexType(name.to_string())),
+            TypeId::Defined(index) => match &self.types[index] {
+                Some(Type::Complex(base)) => Ok(Some(base.clone())),
+                Some(Type::Simple(_)) => Err(SchemaError::NotComplexType(name.to_string())),
+                None => Err(SchemaError::Circular(name.to_string())),
+            },
+        }
+    }
+
+    /// Compiles `<xs:simpleContent>` and returns the type of the content.
+    fn simple_content(
+        &mut self,
+        e: &'d Element,
+        context: &Context,
+        ty: &mut ComplexType,
+    ) -> Result<TypeId> {
+        let context = context.nested(e)?;
+        let (kind, derivation) = children(e)
+            .next()
+            .ok_or_else(|| SchemaError::MissingContent(tag(e)))?;
+        let name = context.resolve(required(derivation, "base")?.trim())?;
+        let base = self.type_ref(&name)?;
+        let content = match base {
+            TypeId::Builtin(Builtin::AnyType) => TypeId::Builtin(Builtin::AnySimpleType),
+            TypeId::Builtin(_) => base,
+            TypeId::Defined(index) => match &self.types[index] {
+                Some(Type::Simple(_)) => base,
+                Some(Type::Complex(complex)) => {
+                    ty.attributes = complex.attributes.clone();
+                    ty.any_attribute = complex.any_attribute.clone();
+                    match complex.content {
+                        Content::Simple(content) => content,
+                        _ => return Err(SchemaError::NotSimpleType(name.to_string())),
+                    }
+                }
+                None => return Err(SchemaError::Circular(name.to_string())),
+            },
+        };
+        let content = match kind {
+            b"extension" => content,
+            b"restriction" => {
+                let base = match children(derivation).find(|(kind, _)| *kind == b"simpleType") {
+                    Some((kind, child)) => self.anonymous_type(kind, child, &context)?,
+                    None => content,
+                };
+                let restriction = SimpleType::Restriction {
+                    base,
+                    facets: Box::new(facets(derivation)?),
+                };
+                self.types.push(Some(Type::Simple(restriction)));
+                TypeId::Defined(self.types.len() - 1)
+            }
+            _ => return Err(unsupported(kind)),
+        };
+        for (kind, child) in children(derivation) {
+            if matches!(kind, b"attribute" | b"attributeGroup" | b"anyAttribute") {
+                self.attribute_item(kind, child, &context, ty)?;
+            }
+        }
+        Ok(content)
+    }
+
+    /// Compiles `<xs:complexContent>` into the particle and attributes of `ty`.
+    fn complex_content(
+        &mut self,
+        e: &'d Element,
+        context: &Context,
+        ty: &mut ComplexType,
+    ) -> Result<()> {
+        let context = context.nested(e)?;
+        let (kind, derivation) = children(e)
+            .next()
+            .ok_or_else(|| SchemaError::MissingContent(tag(e)))?;
+        let name = context.resolve(required(derivation, "base")?.trim())?;
+        let base = self.type_ref(&name)?;
+        let base = self.complex_base(base, &name)?;
+        if let Some(base) = &base {
+            ty.attributes = base.attributes.clone();
+            ty.any_attribute = base.any_attribute.clone();
+        }
+        let mut particle = None;
+        for (child_kind, child) in children(derivation) {
+            match child_kind {
+                b"sequence" | b"choice" | b"all" | b"group" => {
+                    particle = self.particle(child_kind, child, &context)?;
+                }
+                b"attribute" | b"attributeGroup" | b"anyAttribute" => {
+                    self.attribute_item(child_kind, child, &context, ty)?;
+                }
+                _ => return Err(unsupported(child_kind)),
+            }
+        }
+        let inherited = base.and_then(|base| base.particle);
+        ty.particle = match (kind, inherited, particle) {
+            (b"restriction", _, particle) | (b"extension", None, particle) => particle,
+            (b"extension", inherited, None) => inherited,
+            (b"extension", Some(inherited), Some(particle)) => {
+                if matches!(inherited.group, Group::All(_))
+                    || matches!(particle.group, Group::All(_))
+                {
+                    return Err(SchemaError::Unsupported(format!(
+                        "extension of `{}` with xs:all",
+                        name
+                    )));
+                }
+                // Content of the base type is followed by the content of the extension
+                Some(Particle::once(Group::Sequence(vec![inherited, particle])))
+            }
+            _ => return Err(unsupported(kind)),
+        };
+        Ok(())
+    }
+
+    //----------------------------------------------------------------------------------------------
+
+    /// Adds an attribute declaration, attributes of an attribute group or
+    /// an attribute wildcard to the type.
+    fn attribute_item(
+        &mut self,
+        kind: &[u8],
+        e: &'d Element,
+        context: &Context,
+        ty: &mut ComplexType,
+    ) -> Result<()> {
+        match kind {
+            b"attribute" => {
+                let (name, decl) = self.attribute_use(e, context)?;
+                // Declarations of derived types replace inherited declarations
+                ty.attributes.retain(|a| a.name != name);
+                ty.attributes.extend(decl);
+            }
+            b"attributeGroup" => {
+                let name = context.resolve(required(e, "ref")?.trim())?;
+                let group = self.attribute_group(&name)?;
+                for decl in group.attributes {
+                    ty.attributes.retain(|a| a.name != decl.name);
+                    ty.attributes.push(decl);
+                }
+                if group.any_attribute.is_some() {
+                    ty.any_attribute = group.any_attribute;
+                }
+            }
+            b"anyAttribute" => ty.any_attribute = Some(wildcard(e, context)?),
+            _ => return Err(unsupported(kind)),
+        }
+        Ok(())
+    }
+
+    /// Compiles a local attribute declaration or a reference to a global one.
+    /// Returns the name of the attribute and its declaration, or `None` if
+    /// the attribute is prohibited.
+    fn attribute_use(
+        &mut self,
+        e: &'d Element,
+        context: &Context,
+    ) -> Result<(Name, Option<AttributeDecl>)> {
+        let context = context.nested(e)?;
+        let required = match attribute(e, "use")?.as_deref().map(str::trim) {
+            None | Some("optional") => Some(false),
+            Some("required") => Some(true),
+            Some("prohibited") => None,
+            Some(value) => return Err(invalid(e, "use", value)),
+        };
+        let mut decl = match attribute(e, "ref")? {
+            Some(name) => {
+                let name = context.resolve(name.trim())?;
+                if name.ns.as_deref() == Some(XML) {
+                    // Attributes from the `xml` namespace are always known
+                    AttributeDecl {
+                        name,
+                        ty: TypeId::Builtin(Builtin::AnySimpleType),
+                        required: false,
+                        fixed: None,
+                    }
+                } else {
+                    self.global_attribute(&name)?
+                }
+            }
+            None => AttributeDecl {
+                name: context.local_name(e, context.qualified_attributes)?,
+                ty: self.attribute_type(e, &context)?,
+                required: false,
+                fixed: None,
+            },
+        };
+        if let Some(fixed) = attribute(e, "fixed")? {
+            decl.fixed = Some(fixed);
+        }
+        match required {
+            Some(required) => {
+                decl.required = required;
+                Ok((decl.name.clone(), Some(decl)))
+            }
+            None => Ok((decl.name, None)),
+        }
+    }
+
+    fn global_attribute(&mut self, name: &Name) -> Result<AttributeDecl> {
+        let (e, context) = self.global(Kind::Attribute, name)?;
+        let context = context.nested(e)?;
+        Ok(AttributeDecl {
+            name: name.clone(),
+            ty: self.attribute_type(e, &context)?,
+            required: false,
+            fixed: attribute(e, "fixed")?,
+        })
+    }
+
+    fn attribute_type(&mut self, e: &'d Element, context: &Context) -> Result<TypeId> {
+        if attribute(e, "type")?.is_none() && children(e).next().is_none() {
+            return Ok(TypeId::Builtin(Builtin::AnySimpleType));
+        }
+        self.simple_type_ref(e, "type", context)
+    }
+
+    /// Returns attribute declarations and the wildcard of the attribute group.
+    fn attribute_group(&mut self, name: &Name) -> Result<ComplexType> {
+        self.enter(Kind::AttributeGroup, name)?;
+        let (e, context) = self.global(Kind::AttributeGroup, name)?;
+        let context = context.nested(e)?;
+        let mut group = ComplexType {
+            attributes: Vec::new(),
+            any_attribute: None,
+            content: Content::Empty,
+            particle: None,
+        };
+        for (kind, child) in children(e) {
+            self.attribute_item(kind, child, &context, &mut group)?;
+        }
+        self.expanding.pop();
+        Ok(group)
+    }
+
+    //----------------------------------------------------------------------------------------------
+
+    /// Compiles a particle. Returns `None` if the particle cannot occur (`maxOccurs="0"`).
+    fn particle(
+        &mut self,
+        kind: &[u8],
+        e: &'d Element,
+        context: &Context,
+    ) -> Result<Option<Particle>> {
+        let context = context.nested(e)?;
+        let min = number(e, "minOccurs")?.unwrap_or(1);
+        let max = match attribute(e, "maxOccurs")? {
+            Some(max) if max.trim() == "unbounded" => None,
+            Some(_) => number(e, "maxOccurs")?,
+            None => Some(1),
+        };
+        match max {
+            Some(0) => return Ok(None),
+            Some(max) if max < min => {
+                return Err(invalid(e, "maxOccurs", &max.to_string()));
+            }
+            _ => {}
+        }
+        let group = match kind {
+            b"element" => Group::Term(Term::Element(self.element_particle(e, &context)?)),
+            b"any" => Group::Term(Term::Any(wildcard(e, &context)?)),
+            b"sequence" | b"choice" | b"all" => {
+                let mut particles = Vec::new();
+                for (child_kind, child) in children(e) {
+                    match child_kind {
+                        b"element" | b"any" | b"sequence" | b"choice" | b"group" => {
+                            particles.extend(self.particle(child_kind, child, &context)?);
+                        }
+                        _ => return Err(unsupported(child_kind)),
+                    }
+                }
+                match kind {
+                    b"sequence" => Group::Sequence(particles),
+                    b"choice" => Group::Choice(particles),
+                    _ => Group::All(particles),
+                }
+            }
+            b"group" => {
+                let name = context.resolve(required(e, "ref")?.trim())?;
+                self.group(&name)?.group
+            }
+            _ => return Err(unsupported(kind)),
+        };
+        Ok(Some(Particle { min, max, group }))
+    }
+
+    /// Returns the index of the local element declaration, or of the referenced
+    /// global declaration.
+    fn element_particle(&mut self, e: &'d Element, context: &Context) -> Result<usize> {
+        if let Some(name) = attribute(e, "ref")? {
+            return self.global_element(&context.resolve(name.trim())?);
+        }
+        let index = self.declare(context.local_name(e, context.qualified_elements)?);
+        self.element_type(index, e, context)?;
+        Ok(index)
+    }
+
+    /// Expands the named model group.
+    fn group(&mut self, name: &Name) -> Result<Particle> {
+        self.enter(Kind::Group, name)?;
+        let (e, context) = self.global(Kind::Group, name)?;
+        let context = context.nested(e)?;
+        let particle = match children(e).next() {
+            Some((kind @ (b"sequence" | b"choice" | b"all"), child)) => {
+                self.particle(kind, child, &context)?
+            }
+            Some((kind, _)) => return Err(unsupported(kind)),
+            None => None,
+        };
+        self.expanding.pop();
+        Ok(particle.unwrap_or_else(|| Particle::once(Group::Sequence(Vec::new()))))
+    }
+}
+
+/// Parses a wildcard: `<xs:any>` or `<xs:anyAttribute>`.
+fn wildcard(e: &Element, context: &Context) -> Result<Wildcard> {
+    let namespaces = match attribute(e, "namespace")?.as_deref().map(str::trim) {
+        None | Some("##any") => Namespaces::Any,
+        Some("##other") => Namespaces::Not(context.target.clone()),
+        Some(list) => Namespaces::List(
+            list.split_whitespace()
+                .map(|ns| match ns {
+                    "##local" => None,
+                    "##targetNamespace" => context.target.clone(),
+                    ns => Some(ns.to_string()),
+                })
+                .collect(),
+        ),
+    };
+    let process = match attribute(e, "processContents")?.as_deref().map(str::trim) {
+        None | Some("strict") => Process::Strict,
+        Some("lax") => Process::Lax,
+        Some("skip") => Process::Skip,
+        Some(value) => return Err(invalid(e, "processContents", value)),
+    };
+    Ok(Wildcard {
+        namespaces,
+        process,
+    })
+}
+
+/// Parses facets of a restriction of a simple type.
+fn facets(e: &Element) -> Result<Facets> {
+    let mut facets = Facets::default();
+    for (kind, child) in children(e) {
+        let value = || required(child, "value");
+        let length = || -> Result<Option<usize>> {
+            let value = value()?;
+            match value.trim().parse() {
+                Ok(length) => Ok(Some(length)),
+                Err(_) => Err(invalid(child, "value", &value)),
+            }
+        };
+        match kind {
+            // Base type and attributes of a complex type
+            b"simpleType" | b"attribute" | b"attributeGroup" | b"anyAttribute" => {}
+            b"enumeration" => facets.enumeration.push(value()?),
+            b"pattern" => {
+                let pattern = value()?;
+                match Pattern::new(&pattern) {
+                    Ok(compiled) => facets.patterns.push(compiled),
+                    Err(reason) => return Err(SchemaError::InvalidPattern { pattern, reason }),
+                }
+            }
+            b"whiteSpace" => {
+                let value = value()?;
+                match WhiteSpace::parse(value.trim()) {
+                    Some(white_space) => facets.white_space = Some(white_space),
+                    None => return Err(invalid(child, "value", &value)),
+                }
+            }
+            b"length" => facets.length = length()?,
+            b"minLength" => facets.min_length = length()?,
+            b"maxLength" => facets.max_length = length()?,
+            b"totalDigits" => facets.total_digits = length()?,
+            b"fractionDigits" => facets.fraction_digits = length()?,
+            b"minInclusive" => facets.min_inclusive = Some(value()?.trim().to_string()),
+            b"maxInclusive" => facets.max_inclusive = Some(value()?.trim().to_string()),
+            b"minExclusive" => facets.min_exclusive = Some(value()?.trim().to_string()),
+            b"maxExclusive" => facets.max_exclusive = Some(value()?.trim().to_string()),
+            _ => return Err(unsupported(kind)),
+        }
+    }
+    Ok(facets)
+}
@@ -0,0 +1,1103 @@
+//! Built-in datatypes of XML Schema and checks of values of simple types
+//! against their facets.
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
+use crate::utils::{is_whitespace, is_xml11_name_char, is_xml_name};
+use crate::xsd::pattern::Pattern;
+use crate::xsd::schema::{Schema, Type, TypeId};
+use crate::xsd::ValueError;
+
+/// Normalization of whitespaces in values, defined by the [`whiteSpace`] facet.
+///
+/// [`whiteSpace`]: https://www.w3.org/TR/xmlschema-2/#rf-whiteSpace
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum WhiteSpace {
+    /// The value is not changed
+    Preserve,
+    /// Tabs, line feeds and carriage returns are replaced by spaces
+    Replace,
+    /// After replacement leading and trailing spaces are removed and sequences
+    /// of spaces are replaced by a single space
+    Collapse,
+}
+
+impl WhiteSpace {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "preserve" => Some(Self::Preserve),
+            "replace" => Some(Self::Replace),
+            "collapse" => Some(Self::Collapse),
+            _ => None,
+        }
+    }
+
+    pub fn apply(self, value: &str) -> Cow<'_, str> {
+        match self {
+            Self::Preserve => Cow::Borrowed(value),
+            Self::Replace if !value.bytes().any(|b| b != b' ' && is_whitespace(b)) => {
+                Cow::Borrowed(value)
+            }
+            Self::Replace => Cow::Owned(value.replace(['\t', '\n', '\r'], " ")),
+            Self::Collapse => {
+                let words = value.split(|c: char| c.is_ascii() && is_whitespace(c as u8));
+                let mut result = String::with_capacity(value.len());
+                for word in words.filter(|w| !w.is_empty()) {
+                    if !result.is_empty() {
+                        result.push(' ');
+                    }
+                    result.push_str(word);
+                }
+                if result == value {
+                    Cow::Borrowed(value)
+                } else {
+                    Cow::Owned(result)
+                }
+            }
+        }
+    }
+}
+
+/// How values of a datatype are compared by the range facets and enumerations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Order {
+    /// Values are compared as strings and range facets are not applicable
+    None,
+    /// Values are decimal numbers of arbitrary precision
+    Decimal,
+    /// Values are floating point numbers
+    Float,
+    /// Dates and times of the datatype, which are compared as points of time
+    Time(Builtin),
+    /// Durations, which are compared by the points of time they give when added
+    /// to the same dates
+    Duration,
+}
+
+macro_rules! builtins {
+    ($($(#[$meta:meta])* $variant:ident => $name:literal,)*) => {
+        /// A [built-in datatype] of XML Schema.
+        ///
+        /// [built-in datatype]: https://www.w3.org/TR/xmlschema-2/#built-in-datatypes
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub(super) enum Builtin {
+            $($(#[$meta])* $variant,)*
+        }
+
+        impl Builtin {
+            /// Returns the datatype with the local name `name` in the XML Schema namespace.
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $($name => Some(Self::$variant),)*
+                    _ => None,
+                }
+            }
+
+            /// Returns the local name of the datatype.
+            pub const fn name(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $name,)*
+                }
+            }
+        }
+    };
+}
+
+builtins! {
+    /// The complex ur-type: any attributes and any content
+    AnyType => "anyType",
+    AnySimpleType => "anySimpleType",
+    String => "string",
+    NormalizedString => "normalizedString",
+    Token => "token",
+    Language => "language",
+    Name => "Name",
+    NCName => "NCName",
+    NmToken => "NMTOKEN",
+    NmTokens => "NMTOKENS",
+    Id => "ID",
+    IdRef => "IDREF",
+    IdRefs => "IDREFS",
+    Entity => "ENTITY",
+    Entities => "ENTITIES",
+    QName => "QName",
+    Notation => "NOTATION",
+    AnyUri => "anyURI",
+    Boolean => "boolean",
+    Decimal => "decimal",
+    Integer => "integer",
+    NonPositiveInteger => "nonPositiveInteger",
+    NegativeInteger => "negativeInteger",
+    NonNegativeInteger => "nonNegativeInteger",
+    PositiveInteger => "positiveInteger",
+    Long => "long",
+    Int => "int",
+    Short => "short",
+    Byte => "byte",
+    UnsignedLong => "unsignedLong",
+    UnsignedInt => "unsignedInt",
+    UnsignedShort => "unsignedShort",
+    UnsignedByte => "unsignedByte",
+    Float => "float",
+    Double => "double",
+    Duration => "duration",
+    DateTime => "dateTime",
+    Date => "date",
+    Time => "time",
+    GYear => "gYear",
+    GYearMonth => "gYearMonth",
+    GMonth => "gMonth",
+    GMonthDay => "gMonthDay",
+    GDay => "gDay",
+    HexBinary => "hexBinary",
+    Base64Binary => "base64Binary",
+}
+
+impl Builtin {
+    const fn white_space(self) -> WhiteSpace {
+        match self {
+            Self::AnySimpleType | Self::AnyType | Self::String => WhiteSpace::Preserve,
+            Self::NormalizedString => WhiteSpace::Replace,
+            _ => WhiteSpace::Collapse,
+        }
+    }
+
+    const fn order(self) -> Order {
+        match self {
+            Self::Decimal
+            | Self::Integer
+            | Self::NonPositiveInteger
+            | Self::NegativeInteger
+            | Self::NonNegativeInteger
+            | Self::PositiveInteger
+            | Self::Long
+            | Self::Int
+            | Self::Short
+            | Self::Byte
+            | Self::UnsignedLong
+            | Self::UnsignedInt
+            | Self::UnsignedShort
+            | Self::UnsignedByte => Order::Decimal,
+            Self::Float | Self::Double => Order::Float,
+            Self::Duration => Order::Duration,
+            Self::DateTime
+            | Self::Date
+            | Self::Time
+            | Self::GYear
+            | Self::GYearMonth
+            | Self::GMonth
+            | Self::GMonthDay
+            | Self::GDay => Order::Time(self),
+            _ => Order::None,
+        }
+    }
+
+    /// Returns the length of the value as measured by the length facets: number
+    /// of octets for binary types, number of items for list types and number
+    /// of characters for other types.
+    fn length(self, value: &str) -> usize {
+        match self {
+            Self::HexBinary => value.len() / 2,
+            Self::Base64Binary => base64_len(value).unwrap_or(0),
+            Self::NmTokens | Self::IdRefs | Self::Entities => value.split(' ').count(),
+            _ => value.chars().count(),
+        }
+    }
+
+    /// Checks that the value with already normalized whitespaces belongs to
+    /// the lexical space of the datatype.
+    fn check(self, value: &str) -> bool {
+        let tokens = |check: fn(&str) -> bool| !value.is_empty() && value.split(' ').all(check);
+        match self {
+            Self::AnyType
+            | Self::AnySimpleType
+            | Self::String
+            | Self::NormalizedString
+            | Self::Token
+            | Self::AnyUri => true,
+            Self::Language => is_language(value),
+            Self::Name => is_xml_name(value),
+            Self::NCName | Self::Id | Self::IdRef | Self::Entity => is_ncname(value),
+            Self::NmToken => is_nmtoken(value),
+            Self::NmTokens => tokens(is_nmtoken),
+            Self::IdRefs | Self::Entities => tokens(is_ncname),
+            Self::QName | Self::Notation => match value.split_once(':') {
+                Some((prefix, local)) => is_ncname(prefix) && is_ncname(local),
+                None => is_ncname(value),
+            },
+            Self::Boolean => matches!(value, "true" | "false" | "1" | "0"),
+            Self::Decimal => parse_decimal(value).is_some(),
+            Self::Integer => is_integer(value),
+            Self::NonPositiveInteger => is_integer(value) && !is_positive(value),
+            Self::NegativeInteger => is_integer(value) && is_negative(value),
+            Self::NonNegativeInteger => is_integer(value) && !is_negative(value),
+            Self::PositiveInteger => is_integer(value) && is_positive(value),
+            Self::Long => in_range(value, i64::MIN.into(), i64::MAX.into()),
+            Self::Int => in_range(value, i32::MIN.into(), i32::MAX.into()),
+            Self::Short => in_range(value, i16::MIN.into(), i16::MAX.into()),
+            Self::Byte => in_range(value, i8::MIN.into(), i8::MAX.into()),
+            Self::UnsignedLong => in_range(value, 0, u64::MAX.into()),
+            Self::UnsignedInt => in_range(value, 0, u32::MAX.into()),
+            Self::UnsignedShort => in_range(value, 0, u16::MAX.into()),
+            Self::UnsignedByte => in_range(value, 0, u8::MAX.into()),
+            Self::Float | Self::Double => parse_float(value).is_some(),
+            Self::Duration => is_duration(value),
+            Self::DateTime => date(value)
+                .and_then(|rest| rest.strip_prefix('T'))
+                .and_then(time)
+                .is_some_and(is_timezone),
+            Self::Date => date(value).is_some_and(is_timezone),
+            Self::Time => time(value).is_some_and(is_timezone),
+            Self::GYear => year(value).is_some_and(is_timezone),
+            Self::GYearMonth => year(value)
+                .and_then(|rest| rest.strip_prefix('-'))
+                .and_then(month)
+                .is_some_and(|(_, rest)| is_timezone(rest)),
+            Self::GMonth => value
+                .strip_prefix("--")
+                .and_then(month)
+                .is_some_and(|(_, rest)| is_timezone(rest)),
+            Self::GMonthDay => value
+                .strip_prefix("--")
+                .and_then(month)
+                .and_then(|(month, rest)| Some((month, rest.strip_prefix('-')?)))
+                // February 29 is allowed, because the year is not known
+                .and_then(|(month, rest)| day(rest, days_in_month(2000, month)))
+                .is_some_and(is_timezone),
+            Self::GDay => value
+                .strip_prefix("---")
+                .and_then(|rest| day(rest, 31))
+                .is_some_and(is_timezone),
+            Self::HexBinary => value.len() % 2 == 0 && value.bytes().all(|b| b.is_ascii_hexdigit()),
+            Self::Base64Binary => base64_len(value).is_some(),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+fn is_ncname(value: &str) -> bool {
+    is_xml_name(value) && !value.contains(':')
+}
+
+fn is_nmtoken(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(is_xml11_name_char)
+}
+
+/// Checks that `value` matches `[a-zA-Z]{1,8}(-[a-zA-Z0-9]{1,8})*`.
+fn is_language(value: &str) -> bool {
+    value.split('-').enumerate().all(|(i, part)| {
+        (1..=8).contains(&part.len())
+            && part
+                .bytes()
+                .all(|b| b.is_ascii_alphabetic() || (i > 0 && b.is_ascii_digit()))
+    })
+}
+
+/// A decimal number split into parts without insignificant zeroes.
+#[derive(Clone, Copy, Debug)]
+struct Decimal<'a> {
+    negative: bool,
+    /// Digits of the integer part without leading zeroes
+    int: &'a str,
+    /// Digits of the fractional part without trailing zeroes
+    frac: &'a str,
+}
+
+impl Decimal<'_> {
+    const fn is_zero(&self) -> bool {
+        self.int.is_empty() && self.frac.is_empty()
+    }
+
+    fn total_digits(&self) -> usize {
+        (self.int.len() + self.frac.len()).max(1)
+    }
+
+    fn cmp(&self, other: &Self) -> Ordering {
+        let sign = |d: &Self| match (d.is_zero(), d.negative) {
+            (true, _) => 0,
+            (false, true) => -1,
+            (false, false) => 1,
+        };
+        match sign(self).cmp(&sign(other)) {
+            Ordering::Equal => {}
+            ordering => return ordering,
+        }
+        let magnitude = self
+            .int
+            .len()
+            .cmp(&other.int.len())
+            .then_with(|| self.int.cmp(other.int))
+            .then_with(|| self.frac.cmp(other.frac));
+        if self.negative {
+            magnitude.reverse()
+        } else {
+            magnitude
+        }
+    }
+}
+
+/// Parses `[+-]?(\d+(\.\d*)?|\.\d+)`.
+fn parse_decimal(value: &str) -> Option<Decimal<'_>> {
+    let (negative, digits) = match value.as_bytes().first() {
+        Some(b'-') => (true, &value[1..]),
+        Some(b'+') => (false, &value[1..]),
+        _ => (false, value),
+    };
+    let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+    let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if int.len() + frac.len() == 0 || !all_digits(int) || !all_digits(frac) {
+        return None;
+    }
+    Some(Decimal {
+        negative,
+        int: int.trim_start_matches('0'),
+        frac: frac.trim_end_matches('0'),
+    })
+}
+
+fn is_integer(value: &str) -> bool {
+    !value.contains('.') && parse_decimal(value).is_some()
+}
+
+fn is_negative(value: &str) -> bool {
+    parse_decimal(value).is_some_and(|d| d.negative && !d.is_zero())
+}
+
+fn is_positive(value: &str) -> bool {
+    parse_decimal(value).is_some_and(|d| !d.negative && !d.is_zero())
+}
+
+fn in_range(value: &str, min: i128, max: i128) -> bool {
+    is_integer(value)
+        && value
+            .parse::<i128>()
+            .is_ok_and(|v| (min..=max).contains(&v))
+}
+
+/// Parses a `float` or a `double` value: a decimal number with an optional
+/// exponent, or one of the special values `INF`, `+INF`, `-INF` and `NaN`.
+fn parse_float(value: &str) -> Option<f64> {
+    match value {
+        "INF" | "+INF" => return Some(f64::INFINITY),
+        "-INF" => return Some(f64::NEG_INFINITY),
+        "NaN" => return Some(f64::NAN),
+        _ => {}
+    }
+    let mantissa = match value.find(['e', 'E']) {
+        Some(i) => {
+            let exponent = &value[i + 1..];
+            let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+            if exponent.is_empty() || !exponent.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            &value[..i]
+        }
+        None => value,
+    };
+    parse_decimal(mantissa)?;
+    value.parse().ok()
+}
+
+/// Returns the number of octets of the base64-encoded value, or `None` if
+/// the value is not valid.
+fn base64_len(value: &str) -> Option<usize> {
+    let bytes: Vec<u8> = value.bytes().filter(|b| *b != b' ').collect();
+    if bytes.len() % 4 != 0 {
+        return None;
+    }
+    let padding = bytes.iter().rev().take_while(|b| **b == b'=').count();
+    if padding > 2 {
+        return None;
+    }
+    let valid = bytes[..bytes.len() - padding]
+        .iter()
+        .all(|b| b.is_ascii_alphanumeric() || *b == b'+' || *b == b'/');
+    if valid {
+        Some(bytes.len() / 4 * 3 - padding)
+    } else {
+        None
+    }
+}
+
+/// Parses exactly two digits and returns their value and the rest of the input.
+fn two_digits(value: &str) -> Option<(u32, &str)> {
+    let digits = value.get(..2)?;
+    if digits.bytes().all(|b| b.is_ascii_digit()) {
+        Some((digits.parse().ok()?, &value[2..]))
+    } else {
+        None
+    }
+}
+
+/// Parses `-?\d{4,}` without leading zeroes in years after 9999.
+fn year(value: &str) -> Option<&str> {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    let len = digits.bytes().take_while(u8::is_ascii_digit).count();
+    if len < 4 || (len > 4 && digits.starts_with('0')) {
+        return None;
+    }
+    Some(&digits[len..])
+}
+
+fn month(value: &str) -> Option<(u32, &str)> {
+    two_digits(value).filter(|(month, _)| (1..=12).contains(month))
+}
+
+fn day(value: &str, max: u32) -> Option<&str> {
+    two_digits(value)
+        .filter(|(day, _)| (1..=max).contains(day))
+        .map(|(_, rest)| rest)
+}
+
+const fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Parses `YYYY-MM-DD` and returns the rest of the input.
+fn date(value: &str) -> Option<&str> {
+    let rest = year(value)?;
+    let year: i64 = value[..value.len() - rest.len()].parse().ok()?;
+    let (month, rest) = month(rest.strip_prefix('-')?)?;
+    day(rest.strip_prefix('-')?, days_in_month(year, month))
+}
+
+/// Parses `hh:mm:ss(.s+)?` and returns the rest of the input.
+fn time(value: &str) -> Option<&str> {
+    let (hour, rest) = two_digits(value)?;
+    let (minute, rest) = two_digits(rest.strip_prefix(':')?)?;
+    let (second, rest) = two_digits(rest.strip_prefix(':')?)?;
+    let (fraction, rest) = match rest.strip_prefix('.') {
+        Some(rest) => {
+            let len = rest.bytes().take_while(u8::is_ascii_digit).count();
+            if len == 0 {
+                return None;
+            }
+            (&rest[..len], &rest[len..])
+        }
+        None => ("", rest),
+    };
+    // 24:00:00 is the end of a day
+    let end_of_day =
+        hour == 24 && minute == 0 && second == 0 && fraction.bytes().all(|b| b == b'0');
+    if (hour < 24 && minute < 60 && second < 60) || end_of_day {
+        Some(rest)
+    } else {
+        None
+    }
+}
+
+/// Checks that `value` is empty or is a timezone: `Z` or `[+-]hh:mm`.
+fn is_timezone(value: &str) -> bool {
+    match value {
+        "" | "Z" => true,
+        _ => {
+            let rest = match value.strip_prefix(['+', '-']) {
+                Some(rest) => rest,
+                None => return false,
+            };
+            let parsed = two_digits(rest).and_then(|(hour, rest)| {
+                let (minute, rest) = two_digits(rest.strip_prefix(':')?)?;
+                Some((hour, minute, rest))
+            });
+            matches!(parsed, Some((hour, minute, "")) if minute < 60 && (hour < 14 || (hour == 14 && minute == 0)))
+        }
+    }
+}
+
+/// Checks that `value` matches `-?P(nY)?(nM)?(nD)?(T(nH)?(nM)?(n(.n)?S)?)?` with
+/// at least one component.
+fn is_duration(value: &str) -> bool {
+    let rest = value.strip_prefix('-').unwrap_or(value);
+    let rest = match rest.strip_prefix('P') {
+        Some(rest) => rest,
+        None => return false,
+    };
+    let (date, time) = match rest.split_once('T') {
+        Some((_, "")) => return false,
+        Some((date, time)) => (date, Some(time)),
+        None => (rest, None),
+    };
+    /// Parses components of the duration and returns `None` if the components
+    /// are invalid, or the number of them
+    fn components(mut value: &str, designators: &[u8], fraction: u8) -> Option<usize> {
+        let mut count = 0;
+        let mut allowed = designators;
+        while !value.is_empty() {
+            let len = value
+                .bytes()
+                .take_while(|b| b.is_ascii_digit() || (*b == b'.' && fraction != 0))
+                .count();
+            let number = &value[..len];
+            let designator = *value.as_bytes().get(len)?;
+            let position = allowed.iter().position(|d| *d == designator)?;
+            if number.is_empty()
+                || number.ends_with('.')
+                || (number.contains('.') && designator != fraction)
+                || number.matches('.').count() > 1
+            {
+                return None;
+            }
+            allowed = &allowed[position + 1..];
+            value = &value[len + 1..];
+            count += 1;
+        }
+        Some(count)
+    }
+    match (
+        components(date, b"YMD", 0),
+        time.map(|t| components(t, b"HMS", b'S')),
+    ) {
+        (Some(date), None) => date > 0,
+        (Some(_), Some(Some(time))) => time > 0,
+        _ => false,
+    }
+}
+
+/// Nanoseconds in a second. Fractions of seconds are compared up to nanoseconds.
+const NANOS: i128 = 1_000_000_000;
+
+/// Parses the fractional part of seconds into nanoseconds.
+fn nanos(fraction: &str) -> i128 {
+    let digits = fraction.get(..9).unwrap_or(fraction);
+    format!("{:0<9}", digits).parse().unwrap_or(0)
+}
+
+/// Returns the number of days from 1970-01-01 to the date in the proleptic
+/// Gregorian calendar.
+fn days_from_civil(year: i128, month: u32, day: u32) -> i128 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * ((i128::from(month) + 9) % 12) + 2) / 5 + i128::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// A value of a date or time datatype as a point of time.
+#[derive(Clone, Copy, Debug)]
+struct Instant {
+    /// Nanoseconds since 1970-01-01T00:00:00 in UTC, or in local time if
+    /// the value has no timezone
+    nanos: i128,
+    timezone: bool,
+}
+
+impl Instant {
+    /// Parses a valid value of the date or time datatype. Missing components
+    /// are taken from the date 2000-01-01.
+    fn parse(builtin: Builtin, value: &str) -> Option<Self> {
+        // Strip the timezone: `Z` or `[+-]hh:mm`
+        let bytes = value.as_bytes();
+        let (value, offset) = if let Some(value) = value.strip_suffix('Z') {
+            (value, Some(0))
+        } else if bytes.len() > 6
+            && matches!(bytes[bytes.len() - 6], b'+' | b'-')
+            && bytes[bytes.len() - 3] == b':'
+        {
+            let (value, timezone) = value.split_at(value.len() - 6);
+            let hours: i128 = timezone[1..3].parse().ok()?;
+            let minutes: i128 = timezone[4..].parse().ok()?;
+            let offset = hours * 60 + minutes;
+            (
+                value,
+                Some(if timezone.starts_with('-') {
+                    -offset
+                } else {
+                    offset
+                }),
+            )
+        } else {
+            (value, None)
+        };
+        let (date, time) = match builtin {
+            Builtin::DateTime => value.split_once('T')?,
+            Builtin::Time => ("", value),
+            _ => (value, ""),
+        };
+        let number = |s: &str| s.parse::<u32>().ok();
+        let (year, month, day) = match builtin {
+            Builtin::DateTime | Builtin::Date => {
+                let (rest, day) = date.rsplit_once('-')?;
+                let (year, month) = rest.rsplit_once('-')?;
+                (year.parse().ok()?, number(month)?, number(day)?)
+            }
+            Builtin::GYear => (date.parse().ok()?, 1, 1),
+            Builtin::GYearMonth => {
+                let (year, month) = date.rsplit_once('-')?;
+                (year.parse().ok()?, number(month)?, 1)
+            }
+            Builtin::GMonth => (2000, number(date.strip_prefix("--")?)?, 1),
+            Builtin::GMonthDay => {
+                let (month, day) = date.strip_prefix("--")?.split_once('-')?;
+                (2000, number(month)?, number(day)?)
+            }
+            Builtin::GDay => (2000, 1, number(date.strip_prefix("---")?)?),
+            _ => (2000, 1, 1),
+        };
+        let mut nanos = days_from_civil(year, month, day) * 86_400 * NANOS;
+        if !time.is_empty() {
+            let mut parts = time.splitn(3, ':');
+            let hour = i128::from(number(parts.next()?)?);
+            let minute = i128::from(number(parts.next()?)?);
+            let second = parts.next()?;
+            let (second, fraction) = second.split_once('.').unwrap_or((second, ""));
+            let second = i128::from(number(second)?);
+            nanos += ((hour * 60 + minute) * 60 + second) * NANOS + self::nanos(fraction);
+        }
+        Some(Self {
+            nanos: nanos - offset.unwrap_or(0) * 60 * NANOS,
+            timezone: offset.is_some(),
+        })
+    }
+
+    /// Compares points of time. A value without timezone can be in any timezone
+    /// from `-14:00` to `+14:00`, so it is ordered with a value with timezone
+    /// only if they differ by more than 14 hours.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.timezone == other.timezone {
+            return Some(self.nanos.cmp(&other.nanos));
+        }
+        let shift = 14 * 60 * 60 * NANOS;
+        let earliest = (self.nanos - shift).cmp(&other.nanos);
+        let latest = (self.nanos + shift).cmp(&other.nanos);
+        if earliest == latest {
+            Some(earliest)
+        } else {
+            None
+        }
+    }
+}
+
+/// A value of `xs:duration`.
+#[derive(Clone, Copy, Debug)]
+struct Duration {
+    months: i128,
+    /// Days, hours, minutes and seconds in nanoseconds
+    nanos: i128,
+}
+
+impl Duration {
+    /// Parses a valid value of `xs:duration`.
+    fn parse(value: &str) -> Option<Self> {
+        let (negative, rest) = match value.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, value),
+        };
+        let mut rest = rest.strip_prefix('P')?;
+        let mut duration = Self {
+            months: 0,
+            nanos: 0,
+        };
+        let mut time = false;
+        while !rest.is_empty() {
+            if let Some(r) = rest.strip_prefix('T') {
+                time = true;
+                rest = r;
+                continue;
+            }
+            let len = rest
+                .bytes()
+                .take_while(|b| b.is_ascii_digit() || *b == b'.')
+                .count();
+            let (number, fraction) = rest[..len].split_once('.').unwrap_or((&rest[..len], ""));
+            let number: i128 = number.parse().ok()?;
+            match (time, *rest.as_bytes().get(len)?) {
+                (false, b'Y') => duration.months += number * 12,
+                (false, b'M') => duration.months += number,
+                (false, b'D') => duration.nanos += number * 86_400 * NANOS,
+                (true, b'H') => duration.nanos += number * 3_600 * NANOS,
+                (true, b'M') => duration.nanos += number * 60 * NANOS,
+                (true, b'S') => duration.nanos += number * NANOS + nanos(fraction),
+                _ => return None,
+            }
+            rest = &rest[len + 1..];
+        }
+        if negative {
+            duration.months = -duration.months;
+            duration.nanos = -duration.nanos;
+        }
+        Some(duration)
+    }
+
+    /// Compares durations by adding them to the four dates defined by the
+    /// [specification]. Durations are not ordered if the results differ, for
+    /// example, `P1M` and `P30D`.
+    ///
+    /// [specification]: https://www.w3.org/TR/xmlschema-2/#duration-order
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let add = |duration: &Self, year: i128, month: i128| {
+            let months = year * 12 + month - 1 + duration.months;
+            let days = days_from_civil(months.div_euclid(12), months.rem_euclid(12) as u32 + 1, 1);
+            days * 86_400 * NANOS + duration.nanos
+        };
+        let mut orders = [(1696, 9), (1697, 2), (1903, 3), (1903, 7)]
+            .iter()
+            .map(|&(year, month)| add(self, year, month).cmp(&add(other, year, month)));
+        let first = orders.next()?;
+        orders.all(|order| order == first).then_some(first)
+    }
+}
+
+/// Compares two values of a datatype. Returns `None` if values are not ordered
+/// or cannot be parsed.
+fn compare(order: Order, a: &str, b: &str) -> Option<Ordering> {
+    match order {
+        Order::None => None,
+        Order::Decimal => Some(parse_decimal(a)?.cmp(&parse_decimal(b)?)),
+        Order::Float => parse_float(a)?.partial_cmp(&parse_float(b)?),
+        Order::Time(builtin) => {
+            Instant::parse(builtin, a)?.partial_cmp(&Instant::parse(builtin, b)?)
+        }
+        Order::Duration => Duration::parse(a)?.partial_cmp(&Duration::parse(b)?),
+    }
+}
+
+fn equal(order: Order, a: &str, b: &str) -> bool {
+    match order {
+        Order::None => a == b,
+        _ => compare(order, a, b) == Some(Ordering::Equal),
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// [Constraining facets] of a simple type derived by restriction.
+///
+/// [Constraining facets]: https://www.w3.org/TR/xmlschema-2/#rf-facets
+#[derive(Clone, Debug, Default)]
+pub(super) struct Facets {
+    pub white_space: Option<WhiteSpace>,
+    pub length: Option<usize>,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    /// Patterns of one derivation step, the value should match any of them
+    pub patterns: Vec<Pattern>,
+    /// Allowed values as written in the schema
+    pub enumeration: Vec<String>,
+    pub min_inclusive: Option<String>,
+    pub max_inclusive: Option<String>,
+    pub min_exclusive: Option<String>,
+    pub max_exclusive: Option<String>,
+    pub total_digits: Option<usize>,
+    pub fraction_digits: Option<usize>,
+}
+
+/// A simple type defined in the schema.
+#[derive(Clone, Debug)]
+pub(super) enum SimpleType {
+    Restriction { base: TypeId, facets: Box<Facets> },
+    List(TypeId),
+    Union(Vec<TypeId>),
+}
+
+/// A [variety] of a simple type.
+///
+/// [variety]: https://www.w3.org/TR/xmlschema-2/#variety
+#[derive(Clone, Copy, Debug)]
+enum Variety {
+    Atomic(Builtin),
+    List,
+    Union,
+}
+
+impl Facets {
+    /// Checks the value normalized according to `white_space`.
+    fn check(
+        &self,
+        value: &str,
+        variety: Variety,
+        white_space: WhiteSpace,
+    ) -> Result<(), ValueError> {
+        let length = || match variety {
+            Variety::Atomic(builtin) => builtin.length(value),
+            Variety::List => value.split(' ').filter(|item| !item.is_empty()).count(),
+            Variety::Union => value.chars().count(),
+        };
+        if let Some(expected) = self.length {
+            let found = length();
+            if found != expected {
+                return Err(ValueError::Length { expected, found });
+            }
+        }
+        if let Some(min) = self.min_length {
+            let found = length();
+            if found < min {
+                return Err(ValueError::MinLength { min, found });
+            }
+        }
+        if let Some(max) = self.max_length {
+            let found = length();
+            if found > max {
+                return Err(ValueError::MaxLength { max, found });
+            }
+        }
+        if !self.patterns.is_empty() && !self.patterns.iter().any(|p| p.is_match(value)) {
+            let patterns: Vec<_> = self.patterns.iter().map(|p| p.source.as_str()).collect();
+            return Err(ValueError::Pattern(patterns.join("|")));
+        }
+        let order = match variety {
+            Variety::Atomic(builtin) => builtin.order(),
+            Variety::List | Variety::Union => Order::None,
+        };
+        if !self.enumeration.is_empty()
+            && !self
+                .enumeration
+                .iter()
+                .any(|e| equal(order, &white_space.apply(e), value))
+        {
+            return Err(ValueError::Enumeration(self.enumeration.clone()));
+        }
+        let cmp = |bound: &str| compare(order, value, bound);
+        if let Some(min) = &self.min_inclusive {
+            if cmp(min) == Some(Ordering::Less) {
+                return Err(ValueError::MinInclusive(min.clone()));
+            }
+        }
+        if let Some(min) = &self.min_exclusive {
+            if matches!(cmp(min), Some(Ordering::Less | Ordering::Equal)) {
+                return Err(ValueError::MinExclusive(min.clone()));
+            }
+        }
+        if let Some(max) = &self.max_inclusive {
+            if cmp(max) == Some(Ordering::Greater) {
+                return Err(ValueError::MaxInclusive(max.clone()));
+            }
+        }
+        if let Some(max) = &self.max_exclusive {
+            if matches!(cmp(max), Some(Ordering::Greater | Ordering::Equal)) {
+                return Err(ValueError::MaxExclusive(max.clone()));
+            }
+        }
+        if let Some(decimal) = parse_decimal(value) {
+            if let Some(max) = self.total_digits {
+                if decimal.total_digits() > max {
+                    return Err(ValueError::TotalDigits(max));
+                }
+            }
+            if let Some(max) = self.fraction_digits {
+                if decimal.frac.len() > max {
+                    return Err(ValueError::FractionDigits(max));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks of values of simple types
+impl Schema {
+    /// Returns the whitespace normalization of values of the simple type.
+    pub(super) fn white_space(&self, ty: TypeId) -> WhiteSpace {
+        match ty {
+            TypeId::Builtin(builtin) => builtin.white_space(),
+            TypeId::Defined(index) => match &self.types[index] {
+                Type::Simple(SimpleType::Restriction { base, facets }) => facets
+                    .white_space
+                    .unwrap_or_else(|| self.white_space(*base)),
+                Type::Simple(SimpleType::List(_)) => WhiteSpace::Collapse,
+                // Members of a union normalize the value themselves
+                Type::Simple(SimpleType::Union(_)) | Type::Complex(_) => WhiteSpace::Preserve,
+            },
+        }
+    }
+
+    fn variety(&self, ty: TypeId) -> Variety {
+        match ty {
+            TypeId::Builtin(builtin) => Variety::Atomic(builtin),
+            TypeId::Defined(index) => match &self.types[index] {
+                Type::Simple(SimpleType::Restriction { base, .. }) => self.variety(*base),
+                Type::Simple(SimpleType::List(_)) => Variety::List,
+                Type::Simple(SimpleType::Union(_)) => Variety::Union,
+                Type::Complex(_) => Variety::Atomic(Builtin::AnySimpleType),
+            },
+        }
+    }
+
+    /// Checks that the value belongs to the simple type `ty`.
+    pub(super) fn check_value(&self, ty: TypeId, value: &str) -> Result<(), ValueError> {
+        let value = self.white_space(ty).apply(value);
+        self.check_normalized(ty, &value)
+    }
+
+    fn check_normalized(&self, ty: TypeId, value: &str) -> Result<(), ValueError> {
+        match ty {
+            TypeId::Builtin(builtin) if builtin.check(value) => Ok(()),
+            TypeId::Builtin(builtin) => Err(ValueError::Invalid(builtin.name())),
+            TypeId::Defined(index) => match &self.types[index] {
+                Type::Simple(SimpleType::Restriction { base, facets }) => {
+                    self.check_normalized(*base, value)?;
+                    facets.check(value, self.variety(*base), self.white_space(ty))
+                }
+                Type::Simple(SimpleType::List(item)) => value
+                    .split(' ')
+                    .filter(|item| !item.is_empty())
+                    .try_for_each(|v| self.check_value(*item, v)),
+                Type::Simple(SimpleType::Union(members)) => {
+                    if members.iter().any(|m| self.check_value(*m, value).is_ok()) {
+                        Ok(())
+                    } else {
+                        Err(ValueError::Union)
+                    }
+                }
+                Type::Complex(_) => Ok(()),
+            },
+        }
+    }
+
+    /// Checks that the values of range facets belong to the base types of
+    /// restrictions. Returns the name of the first invalid facet and its value.
+    pub(super) fn invalid_bound(&self) -> Option<(&'static str, &str)> {
+        self.types.iter().find_map(|ty| match ty {
+            Type::Simple(SimpleType::Restriction { base, facets }) => [
+                ("minInclusive", &facets.min_inclusive),
+                ("maxInclusive", &facets.max_inclusive),
+                ("minExclusive", &facets.min_exclusive),
+                ("maxExclusive", &facets.max_exclusive),
+            ]
+            .into_iter()
+            .find_map(|(facet, bound)| {
+                let bound = bound.as_deref()?;
+                self.check_value(*base, bound)
+                    .is_err()
+                    .then_some((facet, bound))
+            }),
+            _ => None,
+        })
+    }
+
+    /// Returns `true` if two values of the simple type `ty` are equal.
+    pub(super) fn values_equal(&self, ty: TypeId, a: &str, b: &str) -> bool {
+        let white_space = self.white_space(ty);
+        let order = match self.variety(ty) {
+            Variety::Atomic(builtin) => builtin.order(),
+            Variety::List | Variety::Union => Order::None,
+        };
+        equal(order, &white_space.apply(a), &white_space.apply(b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn white_space() {
+        assert_eq!(WhiteSpace::Preserve.apply(" a\tb "), " a\tb ");
+        assert_eq!(WhiteSpace::Replace.apply(" a\tb\r\n"), " a b  ");
+        assert_eq!(WhiteSpace::Collapse.apply(" a\t\tb\r\n"), "a b");
+    }
+
+    #[test]
+    fn decimals() {
+        let cmp = |a, b| compare(Order::Decimal, a, b);
+        assert_eq!(cmp("1.50", "+01.5"), Some(Ordering::Equal));
+        assert_eq!(cmp("-0", "0.0"), Some(Ordering::Equal));
+        assert_eq!(cmp("-2", "-10"), Some(Ordering::Greater));
+        assert_eq!(cmp(".45", "0.5"), Some(Ordering::Less));
+        assert_eq!(
+            cmp("123456789012345678901234567890", "9"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(cmp("1.", "x"), None);
+    }
+
+    #[test]
+    fn times() {
+        let cmp = |builtin, a, b| compare(Order::Time(builtin), a, b);
+        assert_eq!(
+            cmp(Builtin::Date, "-0044-03-15", "0001-01-01"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            cmp(
+                Builtin::DateTime,
+                "2024-01-01T24:00:00",
+                "2024-01-02T00:00:00"
+            ),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            cmp(Builtin::Time, "12:00:00+01:00", "11:00:00Z"),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            cmp(Builtin::Time, "12:00:00.25", "12:00:00.3"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(cmp(Builtin::Date, "2024-01-01", "2024-01-01Z"), None);
+        assert_eq!(
+            cmp(Builtin::GMonthDay, "--12-25", "--02-29"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            cmp(Builtin::GYearMonth, "10000-01", "9999-12"),
+            Some(Ordering::Greater)
+        );
+
+        let cmp = |a, b| compare(Order::Duration, a, b);
+        assert_eq!(cmp("P1Y", "P12M"), Some(Ordering::Equal));
+        assert_eq!(cmp("PT36H", "P1DT12H"), Some(Ordering::Equal));
+        assert_eq!(cmp("-P1D", "PT1S"), Some(Ordering::Less));
+        assert_eq!(cmp("P1Y", "P365D"), None);
+        assert_eq!(cmp("P1Y", "P367D"), Some(Ordering::Less));
+    }
+
+    #[test]
+    fn numbers() {
+        assert!(Builtin::Int.check("-2147483648"));
+        assert!(!Builtin::Int.check("2147483648"));
+        assert!(Builtin::UnsignedByte.check("+255"));
+        assert!(!Builtin::UnsignedByte.check("-1"));
+        assert!(Builtin::NonPositiveInteger.check("-0"));
+        assert!(!Builtin::PositiveInteger.check("0"));
+        assert!(!Builtin::Integer.check("1.0"));
+        assert!(Builtin::Decimal.check("-.5"));
+        assert!(!Builtin::Decimal.check("."));
+        assert!(Builtin::Double.check("-1.5E-3"));
+        assert!(Builtin::Float.check("-INF"));
+        assert!(!Builtin::Float.check("inf"));
+        assert!(!Builtin::Double.check("1e"));
+    }
+
+    #[test]
+    fn dates() {
+        assert!(Builtin::Date.check("2024-02-29"));
+        assert!(!Builtin::Date.check("2023-02-29"));
+        assert!(Builtin::DateTime.check("2024-01-31T24:00:00Z"));
+        assert!(Builtin::DateTime.check("-0044-03-15T12:30:00.5+01:00"));
+        assert!(!Builtin::DateTime.check("2024-01-31T12:60:00"));
+        assert!(!Builtin::DateTime.check("2024-01-31"));
+        assert!(Builtin::Time.check("23:59:59-14:00"));
+        assert!(!Builtin::Time.check("23:59:59+15:00"));
+        assert!(Builtin::GMonthDay.check("--02-29"));
+        assert!(Builtin::GYearMonth.check("2024-12"));
+        assert!(!Builtin::GYear.check("024"));
+        assert!(Builtin::Duration.check("P1Y2M3DT4H5M6.7S"));
+        assert!(Builtin::Duration.check("-PT1M"));
+        assert!(!Builtin::Duration.check("P"));
+        assert!(!Builtin::Duration.check("P1DT"));
+        assert!(!Builtin::Duration.check("P1M1Y"));
+        assert!(!Builtin::Duration.check("P1.5D"));
+    }
+
+    #[test]
+    fn others() {
+        assert!(Builtin::Boolean.check("1"));
+        assert!(!Builtin::Boolean.check("yes"));
+        assert!(Builtin::Language.check("en-US"));
+        assert!(!Builtin::Language.check("1en"));
+        assert!(Builtin::QName.check("xs:int"));
+        assert!(!Builtin::NCName.check("xs:int"));
+        assert!(Builtin::HexBinary.check("0aF1"));
+        assert_eq!(base64_len("YWJj ZA=="), Some(4));
+        assert_eq!(base64_len("YWJjZA="), None);
+    }
+}
@@ -0,0 +1,267 @@
+use std::future::poll_fn;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_io::{AsyncBufRead, AsyncRead};
+use pretty_assertions::assert_eq;
+use quick_xml::async_io::FuturesIo;
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event::*};
+use quick_xml::name::{Namespace, QName, ResolveResult::*};
+use quick_xml::reader::{NsReader, Reader};
+use quick_xml::utils::Bytes;
+use quick_xml::writer::Writer;
+
+// Import `small_buffers_tests!`
+#[macro_use]
+mod helpers;
+
+/// A reader that returns at most `capacity` bytes from each `poll_fill_buf` call
+struct Chunked<'a> {
+    data: &'a [u8],
+    capacity: usize,
+}
+
+impl<'a> AsyncRead for Chunked<'a> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let chunk = match Pin::new(&mut *this).poll_fill_buf(cx) {
+            Poll::Ready(Ok(chunk)) => chunk,
+            poll => return poll.map_ok(|_| 0),
+        };
+        let len = chunk.len().min(buf.len());
+        buf[..len].copy_from_slice(&chunk[..len]);
+        Pin::new(this).consume(len);
+        Poll::Ready(Ok(len))
+    }
+}
+
+impl<'a> AsyncBufRead for Chunked<'a> {
+    fn poll_fill_buf(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        let len = this.data.len().min(this.capacity);
+        Poll::Ready(Ok(&this.data[..len]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        this.data = &this.data[amt..];
+    }
+}
+
+/// Mimics `BufReader::with_capacity` for the `small_buffers_tests!` macro
+struct SmallBuffer;
+
+impl SmallBuffer {
+    fn with_capacity(capacity: usize, data: &[u8]) -> FuturesIo<Chunked<'_>> {
+        FuturesIo(Chunked { data, capacity })
+    }
+}
+
+small_buffers_tests!(
+    #[tokio::test]
+    read_event_into_async: super::SmallBuffer,
+    async, await
+);
+
+#[tokio::test]
+async fn test_sample() {
+    let src = include_str!("documents/sample_rss.xml");
+    let mut reader = Reader::from_reader(FuturesIo(src.as_bytes()));
+    let mut buf = Vec::new();
+    let mut count = 0;
+    // Expected number of iterations, to prevent infinity loops if refactoring breaks test
+    let mut reads = 0;
+    loop {
+        reads += 1;
+        assert!(
+            reads <= 10000,
+            "too many events, possible infinity loop: {reads}"
+        );
+        match reader.read_event_into_async(&mut buf).await {
+            Ok(Start(_)) => count += 1,
+            Ok(Decl(e)) => assert_eq!(e.version().unwrap(), b"1.0".as_ref()),
+            Ok(Eof) => break,
+            Ok(_) => (),
+            Err(e) => panic!("{} at {}", e, reader.error_position()),
+        }
+        buf.clear();
+    }
+    assert_eq!((count, reads), (1247, 5457));
+}
+
+#[tokio::test]
+async fn read_to_end() {
+    let mut r = Reader::from_reader(FuturesIo(b"<tag> <nested/> </tag>" as &[u8]));
+    //                                          ^0   ^5         ^16
+    r.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    assert_eq!(
+        r.read_event_into_async(&mut buf).await.unwrap(),
+        Start(BytesStart::new("tag"))
+    );
+    assert_eq!(
+        r.read_to_end_into_async(QName(b"tag"), &mut buf)
+            .await
+            .unwrap(),
+        5..16
+    );
+    assert_eq!(r.read_event_into_async(&mut buf).await.unwrap(), Eof);
+}
+
+#[tokio::test]
+async fn read_text() {
+    let mut r = Reader::from_reader(FuturesIo(b"<tag>text <b>bold</b></tag>" as &[u8]));
+
+    let mut buf = Vec::new();
+    assert_eq!(
+        r.read_event_into_async(&mut buf).await.unwrap(),
+        Start(BytesStart::new("tag"))
+    );
+    let mut text = Vec::new();
+    assert_eq!(
+        r.read_text_into_async(QName(b"tag"), &mut text)
+            .await
+            .unwrap(),
+        BytesText::from_escaped("text <b>bold</b>")
+    );
+    assert_eq!(r.read_event_into_async(&mut buf).await.unwrap(), Eof);
+}
+
+#[tokio::test]
+async fn ns_reader() {
+    let mut r = NsReader::from_reader(FuturesIo(
+        br#"<a:root xmlns:a="urn:a"><a:item/></a:root>"# as &[u8],
+    ));
+    let ns = Namespace(b"urn:a");
+
+    let mut buf = Vec::new();
+    assert_eq!(
+        r.read_resolved_event_into_async(&mut buf).await.unwrap(),
+        (
+            Bound(ns),
+            Start(BytesStart::from_content(r#"a:root xmlns:a="urn:a""#, 6))
+        )
+    );
+    assert_eq!(
+        r.read_resolved_event_into_async(&mut buf).await.unwrap(),
+        (Bound(ns), Empty(BytesStart::new("a:item")))
+    );
+    assert_eq!(
+        r.read_resolved_event_into_async(&mut buf).await.unwrap(),
+        (Bound(ns), End(BytesEnd::new("a:root")))
+    );
+}
+
+/// Binary data can be read from `Reader::stream()` using `futures-io` traits
+#[tokio::test]
+async fn stream() {
+    let mut buf = Vec::new();
+    let mut reader = Reader::from_reader(FuturesIo(Chunked {
+        data: b"<data>_binary << data&></data>",
+        capacity: 8,
+    }));
+
+    assert_eq!(
+        reader.read_event_into_async(&mut buf).await.unwrap(),
+        Start(BytesStart::new("data"))
+    );
+
+    let mut inner = reader.stream();
+    let mut binary = [0u8; 17];
+    let mut filled = 0;
+    while filled < binary.len() {
+        let n = poll_fn(|cx| Pin::new(&mut inner).poll_read(cx, &mut binary[filled..]))
+            .await
+            .unwrap();
+        assert_ne!(n, 0, "unexpected end of stream");
+        filled += n;
+    }
+    assert_eq!(Bytes(&binary), Bytes(b"_binary << data&>"));
+    assert_eq!(inner.offset(), 23);
+    assert_eq!(reader.buffer_position(), 23);
+
+    assert_eq!(
+        reader.read_event_into_async(&mut buf).await.unwrap(),
+        End(BytesEnd::new("data"))
+    );
+    assert_eq!(reader.read_event_into_async(&mut buf).await.unwrap(), Eof);
+}
+
+#[tokio::test]
+async fn event_stream() {
+    use futures_core::Stream;
+
+    let reader = Reader::from_reader(SmallBuffer::with_capacity(3, b"<root>text</root>"));
+    let mut stream = reader.into_event_stream();
+
+    let mut events = Vec::new();
+    while let Some(event) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+        events.push(event.unwrap());
+    }
+    assert_eq!(
+        events,
+        [
+            Start(BytesStart::new("root")),
+            Text(BytesText::new("text")),
+            End(BytesEnd::new("root")),
+        ]
+    );
+}
+
+mod writer {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn events() {
+        let mut writer = Writer::new(FuturesIo(Vec::new()));
+        writer
+            .write_event_async(Start(BytesStart::new("root")))
+            .await
+            .unwrap();
+        writer
+            .write_event_async(Text(BytesText::new("a < b")))
+            .await
+            .unwrap();
+        writer
+            .write_event_async(End(BytesEnd::new("root")))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            std::str::from_utf8(&writer.into_inner().0).unwrap(),
+            "<root>a &lt; b</root>"
+        );
+    }
+
+    #[tokio::test]
+    async fn element_writer() {
+        let mut writer = Writer::new_with_indent(FuturesIo(Vec::new()), b' ', 2);
+        writer
+            .create_element("root")
+            .with_attribute(("attr", "value"))
+            .write_inner_content_async::<_, _, quick_xml::Error>(|writer| async move {
+                writer
+                    .create_element("child")
+                    .write_text_content_async(BytesText::new("text"))
+                    .await?;
+                writer.create_element("empty").write_empty_async().await
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            std::str::from_utf8(&writer.into_inner().0).unwrap(),
+            r#"<root attr="value">
+  <child>text</child>
+  <empty/>
+</root>"#
+        );
+    }
+}
@@ -220,3 +220,192 @@ async fn issue774() {
         End(BytesEnd::new("tag"))
     );
 }
+
+mod event_stream {
+    use super::*;
+    use futures_core::stream::{FusedStream, Stream};
+    use pretty_assertions::assert_eq;
+    use quick_xml::errors::{Error, IllFormedError};
+    use quick_xml::name::OwnedResolveResult::*;
+    use quick_xml::reader::NsReader;
+    use std::future::poll_fn;
+    use std::pin::Pin;
+
+    async fn next<S: Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
+        poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx)).await
+    }
+
+    #[tokio::test]
+    async fn events() {
+        let reader = Reader::from_reader(BufReader::with_capacity(
+            4,
+            b"<root><item>text</item><empty/></root>" as &[u8],
+        ));
+        let mut stream = reader.into_event_stream();
+
+        let mut events = Vec::new();
+        while let Some(event) = next(&mut stream).await {
+            events.push(event.unwrap());
+        }
+        assert_eq!(
+            events,
+            [
+                Start(BytesStart::new("root")),
+                Start(BytesStart::new("item")),
+                Text(BytesText::new("text")),
+                End(BytesEnd::new("item")),
+                Empty(BytesStart::new("empty")),
+                End(BytesEnd::new("root")),
+            ]
+        );
+        assert!(stream.is_terminated());
+        assert!(next(&mut stream).await.is_none());
+
+        let reader = stream.into_inner().unwrap();
+        assert_eq!(reader.buffer_position(), 38);
+    }
+
+    /// The stream ends after the first error, but reading can be continued from the reader
+    #[tokio::test]
+    async fn error() {
+        let reader = Reader::from_reader(b"<a></b><c/>" as &[u8]);
+        let mut stream = reader.into_event_stream();
+
+        assert_eq!(
+            next(&mut stream).await.unwrap().unwrap(),
+            Start(BytesStart::new("a"))
+        );
+        match next(&mut stream).await {
+            Some(Err(Error::IllFormed(cause))) => assert_eq!(
+                cause,
+                IllFormedError::MismatchedEndTag {
+                    expected: "a".into(),
+                    found: "b".into(),
+                }
+            ),
+            x => panic!("Expected `Some(Err(IllFormed(_)))`, but got `{:?}`", x),
+        }
+        assert!(stream.is_terminated());
+        assert!(next(&mut stream).await.is_none());
+
+        let mut reader = stream.into_inner().unwrap();
+        assert_eq!(
+            reader.read_event_into_async(&mut Vec::new()).await.unwrap(),
+            Empty(BytesStart::new("c"))
+        );
+    }
+
+    #[tokio::test]
+    async fn namespaces() {
+        let reader = NsReader::from_reader(
+            br#"<a:root xmlns:a="urn:a"><item xmlns="urn:b"/>text</a:root>"# as &[u8],
+        );
+        let mut stream = reader.into_event_stream();
+
+        let mut items = Vec::new();
+        while let Some(item) = next(&mut stream).await {
+            items.push(item.unwrap());
+        }
+        assert_eq!(
+            items,
+            [
+                (
+                    Bound(b"urn:a".to_vec()),
+                    Start(BytesStart::from_content(r#"a:root xmlns:a="urn:a""#, 6))
+                ),
+                (
+                    Bound(b"urn:b".to_vec()),
+                    Empty(BytesStart::from_content(r#"item xmlns="urn:b""#, 4))
+                ),
+                (Unbound, Text(BytesText::new("text"))),
+                (Bound(b"urn:a".to_vec()), End(BytesEnd::new("a:root"))),
+            ]
+        );
+    }
+
+    /// An unknown prefix is returned in the item and does not stop the stream
+    #[tokio::test]
+    async fn unknown_prefix() {
+        let reader = NsReader::from_reader(b"<root><p:item/><item/></root>" as &[u8]);
+        let mut stream = reader.into_event_stream();
+
+        let mut items = Vec::new();
+        while let Some(item) = next(&mut stream).await {
+            items.push(item.unwrap());
+        }
+        assert_eq!(
+            items,
+            [
+                (Unbound, Start(BytesStart::new("root"))),
+                (Unknown(b"p".to_vec()), Empty(BytesStart::new("p:item"))),
+                (Unbound, Empty(BytesStart::new("item"))),
+                (Unbound, End(BytesEnd::new("root"))),
+            ]
+        );
+        assert!(stream.is_terminated());
+    }
+
+    /// A source which is not `Send` and borrows its data
+    struct Local<'a> {
+        data: &'a [u8],
+        _not_send: std::rc::Rc<()>,
+    }
+
+    impl<'a> tokio::io::AsyncRead for Local<'a> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+            buf: &mut tokio::io::ReadBuf<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            Pin::new(&mut self.data).poll_read(cx, buf)
+        }
+    }
+
+    impl<'a> tokio::io::AsyncBufRead for Local<'a> {
+        fn poll_fill_buf(
+            self: Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<&[u8]>> {
+            // Return one byte at a time to check that reading is resumed correctly
+            let data = self.get_mut().data;
+            std::task::Poll::Ready(Ok(&data[..data.len().min(1)]))
+        }
+
+        fn consume(mut self: Pin<&mut Self>, amt: usize) {
+            self.data = &self.data[amt..];
+        }
+    }
+
+    #[tokio::test]
+    async fn local_borrowed_reader() {
+        let xml = String::from("<root>text</root>");
+        let reader = Reader::from_reader(Local {
+            data: xml.as_bytes(),
+            _not_send: Default::default(),
+        });
+        let mut stream = reader.into_event_stream();
+
+        let mut events = Vec::new();
+        while let Some(event) = next(&mut stream).await {
+            events.push(event.unwrap());
+        }
+        assert_eq!(
+            events,
+            [
+                Start(BytesStart::new("root")),
+                Text(BytesText::new("text")),
+                End(BytesEnd::new("root")),
+            ]
+        );
+    }
+
+    #[test]
+    fn stream_is_send() {
+        fn check_send<T: Send>(_: &T) {}
+
+        let stream = Reader::from_reader(b"" as &[u8]).into_event_stream();
+        check_send(&stream);
+        let stream = NsReader::from_reader(b"" as &[u8]).into_event_stream();
+        check_send(&stream);
+    }
+}
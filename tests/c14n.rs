@@ -0,0 +1,302 @@
+//! Tests of canonicalization. Most of the tests are examples from the
+//! [Canonical XML 1.0] and [Exclusive XML Canonicalization 1.0] specifications.
+//!
+//! [Canonical XML 1.0]: https://www.w3.org/TR/2001/REC-xml-c14n-20010315#Examples
+//! [Exclusive XML Canonicalization 1.0]: https://www.w3.org/TR/2002/REC-xml-exc-c14n-20020718/#sec-Enveloping
+
+use quick_xml::c14n::{canonicalize, Algorithm, Canonicalizer};
+use quick_xml::events::Event;
+use quick_xml::name::QName;
+use quick_xml::reader::{NsReader, Reader};
+
+use pretty_assertions::assert_eq;
+
+fn c14n(xml: &str, algorithm: Algorithm) -> String {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().expand_entities = true;
+    let result = canonicalize(&mut reader, Vec::new(), algorithm).unwrap();
+    String::from_utf8(result).unwrap()
+}
+
+/// Canonicalizes the subtree of the element with the specified name
+fn c14n_subtree(xml: &str, name: &str, c14n: &mut Canonicalizer<Vec<u8>>) -> String {
+    let mut reader = NsReader::from_str(xml);
+    loop {
+        match reader.read_event().unwrap() {
+            Event::Start(e) if e.name() == QName(name.as_bytes()) => {
+                c14n.inherit_namespaces(reader.resolver().bindings())
+                    .unwrap();
+                c14n.write_event(Event::Start(e)).unwrap();
+                let mut depth = 1;
+                while depth > 0 {
+                    let event = reader.read_event().unwrap();
+                    match event {
+                        Event::Start(_) => depth += 1,
+                        Event::End(_) => depth -= 1,
+                        _ => {}
+                    }
+                    c14n.write_event(event).unwrap();
+                }
+                break;
+            }
+            Event::Eof => panic!("element `{}` not found", name),
+            _ => {}
+        }
+    }
+    String::from_utf8(c14n.get_ref().clone()).unwrap()
+}
+
+/// Example 3.1: PIs, Comments, and Outside of Document Element
+mod outside_document_element {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const XML: &str = r#"<?xml version="1.0"?>
+
+<?xml-stylesheet   href="doc.xsl"
+   type="text/xsl"   ?>
+
+<!DOCTYPE doc SYSTEM "doc.dtd">
+
+<doc>Hello, world!<!-- Comment 1 --></doc>
+
+<?pi-without-data     ?>
+
+<!-- Comment 2 -->
+
+<!-- Comment 3 -->"#;
+
+    #[test]
+    fn without_comments() {
+        assert_eq!(
+            c14n(XML, Algorithm::Canonical),
+            r#"<?xml-stylesheet href="doc.xsl"
+   type="text/xsl"   ?>
+<doc>Hello, world!</doc>
+<?pi-without-data?>"#
+        );
+    }
+
+    #[test]
+    fn with_comments() {
+        assert_eq!(
+            c14n(XML, Algorithm::CanonicalWithComments),
+            r#"<?xml-stylesheet href="doc.xsl"
+   type="text/xsl"   ?>
+<doc>Hello, world!<!-- Comment 1 --></doc>
+<?pi-without-data?>
+<!-- Comment 2 -->
+<!-- Comment 3 -->"#
+        );
+    }
+}
+
+/// Example 3.2: Whitespace in Document Content
+#[test]
+fn whitespace_in_content() {
+    let xml = r#"<doc>
+   <clean>   </clean>
+   <dirty>   A   B   </dirty>
+   <mixed>
+      A
+      <clean>   </clean>
+      B
+      <dirty>   A   B   </dirty>
+      C
+   </mixed>
+</doc>"#;
+    assert_eq!(c14n(xml, Algorithm::Canonical), xml);
+}
+
+/// Example 3.3: Start and End Tags. The default attribute declared in the DTD
+/// is not added, so the DTD is omitted
+#[test]
+fn start_and_end_tags() {
+    let xml = r#"<doc>
+   <e1   />
+   <e2   ></e2>
+   <e3   name = "elem3"   id="elem3"   />
+   <e4   name="elem4"   id="elem4"   ></e4>
+   <e5 a:attr="out" b:attr="sorted" attr2="all" attr="I'm"
+      xmlns:b="http://www.ietf.org"
+      xmlns:a="http://www.w3.org"
+      xmlns="http://example.org"/>
+   <e6 xmlns="" xmlns:a="http://www.w3.org">
+      <e7 xmlns="http://www.ietf.org">
+         <e8 xmlns="" xmlns:a="http://www.w3.org">
+            <e9 xmlns="" xmlns:a="http://www.ietf.org"/>
+         </e8>
+      </e7>
+   </e6>
+</doc>"#;
+    assert_eq!(
+        c14n(xml, Algorithm::Canonical),
+        r#"<doc>
+   <e1></e1>
+   <e2></e2>
+   <e3 id="elem3" name="elem3"></e3>
+   <e4 id="elem4" name="elem4"></e4>
+   <e5 xmlns="http://example.org" xmlns:a="http://www.w3.org" xmlns:b="http://www.ietf.org" attr="I'm" attr2="all" b:attr="sorted" a:attr="out"></e5>
+   <e6 xmlns:a="http://www.w3.org">
+      <e7 xmlns="http://www.ietf.org">
+         <e8 xmlns="">
+            <e9 xmlns:a="http://www.ietf.org"></e9>
+         </e8>
+      </e7>
+   </e6>
+</doc>"#
+    );
+}
+
+/// Example 3.4: Character Modifications and Character References. Attributes
+/// which types are declared in the DTD are omitted
+#[test]
+fn character_references() {
+    let xml = "<doc>
+   <text>First line&#x0d;&#10;Second line</text>
+   <value>&#x32;</value>
+   <compute><![CDATA[value>\"0\" && value<\"10\" ?\"valid\":\"error\"]]></compute>
+   <compute expr='value>\"0\" &amp;&amp; value&lt;\"10\" ?\"valid\":\"error\"'>valid</compute>
+   <norm attr=' &apos;   &#x20;&#13;&#xa;&#9;   &apos; '/>
+</doc>";
+    assert_eq!(
+        c14n(xml, Algorithm::Canonical),
+        "<doc>
+   <text>First line&#xD;
+Second line</text>
+   <value>2</value>
+   <compute>value&gt;\"0\" &amp;&amp; value&lt;\"10\" ?\"valid\":\"error\"</compute>
+   <compute expr=\"value>&quot;0&quot; &amp;&amp; value&lt;&quot;10&quot; ?&quot;valid&quot;:&quot;error&quot;\">valid</compute>
+   <norm attr=\" '    &#xD;&#xA;&#x9;   ' \"></norm>
+</doc>"
+    );
+}
+
+/// Example 3.5: Entity References, only internal entities
+#[test]
+fn entity_references() {
+    let xml = r#"<!DOCTYPE doc [
+<!ENTITY ent1 "Hello">
+<!ENTITY ent2 "world">
+]>
+<doc>&ent1;, &ent2;!</doc>"#;
+    assert_eq!(c14n(xml, Algorithm::Canonical), "<doc>Hello, world!</doc>");
+}
+
+/// Example 3.6: UTF-8 Encoding
+#[test]
+fn utf8() {
+    let xml = r#"<?xml version="1.0" encoding="ISO-8859-1"?>
+<doc>&#169;</doc>"#;
+    assert_eq!(c14n(xml, Algorithm::Canonical), "<doc>\u{A9}</doc>");
+}
+
+#[test]
+fn line_endings() {
+    let xml = "<doc attr='a\r\nb'>a\r\nb\rc<?pi a\r\nb?><!--a\r\nb--></doc>";
+    assert_eq!(
+        c14n(xml, Algorithm::CanonicalWithComments),
+        "<doc attr=\"a b\">a\nb\nc<?pi a\nb?><!--a\nb--></doc>"
+    );
+}
+
+/// Example from the section 2.2 of the Exclusive XML Canonicalization
+mod subtree {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const XML: &str = r#"<n0:local xmlns:n0="foo:bar" xmlns:n3="ftp://example.org">
+  <n1:elem2 xmlns:n1="http://example.net" xml:lang="en">
+     <n3:stuff xmlns:n3="ftp://example.org"/>
+  </n1:elem2>
+</n0:local>"#;
+
+    #[test]
+    fn inclusive() {
+        let mut c14n = Canonicalizer::new(Vec::new(), Algorithm::Canonical);
+        assert_eq!(
+            c14n_subtree(XML, "n1:elem2", &mut c14n),
+            r#"<n1:elem2 xmlns:n0="foo:bar" xmlns:n1="http://example.net" xmlns:n3="ftp://example.org" xml:lang="en">
+     <n3:stuff></n3:stuff>
+  </n1:elem2>"#
+        );
+    }
+
+    #[test]
+    fn exclusive() {
+        let mut c14n = Canonicalizer::new(Vec::new(), Algorithm::Exclusive);
+        assert_eq!(
+            c14n_subtree(XML, "n1:elem2", &mut c14n),
+            r#"<n1:elem2 xmlns:n1="http://example.net" xml:lang="en">
+     <n3:stuff xmlns:n3="ftp://example.org"></n3:stuff>
+  </n1:elem2>"#
+        );
+    }
+
+    #[test]
+    fn exclusive_with_prefix_list() {
+        let mut c14n = Canonicalizer::new(Vec::new(), Algorithm::Exclusive);
+        c14n.inclusive_prefixes(["n0", "#default"]);
+        assert_eq!(
+            c14n_subtree(XML, "n1:elem2", &mut c14n),
+            r#"<n1:elem2 xmlns:n0="foo:bar" xmlns:n1="http://example.net" xml:lang="en">
+     <n3:stuff xmlns:n3="ftp://example.org"></n3:stuff>
+  </n1:elem2>"#
+        );
+    }
+}
+
+mod exclusive {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn unused_namespaces() {
+        let xml = r#"<a:root xmlns:a="urn:a" xmlns:b="urn:b" xmlns:c="urn:c">
+  <b:child c:attr="1" b:attr="2"/>
+  <b:child/>
+</a:root>"#;
+        assert_eq!(
+            c14n(xml, Algorithm::Exclusive),
+            r#"<a:root xmlns:a="urn:a">
+  <b:child xmlns:b="urn:b" xmlns:c="urn:c" b:attr="2" c:attr="1"></b:child>
+  <b:child xmlns:b="urn:b"></b:child>
+</a:root>"#
+        );
+    }
+
+    #[test]
+    fn default_namespace() {
+        let xml = r#"<root xmlns="urn:a"><child xmlns=""><leaf/></child><a:child xmlns:a="urn:b"/></root>"#;
+        assert_eq!(
+            c14n(xml, Algorithm::Exclusive),
+            r#"<root xmlns="urn:a"><child xmlns=""><leaf></leaf></child><a:child xmlns:a="urn:b"></a:child></root>"#
+        );
+        assert_eq!(
+            c14n(xml, Algorithm::Canonical),
+            r#"<root xmlns="urn:a"><child xmlns=""><leaf></leaf></child><a:child xmlns:a="urn:b"></a:child></root>"#
+        );
+    }
+
+    #[test]
+    fn with_comments() {
+        let xml = "<!--before--><root><!--inside--></root><!--after-->";
+        assert_eq!(c14n(xml, Algorithm::Exclusive), "<root></root>");
+        assert_eq!(
+            c14n(xml, Algorithm::ExclusiveWithComments),
+            "<!--before-->\n<root><!--inside--></root>\n<!--after-->"
+        );
+    }
+}
+
+#[test]
+fn algorithm_uri() {
+    for algorithm in [
+        Algorithm::Canonical,
+        Algorithm::CanonicalWithComments,
+        Algorithm::Exclusive,
+        Algorithm::ExclusiveWithComments,
+    ] {
+        assert_eq!(Algorithm::from_uri(algorithm.uri()), Some(algorithm));
+    }
+}
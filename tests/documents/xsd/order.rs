@@ -0,0 +1,123 @@
+// Generated by quick-xml from an XML Schema.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename = "order")]
+pub struct Order {
+    #[serde(rename = "@id")]
+    pub id: u64,
+    #[serde(rename = "@status", skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    #[serde(rename = "@tags", skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Tags>,
+    pub customer: Customer,
+    #[serde(default)]
+    pub item: Vec<Item>,
+    #[serde(rename = "$value")]
+    pub choice: OrderChoice,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<Note>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<Category>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub history: Option<History>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum Status {
+    #[serde(rename = "new")]
+    New,
+    #[serde(rename = "in-progress")]
+    InProgress,
+    #[serde(rename = "shipped")]
+    Shipped,
+}
+
+pub type Tags = Vec<String>;
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Customer {
+    #[serde(rename = "@vip", skip_serializing_if = "Option::is_none")]
+    pub vip: Option<bool>,
+    pub name: String,
+    #[serde(default)]
+    pub email: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Item {
+    #[serde(rename = "@sku")]
+    pub sku: Sku,
+    #[serde(rename = "@qty")]
+    pub qty: String,
+    #[serde(rename = "@price", skip_serializing_if = "Option::is_none")]
+    pub price: Option<String>,
+    #[serde(rename = "$text")]
+    pub text: String,
+}
+
+pub type Sku = String;
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum OrderChoice {
+    #[serde(rename = "pickup")]
+    Pickup(String),
+    #[serde(rename = "delivery")]
+    Delivery(Address),
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Address {
+    pub street: String,
+    pub city: String,
+    #[serde(rename = "postalCode", skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Note {
+    #[serde(rename = "$value", default)]
+    pub content: Vec<NoteContent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum NoteContent {
+    #[serde(rename = "b")]
+    B(String),
+    #[serde(rename = "i")]
+    I(String),
+    #[serde(rename = "br")]
+    Br(Br),
+    #[serde(rename = "$text")]
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Br {}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Category {
+    #[serde(rename = "@name")]
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<Box<Category>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct History {
+    #[serde(rename = "$value", default)]
+    pub content: Vec<HistoryContent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum HistoryContent {
+    #[serde(rename = "event")]
+    Event(String),
+    #[serde(rename = "date")]
+    Date(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename = "customer")]
+pub struct CustomerElement(pub Customer);
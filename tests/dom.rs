@@ -0,0 +1,169 @@
+//! Contains tests for the owned document tree.
+
+use quick_xml::dom::{Document, Element, Node};
+use quick_xml::errors::{Error, IllFormedError};
+use quick_xml::events::{BytesCData, BytesText};
+use quick_xml::reader::{NsReader, Reader};
+use quick_xml::writer::Writer;
+
+use pretty_assertions::assert_eq;
+
+fn write(document: &Document) -> String {
+    let mut writer = Writer::new(Vec::new());
+    document.write_to(&mut writer).unwrap();
+    String::from_utf8(writer.into_inner()).unwrap()
+}
+
+/// Checks that reading document into a tree and writing it back produces the same document
+mod roundtrip {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    macro_rules! roundtrip {
+        ($name:ident: $xml:literal) => {
+            #[test]
+            fn $name() {
+                let document: Document = $xml.parse().unwrap();
+                assert_eq!(write(&document), $xml);
+            }
+        };
+    }
+
+    roundtrip!(empty: "");
+    roundtrip!(text_only: "only text &amp; reference");
+    roundtrip!(self_closed: "<root attr='value' other = \"&quot;\"/>");
+    roundtrip!(expanded: "<root attr='value'></root>");
+    roundtrip!(decl: "<?xml version='1.0' encoding='utf-8'?>\n<root/>\n");
+    roundtrip!(doctype: "<!DOCTYPE root [<!ENTITY e 'value'>]><root>&e;</root>");
+    roundtrip!(comments: "<!-- before --><root><!-- inside --></root><!-- after -->");
+    roundtrip!(pi: "<?pi content?><root><?target?></root>");
+    roundtrip!(cdata: "<root><![CDATA[<not markup> & ]]></root>");
+    roundtrip!(mixed: "<p>Some <b>bold</b> and <i>italic <b>nested</b></i> text</p>");
+
+    #[test]
+    fn document() {
+        let xml = include_str!("documents/sample_ns.xml");
+        let document: Document = xml.parse().unwrap();
+        assert_eq!(write(&document), xml);
+    }
+}
+
+#[test]
+fn navigation() {
+    let document: Document = r#"
+        <config version="2">
+            <!-- server settings -->
+            <server host="localhost">
+                <port>8080</port>
+            </server>
+            <name>A &amp; B&#x21;<![CDATA[ <raw>]]></name>
+        </config>
+    "#
+    .parse()
+    .unwrap();
+
+    let root = document.root().unwrap();
+    assert_eq!(root.name().as_ref(), b"config");
+    assert_eq!(
+        root.attribute("version").unwrap().unwrap().value.as_ref(),
+        b"2"
+    );
+    assert_eq!(root.attribute("missing").unwrap(), None);
+    assert_eq!(
+        root.elements()
+            .map(|e| e.name().as_ref().to_vec())
+            .collect::<Vec<_>>(),
+        vec![b"server".to_vec(), b"name".to_vec()]
+    );
+
+    let server = root.child("server").unwrap();
+    assert_eq!(server.child("port").unwrap().text().unwrap(), "8080");
+    assert_eq!(root.child("name").unwrap().text().unwrap(), "A & B! <raw>");
+    assert!(root.child("port").is_none());
+}
+
+#[test]
+fn unknown_entity_in_text() {
+    let document: Document = "<root>&unknown;</root>".parse().unwrap();
+    match document.root().unwrap().text() {
+        Err(Error::Escape(_)) => {}
+        x => panic!("Expected `Err(Escape(_))`, but got `{:?}`", x),
+    }
+}
+
+#[test]
+fn modify() {
+    let mut document: Document = "<root><a/></root>".parse().unwrap();
+
+    let root = document.root_mut().unwrap();
+    root.child_mut("a").unwrap().push_text("<text>");
+    root.push(
+        Element::new("b")
+            .with_attribute(("attr", "\"quoted\""))
+            .with_child(BytesCData::new("cdata"))
+            .with_child(Element::new("c")),
+    );
+    root.children_mut()
+        .insert(0, Node::Text(BytesText::new("first")));
+
+    assert_eq!(
+        write(&document),
+        r#"<root>first<a>&lt;text&gt;</a><b attr="&quot;quoted&quot;"><![CDATA[cdata]]><c/></b></root>"#
+    );
+}
+
+#[test]
+fn build() {
+    let mut document = Document::new();
+    document.push(Element::new("root").with_text("text"));
+
+    assert_eq!(write(&document), "<root>text</root>");
+}
+
+#[test]
+fn namespaces() {
+    let mut reader = NsReader::from_str(
+        r#"<root xmlns="urn:default" xmlns:p="urn:p"><p:child/><child xmlns=""/></root>"#,
+    );
+    let document = Document::read_from_ns(&mut reader).unwrap();
+
+    let root = document.root().unwrap();
+    assert_eq!(root.namespace(), Some(&b"urn:default"[..]));
+
+    let mut children = root.elements();
+    assert_eq!(children.next().unwrap().namespace(), Some(&b"urn:p"[..]));
+    assert_eq!(children.next().unwrap().namespace(), None);
+    assert_eq!(children.next(), None);
+}
+
+#[test]
+fn reader_config() {
+    let mut reader = Reader::from_str("<root>\n  <child/>\n</root>");
+    reader.config_mut().trim_text(true);
+    let document = Document::read_from(&mut reader).unwrap();
+
+    assert_eq!(write(&document), "<root><child/></root>");
+}
+
+#[test]
+fn unclosed() {
+    match "<root><child>".parse::<Document>() {
+        Err(Error::IllFormed(cause)) => {
+            assert_eq!(cause, IllFormedError::MissingEndTag("child".into()))
+        }
+        x => panic!("Expected `Err(IllFormed(_))`, but got `{:?}`", x),
+    }
+}
+
+#[test]
+fn unmatched_end() {
+    let mut reader = Reader::from_str("<root/></other>");
+    reader.config_mut().allow_unmatched_ends = true;
+
+    match Document::read_from(&mut reader) {
+        Err(Error::IllFormed(cause)) => {
+            assert_eq!(cause, IllFormedError::UnmatchedEndTag("other".into()))
+        }
+        x => panic!("Expected `Err(IllFormed(_))`, but got `{:?}`", x),
+    }
+}
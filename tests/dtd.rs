@@ -0,0 +1,505 @@
+//! Contains tests for the DTD reader.
+
+use quick_xml::dtd::*;
+use quick_xml::events::Event;
+use quick_xml::reader::Reader;
+
+use pretty_assertions::assert_eq;
+
+/// Reads all events from DTD, stopping at the first error
+fn read(dtd: &str) -> Vec<Result<DtdEvent<'_>, DtdError>> {
+    DtdReader::new(dtd).collect()
+}
+
+/// Reads the single markup declaration from external subset
+fn decl(dtd: &str) -> DtdEvent<'_> {
+    let mut events = read(dtd);
+    assert_eq!(events.len(), 1, "{:?}", events);
+    events.pop().unwrap().unwrap()
+}
+
+const fn name(name: &str) -> ContentParticle<'_> {
+    ContentParticle {
+        kind: ParticleKind::Name(name),
+        repeat: Repeat::Once,
+    }
+}
+
+fn repeat<'a>(cp: ContentParticle<'a>, repeat: Repeat) -> ContentParticle<'a> {
+    ContentParticle {
+        kind: cp.kind,
+        repeat,
+    }
+}
+
+mod doctype {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn name_only() {
+        assert_eq!(
+            read("root"),
+            vec![Ok(DtdEvent::DocType(DocTypeDecl {
+                name: "root",
+                external_id: None,
+            }))]
+        );
+    }
+
+    #[test]
+    fn system() {
+        assert_eq!(
+            read("root SYSTEM 'root.dtd' "),
+            vec![Ok(DtdEvent::DocType(DocTypeDecl {
+                name: "root",
+                external_id: Some(ExternalId::System("root.dtd")),
+            }))]
+        );
+    }
+
+    #[test]
+    fn public() {
+        assert_eq!(
+            read(r#"html PUBLIC "-//W3C//DTD XHTML 1.0 Strict//EN" "xhtml1-strict.dtd""#),
+            vec![Ok(DtdEvent::DocType(DocTypeDecl {
+                name: "html",
+                external_id: Some(ExternalId::Public {
+                    public_id: "-//W3C//DTD XHTML 1.0 Strict//EN",
+                    system_id: Some("xhtml1-strict.dtd"),
+                }),
+            }))]
+        );
+    }
+
+    #[test]
+    fn public_without_system() {
+        assert_eq!(
+            read(r#"html PUBLIC "-//W3C//DTD XHTML 1.0 Strict//EN""#),
+            vec![Err(DtdError::UnexpectedEof("system literal"))]
+        );
+    }
+
+    #[test]
+    fn internal_subset() {
+        assert_eq!(
+            read("root SYSTEM 'root.dtd' [ %pe; <!-- <!ENTITY> --> <?pi <!ENTITY>?> ] "),
+            vec![
+                Ok(DtdEvent::DocType(DocTypeDecl {
+                    name: "root",
+                    external_id: Some(ExternalId::System("root.dtd")),
+                })),
+                Ok(DtdEvent::PEReference("pe")),
+            ]
+        );
+    }
+
+    #[test]
+    fn unclosed_internal_subset() {
+        assert_eq!(
+            read("root[<!ELEMENT root ANY>"),
+            vec![
+                Ok(DtdEvent::DocType(DocTypeDecl {
+                    name: "root",
+                    external_id: None,
+                })),
+                Ok(DtdEvent::ElementDecl(ElementDecl {
+                    name: "root",
+                    content: ContentSpec::Any,
+                })),
+                Err(DtdError::UnexpectedEof("`]`")),
+            ]
+        );
+    }
+
+    #[test]
+    fn content_after_internal_subset() {
+        assert_eq!(
+            read("root [] garbage"),
+            vec![
+                Ok(DtdEvent::DocType(DocTypeDecl {
+                    name: "root",
+                    external_id: None,
+                })),
+                Err(DtdError::Unexpected(8, "end of DOCTYPE")),
+            ]
+        );
+    }
+}
+
+mod element {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    macro_rules! check {
+        ($name:ident: $dtd:literal => $content:expr) => {
+            #[test]
+            fn $name() {
+                assert_eq!(
+                    decl($dtd),
+                    DtdEvent::ElementDecl(ElementDecl {
+                        name: "e",
+                        content: $content,
+                    })
+                );
+            }
+        };
+    }
+
+    check!(empty: "<!ELEMENT e EMPTY>" => ContentSpec::Empty);
+    check!(any: "<!ELEMENT e ANY >" => ContentSpec::Any);
+    check!(pcdata: "<!ELEMENT e (#PCDATA)>" => ContentSpec::Mixed(vec![]));
+    check!(pcdata_star: "<!ELEMENT e ( #PCDATA )*>" => ContentSpec::Mixed(vec![]));
+    check!(mixed: "<!ELEMENT e (#PCDATA | a|b )*>" => ContentSpec::Mixed(vec!["a", "b"]));
+    check!(single: "<!ELEMENT e (a)>" => ContentSpec::Children(ContentParticle {
+        kind: ParticleKind::Seq(vec![name("a")]),
+        repeat: Repeat::Once,
+    }));
+    check!(seq: "<!ELEMENT e (a, b?, c*, d+)+>" => ContentSpec::Children(repeat(
+        ContentParticle {
+            kind: ParticleKind::Seq(vec![
+                name("a"),
+                repeat(name("b"), Repeat::Optional),
+                repeat(name("c"), Repeat::ZeroOrMore),
+                repeat(name("d"), Repeat::OneOrMore),
+            ]),
+            repeat: Repeat::Once,
+        },
+        Repeat::OneOrMore,
+    )));
+    check!(nested: "<!ELEMENT e ( (a | b)* , c )>" => ContentSpec::Children(ContentParticle {
+        kind: ParticleKind::Seq(vec![
+            ContentParticle {
+                kind: ParticleKind::Choice(vec![name("a"), name("b")]),
+                repeat: Repeat::ZeroOrMore,
+            },
+            name("c"),
+        ]),
+        repeat: Repeat::Once,
+    }));
+
+    #[test]
+    fn mixed_without_star() {
+        assert_eq!(
+            read("<!ELEMENT e (#PCDATA|a)>"),
+            vec![Err(DtdError::Unexpected(23, "`*`"))]
+        );
+    }
+
+    #[test]
+    fn mixed_separators() {
+        assert_eq!(
+            read("<!ELEMENT e (a|b,c)>"),
+            vec![Err(DtdError::Unexpected(16, "`|` or `)`"))]
+        );
+    }
+
+    #[test]
+    fn unknown_content() {
+        assert_eq!(
+            read("<!ELEMENT e SOME>"),
+            vec![Err(DtdError::Unexpected(12, "`EMPTY`, `ANY` or `(`"))]
+        );
+    }
+}
+
+mod attlist {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn types() {
+        assert_eq!(
+            decl(
+                "<!ATTLIST e
+                    a CDATA #IMPLIED
+                    b ID #REQUIRED
+                    c IDREF #IMPLIED
+                    d IDREFS #IMPLIED
+                    e ENTITY #IMPLIED
+                    f ENTITIES #IMPLIED
+                    g NMTOKEN #IMPLIED
+                    h NMTOKENS #IMPLIED
+                    i NOTATION ( png | jpeg ) #IMPLIED
+                    j (yes|no) 'no'
+                    k CDATA #FIXED \"fixed &amp; value\"
+                >"
+            ),
+            DtdEvent::AttlistDecl(AttlistDecl {
+                element: "e",
+                attributes: vec![
+                    AttributeDef {
+                        name: "a",
+                        ty: AttributeType::CData,
+                        default: DefaultDecl::Implied,
+                    },
+                    AttributeDef {
+                        name: "b",
+                        ty: AttributeType::Id,
+                        default: DefaultDecl::Required,
+                    },
+                    AttributeDef {
+                        name: "c",
+                        ty: AttributeType::IdRef,
+                        default: DefaultDecl::Implied,
+                    },
+                    AttributeDef {
+                        name: "d",
+                        ty: AttributeType::IdRefs,
+                        default: DefaultDecl::Implied,
+                    },
+                    AttributeDef {
+                        name: "e",
+                        ty: AttributeType::Entity,
+                        default: DefaultDecl::Implied,
+                    },
+                    AttributeDef {
+                        name: "f",
+                        ty: AttributeType::Entities,
+                        default: DefaultDecl::Implied,
+                    },
+                    AttributeDef {
+                        name: "g",
+                        ty: AttributeType::NmToken,
+                        default: DefaultDecl::Implied,
+                    },
+                    AttributeDef {
+                        name: "h",
+                        ty: AttributeType::NmTokens,
+                        default: DefaultDecl::Implied,
+                    },
+                    AttributeDef {
+                        name: "i",
+                        ty: AttributeType::Notation(vec!["png", "jpeg"]),
+                        default: DefaultDecl::Implied,
+                    },
+                    AttributeDef {
+                        name: "j",
+                        ty: AttributeType::Enumeration(vec!["yes", "no"]),
+                        default: DefaultDecl::Value("no"),
+                    },
+                    AttributeDef {
+                        name: "k",
+                        ty: AttributeType::CData,
+                        default: DefaultDecl::Fixed("fixed &amp; value"),
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(
+            decl("<!ATTLIST e>"),
+            DtdEvent::AttlistDecl(AttlistDecl {
+                element: "e",
+                attributes: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_type() {
+        assert_eq!(
+            read("<!ATTLIST e a STRING #IMPLIED>"),
+            vec![Err(DtdError::Unexpected(14, "attribute type"))]
+        );
+    }
+
+    #[test]
+    fn missing_default() {
+        assert_eq!(
+            read("<!ATTLIST e a CDATA>"),
+            vec![Err(DtdError::Unexpected(19, "whitespace"))]
+        );
+    }
+}
+
+mod entity {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn internal() {
+        assert_eq!(
+            decl("<!ENTITY e '<a>&b;</a>'>"),
+            DtdEvent::EntityDecl(EntityDecl {
+                name: "e",
+                parameter: false,
+                def: EntityDef::Internal("<a>&b;</a>"),
+            })
+        );
+    }
+
+    #[test]
+    fn internal_parameter() {
+        assert_eq!(
+            decl(r#"<!ENTITY % e "a | b" >"#),
+            DtdEvent::EntityDecl(EntityDecl {
+                name: "e",
+                parameter: true,
+                def: EntityDef::Internal("a | b"),
+            })
+        );
+    }
+
+    #[test]
+    fn external() {
+        assert_eq!(
+            decl(r#"<!ENTITY e PUBLIC "-//Public//ID" "e.xml">"#),
+            DtdEvent::EntityDecl(EntityDecl {
+                name: "e",
+                parameter: false,
+                def: EntityDef::External {
+                    id: ExternalId::Public {
+                        public_id: "-//Public//ID",
+                        system_id: Some("e.xml"),
+                    },
+                    ndata: None,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn external_parameter() {
+        assert_eq!(
+            decl(r#"<!ENTITY % e SYSTEM "e.dtd">"#),
+            DtdEvent::EntityDecl(EntityDecl {
+                name: "e",
+                parameter: true,
+                def: EntityDef::External {
+                    id: ExternalId::System("e.dtd"),
+                    ndata: None,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn unparsed() {
+        assert_eq!(
+            decl(r#"<!ENTITY logo SYSTEM "logo.png" NDATA png >"#),
+            DtdEvent::EntityDecl(EntityDecl {
+                name: "logo",
+                parameter: false,
+                def: EntityDef::External {
+                    id: ExternalId::System("logo.png"),
+                    ndata: Some("png"),
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn unparsed_parameter() {
+        assert_eq!(
+            read(r#"<!ENTITY % logo SYSTEM "logo.png" NDATA png>"#),
+            vec![Err(DtdError::Unexpected(34, "`>`"))]
+        );
+    }
+
+    #[test]
+    fn unclosed_literal() {
+        assert_eq!(
+            read("<!ENTITY e 'value>"),
+            vec![Err(DtdError::UnexpectedEof("closing quote"))]
+        );
+    }
+}
+
+mod notation {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn system() {
+        assert_eq!(
+            decl("<!NOTATION png SYSTEM 'image/png'>"),
+            DtdEvent::NotationDecl(NotationDecl {
+                name: "png",
+                id: ExternalId::System("image/png"),
+            })
+        );
+    }
+
+    #[test]
+    fn public() {
+        assert_eq!(
+            decl("<!NOTATION png PUBLIC 'PNG' >"),
+            DtdEvent::NotationDecl(NotationDecl {
+                name: "png",
+                id: ExternalId::Public {
+                    public_id: "PNG",
+                    system_id: None,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn public_and_system() {
+        assert_eq!(
+            decl("<!NOTATION png PUBLIC 'PNG' 'image/png'>"),
+            DtdEvent::NotationDecl(NotationDecl {
+                name: "png",
+                id: ExternalId::Public {
+                    public_id: "PNG",
+                    system_id: Some("image/png"),
+                },
+            })
+        );
+    }
+}
+
+#[test]
+fn unknown_declaration() {
+    assert_eq!(
+        read("<!ENTITY e 'e'> <![INCLUDE[ <!ENTITY f 'f'> ]]> <!ENTITY g 'g'>"),
+        vec![
+            Ok(DtdEvent::EntityDecl(EntityDecl {
+                name: "e",
+                parameter: false,
+                def: EntityDef::Internal("e"),
+            })),
+            Err(DtdError::Unexpected(16, "markup declaration")),
+        ]
+    );
+}
+
+/// Checks that DTD can be read from the `DocType` event
+#[test]
+fn from_reader() {
+    let mut reader = Reader::from_str(
+        r#"<!DOCTYPE root [
+            <!ELEMENT root (#PCDATA)>
+            <!ATTLIST root lang NMTOKEN "en">
+        ]><root/>"#,
+    );
+    let doctype = match reader.read_event().unwrap() {
+        Event::DocType(e) => e.decode().unwrap().into_owned(),
+        e => panic!("Expected `DocType`, but got `{:?}`", e),
+    };
+    assert_eq!(
+        read(&doctype),
+        vec![
+            Ok(DtdEvent::DocType(DocTypeDecl {
+                name: "root",
+                external_id: None,
+            })),
+            Ok(DtdEvent::ElementDecl(ElementDecl {
+                name: "root",
+                content: ContentSpec::Mixed(vec![]),
+            })),
+            Ok(DtdEvent::AttlistDecl(AttlistDecl {
+                element: "root",
+                attributes: vec![AttributeDef {
+                    name: "lang",
+                    ty: AttributeType::NmToken,
+                    default: DefaultDecl::Value("en"),
+                }],
+            })),
+        ]
+    );
+}
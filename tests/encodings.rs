@@ -1,4 +1,4 @@
-use encoding_rs::{UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1251};
+use encoding_rs::{ISO_8859_2, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1251};
 use pretty_assertions::assert_eq;
 use quick_xml::events::Event::*;
 use quick_xml::reader::Reader;
@@ -350,14 +350,15 @@ mod detect {
     }
 
     // Without BOM
+    // UTF-16 documents are transcoded to UTF-8
     detect_test!(utf8, UTF_8, "utf8");
-    detect_test!(utf16be, UTF_16BE, "utf16be" break);
-    detect_test!(utf16le, UTF_16LE, "utf16le" break);
+    detect_test!(utf16be, UTF_8, "utf16be");
+    detect_test!(utf16le, UTF_8, "utf16le");
 
     // With BOM
     detect_test!(utf8_bom, UTF_8, "utf8-bom");
-    detect_test!(utf16be_bom, UTF_16BE, "utf16be-bom" break);
-    detect_test!(utf16le_bom, UTF_16LE, "utf16le-bom" break);
+    detect_test!(utf16be_bom, UTF_8, "utf16be-bom");
+    detect_test!(utf16le_bom, UTF_8, "utf16le-bom");
 
     // legacy multi-byte encodings (7)
     check_detection!(big5, BIG5, "Big5");
@@ -405,10 +406,10 @@ mod detect {
 }
 
 /// Checks that encoding is detected by BOM and changed after XML declaration
-/// BOM indicates UTF-16LE, but XML declares windows-1251
+/// BOM indicates UTF-8, but XML declares windows-1251
 #[test]
 fn bom_overridden_by_declaration() {
-    let mut reader = Reader::from_reader(b"\xFF\xFE<?xml encoding='windows-1251'?>".as_ref());
+    let mut reader = Reader::from_reader(b"\xEF\xBB\xBF<?xml encoding='windows-1251'?>".as_ref());
     let mut buf = Vec::new();
 
     assert_eq!(reader.decoder().encoding(), UTF_8);
@@ -421,16 +422,17 @@ fn bom_overridden_by_declaration() {
 /// Checks that encoding is changed by XML declaration, but only once
 #[test]
 fn only_one_declaration_changes_encoding() {
-    let mut reader =
-        Reader::from_reader(b"<?xml encoding='UTF-16'?><?xml encoding='windows-1251'?>".as_ref());
+    let mut reader = Reader::from_reader(
+        b"<?xml encoding='windows-1251'?><?xml encoding='ISO-8859-2'?>".as_ref(),
+    );
     let mut buf = Vec::new();
 
     assert_eq!(reader.decoder().encoding(), UTF_8);
     assert!(matches!(reader.read_event_into(&mut buf).unwrap(), Decl(_)));
-    assert_eq!(reader.decoder().encoding(), UTF_16LE);
+    assert_eq!(reader.decoder().encoding(), WINDOWS_1251);
 
     assert!(matches!(reader.read_event_into(&mut buf).unwrap(), Decl(_)));
-    assert_eq!(reader.decoder().encoding(), UTF_16LE);
+    assert_eq!(reader.decoder().encoding(), WINDOWS_1251);
 
     assert_eq!(reader.read_event_into(&mut buf).unwrap(), Eof);
 }
@@ -447,3 +449,253 @@ fn str_always_has_utf8() {
 
     assert_eq!(reader.read_event().unwrap(), Eof);
 }
+
+/// Checks that UTF-16 documents are transcoded to UTF-8 by the reader
+mod utf16 {
+    use super::*;
+    use quick_xml::encoding::EncodingError;
+    use quick_xml::errors::Error;
+    use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+    use quick_xml::name::QName;
+    use std::io::{BufReader, Read};
+
+    use pretty_assertions::assert_eq;
+
+    /// Encodes a string to UTF-16 LE with BOM
+    fn to_utf16le(string: &str) -> Vec<u8> {
+        let mut bytes = vec![0xFF, 0xFE];
+        for unit in string.encode_utf16() {
+            bytes.extend_from_slice(&unit.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Reads all events using `Reader::read_event_into` from a `BufReader`
+    /// with the specified capacity
+    fn buffered(xml: &[u8], capacity: usize) -> Vec<Event<'static>> {
+        let mut reader = Reader::from_reader(BufReader::with_capacity(capacity, xml));
+        let mut buf = Vec::new();
+        let mut events = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf).unwrap() {
+                Eof => break,
+                e => events.push(e.into_owned()),
+            }
+            assert_eq!(reader.decoder().encoding(), UTF_8);
+            buf.clear();
+        }
+        events
+    }
+
+    /// Reads all events using `Reader::read_event` from a slice
+    fn borrowed(xml: &[u8]) -> Vec<Event<'static>> {
+        let mut reader = Reader::from_reader(xml);
+        let mut events = Vec::new();
+        loop {
+            match reader.read_event().unwrap() {
+                Eof => break,
+                e => events.push(e.into_owned()),
+            }
+            assert_eq!(reader.decoder().encoding(), UTF_8);
+        }
+        events
+    }
+
+    macro_rules! check {
+        ($test:ident, $file:literal) => {
+            #[test]
+            fn $test() {
+                let xml = include_bytes!(concat!("documents/encoding/", $file, ".xml"));
+                let expected = borrowed(UTF8_TEXT);
+
+                assert_eq!(borrowed(xml), expected);
+                assert_eq!(buffered(xml, 8192), expected);
+                // Surrogate pairs and code units are split between reads
+                assert_eq!(buffered(xml, 4), expected);
+                assert_eq!(buffered(xml, 5), expected);
+                // Encoding is detected from several reads
+                assert_eq!(buffered(xml, 1), expected);
+                assert_eq!(buffered(xml, 3), expected);
+            }
+        };
+    }
+
+    // Without BOM
+    check!(utf16be, "utf16be");
+    check!(utf16le, "utf16le");
+
+    // With BOM
+    check!(utf16be_bom, "utf16be-bom");
+    check!(utf16le_bom, "utf16le-bom");
+
+    /// Declaration is read after the encoding was detected, so it cannot change it
+    #[test]
+    fn declaration_ignored() {
+        let xml = to_utf16le("<?xml encoding='windows-1251'?><root>текст</root>");
+        let mut reader = Reader::from_reader(xml.as_ref());
+
+        assert!(matches!(reader.read_event().unwrap(), Decl(_)));
+        assert_eq!(reader.decoder().encoding(), UTF_8);
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("root"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("текст"))
+        );
+    }
+
+    /// UTF-16 cannot be declared in the document that starts as an ASCII-compatible one
+    #[test]
+    fn utf16_declared_in_utf8() {
+        let mut reader = Reader::from_reader(b"<?xml encoding='UTF-16'?><root/>".as_ref());
+
+        assert!(matches!(reader.read_event().unwrap(), Decl(_)));
+        assert_eq!(reader.decoder().encoding(), UTF_8);
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Empty(BytesStart::new("root"))
+        );
+    }
+
+    /// Bytes consumed to detect encoding of a document which is not UTF-16
+    /// encoded are parsed as usual
+    #[test]
+    fn small_reads_of_utf8() {
+        let xml = "<root>текст</root>".as_bytes();
+        assert_eq!(buffered(xml, 1), borrowed(xml));
+        assert_eq!(buffered(xml, 2), borrowed(xml));
+
+        let mut reader =
+            Reader::from_reader(BufReader::with_capacity(1, b"<a>binary</a>".as_ref()));
+        let mut buf = Vec::new();
+        assert_eq!(
+            reader.read_event_into(&mut buf).unwrap(),
+            Event::Start(BytesStart::new("a"))
+        );
+        // The first byte of the text was consumed to detect encoding
+        let mut binary = [0; 6];
+        reader.stream().read_exact(&mut binary).unwrap();
+        assert_eq!(&binary, b"binary");
+        assert_eq!(reader.buffer_position(), 9);
+        assert_eq!(
+            reader.read_event_into(&mut buf).unwrap(),
+            Event::End(BytesEnd::new("a"))
+        );
+    }
+
+    /// Raw bytes of a transcoded document are not available
+    #[test]
+    fn stream() {
+        let xml = to_utf16le("<root>binary</root>");
+        let mut reader = Reader::from_reader(xml.as_ref());
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("root"))
+        );
+        let error = reader.stream().read(&mut [0; 6]).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn read_text() {
+        let xml = to_utf16le("<root><inner>текст &amp; 😀</inner></root>");
+        let mut reader = Reader::from_reader(xml.as_ref());
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("root"))
+        );
+        assert_eq!(
+            reader.read_text(QName(b"root")).unwrap().decode().unwrap(),
+            "<inner>текст &amp; 😀</inner>"
+        );
+        assert_eq!(reader.read_event().unwrap(), Eof);
+    }
+
+    /// Checks that malformed UTF-16 is reported by both buffered and borrowed readers
+    mod malformed {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn check(xml: &[u8]) {
+            let mut reader = Reader::from_reader(xml);
+            let error = loop {
+                match reader.read_event() {
+                    Ok(Eof) => panic!("Expected encoding error, got EOF"),
+                    Ok(_) => continue,
+                    Err(e) => break e,
+                }
+            };
+            assert!(
+                matches!(error, Error::Encoding(EncodingError::Other(e)) if e == UTF_16LE),
+                "Expected EncodingError::Other(UTF-16LE), got: {:?}",
+                error,
+            );
+            assert_eq!(reader.read_event().unwrap(), Eof);
+
+            let mut reader = Reader::from_reader(BufReader::with_capacity(5, xml));
+            let mut buf = Vec::new();
+            let error = loop {
+                match reader.read_event_into(&mut buf) {
+                    Ok(Eof) => panic!("Expected encoding error, got EOF"),
+                    Ok(_) => continue,
+                    Err(e) => break e,
+                }
+            };
+            assert!(
+                matches!(error, Error::Encoding(EncodingError::Other(e)) if e == UTF_16LE),
+                "Expected EncodingError::Other(UTF-16LE), got: {:?}",
+                error,
+            );
+            assert_eq!(reader.read_event_into(&mut buf).unwrap(), Eof);
+        }
+
+        #[test]
+        fn unpaired_low_surrogate() {
+            check(&[
+                0xFF, 0xFE, // UTF-16 LE BOM
+                0x3C, 0x00, // '<'
+                0x61, 0x00, // 'a'
+                0x3E, 0x00, // '>'
+                0x00, 0xDC, // unpaired low surrogate
+            ]);
+        }
+
+        #[test]
+        fn unpaired_high_surrogate() {
+            check(&[
+                0xFF, 0xFE, // UTF-16 LE BOM
+                0x3C, 0x00, // '<'
+                0x61, 0x00, // 'a'
+                0x3E, 0x00, // '>'
+                0x00, 0xD8, // high surrogate
+                0x61, 0x00, // 'a'
+            ]);
+        }
+
+        #[test]
+        fn truncated_high_surrogate() {
+            check(&[
+                0xFF, 0xFE, // UTF-16 LE BOM
+                0x3C, 0x00, // '<'
+                0x61, 0x00, // 'a'
+                0x3E, 0x00, // '>'
+                0x00, 0xD8, // high surrogate
+            ]);
+        }
+
+        #[test]
+        fn truncated_code_unit() {
+            check(&[
+                0xFF, 0xFE, // UTF-16 LE BOM
+                0x3C, 0x00, // '<'
+                0x61, 0x00, // 'a'
+                0x3E, 0x00, // '>'
+                0x48, 0x00, // 'H'
+                0x65, // truncated code unit
+            ]);
+        }
+    }
+}
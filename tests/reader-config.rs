@@ -7,7 +7,8 @@
 
 use quick_xml::errors::{Error, IllFormedError};
 use quick_xml::events::{BytesCData, BytesEnd, BytesPI, BytesRef, BytesStart, BytesText, Event};
-use quick_xml::reader::Reader;
+use quick_xml::name::{Namespace, ResolveResult};
+use quick_xml::reader::{NsReader, Reader};
 
 mod allow_dangling_amp {
     use super::*;
@@ -123,6 +124,193 @@ mod allow_unmatched_ends {
     }
 }
 
+mod check_chars {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::reader::{PushEvent, PushReader};
+
+    #[test]
+    fn false_() {
+        let mut reader = Reader::from_str("<a b='\u{1}'>\u{1}&#1;</a>");
+        reader.config_mut().check_chars = false;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::Start(_)));
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("\u{1}"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::GeneralRef(BytesRef::new("#1"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("a")));
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    /// Checks that reading the only event of `xml` returns `InvalidChar(code)`
+    /// error at `position`
+    fn check(xml: &str, code: u32, position: u64) {
+        let mut reader = Reader::from_str(xml);
+        reader.config_mut().check_chars = true;
+
+        let error = loop {
+            match reader.read_event() {
+                Ok(Event::Eof) => panic!("Expected `Err(IllFormed(InvalidChar))` in {:?}", xml),
+                Ok(_) => {}
+                Err(error) => break error,
+            }
+        };
+        match error {
+            Error::IllFormed(cause) => assert_eq!(cause, IllFormedError::InvalidChar(code)),
+            x => panic!("Expected `Err(IllFormed(_))`, but got `{:?}`", x),
+        }
+        assert_eq!(reader.error_position(), position);
+    }
+
+    #[test]
+    fn text() {
+        check("<a>text\u{1}</a>", 0x1, 7);
+        check("text\u{FFFE}", 0xFFFE, 4);
+    }
+
+    #[test]
+    fn attribute() {
+        check("<a b='\u{8}'/>", 0x8, 6);
+        check("<a b='\u{B}'>", 0xB, 6);
+    }
+
+    #[test]
+    fn end() {
+        check("<a></a\u{C}>", 0xC, 6);
+    }
+
+    #[test]
+    fn comment() {
+        check("<!--\u{1F}-->", 0x1F, 4);
+    }
+
+    #[test]
+    fn cdata() {
+        check("<![CDATA[\u{0}]]>", 0x0, 9);
+    }
+
+    #[test]
+    fn pi() {
+        check("<?pi \u{1}?>", 0x1, 5);
+    }
+
+    #[test]
+    fn doctype() {
+        check("<!DOCTYPE \u{1}>", 0x1, 10);
+    }
+
+    #[test]
+    fn char_ref() {
+        check("&#1;", 0x1, 0);
+        check("text&#x0;", 0x0, 4);
+        check("text&#xD800;", 0xD800, 4);
+        check("text&#xFFFF;", 0xFFFF, 4);
+    }
+
+    /// C1 control characters are allowed in XML 1.0 literally
+    #[test]
+    fn xml10() {
+        let mut reader = Reader::from_str("<?xml version='1.0'?>\u{80}\t\r\n&#x9F;");
+        reader.config_mut().check_chars = true;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::Decl(_)));
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("\u{80}\t\r\n"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::GeneralRef(BytesRef::new("#x9F"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    /// Control characters are allowed in XML 1.1 only as character references
+    #[test]
+    fn xml11() {
+        let mut reader =
+            Reader::from_str("<?xml version='1.1'?>&#1;&#x80;\u{85}\t<a>\u{80}</a>\u{1}");
+        reader.config_mut().check_chars = true;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::Decl(_)));
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::GeneralRef(BytesRef::new("#1"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::GeneralRef(BytesRef::new("#x80"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("\u{85}\t"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("a"))
+        );
+        match reader.read_event() {
+            Err(Error::IllFormed(cause)) => assert_eq!(cause, IllFormedError::InvalidChar(0x80)),
+            x => panic!("Expected `Err(IllFormed(_))`, but got `{:?}`", x),
+        }
+        // 21 + 4 + 6 + 3 (NEL is two bytes in UTF-8) + 3
+        assert_eq!(reader.error_position(), 37);
+        // The error is recoverable
+        assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("a")));
+        match reader.read_event() {
+            Err(Error::IllFormed(cause)) => assert_eq!(cause, IllFormedError::InvalidChar(0x1)),
+            x => panic!("Expected `Err(IllFormed(_))`, but got `{:?}`", x),
+        }
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    /// Lone surrogates cannot be encoded in UTF-8
+    #[test]
+    fn surrogate() {
+        let mut reader = Reader::from_reader(b"<a>text\xED\xA0\x80</a>".as_ref());
+        reader.config_mut().check_chars = true;
+
+        let mut buf = Vec::new();
+        assert!(matches!(
+            reader.read_event_into(&mut buf).unwrap(),
+            Event::Start(_)
+        ));
+        match reader.read_event_into(&mut buf) {
+            Err(Error::Encoding(_)) => {}
+            x => panic!("Expected `Err(Encoding(_))`, but got `{:?}`", x),
+        }
+        assert_eq!(reader.error_position(), 7);
+    }
+
+    #[test]
+    fn push() {
+        let mut reader = PushReader::new();
+        reader.config_mut().check_chars = true;
+        reader.feed(b"<a>\x01&#1;");
+        reader.finish();
+
+        assert!(matches!(
+            reader.read_event().unwrap(),
+            PushEvent::Event(Event::Start(_))
+        ));
+        match reader.read_event() {
+            Err(Error::IllFormed(cause)) => assert_eq!(cause, IllFormedError::InvalidChar(0x1)),
+            x => panic!("Expected `Err(IllFormed(_))`, but got `{:?}`", x),
+        }
+        assert_eq!(reader.error_position(), 3);
+        match reader.read_event() {
+            Err(Error::IllFormed(cause)) => assert_eq!(cause, IllFormedError::InvalidChar(0x1)),
+            x => panic!("Expected `Err(IllFormed(_))`, but got `{:?}`", x),
+        }
+        assert_eq!(reader.error_position(), 4);
+    }
+}
+
 mod check_comments {
     use super::*;
 
@@ -461,6 +649,277 @@ mod check_end_names {
     }
 }
 
+mod check_names {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn false_() {
+        let mut reader = Reader::from_str("<1 2='3'><?4?>&5;</1>");
+        reader.config_mut().check_names = false;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::Start(_)));
+        assert!(matches!(reader.read_event().unwrap(), Event::PI(_)));
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::GeneralRef(BytesRef::new("5"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("1")));
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    #[test]
+    fn valid() {
+        let mut reader =
+            Reader::from_str("<ns:élément _a='1' b.c-d='2'><?pi?>&e·f;&#1;</ns:élément>");
+        reader.config_mut().check_names = true;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::Start(_)));
+        assert!(matches!(reader.read_event().unwrap(), Event::PI(_)));
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::GeneralRef(BytesRef::new("e·f"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::GeneralRef(BytesRef::new("#1"))
+        );
+        assert!(matches!(reader.read_event().unwrap(), Event::End(_)));
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    /// Checks that reading the only event of `xml` returns `InvalidName(name)`
+    /// error at `position`
+    fn check(xml: &str, name: &str, position: u64) {
+        let mut reader = Reader::from_str(xml);
+        reader.config_mut().check_end_names = false;
+        reader.config_mut().check_names = true;
+
+        match reader.read_event() {
+            Err(Error::IllFormed(cause)) => {
+                assert_eq!(cause, IllFormedError::InvalidName(name.into()))
+            }
+            x => panic!("Expected `Err(IllFormed(_))`, but got `{:?}`", x),
+        }
+        assert_eq!(reader.error_position(), position);
+        // The error is recoverable
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    #[test]
+    fn start() {
+        check("<1a>", "1a", 1);
+        check("<-a/>", "-a", 1);
+        check("<a\u{1}b>", "a\u{1}b", 1);
+    }
+
+    #[test]
+    fn attribute() {
+        check("<a 1b='c'>", "1b", 3);
+        check("<a b='c' d@e='f'/>", "d@e", 9);
+    }
+
+    #[test]
+    fn end() {
+        check("</.a>", ".a", 2);
+        check("</>", "", 2);
+    }
+
+    #[test]
+    fn pi() {
+        check("<?1pi?>", "1pi", 2);
+    }
+
+    #[test]
+    fn reference() {
+        check("&1a;", "1a", 1);
+        check("&a b;", "a b", 1);
+    }
+}
+
+mod default_attributes {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::events::attributes::{Attribute, Attributes};
+    use quick_xml::XmlVersion;
+
+    const DTD: &str = r#"<!DOCTYPE root [
+        <!ATTLIST root
+            required CDATA  #REQUIRED
+            implied  CDATA  #IMPLIED
+            fixed    CDATA  #FIXED "fixed"
+            quoted   CDATA  'say "hi"'
+            value    CDATA  "default">
+        <!ATTLIST root value CDATA "redeclared" other CDATA "other">
+    ]>"#;
+
+    /// Returns keys and values of attributes as strings
+    fn pairs(attrs: Attributes) -> Vec<(String, String)> {
+        attrs
+            .map(|a| {
+                let a = a.unwrap();
+                (
+                    String::from_utf8(a.key.as_ref().to_vec()).unwrap(),
+                    a.normalized_value(XmlVersion::Implicit1_0)
+                        .unwrap()
+                        .into_owned(),
+                )
+            })
+            .collect()
+    }
+
+    fn pair(key: &str, value: &str) -> (String, String) {
+        (key.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn false_() {
+        let xml = format!("{}<root/>", DTD);
+        let mut reader = Reader::from_str(&xml);
+        reader.config_mut().default_attributes = false;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::DocType(_)));
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Empty(BytesStart::new("root"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    #[test]
+    fn true_() {
+        let xml = format!(r#"{}<root value="specified"><root/><other/></root>"#, DTD);
+        let mut reader = Reader::from_str(&xml);
+        reader.config_mut().default_attributes = true;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::DocType(_)));
+        match reader.read_event().unwrap() {
+            Event::Start(e) => {
+                assert_eq!(
+                    pairs(e.attributes()),
+                    [
+                        pair("value", "specified"),
+                        pair("fixed", "fixed"),
+                        pair("quoted", r#"say "hi""#),
+                        pair("other", "other"),
+                    ]
+                );
+                assert_eq!(
+                    pairs(e.specified_attributes()),
+                    [pair("value", "specified")]
+                );
+                assert_eq!(
+                    pairs(e.defaulted_attributes()),
+                    [
+                        pair("fixed", "fixed"),
+                        pair("quoted", r#"say "hi""#),
+                        pair("other", "other"),
+                    ]
+                );
+            }
+            e => panic!("Expected `Start`, but got `{:?}`", e),
+        }
+        match reader.read_event().unwrap() {
+            Event::Empty(e) => {
+                // The first declaration is binding
+                assert_eq!(
+                    pairs(e.attributes()),
+                    [
+                        pair("fixed", "fixed"),
+                        pair("quoted", r#"say "hi""#),
+                        pair("value", "default"),
+                        pair("other", "other"),
+                    ]
+                );
+                assert_eq!(e.specified_attributes().count(), 0);
+            }
+            e => panic!("Expected `Empty`, but got `{:?}`", e),
+        }
+        // Attributes are not declared for this element
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Empty(BytesStart::new("other"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::End(BytesEnd::new("root"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    /// Attributes pushed to an element with defaulted attributes should be
+    /// specified attributes
+    #[test]
+    fn push_attribute() {
+        let mut reader = Reader::from_str(
+            r#"<!DOCTYPE root [<!ATTLIST root default CDATA "1">]><root specified="2"/>"#,
+        );
+        reader.config_mut().default_attributes = true;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::DocType(_)));
+        match reader.read_event().unwrap() {
+            Event::Empty(mut e) => {
+                e.set_name(b"element");
+                e.push_attribute(Attribute::from(("pushed", "3")));
+                assert_eq!(
+                    pairs(e.specified_attributes()),
+                    [pair("specified", "2"), pair("pushed", "3")]
+                );
+                assert_eq!(pairs(e.defaulted_attributes()), [pair("default", "1")]);
+
+                e.clear_attributes();
+                assert_eq!(e.attributes().count(), 0);
+                assert_eq!(e.defaulted_attributes().count(), 0);
+            }
+            e => panic!("Expected `Empty`, but got `{:?}`", e),
+        }
+    }
+
+    /// Defaulted `xmlns` attributes should declare namespaces
+    #[test]
+    fn namespaces() {
+        let mut reader = NsReader::from_str(
+            r##"<!DOCTYPE book [
+                <!ATTLIST book
+                    xmlns       CDATA #FIXED "http://docbook.org/ns/docbook"
+                    xmlns:xlink CDATA #FIXED "http://www.w3.org/1999/xlink">
+                <!ATTLIST link xlink:href CDATA "#top">
+            ]><book><link/></book>"##,
+        );
+        reader.config_mut().default_attributes = true;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::DocType(_)));
+        match reader.read_resolved_event().unwrap() {
+            (ResolveResult::Bound(ns), Event::Start(_)) => {
+                assert_eq!(ns, Namespace(b"http://docbook.org/ns/docbook"))
+            }
+            e => panic!("Expected bound `Start`, but got `{:?}`", e),
+        }
+        match reader.read_resolved_event().unwrap() {
+            (ResolveResult::Bound(ns), Event::Empty(e)) => {
+                assert_eq!(ns, Namespace(b"http://docbook.org/ns/docbook"));
+                let attr = e.attributes().next().unwrap().unwrap();
+                assert_eq!(
+                    reader.resolver().resolve_attribute(attr.key).0,
+                    ResolveResult::Bound(Namespace(b"http://www.w3.org/1999/xlink"))
+                );
+            }
+            e => panic!("Expected bound `Empty`, but got `{:?}`", e),
+        }
+    }
+
+    #[test]
+    fn invalid_dtd() {
+        let mut reader = Reader::from_str("<!DOCTYPE root [<!ATTLIST>]><root/>");
+        reader.config_mut().default_attributes = true;
+
+        match reader.read_event() {
+            Err(Error::IllFormed(IllFormedError::InvalidDtd(_))) => {}
+            x => panic!("Expected `Err(InvalidDtd)`, but got `{:?}`", x),
+        }
+    }
+}
+
 mod expand_empty_elements {
     use super::*;
     use pretty_assertions::assert_eq;
@@ -496,6 +955,1829 @@ mod expand_empty_elements {
     }
 }
 
+mod expand_entities {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const XML: &str = r#"<!DOCTYPE root [
+        <!ENTITY text "text &amp; &#38;#60;">
+        <!ENTITY markup "<a attr='&text;'>&text;</a><b/>">
+        <!ENTITY empty "">
+    ]><root>&markup;&empty;&unknown;</root>"#;
+
+    /// Entity references should be reported as is
+    #[test]
+    fn false_() {
+        let mut reader = Reader::from_str(XML);
+        reader.config_mut().expand_entities = false;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::DocType(_)));
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("root"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::GeneralRef(BytesRef::new("markup"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::GeneralRef(BytesRef::new("empty"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::GeneralRef(BytesRef::new("unknown"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::End(BytesEnd::new("root"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    macro_rules! check {
+        ($reader:ident, $read_event:expr) => {
+            $reader.config_mut().expand_entities = true;
+
+            assert!(matches!($read_event, Event::DocType(_)));
+            assert_eq!($read_event, Event::Start(BytesStart::new("root")));
+            assert_eq!(
+                $read_event,
+                Event::Start(BytesStart::from_content("a attr='&text;'", 1))
+            );
+            assert_eq!($read_event, Event::Text(BytesText::new("text ")));
+            assert_eq!($read_event, Event::GeneralRef(BytesRef::new("amp")));
+            assert_eq!($read_event, Event::Text(BytesText::new(" ")));
+            // Character reference was replaced in the entity declaration
+            assert_eq!($read_event, Event::GeneralRef(BytesRef::new("#60")));
+            assert_eq!($read_event, Event::End(BytesEnd::new("a")));
+            assert_eq!($read_event, Event::Empty(BytesStart::new("b")));
+            // Entity expanded to nothing
+            assert_eq!($read_event, Event::Text(BytesText::new("")));
+            // Not declared entity
+            assert_eq!($read_event, Event::GeneralRef(BytesRef::new("unknown")));
+            assert_eq!($read_event, Event::End(BytesEnd::new("root")));
+            assert_eq!($read_event, Event::Eof);
+        };
+    }
+
+    #[test]
+    fn borrowed() {
+        let mut reader = Reader::from_str(XML);
+        check!(reader, reader.read_event().unwrap());
+    }
+
+    #[test]
+    fn buffered() {
+        let mut reader = Reader::from_reader(XML.as_bytes());
+        let mut buf = Vec::new();
+        check!(
+            reader,
+            reader.read_event_into(&mut buf).unwrap().into_owned()
+        );
+    }
+
+    #[test]
+    fn first_declaration_is_binding() {
+        let mut reader = Reader::from_str(
+            r#"<!DOCTYPE root [
+                <!ENTITY e "first">
+                <!ENTITY e "second">
+            ]>&e;"#,
+        );
+        reader.config_mut().expand_entities = true;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::DocType(_)));
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("first"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    /// Expansion of recursive entities is an error, but reading can be continued
+    #[test]
+    fn recursive() {
+        let mut reader = Reader::from_str(
+            r#"<!DOCTYPE root [
+                <!ENTITY a "a&b;">
+                <!ENTITY b "b&a;">
+            ]>&a;<root/>"#,
+        );
+        reader.config_mut().expand_entities = true;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::DocType(_)));
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("a"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("b"))
+        );
+        match reader.read_event() {
+            Err(Error::IllFormed(cause)) => {
+                assert_eq!(cause, IllFormedError::RecursiveEntity("a".into()));
+            }
+            x => panic!("Expected `Err(IllFormed(_))`, but got `{:?}`", x),
+        }
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Empty(BytesStart::new("root"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    /// Entities which do not produce any events are returned as one empty text
+    #[test]
+    fn expands_to_nothing() {
+        const XML: &str = r#"<!DOCTYPE root [
+            <!ENTITY e "">
+            <!ENTITY nested "&e;&e;">
+        ]><root>&e;&nested;<a/></root>"#;
+
+        let mut reader = Reader::from_str(XML);
+        reader.config_mut().expand_entities = true;
+        let mut buf = Vec::new();
+
+        assert!(matches!(
+            reader.read_event_into(&mut buf).unwrap(),
+            Event::DocType(_)
+        ));
+        assert_eq!(
+            reader.read_event_into(&mut buf).unwrap(),
+            Event::Start(BytesStart::new("root"))
+        );
+        assert_eq!(
+            reader.read_event_into(&mut buf).unwrap(),
+            Event::Text(BytesText::new(""))
+        );
+        assert_eq!(
+            reader.read_event_into(&mut buf).unwrap(),
+            Event::Text(BytesText::new(""))
+        );
+        assert_eq!(
+            reader.read_event_into(&mut buf).unwrap(),
+            Event::Empty(BytesStart::new("a"))
+        );
+        assert_eq!(
+            reader.read_event_into(&mut buf).unwrap(),
+            Event::End(BytesEnd::new("root"))
+        );
+        assert_eq!(reader.read_event_into(&mut buf).unwrap(), Event::Eof);
+    }
+
+    /// Elements started in the entity should be closed in the same entity
+    #[test]
+    fn unclosed_element() {
+        let mut reader = Reader::from_str(r#"<!DOCTYPE root [<!ENTITY e "<a>">]>&e;</a>"#);
+        reader.config_mut().expand_entities = true;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::DocType(_)));
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("a"))
+        );
+        match reader.read_event() {
+            Err(Error::IllFormed(cause)) => {
+                assert_eq!(cause, IllFormedError::MissingEndTag("a".into()));
+            }
+            x => panic!("Expected `Err(IllFormed(_))`, but got `{:?}`", x),
+        }
+    }
+
+    /// Entity cannot close elements started outside of it
+    #[test]
+    fn unmatched_end() {
+        let mut reader = Reader::from_str(r#"<!DOCTYPE root [<!ENTITY e "</a>">]><a>&e;"#);
+        reader.config_mut().expand_entities = true;
+        reader.config_mut().allow_unmatched_ends = true;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::DocType(_)));
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("a"))
+        );
+        match reader.read_event() {
+            Err(Error::IllFormed(cause)) => {
+                assert_eq!(cause, IllFormedError::UnmatchedEndTag("a".into()));
+            }
+            x => panic!("Expected `Err(IllFormed(_))`, but got `{:?}`", x),
+        }
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    #[test]
+    fn invalid_dtd() {
+        let mut reader = Reader::from_str(r#"<!DOCTYPE root [<!ENTITY e>]><root/>"#);
+        reader.config_mut().expand_entities = true;
+
+        match reader.read_event() {
+            Err(Error::IllFormed(IllFormedError::InvalidDtd(_))) => {}
+            x => panic!(
+                "Expected `Err(IllFormed(InvalidDtd(_)))`, but got `{:?}`",
+                x
+            ),
+        }
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Empty(BytesStart::new("root"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    /// Elements from the entity should be in scope of namespaces of the document
+    #[test]
+    fn namespaces() {
+        let mut reader = NsReader::from_str(
+            r#"<!DOCTYPE root [<!ENTITY e "<p:a/>">]><root xmlns:p="urn:p">&e;</root>"#,
+        );
+        reader.config_mut().expand_entities = true;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::DocType(_)));
+        assert!(matches!(reader.read_event().unwrap(), Event::Start(_)));
+        match reader.read_resolved_event().unwrap() {
+            (ResolveResult::Bound(ns), Event::Empty(e)) => {
+                assert_eq!(ns, Namespace(b"urn:p"));
+                assert_eq!(e.local_name().as_ref(), b"a");
+            }
+            x => panic!("Expected bound `Empty` event, but got `{:?}`", x),
+        }
+    }
+}
+
+mod html {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::reader::{PushEvent, PushReader};
+    use std::io::BufReader;
+
+    /// Reads all events until `Eof` and returns them
+    fn events(reader: &mut Reader<&[u8]>) -> Vec<Event<'static>> {
+        let mut events = Vec::new();
+        loop {
+            match reader.read_event().unwrap() {
+                Event::Eof => break events,
+                event => events.push(event.into_owned()),
+            }
+        }
+    }
+
+    fn html(html: &str) -> Reader<&[u8]> {
+        let mut reader = Reader::from_str(html);
+        reader.config_mut().html = true;
+        reader
+    }
+
+    /// HTML constructs should be read as XML
+    #[test]
+    fn false_() {
+        let mut reader = Reader::from_str("<br><script>a</script>");
+        reader.config_mut().html = false;
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("br"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("script"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("a"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::End(BytesEnd::new("script"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    #[test]
+    fn void() {
+        let mut reader = html("<p>a<BR>b<img src=x.png><input/></p>");
+
+        assert_eq!(
+            events(&mut reader),
+            [
+                Event::Start(BytesStart::new("p")),
+                Event::Text(BytesText::new("a")),
+                Event::Empty(BytesStart::new("BR")),
+                Event::Text(BytesText::new("b")),
+                Event::Empty(BytesStart::from_content("img src=x.png", 3)),
+                Event::Empty(BytesStart::new("input")),
+                Event::End(BytesEnd::new("p")),
+            ]
+        );
+    }
+
+    /// Void elements should be expanded as any other self-closed element
+    #[test]
+    fn void_expanded() {
+        let mut reader = html("<br>text");
+        reader.config_mut().expand_empty_elements = true;
+
+        assert_eq!(
+            events(&mut reader),
+            [
+                Event::Start(BytesStart::new("br")),
+                Event::End(BytesEnd::new("br")),
+                Event::Text(BytesText::new("text")),
+            ]
+        );
+    }
+
+    mod raw_text {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        const HTML: &str = r#"<script>if (a < b && c) x = "</div>";</SCRIPT ><p>&amp;</p>"#;
+
+        fn expected() -> [Event<'static>; 6] {
+            [
+                Event::Start(BytesStart::new("script")),
+                Event::Text(BytesText::from_escaped(r#"if (a < b && c) x = "</div>";"#)),
+                Event::End(BytesEnd::new("script")),
+                Event::Start(BytesStart::new("p")),
+                Event::GeneralRef(BytesRef::new("amp")),
+                Event::End(BytesEnd::new("p")),
+            ]
+        }
+
+        #[test]
+        fn borrowed() {
+            assert_eq!(events(&mut html(HTML)), expected());
+        }
+
+        #[test]
+        fn buffered() {
+            let mut reader = Reader::from_reader(BufReader::with_capacity(3, HTML.as_bytes()));
+            reader.config_mut().html = true;
+
+            let mut buf = Vec::new();
+            let mut events = Vec::new();
+            loop {
+                match reader.read_event_into(&mut buf).unwrap() {
+                    Event::Eof => break,
+                    event => events.push(event.into_owned()),
+                }
+                buf.clear();
+            }
+            assert_eq!(events, expected());
+        }
+
+        #[test]
+        fn push_reader() {
+            let mut reader = PushReader::new();
+            reader.config_mut().html = true;
+
+            let mut events = Vec::new();
+            // Feed data byte by byte to check that the end tag is found across chunks
+            for byte in HTML.as_bytes() {
+                reader.feed(&[*byte]);
+                while let PushEvent::Event(event) = reader.read_event().unwrap() {
+                    events.push(event.into_owned());
+                }
+            }
+            reader.finish();
+            match reader.read_event().unwrap() {
+                PushEvent::Event(Event::Eof) => {}
+                x => panic!("Expected `Eof`, but got `{:?}`", x),
+            }
+            assert_eq!(events, expected());
+        }
+
+        #[test]
+        fn empty() {
+            let mut reader = html("<title></title><textarea/>");
+
+            assert_eq!(
+                events(&mut reader),
+                [
+                    Event::Start(BytesStart::new("title")),
+                    Event::End(BytesEnd::new("title")),
+                    Event::Empty(BytesStart::new("textarea")),
+                ]
+            );
+        }
+
+        /// Raw text, not closed before the end of input, should be returned as is
+        #[test]
+        fn unclosed() {
+            let mut reader = html("<style>a < b </sty");
+
+            assert_eq!(
+                events(&mut reader),
+                [
+                    Event::Start(BytesStart::new("style")),
+                    Event::Text(BytesText::from_escaped("a < b </sty")),
+                    Event::End(BytesEnd::new("style")),
+                ]
+            );
+        }
+    }
+
+    mod implied_ends {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn paragraphs() {
+            let mut reader = html("<p>One<P>Two<div>x</div>");
+
+            assert_eq!(
+                events(&mut reader),
+                [
+                    Event::Start(BytesStart::new("p")),
+                    Event::Text(BytesText::new("One")),
+                    Event::End(BytesEnd::new("p")),
+                    Event::Start(BytesStart::new("P")),
+                    Event::Text(BytesText::new("Two")),
+                    Event::End(BytesEnd::new("P")),
+                    Event::Start(BytesStart::new("div")),
+                    Event::Text(BytesText::new("x")),
+                    Event::End(BytesEnd::new("div")),
+                ]
+            );
+        }
+
+        #[test]
+        fn table() {
+            let mut reader = html("<table><tr><td>1<td>2<tr><th>3</table>");
+
+            assert_eq!(
+                events(&mut reader),
+                [
+                    Event::Start(BytesStart::new("table")),
+                    Event::Start(BytesStart::new("tr")),
+                    Event::Start(BytesStart::new("td")),
+                    Event::Text(BytesText::new("1")),
+                    Event::End(BytesEnd::new("td")),
+                    Event::Start(BytesStart::new("td")),
+                    Event::Text(BytesText::new("2")),
+                    Event::End(BytesEnd::new("td")),
+                    Event::End(BytesEnd::new("tr")),
+                    Event::Start(BytesStart::new("tr")),
+                    Event::Start(BytesStart::new("th")),
+                    Event::Text(BytesText::new("3")),
+                    Event::End(BytesEnd::new("th")),
+                    Event::End(BytesEnd::new("tr")),
+                    Event::End(BytesEnd::new("table")),
+                ]
+            );
+        }
+
+        /// Elements that are not closed at the end of input should be closed
+        #[test]
+        fn eof() {
+            let mut reader = html("<html><body><p>text");
+
+            assert_eq!(
+                events(&mut reader),
+                [
+                    Event::Start(BytesStart::new("html")),
+                    Event::Start(BytesStart::new("body")),
+                    Event::Start(BytesStart::new("p")),
+                    Event::Text(BytesText::new("text")),
+                    Event::End(BytesEnd::new("p")),
+                    Event::End(BytesEnd::new("body")),
+                    Event::End(BytesEnd::new("html")),
+                ]
+            );
+        }
+
+        /// Omitted end tags of elements, which end tags can be omitted in HTML,
+        /// should not be reported in the recovery mode
+        #[test]
+        fn recover_errors() {
+            let mut reader = html("<div><p>1<span>2</div><ul><li>3");
+            reader.config_mut().recover_errors = true;
+
+            assert_eq!(
+                events(&mut reader),
+                [
+                    Event::Start(BytesStart::new("div")),
+                    Event::Start(BytesStart::new("p")),
+                    Event::Text(BytesText::new("1")),
+                    Event::Start(BytesStart::new("span")),
+                    Event::Text(BytesText::new("2")),
+                    Event::End(BytesEnd::new("span")),
+                    Event::End(BytesEnd::new("p")),
+                    Event::End(BytesEnd::new("div")),
+                    Event::Start(BytesStart::new("ul")),
+                    Event::Start(BytesStart::new("li")),
+                    Event::Text(BytesText::new("3")),
+                    Event::End(BytesEnd::new("li")),
+                    Event::End(BytesEnd::new("ul")),
+                ]
+            );
+            let diagnostics: Vec<_> = reader
+                .take_diagnostics()
+                .into_iter()
+                .map(|d| (d.position.byte, d.error.to_string()))
+                .collect();
+            assert_eq!(
+                diagnostics,
+                [
+                    (
+                        16,
+                        "ill-formed document: start tag not closed: `</span>` not found before `</div>`".into()
+                    ),
+                    (
+                        31,
+                        "ill-formed document: start tag not closed: `</ul>` not found before end of input".into()
+                    ),
+                ]
+            );
+        }
+    }
+
+    mod end_names {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        /// Names of end tags should be matched case-insensitively and `End`
+        /// events should use the names of start tags, as implied end tags do
+        #[test]
+        fn case_insensitive() {
+            let mut reader = html("<DIV><span>x</SPAN></div><Ul><li>y</uL>");
+
+            assert_eq!(
+                events(&mut reader),
+                [
+                    Event::Start(BytesStart::new("DIV")),
+                    Event::Start(BytesStart::new("span")),
+                    Event::Text(BytesText::new("x")),
+                    Event::End(BytesEnd::new("span")),
+                    Event::End(BytesEnd::new("DIV")),
+                    Event::Start(BytesStart::new("Ul")),
+                    Event::Start(BytesStart::new("li")),
+                    Event::Text(BytesText::new("y")),
+                    Event::End(BytesEnd::new("li")),
+                    Event::End(BytesEnd::new("Ul")),
+                ]
+            );
+        }
+
+        /// End tag of an outer element should close inner elements
+        #[test]
+        fn outer() {
+            let mut reader = html("<div><b><i>x</div>");
+
+            assert_eq!(
+                events(&mut reader),
+                [
+                    Event::Start(BytesStart::new("div")),
+                    Event::Start(BytesStart::new("b")),
+                    Event::Start(BytesStart::new("i")),
+                    Event::Text(BytesText::new("x")),
+                    Event::End(BytesEnd::new("i")),
+                    Event::End(BytesEnd::new("b")),
+                    Event::End(BytesEnd::new("div")),
+                ]
+            );
+        }
+
+        /// End tag which does not match any opened element is still an error
+        #[test]
+        fn unmatched() {
+            let mut reader = html("<div></span>");
+
+            assert_eq!(
+                reader.read_event().unwrap(),
+                Event::Start(BytesStart::new("div"))
+            );
+            match reader.read_event() {
+                Err(Error::IllFormed(cause)) => assert_eq!(
+                    cause,
+                    IllFormedError::MismatchedEndTag {
+                        expected: "div".into(),
+                        found: "span".into(),
+                    }
+                ),
+                x => panic!("Expected `Err(IllFormed(_))`, but got `{:?}`", x),
+            }
+        }
+    }
+}
+
+mod max_attributes {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::errors::Limit;
+
+    #[test]
+    fn exact() {
+        let mut reader = Reader::from_str("<a x='1' y='2'/><b x='1' y='2'>");
+        reader.config_mut().max_attributes = 2;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::Empty(_)));
+        assert!(matches!(reader.read_event().unwrap(), Event::Start(_)));
+    }
+
+    #[test]
+    fn exceeded() {
+        let mut reader = Reader::from_str("<a x='1'/><b x='1' y='2' z='3'/>");
+        reader.config_mut().max_attributes = 2;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::Empty(_)));
+        match reader.read_event() {
+            Err(Error::LimitExceeded(limit)) => assert_eq!(limit, Limit::Attributes(2)),
+            x => panic!("Expected `Err(LimitExceeded(_))`, but got `{:?}`", x),
+        }
+        assert_eq!(reader.error_position(), 10);
+        // Reading is stopped after exceeding the limit
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    /// Duplicated and malformed attributes are counted too
+    #[test]
+    fn malformed() {
+        let mut reader = Reader::from_str("<a x='1' x='2' y>");
+        reader.config_mut().max_attributes = 2;
+
+        match reader.read_event() {
+            Err(Error::LimitExceeded(limit)) => assert_eq!(limit, Limit::Attributes(2)),
+            x => panic!("Expected `Err(LimitExceeded(_))`, but got `{:?}`", x),
+        }
+    }
+}
+
+mod max_depth {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::errors::Limit;
+
+    #[test]
+    fn exact() {
+        let mut reader = Reader::from_str("<a><b/><b></b></a>");
+        reader.config_mut().max_depth = 2;
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("a"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Empty(BytesStart::new("b"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("b"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("b")));
+        assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("a")));
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    #[test]
+    fn start() {
+        let mut reader = Reader::from_str("<a><b><c>");
+        reader.config_mut().max_depth = 2;
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("a"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("b"))
+        );
+        match reader.read_event() {
+            Err(Error::LimitExceeded(limit)) => assert_eq!(limit, Limit::Depth(2)),
+            x => panic!("Expected `Err(LimitExceeded(_))`, but got `{:?}`", x),
+        }
+        assert_eq!(reader.error_position(), 6);
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    #[test]
+    fn empty() {
+        let mut reader = Reader::from_str("<a><b/>");
+        reader.config_mut().max_depth = 1;
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("a"))
+        );
+        match reader.read_event() {
+            Err(Error::LimitExceeded(limit)) => assert_eq!(limit, Limit::Depth(1)),
+            x => panic!("Expected `Err(LimitExceeded(_))`, but got `{:?}`", x),
+        }
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    /// Elements from the replacement texts of entities are counted together
+    /// with elements of the document
+    #[test]
+    fn entities() {
+        let mut reader = Reader::from_str(
+            r#"<!DOCTYPE a [
+                <!ENTITY inner "<c/>">
+                <!ENTITY outer "<b>&inner;</b>">
+            ]><a>&outer;</a>"#,
+        );
+        reader.config_mut().expand_entities = true;
+        reader.config_mut().max_depth = 2;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::DocType(_)));
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("a"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("b"))
+        );
+        match reader.read_event() {
+            Err(Error::LimitExceeded(limit)) => assert_eq!(limit, Limit::Depth(2)),
+            x => panic!("Expected `Err(LimitExceeded(_))`, but got `{:?}`", x),
+        }
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+}
+
+mod max_entity_expansion {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::errors::Limit;
+
+    const XML: &str = r#"<!DOCTYPE lolz [
+        <!ENTITY lol "lol">
+        <!ENTITY lol1 "&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;">
+        <!ENTITY lol2 "&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;">
+        <!ENTITY lol3 "&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;">
+        <!ENTITY lol4 "&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;&lol3;">
+        <!ENTITY lol5 "&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;&lol4;">
+        <!ENTITY lol6 "&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;&lol5;">
+        <!ENTITY lol7 "&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;&lol6;">
+        <!ENTITY lol8 "&lol7;&lol7;&lol7;&lol7;&lol7;&lol7;&lol7;&lol7;&lol7;&lol7;">
+        <!ENTITY lol9 "&lol8;&lol8;&lol8;&lol8;&lol8;&lol8;&lol8;&lol8;&lol8;&lol8;">
+    ]><lolz>&lol9;</lolz>"#;
+
+    #[test]
+    fn billion_laughs() {
+        let mut reader = Reader::from_str(XML);
+        reader.config_mut().expand_entities = true;
+
+        let mut count = 0;
+        let error = loop {
+            match reader.read_event() {
+                Ok(Event::Eof) => panic!("Expected `Err(LimitExceeded(_))`"),
+                Ok(_) => count += 1,
+                Err(error) => break error,
+            }
+        };
+        match error {
+            Error::LimitExceeded(limit) => {
+                assert_eq!(limit, Limit::EntityExpansion(1024 * 1024))
+            }
+            x => panic!("Expected `LimitExceeded(_)`, but got `{:?}`", x),
+        }
+        // DocType, Start and texts from expansion of the entities
+        assert!(count < 1024 * 1024 / 3, "{}", count);
+        // Reading is stopped after exceeding the limit
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    #[test]
+    fn small() {
+        let mut reader = Reader::from_str(XML);
+        reader.config_mut().expand_entities = true;
+        reader.config_mut().max_entity_expansion = 10;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::DocType(_)));
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("lolz"))
+        );
+        match reader.read_event() {
+            Err(Error::LimitExceeded(limit)) => assert_eq!(limit, Limit::EntityExpansion(10)),
+            x => panic!("Expected `Err(LimitExceeded(_))`, but got `{:?}`", x),
+        }
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+}
+
+mod max_events {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::errors::Limit;
+    use quick_xml::reader::{PushEvent, PushReader};
+
+    #[test]
+    fn exact() {
+        let mut reader = Reader::from_str("<a><b/></a>");
+        reader.config_mut().max_events = 3;
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("a"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Empty(BytesStart::new("b"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("a")));
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    #[test]
+    fn exceeded() {
+        let mut reader = Reader::from_str("<a><b/></a>");
+        reader.config_mut().max_events = 2;
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("a"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Empty(BytesStart::new("b"))
+        );
+        match reader.read_event() {
+            Err(Error::LimitExceeded(limit)) => assert_eq!(limit, Limit::Events(2)),
+            x => panic!("Expected `Err(LimitExceeded(_))`, but got `{:?}`", x),
+        }
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    /// Events from the replacement texts of entities are counted too
+    #[test]
+    fn entities() {
+        let mut reader = Reader::from_str(r#"<!DOCTYPE a [<!ENTITY e "<b/><b/>">]><a>&e;</a>"#);
+        reader.config_mut().expand_entities = true;
+        reader.config_mut().max_events = 3;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::DocType(_)));
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("a"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Empty(BytesStart::new("b"))
+        );
+        match reader.read_event() {
+            Err(Error::LimitExceeded(limit)) => assert_eq!(limit, Limit::Events(3)),
+            x => panic!("Expected `Err(LimitExceeded(_))`, but got `{:?}`", x),
+        }
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    #[test]
+    fn push() {
+        let mut reader = PushReader::new();
+        reader.config_mut().max_events = 1;
+        reader.feed(b"<a><b/>");
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            PushEvent::Event(Event::Start(BytesStart::new("a")))
+        );
+        match reader.read_event() {
+            Err(Error::LimitExceeded(limit)) => assert_eq!(limit, Limit::Events(1)),
+            x => panic!("Expected `Err(LimitExceeded(_))`, but got `{:?}`", x),
+        }
+    }
+}
+
+mod max_name_length {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::errors::Limit;
+
+    #[test]
+    fn exact() {
+        let mut reader = Reader::from_str("<abc def='1'></abc>");
+        reader.config_mut().max_name_length = 3;
+
+        assert!(matches!(reader.read_event().unwrap(), Event::Start(_)));
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::End(BytesEnd::new("abc"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    #[test]
+    fn element() {
+        let mut reader = Reader::from_str("<a/><ns:abc/>");
+        reader.config_mut().max_name_length = 3;
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Empty(BytesStart::new("a"))
+        );
+        match reader.read_event() {
+            Err(Error::LimitExceeded(limit)) => assert_eq!(limit, Limit::NameLength(3)),
+            x => panic!("Expected `Err(LimitExceeded(_))`, but got `{:?}`", x),
+        }
+        assert_eq!(reader.error_position(), 4);
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    #[test]
+    fn attribute() {
+        let mut reader = Reader::from_str("<a abcd='1'/>");
+        reader.config_mut().max_name_length = 3;
+
+        match reader.read_event() {
+            Err(Error::LimitExceeded(limit)) => assert_eq!(limit, Limit::NameLength(3)),
+            x => panic!("Expected `Err(LimitExceeded(_))`, but got `{:?}`", x),
+        }
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    #[test]
+    fn end() {
+        let mut reader = Reader::from_str("<a></abcd>");
+        reader.config_mut().check_end_names = false;
+        reader.config_mut().max_name_length = 3;
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("a"))
+        );
+        match reader.read_event() {
+            Err(Error::LimitExceeded(limit)) => assert_eq!(limit, Limit::NameLength(3)),
+            x => panic!("Expected `Err(LimitExceeded(_))`, but got `{:?}`", x),
+        }
+        assert_eq!(reader.error_position(), 3);
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+}
+
+mod max_text_size {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::errors::Limit;
+    use quick_xml::reader::{PushEvent, PushReader};
+    use std::io::{repeat, BufReader, Read};
+
+    #[test]
+    fn exact() {
+        let mut reader = Reader::from_str("twelve chars<!--12345--><![CDATA[]]><a/>&ab;");
+        reader.config_mut().max_text_size = 12;
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("twelve chars"))
+        );
+        assert!(matches!(reader.read_event().unwrap(), Event::Comment(_)));
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::CData(BytesCData::new(""))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Empty(BytesStart::new("a"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::GeneralRef(BytesRef::new("ab"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    macro_rules! check {
+        ($name:ident, $input:literal, $position:literal) => {
+            mod $name {
+                use super::*;
+                use pretty_assertions::assert_eq;
+
+                #[test]
+                fn borrowed() {
+                    let mut reader = Reader::from_str($input);
+                    reader.config_mut().max_text_size = 4;
+
+                    match reader.read_event() {
+                        Err(Error::LimitExceeded(limit)) => assert_eq!(limit, Limit::TextSize(4)),
+                        x => panic!("Expected `Err(LimitExceeded(_))`, but got `{:?}`", x),
+                    }
+                    assert_eq!(reader.error_position(), $position);
+                    assert_eq!(reader.read_event().unwrap(), Event::Eof);
+                }
+
+                #[test]
+                fn buffered() {
+                    let mut reader = Reader::from_reader($input.as_bytes());
+                    reader.config_mut().max_text_size = 4;
+
+                    let mut buf = Vec::new();
+                    match reader.read_event_into(&mut buf) {
+                        Err(Error::LimitExceeded(limit)) => assert_eq!(limit, Limit::TextSize(4)),
+                        x => panic!("Expected `Err(LimitExceeded(_))`, but got `{:?}`", x),
+                    }
+                    assert_eq!(reader.error_position(), $position);
+                    assert!(buf.len() <= 4, "{:?}", buf);
+                    assert_eq!(reader.read_event_into(&mut buf).unwrap(), Event::Eof);
+                }
+
+                #[test]
+                fn push() {
+                    let mut reader = PushReader::new();
+                    reader.config_mut().max_text_size = 4;
+                    reader.feed($input.as_bytes());
+                    reader.finish();
+
+                    match reader.read_event() {
+                        Err(Error::LimitExceeded(limit)) => assert_eq!(limit, Limit::TextSize(4)),
+                        x => panic!("Expected `Err(LimitExceeded(_))`, but got `{:?}`", x),
+                    }
+                    assert_eq!(reader.read_event().unwrap(), PushEvent::Event(Event::Eof));
+                }
+            }
+        };
+    }
+
+    check!(text, "texts", 0);
+    check!(text_before_markup, "texts<a/>", 0);
+    check!(reference, "&abcd;", 0);
+    check!(comment, "<!---->", 0);
+    check!(cdata, "<![CDATA[]]>", 0);
+    check!(doctype, "<!DOCTYPE e>", 0);
+    check!(pi, "<?pi?>", 0);
+    check!(start, "<abcd>", 0);
+    check!(empty, "<abc/>", 0);
+    check!(end, "</abc>", 0);
+    check!(unclosed_comment, "<!-- ", 0);
+
+    /// The buffer does not grow until the end of the comment is found
+    #[test]
+    fn endless_comment() {
+        let input = b"<!--".chain(repeat(b'-'));
+        let mut reader = Reader::from_reader(BufReader::new(input));
+        reader.config_mut().max_text_size = 1024;
+
+        let mut buf = Vec::new();
+        match reader.read_event_into(&mut buf) {
+            Err(Error::LimitExceeded(limit)) => assert_eq!(limit, Limit::TextSize(1024)),
+            x => panic!("Expected `Err(LimitExceeded(_))`, but got `{:?}`", x),
+        }
+        assert!(buf.len() <= 1024, "{}", buf.len());
+    }
+
+    /// Data are not accumulated until the end of the comment is found
+    #[test]
+    fn endless_comment_push() {
+        let mut reader = PushReader::new();
+        reader.config_mut().max_text_size = 1024;
+        reader.feed(b"<!--");
+
+        let error = loop {
+            match reader.read_event() {
+                Ok(PushEvent::NeedMoreData) => reader.feed(&[b'-'; 100]),
+                x => break x,
+            }
+        };
+        match error {
+            Err(Error::LimitExceeded(limit)) => assert_eq!(limit, Limit::TextSize(1024)),
+            x => panic!("Expected `Err(LimitExceeded(_))`, but got `{:?}`", x),
+        }
+    }
+}
+
+mod recover_errors {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::errors::SyntaxError;
+    use quick_xml::reader::{Diagnostic, Position, PushEvent, PushReader};
+
+    /// Returns the list of `(byte, error)` pairs
+    fn errors(diagnostics: &[Diagnostic]) -> Vec<(u64, String)> {
+        diagnostics
+            .iter()
+            .map(|d| (d.position.byte, d.error.to_string()))
+            .collect()
+    }
+
+    fn recovering(xml: &str) -> Reader<&[u8]> {
+        let mut reader = Reader::from_str(xml);
+        reader.config_mut().recover_errors = true;
+        reader
+    }
+
+    #[test]
+    fn false_() {
+        let mut reader = Reader::from_str("<root>a < b</root>");
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("root"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("a "))
+        );
+        // `<` is read as a start of a tag without a name
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::from_content(" b</root", 0))
+        );
+        assert!(reader.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn unescaped_lt() {
+        let mut reader = recovering("<root>a < b</root>");
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("root"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("a "))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("<"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new(" b"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::End(BytesEnd::new("root"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+
+        assert!(matches!(
+            reader.diagnostics(),
+            [Diagnostic {
+                position: Position { byte: 8, .. },
+                error: Error::IllFormed(IllFormedError::UnescapedLessThan),
+            }]
+        ));
+    }
+
+    #[test]
+    fn lt_at_eof() {
+        let mut reader = recovering("text<");
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("text"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("<"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+
+        assert!(matches!(
+            reader.diagnostics(),
+            [Diagnostic {
+                position: Position { byte: 4, .. },
+                error: Error::Syntax(SyntaxError::UnclosedTag),
+            }]
+        ));
+    }
+
+    #[test]
+    fn invalid_bang() {
+        let mut reader = recovering("<root><!x></root>");
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("root"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("<"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::from_escaped("!x>"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::End(BytesEnd::new("root"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+
+        assert!(matches!(
+            reader.diagnostics(),
+            [Diagnostic {
+                position: Position { byte: 6, .. },
+                error: Error::Syntax(SyntaxError::InvalidBangMarkup),
+            }]
+        ));
+    }
+
+    #[test]
+    fn empty_pi() {
+        let mut reader = recovering("<root><?></root>");
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("root"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("<?>"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::End(BytesEnd::new("root"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+
+        assert!(matches!(
+            reader.diagnostics(),
+            [Diagnostic {
+                position: Position { byte: 6, .. },
+                error: Error::Syntax(SyntaxError::UnclosedPI),
+            }]
+        ));
+    }
+
+    /// An end tag of an outer element closes all inner elements
+    #[test]
+    fn implied_end() {
+        let mut reader = recovering("<a><b><c></a>");
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("a"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("b"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("c"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("c")));
+        assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("b")));
+        assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("a")));
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+
+        assert_eq!(
+            errors(reader.diagnostics()),
+            [
+                (
+                    9,
+                    "ill-formed document: start tag not closed: `</c>` not found before `</a>`"
+                        .into()
+                ),
+                (
+                    9,
+                    "ill-formed document: start tag not closed: `</b>` not found before `</a>`"
+                        .into()
+                ),
+            ]
+        );
+        assert!(matches!(
+            &reader.diagnostics()[0].error,
+            Error::IllFormed(IllFormedError::ImpliedEndTag { expected, found })
+                if expected == "c" && found == "a"
+        ));
+    }
+
+    /// An end tag that does not match any opened element is returned as a text
+    #[test]
+    fn mismatched_end() {
+        let mut reader = recovering("<a></b></a>");
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("a"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("</b>"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("a")));
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+
+        assert!(matches!(
+            reader.diagnostics(),
+            [Diagnostic {
+                position: Position { byte: 3, .. },
+                error: Error::IllFormed(IllFormedError::MismatchedEndTag { .. }),
+            }]
+        ));
+    }
+
+    #[test]
+    fn unmatched_end() {
+        let mut reader = recovering("</a><b/>");
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("</a>"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Empty(BytesStart::new("b"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+
+        assert!(matches!(
+            reader.diagnostics(),
+            [Diagnostic {
+                position: Position { byte: 0, .. },
+                error: Error::IllFormed(IllFormedError::UnmatchedEndTag(_)),
+            }]
+        ));
+    }
+
+    /// Elements that are not closed at the end of input are closed
+    #[test]
+    fn unclosed_elements() {
+        let mut reader = recovering("<a><b>text");
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("a"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("b"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("text"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("b")));
+        assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("a")));
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+
+        assert_eq!(
+            errors(reader.diagnostics()),
+            [
+                (
+                    10,
+                    "ill-formed document: start tag not closed: `</b>` not found before end of input".into()
+                ),
+                (
+                    10,
+                    "ill-formed document: start tag not closed: `</a>` not found before end of input".into()
+                ),
+            ]
+        );
+    }
+
+    /// Markup that is not closed at the end of input is dropped
+    #[test]
+    fn unclosed_markup() {
+        let mut reader = recovering("<a><!-- comment");
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("a"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("a")));
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+
+        assert!(matches!(
+            reader.diagnostics(),
+            [
+                Diagnostic {
+                    position: Position { byte: 3, .. },
+                    error: Error::Syntax(SyntaxError::UnclosedComment),
+                },
+                Diagnostic {
+                    position: Position { byte: 15, .. },
+                    error: Error::IllFormed(IllFormedError::MissingEndTag(_)),
+                },
+            ]
+        ));
+    }
+
+    #[test]
+    fn dangling_amp() {
+        let mut reader = recovering("<a>x & y</a>");
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("a"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("x "))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::from_escaped("& y"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("a")));
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+
+        assert!(matches!(
+            reader.diagnostics(),
+            [Diagnostic {
+                position: Position { byte: 5, .. },
+                error: Error::IllFormed(IllFormedError::UnclosedReference),
+            }]
+        ));
+    }
+
+    #[test]
+    fn double_hyphen_in_comment() {
+        let mut reader = recovering("<!-- a -- b -->");
+        reader.config_mut().check_comments = true;
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Comment(BytesText::from_escaped(" a -- b "))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+
+        assert!(matches!(
+            reader.diagnostics(),
+            [Diagnostic {
+                position: Position { byte: 7, .. },
+                error: Error::IllFormed(IllFormedError::DoubleHyphenInComment),
+            }]
+        ));
+    }
+
+    #[test]
+    fn invalid_name() {
+        let mut reader = recovering("<a><1/></a>");
+        reader.config_mut().check_names = true;
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("a"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::new("<"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Text(BytesText::from_escaped("1/>"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("a")));
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+
+        let mut reader = recovering("<a><b-?/></a>");
+        reader.config_mut().check_names = true;
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("a"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Empty(BytesStart::new("b-?"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("a")));
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+
+        assert!(matches!(
+            reader.diagnostics(),
+            [Diagnostic {
+                position: Position { byte: 4, .. },
+                error: Error::IllFormed(IllFormedError::InvalidName(_)),
+            }]
+        ));
+    }
+
+    #[test]
+    fn track_position() {
+        let mut reader = recovering("<root>\n  <a>\n  1 < 2\n</root>");
+        reader.config_mut().track_position = true;
+
+        while reader.read_event().unwrap() != Event::Eof {}
+
+        assert_eq!(
+            reader
+                .diagnostics()
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>(),
+            [
+                "line 3, column 5: ill-formed document: `<` does not start a markup and should be escaped as `&lt;`",
+                "line 4, column 1: ill-formed document: start tag not closed: `</a>` not found before `</root>`",
+            ]
+        );
+    }
+
+    #[test]
+    fn take_diagnostics() {
+        let mut reader = recovering("<a>1 < 2</b></a>");
+
+        reader.read_event().unwrap(); // <a>
+        reader.read_event().unwrap(); // 1
+        reader.read_event().unwrap(); // <
+        assert_eq!(reader.take_diagnostics().len(), 1);
+        assert!(reader.diagnostics().is_empty());
+
+        reader.read_event().unwrap(); // 2
+        reader.read_event().unwrap(); // </b>
+        assert_eq!(
+            errors(&reader.take_diagnostics()),
+            [(
+                8,
+                "ill-formed document: expected `</a>`, but `</b>` was found".into()
+            )]
+        );
+        assert!(reader.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn ns_reader() {
+        let mut reader = NsReader::from_str("<a xmlns='urn:a'><b></a><c/>");
+        reader.config_mut().recover_errors = true;
+        let ns = || ResolveResult::Bound(Namespace(b"urn:a"));
+
+        assert_eq!(
+            reader.read_resolved_event().unwrap(),
+            (
+                ns(),
+                Event::Start(BytesStart::from_content("a xmlns='urn:a'", 1))
+            )
+        );
+        assert_eq!(
+            reader.read_resolved_event().unwrap(),
+            (ns(), Event::Start(BytesStart::new("b")))
+        );
+        assert_eq!(
+            reader.read_resolved_event().unwrap(),
+            (ns(), Event::End(BytesEnd::new("b")))
+        );
+        assert_eq!(
+            reader.read_resolved_event().unwrap(),
+            (ns(), Event::End(BytesEnd::new("a")))
+        );
+        // Namespace scopes of the closed elements are popped
+        assert_eq!(
+            reader.read_resolved_event().unwrap(),
+            (ResolveResult::Unbound, Event::Empty(BytesStart::new("c")))
+        );
+        assert_eq!(reader.take_diagnostics().len(), 1);
+    }
+
+    #[test]
+    fn push_reader() {
+        let mut reader = PushReader::new();
+        reader.config_mut().recover_errors = true;
+        // Feed data byte by byte to check that recovery does not depend on chunks
+        for byte in b"<a><b>1 < 2 & 3</a><c></d>" {
+            reader.feed(&[*byte]);
+        }
+        reader.finish();
+
+        let mut events = Vec::new();
+        loop {
+            match reader.read_event().unwrap() {
+                PushEvent::Event(Event::Eof) => break,
+                PushEvent::Event(event) => events.push(event.into_owned()),
+                PushEvent::NeedMoreData => unreachable!("all data was fed"),
+            }
+        }
+        assert_eq!(
+            events,
+            [
+                Event::Start(BytesStart::new("a")),
+                Event::Start(BytesStart::new("b")),
+                Event::Text(BytesText::new("1 ")),
+                Event::Text(BytesText::new("<")),
+                Event::Text(BytesText::new(" 2 ")),
+                Event::Text(BytesText::from_escaped("& 3")),
+                Event::End(BytesEnd::new("b")),
+                Event::End(BytesEnd::new("a")),
+                Event::Start(BytesStart::new("c")),
+                Event::Text(BytesText::new("</d>")),
+                Event::End(BytesEnd::new("c")),
+            ]
+        );
+        assert_eq!(
+            errors(&reader.take_diagnostics()),
+            [
+                (
+                    8,
+                    "ill-formed document: `<` does not start a markup and should be escaped as `&lt;`".into()
+                ),
+                (
+                    12,
+                    "ill-formed document: entity or character reference not closed: `;` not found before end of input"
+                        .into()
+                ),
+                (
+                    15,
+                    "ill-formed document: start tag not closed: `</b>` not found before `</a>`".into()
+                ),
+                (22, "ill-formed document: expected `</c>`, but `</d>` was found".into()),
+                (
+                    26,
+                    "ill-formed document: start tag not closed: `</c>` not found before end of input".into()
+                ),
+            ]
+        );
+    }
+}
+
+mod track_position {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::name::QName;
+    use quick_xml::reader::{NsReader, Position};
+    use std::io::BufReader;
+
+    const XML: &str = "<?xml version='1.0'?>\n<root>\n  <ß attr='ü'/>&amp;text\n</root>";
+    /// Positions after each event in the `XML`
+    const POSITIONS: &[(u64, u64, u64)] = &[
+        (21, 1, 22), // <?xml version='1.0'?>
+        (22, 2, 1),  // \n
+        (28, 2, 7),  // <root>
+        (31, 3, 3),  // \n__
+        (46, 3, 16), // <ß attr='ü'/>
+        (51, 3, 21), // &amp;
+        (56, 4, 1),  // text\n
+        (63, 4, 8),  // </root>
+        (63, 4, 8),  // EOF
+    ];
+
+    fn pos(byte: u64, line: u64, column: u64) -> Position {
+        Position { byte, line, column }
+    }
+
+    #[test]
+    fn false_() {
+        let mut reader = Reader::from_str("<root>\n</other>");
+        reader.config_mut().track_position = false;
+
+        reader.read_event().unwrap();
+        assert_eq!(reader.position(), pos(6, 0, 0));
+
+        reader.read_event().unwrap();
+        match reader.read_event() {
+            Err(Error::IllFormed(cause)) => assert_eq!(
+                cause,
+                IllFormedError::MismatchedEndTag {
+                    expected: "root".into(),
+                    found: "other".into(),
+                }
+            ),
+            x => panic!("Expected `Err(IllFormed(_))`, but got `{:?}`", x),
+        }
+    }
+
+    #[test]
+    fn borrowed() {
+        let mut reader = Reader::from_str(XML);
+        reader.config_mut().track_position = true;
+
+        assert_eq!(reader.position(), pos(0, 1, 1));
+        for &(byte, line, column) in POSITIONS {
+            reader.read_event().unwrap();
+            assert_eq!(reader.position(), pos(byte, line, column));
+        }
+    }
+
+    #[test]
+    fn buffered() {
+        // Feed data by one byte to check that multi-byte characters split between
+        // chunks are counted correctly
+        let mut reader = Reader::from_reader(BufReader::with_capacity(1, XML.as_bytes()));
+        reader.config_mut().track_position = true;
+
+        let mut buf = Vec::new();
+        assert_eq!(reader.position(), pos(0, 1, 1));
+        for &(byte, line, column) in POSITIONS {
+            reader.read_event_into(&mut buf).unwrap();
+            assert_eq!(reader.position(), pos(byte, line, column));
+        }
+    }
+
+    #[test]
+    fn trim_text_start() {
+        let mut reader = Reader::from_str("<a>\n\n  <b/>");
+        reader.config_mut().trim_text_start = true;
+        reader.config_mut().track_position = true;
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("a"))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Empty(BytesStart::new("b"))
+        );
+        assert_eq!(reader.position(), pos(11, 3, 7));
+    }
+
+    /// Checks that errors are reported with position of the erroneous markup
+    mod errors {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn mismatched_end_tag() {
+            let mut reader = Reader::from_str("<a>\n  <b>\n  </c>");
+            reader.config_mut().track_position = true;
+
+            for _ in 0..4 {
+                reader.read_event().unwrap();
+            }
+            match reader.read_event() {
+                Err(e @ Error::Positioned { .. }) => {
+                    assert_eq!(e.position(), Some(pos(12, 3, 3)));
+                    assert_eq!(reader.error_position(), 12);
+                    assert!(
+                        e.to_string()
+                            .starts_with("line 3, column 3: ill-formed document"),
+                        "{}",
+                        e
+                    );
+                    match e {
+                        Error::Positioned { error, .. } => assert!(matches!(
+                            *error,
+                            Error::IllFormed(IllFormedError::MismatchedEndTag { .. })
+                        )),
+                        _ => unreachable!(),
+                    }
+                }
+                x => panic!("Expected `Err(Positioned {{ .. }})`, but got `{:?}`", x),
+            }
+            // Ill-formed errors are recoverable, position should continue to be tracked
+            assert_eq!(reader.read_event().unwrap(), Event::Eof);
+            assert_eq!(reader.position(), pos(16, 3, 7));
+        }
+
+        #[test]
+        fn double_hyphen_in_comment() {
+            let mut reader = Reader::from_str("\n<!-- a -- b -->");
+            reader.config_mut().check_comments = true;
+            reader.config_mut().track_position = true;
+
+            reader.read_event().unwrap();
+            match reader.read_event() {
+                Err(Error::Positioned { position, error }) => {
+                    assert_eq!(position, pos(8, 2, 8));
+                    assert!(matches!(
+                        *error,
+                        Error::IllFormed(IllFormedError::DoubleHyphenInComment)
+                    ));
+                }
+                x => panic!("Expected `Err(Positioned {{ .. }})`, but got `{:?}`", x),
+            }
+        }
+
+        #[test]
+        fn unclosed_tag() {
+            let mut reader =
+                Reader::from_reader(BufReader::with_capacity(1, "<root>\n<ü".as_bytes()));
+            reader.config_mut().track_position = true;
+
+            let mut buf = Vec::new();
+            reader.read_event_into(&mut buf).unwrap();
+            reader.read_event_into(&mut buf).unwrap();
+            match reader.read_event_into(&mut buf) {
+                Err(Error::Positioned { position, error }) => {
+                    assert_eq!(position, pos(7, 2, 1));
+                    assert!(matches!(*error, Error::Syntax(_)));
+                }
+                x => panic!("Expected `Err(Positioned {{ .. }})`, but got `{:?}`", x),
+            }
+        }
+
+        #[test]
+        fn missed_end() {
+            let mut reader = Reader::from_str("<root>\n<child>");
+            reader.config_mut().track_position = true;
+
+            reader.read_event().unwrap();
+            match reader.read_to_end(QName(b"root")) {
+                Err(Error::Positioned { position, error }) => {
+                    assert_eq!(position, pos(14, 2, 8));
+                    assert!(matches!(
+                        *error,
+                        Error::IllFormed(IllFormedError::MissingEndTag(_))
+                    ));
+                }
+                x => panic!("Expected `Err(Positioned {{ .. }})`, but got `{:?}`", x),
+            }
+        }
+
+        #[test]
+        fn namespace() {
+            let mut reader = NsReader::from_str("<a>\n<b xmlns:xml='urn:wrong'/>");
+            reader.config_mut().track_position = true;
+
+            reader.read_event().unwrap();
+            reader.read_event().unwrap();
+            match reader.read_event() {
+                Err(Error::Positioned { position, error }) => {
+                    assert_eq!(position, pos(30, 2, 27));
+                    assert!(matches!(*error, Error::Namespace(_)));
+                }
+                x => panic!("Expected `Err(Positioned {{ .. }})`, but got `{:?}`", x),
+            }
+        }
+    }
+}
+
 mod trim_markup_names_in_closing_tags {
     use super::*;
     use pretty_assertions::assert_eq;
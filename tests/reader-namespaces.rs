@@ -503,6 +503,94 @@ fn reserved_name() {
     }
 }
 
+/// Attributes with the same local name and prefixes bound to the same namespace
+/// are forbidden by the [Namespaces in XML] specification.
+///
+/// [Namespaces in XML]: https://www.w3.org/TR/xml-names11/#uniqAttrs
+mod duplicated_attributes {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::errors::Error;
+    use quick_xml::name::NamespaceError;
+
+    #[test]
+    fn same_namespace() {
+        let mut reader =
+            NsReader::from_str(r#"<root xmlns:a="ns" xmlns:b="ns" a:attr="1" b:attr="2"/><next/>"#);
+        match reader.read_resolved_event() {
+            Err(Error::Namespace(error)) => assert_eq!(
+                error,
+                NamespaceError::DuplicatedAttribute {
+                    first: b"a:attr".to_vec(),
+                    second: b"b:attr".to_vec(),
+                }
+            ),
+            x => panic!("Expected `Err(Namespace(_))`, but got `{:?}`", x),
+        }
+        // Scope of the empty element was closed, so the next element is unbound
+        assert_eq!(
+            reader.read_resolved_event().unwrap(),
+            (Unbound, Empty(BytesStart::new("next")))
+        );
+        assert_eq!(reader.read_event().unwrap(), Eof);
+    }
+
+    #[test]
+    fn inherited_namespace() {
+        let mut reader = NsReader::from_str(
+            r#"<root xmlns:a="ns"><child xmlns:b="ns" b:attr="1" a:attr="2"></child></root>"#,
+        );
+        assert_eq!(
+            reader.read_resolved_event().unwrap(),
+            (
+                Unbound,
+                Start(BytesStart::from_content(r#"root xmlns:a="ns""#, 4))
+            )
+        );
+        match reader.read_resolved_event() {
+            Err(Error::Namespace(error)) => assert_eq!(
+                error,
+                NamespaceError::DuplicatedAttribute {
+                    first: b"b:attr".to_vec(),
+                    second: b"a:attr".to_vec(),
+                }
+            ),
+            x => panic!("Expected `Err(Namespace(_))`, but got `{:?}`", x),
+        }
+    }
+
+    #[test]
+    fn different_namespaces() {
+        let mut reader = NsReader::from_str(
+            r#"<root xmlns:a="ns1" xmlns:b="ns2" a:attr="1" b:attr="2" attr="3"/>"#,
+        );
+        assert_eq!(
+            reader.read_resolved_event().unwrap().1,
+            Empty(BytesStart::from_content(
+                r#"root xmlns:a="ns1" xmlns:b="ns2" a:attr="1" b:attr="2" attr="3""#,
+                4
+            ))
+        );
+        assert_eq!(reader.read_event().unwrap(), Eof);
+    }
+
+    /// Unprefixed attributes are not in any namespace, even if a default
+    /// namespace is declared
+    #[test]
+    fn default_namespace() {
+        let mut reader =
+            NsReader::from_str(r#"<root xmlns="ns" xmlns:a="ns" attr="1" a:attr="2"/>"#);
+        assert_eq!(
+            reader.read_resolved_event().unwrap().1,
+            Empty(BytesStart::from_content(
+                r#"root xmlns="ns" xmlns:a="ns" attr="1" a:attr="2""#,
+                4
+            ))
+        );
+        assert_eq!(reader.read_event().unwrap(), Eof);
+    }
+}
+
 mod read_to_end {
     use super::*;
     use pretty_assertions::assert_eq;
@@ -0,0 +1,394 @@
+//! Contains tests for the push-based reader.
+
+use quick_xml::errors::{Error, IllFormedError, SyntaxError};
+use quick_xml::events::{BytesEnd, BytesPI, BytesStart, BytesText, Event};
+use quick_xml::reader::{Config, Position, PushEvent, PushReader, Reader};
+
+use pretty_assertions::assert_eq;
+
+/// Reads all events from `xml` using the ordinary reader. Errors are converted
+/// to strings, because `Error` does not implement `PartialEq`
+fn pull(xml: &[u8], config: &Config) -> Vec<Result<Event<'static>, String>> {
+    let mut reader = Reader::from_reader(xml);
+    *reader.config_mut() = config.clone();
+
+    let mut events = Vec::new();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Eof) => break,
+            Ok(event) => events.push(Ok(event.into_owned())),
+            Err(e) => {
+                events.push(Err(e.to_string()));
+                if !matches!(e, Error::IllFormed(_)) {
+                    break;
+                }
+            }
+        }
+    }
+    events
+}
+
+/// Reads all events from `xml` using the push reader, feeding data by chunks
+/// of `size` bytes
+fn push(xml: &[u8], config: &Config, size: usize) -> Vec<Result<Event<'static>, String>> {
+    let mut reader = PushReader::new();
+    *reader.config_mut() = config.clone();
+
+    let mut chunks = xml.chunks(size);
+    let mut events = Vec::new();
+    loop {
+        match reader.read_event() {
+            Ok(PushEvent::NeedMoreData) => match chunks.next() {
+                Some(chunk) => reader.feed(chunk),
+                None => reader.finish(),
+            },
+            Ok(PushEvent::Event(Event::Eof)) => break,
+            Ok(PushEvent::Event(event)) => events.push(Ok(event.into_owned())),
+            Err(e) => {
+                events.push(Err(e.to_string()));
+                if !matches!(e, Error::IllFormed(_)) {
+                    break;
+                }
+            }
+        }
+    }
+    events
+}
+
+/// Checks that push reader produces the same events as the ordinary reader
+/// regardless of how input is split into chunks. For big documents only some
+/// chunk sizes are checked
+fn check(xml: &str, config: Config) {
+    check_bytes(xml.as_bytes(), config);
+}
+
+/// The same as [`check`], but for documents in any encoding
+fn check_bytes(xml: &[u8], config: Config) {
+    let expected = pull(xml, &config);
+    let len = xml.len().max(1);
+    for size in (1..=len.min(64)).chain(std::iter::once(len)) {
+        assert_eq!(push(xml, &config, size), expected, "chunk size: {}", size);
+    }
+}
+
+/// Checks that events are the same as produced by the ordinary reader
+mod same_as_reader {
+    use super::*;
+
+    macro_rules! check {
+        ($name:ident: $xml:expr) => {
+            check!($name: $xml, |_config| {});
+        };
+        ($name:ident: $xml:expr, $configure:expr) => {
+            #[test]
+            fn $name() {
+                let mut config = Config::default();
+                #[allow(clippy::redundant_closure_call)]
+                ($configure)(&mut config);
+                check($xml, config);
+            }
+        };
+    }
+
+    check!(empty: "");
+    check!(text: "text only");
+    check!(bom: "\u{FEFF}<root/>");
+    check!(decl: "<?xml version='1.0' encoding='utf-8'?>\n<root/>");
+    check!(pi: "<?pi with content?><?pi?>");
+    check!(comment: "<!-- co-mm>en-t -->text<!---->");
+    check!(cdata: "<![CDATA[ <cdata> ]]]>]]>text");
+    check!(doctype: "<!DOCTYPE root [<!ENTITY e '>'> <!-- > -->]><root>&e;</root>");
+    check!(attributes: "<root a='>' b=\"'>\"><empty attr='/>'/></root>");
+    check!(references: "<root>text &amp; &#x20; &lt;more text&gt;</root>");
+    check!(multibyte: "<корень атрибут='значение'>текст</корень>");
+
+    check!(expand_empty_elements: "<root><empty/></root>", |config: &mut Config| {
+        config.expand_empty_elements = true;
+    });
+    check!(trim_text: "<root>\n  <child> text </child>\n  </root>\n", |config: &mut Config| {
+        config.trim_text(true);
+    });
+    check!(trim_text_before_reference: "  \n &amp;", |config: &mut Config| {
+        config.trim_text(true);
+    });
+    check!(track_position: "<root>\n  <ч attr='ü'/>&amp;text\n</root>", |config: &mut Config| {
+        config.track_position = true;
+    });
+
+    check!(document: include_str!("documents/sample_ns.xml"));
+    check!(rss: include_str!("documents/sample_rss.xml"));
+
+    /// Checks that errors are the same as produced by the ordinary reader
+    mod errors {
+        use super::*;
+
+        check!(mismatched_end: "<root></other>text");
+        check!(unmatched_end: "</root>text");
+        check!(double_hyphen_in_comment: "<!-- -- -->text", |config: &mut Config| {
+            config.check_comments = true;
+        });
+        check!(dangling_amp: "text & more &amp", |config: &mut Config| {
+            config.allow_dangling_amp = true;
+        });
+        check!(unclosed_reference: "text &amp <root/> &");
+        check!(unclosed_tag: "<root attr='>");
+        check!(unclosed_end: "<root></root");
+        check!(unclosed_bang: "<!");
+        check!(invalid_bang: "<!x>");
+        check!(unclosed_comment: "<!-- comment -");
+        check!(unclosed_cdata: "<![CDATA[ cdata ]");
+        check!(unclosed_doctype: "<!DOCTYPE root [<!ENTITY e '>'>");
+        check!(unclosed_pi: "<?pi ?");
+        check!(lone_lt: "text <");
+    }
+}
+
+#[test]
+fn need_more_data() {
+    let mut reader = PushReader::new();
+    assert_eq!(reader.read_event().unwrap(), PushEvent::NeedMoreData);
+
+    reader.feed(b"<root");
+    assert_eq!(reader.read_event().unwrap(), PushEvent::NeedMoreData);
+
+    reader.feed(b">text");
+    assert_eq!(
+        reader.read_event().unwrap(),
+        PushEvent::Event(Event::Start(BytesStart::new("root")))
+    );
+    // Text can continue in the next chunk
+    assert_eq!(reader.read_event().unwrap(), PushEvent::NeedMoreData);
+    assert_eq!(reader.read_event().unwrap(), PushEvent::NeedMoreData);
+
+    reader.feed(b"</root>");
+    assert_eq!(
+        reader.read_event().unwrap(),
+        PushEvent::Event(Event::Text(BytesText::new("text")))
+    );
+    assert_eq!(
+        reader.read_event().unwrap(),
+        PushEvent::Event(Event::End(BytesEnd::new("root")))
+    );
+    assert_eq!(reader.read_event().unwrap(), PushEvent::NeedMoreData);
+    assert_eq!(reader.buffer_position(), 17);
+
+    reader.finish();
+    assert_eq!(reader.read_event().unwrap(), PushEvent::Event(Event::Eof));
+    assert_eq!(reader.read_event().unwrap(), PushEvent::Event(Event::Eof));
+}
+
+/// Empty chunks should not reset the state of markup parsers
+#[test]
+fn empty_chunk() {
+    let mut reader = PushReader::new();
+    reader.feed(b"<?pi ?");
+    assert_eq!(reader.read_event().unwrap(), PushEvent::NeedMoreData);
+
+    reader.feed(b"");
+    assert_eq!(reader.read_event().unwrap(), PushEvent::NeedMoreData);
+
+    reader.feed(b">");
+    assert_eq!(
+        reader.read_event().unwrap(),
+        PushEvent::Event(Event::PI(BytesPI::new("pi ")))
+    );
+}
+
+#[test]
+fn text_at_end() {
+    let mut reader = PushReader::new();
+    reader.feed(b"<root/>text");
+
+    assert_eq!(
+        reader.read_event().unwrap(),
+        PushEvent::Event(Event::Empty(BytesStart::new("root")))
+    );
+    assert_eq!(reader.read_event().unwrap(), PushEvent::NeedMoreData);
+
+    reader.finish();
+    assert_eq!(
+        reader.read_event().unwrap(),
+        PushEvent::Event(Event::Text(BytesText::new("text")))
+    );
+    assert_eq!(reader.read_event().unwrap(), PushEvent::Event(Event::Eof));
+}
+
+#[test]
+fn unclosed_tag() {
+    let mut reader = PushReader::new();
+    reader.feed(b"<root>\n<child");
+    reader.config_mut().track_position = true;
+
+    assert_eq!(
+        reader.read_event().unwrap(),
+        PushEvent::Event(Event::Start(BytesStart::new("root")))
+    );
+    assert_eq!(
+        reader.read_event().unwrap(),
+        PushEvent::Event(Event::Text(BytesText::new("\n")))
+    );
+    assert_eq!(reader.read_event().unwrap(), PushEvent::NeedMoreData);
+
+    reader.finish();
+    match reader.read_event() {
+        Err(Error::Positioned { position, error }) => {
+            assert_eq!(
+                position,
+                Position {
+                    byte: 7,
+                    line: 2,
+                    column: 1,
+                }
+            );
+            assert!(matches!(*error, Error::Syntax(SyntaxError::UnclosedTag)));
+        }
+        x => panic!("Expected `Err(Positioned {{ .. }})`, but got `{:?}`", x),
+    }
+    assert_eq!(reader.error_position(), 7);
+    assert_eq!(reader.read_event().unwrap(), PushEvent::Event(Event::Eof));
+}
+
+/// Ill-formed errors are recoverable, reader should continue reading after them
+#[test]
+fn recover_after_ill_formed() {
+    let mut reader = PushReader::new();
+    reader.feed(b"<a></b><c/>");
+
+    assert_eq!(
+        reader.read_event().unwrap(),
+        PushEvent::Event(Event::Start(BytesStart::new("a")))
+    );
+    match reader.read_event() {
+        Err(Error::IllFormed(cause)) => assert_eq!(
+            cause,
+            IllFormedError::MismatchedEndTag {
+                expected: "a".into(),
+                found: "b".into(),
+            }
+        ),
+        x => panic!("Expected `Err(IllFormed(_))`, but got `{:?}`", x),
+    }
+    assert_eq!(
+        reader.read_event().unwrap(),
+        PushEvent::Event(Event::Empty(BytesStart::new("c")))
+    );
+}
+
+/// UTF-16 documents are transcoded to UTF-8 before parsing
+#[cfg(feature = "encoding")]
+mod utf16 {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::encoding::EncodingError;
+
+    const XML: &str =
+        "<?xml version='1.0' encoding='UTF-16'?>\n<корень атрибут='😀'>текст &amp; 😀</корень>";
+
+    fn utf16le(bom: bool, xml: &str) -> Vec<u8> {
+        let mut bytes = if bom { vec![0xFF, 0xFE] } else { Vec::new() };
+        for unit in xml.encode_utf16() {
+            bytes.extend_from_slice(&unit.to_le_bytes());
+        }
+        bytes
+    }
+
+    fn utf16be(bom: bool, xml: &str) -> Vec<u8> {
+        let mut bytes = if bom { vec![0xFE, 0xFF] } else { Vec::new() };
+        for unit in xml.encode_utf16() {
+            bytes.extend_from_slice(&unit.to_be_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn le_with_bom() {
+        check_bytes(&utf16le(true, XML), Config::default());
+    }
+
+    #[test]
+    fn be_with_bom() {
+        check_bytes(&utf16be(true, XML), Config::default());
+    }
+
+    #[test]
+    fn le_without_bom() {
+        check_bytes(&utf16le(false, XML), Config::default());
+    }
+
+    #[test]
+    fn be_without_bom() {
+        check_bytes(&utf16be(false, XML), Config::default());
+    }
+
+    #[test]
+    fn events() {
+        let mut reader = PushReader::new();
+        for byte in utf16le(true, "<root>текст</root>") {
+            reader.feed(&[byte]);
+        }
+        reader.finish();
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            PushEvent::Event(Event::Start(BytesStart::new("root")))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            PushEvent::Event(Event::Text(BytesText::new("текст")))
+        );
+        assert_eq!(
+            reader.read_event().unwrap(),
+            PushEvent::Event(Event::End(BytesEnd::new("root")))
+        );
+        assert_eq!(reader.read_event().unwrap(), PushEvent::Event(Event::Eof));
+        // Events contain transcoded data
+        assert_eq!(reader.decoder().encoding(), encoding_rs::UTF_8);
+    }
+
+    /// Events before the malformed data are returned, then an error
+    #[test]
+    fn malformed() {
+        let mut xml = utf16le(true, "<root>text");
+        // Unpaired low surrogate
+        xml.extend_from_slice(&[0x00, 0xDC]);
+        xml.extend_from_slice(&utf16le(false, "</root>"));
+
+        let mut reader = PushReader::new();
+        reader.feed(&xml);
+        reader.finish();
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            PushEvent::Event(Event::Start(BytesStart::new("root")))
+        );
+        match reader.read_event() {
+            Err(Error::Encoding(EncodingError::Other(encoding))) => {
+                assert_eq!(encoding, encoding_rs::UTF_16LE)
+            }
+            x => panic!("Expected `Err(Encoding(_))`, but got `{:?}`", x),
+        }
+        assert_eq!(reader.error_position(), 10);
+        assert_eq!(reader.read_event().unwrap(), PushEvent::Event(Event::Eof));
+    }
+
+    /// Incomplete code unit at the end of input is an error
+    #[test]
+    fn truncated() {
+        let mut xml = utf16le(true, "<root/>");
+        xml.push(b'x');
+
+        let mut reader = PushReader::new();
+        reader.feed(&xml);
+        assert_eq!(
+            reader.read_event().unwrap(),
+            PushEvent::Event(Event::Empty(BytesStart::new("root")))
+        );
+        assert_eq!(reader.read_event().unwrap(), PushEvent::NeedMoreData);
+
+        reader.finish();
+        assert!(matches!(
+            reader.read_event(),
+            Err(Error::Encoding(EncodingError::Other(_)))
+        ));
+    }
+}
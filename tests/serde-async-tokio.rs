@@ -0,0 +1,330 @@
+use pretty_assertions::assert_eq;
+use quick_xml::de::{from_async_reader, read_element_async};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::reader::NsReader;
+use quick_xml::se::{
+    self, to_async_writer, to_async_writer_with, to_async_writer_with_root, QuoteLevel, Serializer,
+};
+use quick_xml::writer::Writer;
+use quick_xml::DeError;
+use serde::{Deserialize, Serialize};
+use tokio::io::BufReader;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Item {
+    #[serde(rename = "@id")]
+    id: u32,
+    #[serde(default)]
+    value: Option<String>,
+}
+
+#[tokio::test]
+async fn root() {
+    let xml = r#"
+        <?xml version="1.0"?>
+        <!-- comment -->
+        <item id="1">
+            <value>text &amp; reference</value>
+        </item>
+        <!-- trailing comment -->
+    "#;
+    let item: Item = from_async_reader(xml.as_bytes()).await.unwrap();
+    assert_eq!(
+        item,
+        Item {
+            id: 1,
+            value: Some("text & reference".into()),
+        }
+    );
+}
+
+#[tokio::test]
+async fn empty_root() {
+    let item: Item = from_async_reader(r#"<item id="1"/>"#.as_bytes())
+        .await
+        .unwrap();
+    assert_eq!(item, Item { id: 1, value: None });
+}
+
+#[tokio::test]
+async fn no_root() {
+    match from_async_reader::<_, Item>("<!-- comment -->".as_bytes()).await {
+        Err(DeError::UnexpectedEof) => {}
+        x => panic!("Expected `Err(UnexpectedEof)`, but got {:?}", x),
+    }
+}
+
+/// Reading from a reader with a buffer smaller than the document
+#[tokio::test]
+async fn small_buffer() {
+    let xml = format!(
+        r#"<item id="2"><value>{}</value></item>"#,
+        "long text ".repeat(100)
+    );
+    let reader = BufReader::with_capacity(16, xml.as_bytes());
+    let item: Item = from_async_reader(reader).await.unwrap();
+    assert_eq!(item.value.unwrap().len(), 1000);
+}
+
+#[tokio::test]
+async fn stream() {
+    let xml = r#"
+        <stream>
+            <item id="1"/>
+            text
+            <item id="2"><value>two</value></item>
+        </stream>
+        <item id="3"/>
+    "#;
+    let mut reader = NsReader::from_reader(xml.as_bytes());
+    let mut buf = Vec::new();
+    loop {
+        if let Event::Start(_) = reader.read_event_into_async(&mut buf).await.unwrap() {
+            break;
+        }
+    }
+
+    let mut items = Vec::new();
+    while let Some(item) = read_element_async::<_, Item>(&mut reader).await.unwrap() {
+        items.push(item);
+    }
+    assert_eq!(
+        items,
+        vec![
+            Item { id: 1, value: None },
+            Item {
+                id: 2,
+                value: Some("two".into()),
+            },
+        ]
+    );
+
+    // </stream> was consumed, the reader continues after it
+    assert_eq!(
+        read_element_async::<_, Item>(&mut reader).await.unwrap(),
+        Some(Item { id: 3, value: None })
+    );
+    assert_eq!(
+        read_element_async::<_, Item>(&mut reader).await.unwrap(),
+        None
+    );
+}
+
+/// Namespaces declared on the ancestors are visible to the deserializer
+#[tokio::test]
+async fn inherited_namespaces() {
+    let xml = r#"
+        <stream xmlns:xsi='http://www.w3.org/2001/XMLSchema-instance'>
+            <item xsi:nil="true"/>
+            <item xmlns:xsi="urn:other" xsi:nil="true"/>
+        </stream>
+    "#;
+    let mut reader = NsReader::from_reader(xml.as_bytes());
+    let mut buf = Vec::new();
+    loop {
+        if let Event::Start(_) = reader.read_event_into_async(&mut buf).await.unwrap() {
+            break;
+        }
+    }
+
+    let item: Option<Option<()>> = read_element_async(&mut reader).await.unwrap();
+    assert_eq!(item, Some(None));
+    // `xsi` is bound to another namespace, so this is not `xsi:nil`
+    let item: Option<Option<()>> = read_element_async(&mut reader).await.unwrap();
+    assert_eq!(item, Some(Some(())));
+}
+
+/// The version of the document is used by the deserializer
+#[tokio::test]
+async fn xml_version() {
+    // NEL is normalized to a new line only in XML 1.1
+    let xml = "<?xml version='1.1'?><root>a\u{85}b</root>";
+    let text: String = from_async_reader(xml.as_bytes()).await.unwrap();
+    assert_eq!(text, "a\nb");
+
+    let xml = "<?xml version='1.0'?><root>a\u{85}b</root>";
+    let text: String = from_async_reader(xml.as_bytes()).await.unwrap();
+    assert_eq!(text, "a\u{85}b");
+}
+
+#[cfg(feature = "encoding")]
+#[tokio::test]
+async fn encoding() {
+    let mut xml = br#"<?xml version="1.0" encoding="windows-1251"?><root>"#.to_vec();
+    // "текст" in windows-1251
+    xml.extend_from_slice(&[0xF2, 0xE5, 0xEA, 0xF1, 0xF2]);
+    xml.extend_from_slice(b"</root>");
+
+    let text: String = from_async_reader(xml.as_slice()).await.unwrap();
+    assert_eq!(text, "текст");
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    #[serde(rename = "@title")]
+    title: &'static str,
+    row: Vec<Row>,
+}
+
+#[derive(Debug, Serialize)]
+struct Row {
+    #[serde(rename = "@id")]
+    id: u32,
+    #[serde(rename = "$text")]
+    text: &'static str,
+}
+
+const REPORT: Report = Report {
+    title: "<\"quoted\" & 'apostrophes'>",
+    row: Vec::new(),
+};
+
+fn report() -> Report {
+    Report {
+        row: vec![
+            Row { id: 1, text: "one" },
+            Row {
+                id: 2,
+                text: "two & <three>",
+            },
+        ],
+        ..REPORT
+    }
+}
+
+/// Async serializer produces the same output as the sync one
+#[tokio::test]
+async fn to_writer() {
+    let mut buffer = Vec::new();
+    to_async_writer(&mut buffer, &report()).await.unwrap();
+    assert_eq!(
+        String::from_utf8(buffer).unwrap(),
+        se::to_string(&report()).unwrap()
+    );
+
+    let mut buffer = Vec::new();
+    to_async_writer_with_root(&mut buffer, "report", &report())
+        .await
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(buffer).unwrap(),
+        se::to_string_with_root("report", &report()).unwrap()
+    );
+}
+
+/// Async serializer with settings produces the same output as the sync one
+/// with the same settings
+#[tokio::test]
+async fn to_writer_with() {
+    let mut buffer = Vec::new();
+    to_async_writer_with(&mut buffer, None, &report(), |ser| {
+        ser.indent('\t', 1).set_quote_level(QuoteLevel::Minimal);
+    })
+    .await
+    .unwrap();
+
+    let mut expected = String::new();
+    let mut ser = Serializer::new(&mut expected);
+    ser.indent('\t', 1).set_quote_level(QuoteLevel::Minimal);
+    report().serialize(ser).unwrap();
+
+    assert_eq!(String::from_utf8(buffer).unwrap(), expected);
+
+    let mut buffer = Vec::new();
+    to_async_writer_with(&mut buffer, Some("report"), &report(), |ser| {
+        ser.indent(' ', 4)
+            .set_quote_level(QuoteLevel::Full)
+            .expand_empty_elements(true);
+    })
+    .await
+    .unwrap();
+
+    let mut expected = String::new();
+    let mut ser = Serializer::with_root(&mut expected, Some("report")).unwrap();
+    ser.indent(' ', 4)
+        .set_quote_level(QuoteLevel::Full)
+        .expand_empty_elements(true);
+    report().serialize(ser).unwrap();
+
+    assert_eq!(String::from_utf8(buffer).unwrap(), expected);
+    // Settings were applied
+    assert!(expected.contains("\n    <row id=\"1\">one</row>"));
+    assert!(expected.contains("&lt;&quot;quoted&quot; &amp; &apos;apostrophes&apos;&gt;"));
+}
+
+#[tokio::test]
+async fn to_writer_invalid_root() {
+    let mut buffer = Vec::new();
+    assert!(
+        to_async_writer_with_root(&mut buffer, "<invalid>", &report())
+            .await
+            .is_err()
+    );
+    assert_eq!(buffer, b"");
+
+    let mut buffer = Vec::new();
+    assert!(
+        to_async_writer_with(&mut buffer, Some("<invalid>"), &report(), |_| {})
+            .await
+            .is_err()
+    );
+    assert_eq!(buffer, b"");
+}
+
+/// `write_serializable_async` produces the same output as `write_serializable`
+#[tokio::test]
+async fn write_serializable() {
+    let mut sync = Writer::new_with_indent(Vec::new(), b' ', 2);
+    sync.write_event(Event::Start(BytesStart::new("root")))
+        .unwrap();
+    sync.write_serializable("report", &report()).unwrap();
+    sync.write_serializable("empty", &REPORT).unwrap();
+    sync.write_event(Event::End(BytesStart::new("root").to_end()))
+        .unwrap();
+
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+    writer
+        .write_event_async(Event::Start(BytesStart::new("root")))
+        .await
+        .unwrap();
+    writer
+        .write_serializable_async("report", &report())
+        .await
+        .unwrap();
+    writer
+        .write_serializable_async("empty", &REPORT)
+        .await
+        .unwrap();
+    writer
+        .write_event_async(Event::End(BytesStart::new("root").to_end()))
+        .await
+        .unwrap();
+
+    let expected = String::from_utf8(sync.into_inner()).unwrap();
+    assert_eq!(String::from_utf8(writer.into_inner()).unwrap(), expected);
+    assert_eq!(
+        expected,
+        r#"<root>
+  <report title="&lt;&quot;quoted&quot; &amp; 'apostrophes'&gt;">
+    <row id="1">one</row>
+    <row id="2">two &amp; &lt;three&gt;</row>
+  </report>
+  <empty title="&lt;&quot;quoted&quot; &amp; 'apostrophes'&gt;"/>
+</root>"#
+    );
+}
+
+/// Well-formedness of the document is checked when enabled
+#[tokio::test]
+async fn write_serializable_well_formedness() {
+    let mut writer = Writer::new(Vec::new());
+    writer.config_mut().check_well_formedness = true;
+    writer
+        .write_serializable_async("report", &REPORT)
+        .await
+        .unwrap();
+    assert!(writer
+        .write_serializable_async("report", &REPORT)
+        .await
+        .is_err());
+}
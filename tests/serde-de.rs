@@ -1894,6 +1894,148 @@ mod resolve {
             ])
         );
     }
+
+    /// Tests for the `DtdEntityResolver`
+    mod dtd {
+        use super::*;
+        use pretty_assertions::assert_eq;
+        use quick_xml::de::{DtdEntityResolver, DtdResolverError};
+        use quick_xml::escape::EscapeError;
+
+        fn resolver(dtd: &str) -> Result<DtdEntityResolver, DtdResolverError> {
+            let mut resolver = DtdEntityResolver::with_limit(100);
+            resolver.capture(BytesText::from_escaped(dtd))?;
+            Ok(resolver)
+        }
+
+        #[test]
+        fn nested() {
+            let resolver = resolver(
+                r#"root [
+                    <!ENTITY greeting "Hello, &name;&excl;">
+                    <!ENTITY name 'world'>
+                    <!ENTITY excl "&#33;">
+                    <!ENTITY % param "parameter">
+                    <!ENTITY external SYSTEM "external.xml">
+                ]"#,
+            )
+            .unwrap();
+            assert_eq!(resolver.resolve("greeting"), Some("Hello, world!"));
+            assert_eq!(resolver.resolve("name"), Some("world"));
+            assert_eq!(resolver.resolve("excl"), Some("!"));
+            assert_eq!(resolver.resolve("lt"), Some("<"));
+            assert_eq!(resolver.resolve("param"), None);
+            assert_eq!(resolver.resolve("external"), None);
+        }
+
+        /// Character references are replaced at declaration, then the
+        /// replacement text is parsed again on expansion
+        #[test]
+        fn double_escaped() {
+            let resolver = resolver(
+                r#"root [
+                    <!ENTITY amp "&#38;#38;">
+                    <!ENTITY e "&#38;#60;&#38;amp;">
+                ]"#,
+            )
+            .unwrap();
+            assert_eq!(resolver.resolve("amp"), Some("&"));
+            assert_eq!(resolver.resolve("e"), Some("<&"));
+        }
+
+        #[test]
+        fn first_declaration_is_binding() {
+            let resolver = resolver(
+                r#"root [
+                    <!ENTITY e "first">
+                    <!ENTITY e "second">
+                ]"#,
+            )
+            .unwrap();
+            assert_eq!(resolver.resolve("e"), Some("first"));
+        }
+
+        #[test]
+        fn recursive() {
+            assert_eq!(
+                resolver(
+                    r#"root [
+                        <!ENTITY a "&b;">
+                        <!ENTITY b "&c;">
+                        <!ENTITY c "&a;">
+                    ]"#
+                )
+                .unwrap_err(),
+                DtdResolverError::RecursiveEntity("a".into())
+            );
+        }
+
+        #[test]
+        fn unknown() {
+            assert_eq!(
+                resolver(r#"root [ <!ENTITY a "x &b;"> ]"#).unwrap_err(),
+                DtdResolverError::Escape(EscapeError::UnrecognizedEntity(2..5, "b".into()))
+            );
+        }
+
+        #[test]
+        fn billion_laughs() {
+            assert_eq!(
+                resolver(
+                    r#"lolz [
+                        <!ENTITY lol "lol">
+                        <!ENTITY lol1 "&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;&lol;">
+                        <!ENTITY lol2 "&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;&lol1;">
+                        <!ENTITY lol3 "&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;&lol2;">
+                    ]"#
+                )
+                .unwrap_err(),
+                DtdResolverError::LimitExceeded(100)
+            );
+        }
+
+        #[test]
+        fn deserialize() {
+            let mut de = Deserializer::with_resolver(
+                br#"
+                <!DOCTYPE dict [
+                    <!ENTITY unc "unclassified">
+                    <!ENTITY e "&unc; &amp; &#x31;">
+                ]>
+                <root>
+                    <entity_one>&e;</entity_one>
+                    <entity_two>&lt;&unc;&gt;</entity_two>
+                </root>
+                "#
+                .as_ref(),
+                DtdEntityResolver::new(),
+            );
+
+            let data: BTreeMap<String, String> = BTreeMap::deserialize(&mut de).unwrap();
+            assert_eq!(
+                data,
+                BTreeMap::from_iter([
+                    (String::from("entity_one"), String::from("unclassified & 1")),
+                    (String::from("entity_two"), String::from("<unclassified>")),
+                ])
+            );
+        }
+
+        #[test]
+        fn deserialize_recursive() {
+            let mut de = Deserializer::with_resolver(
+                br#"<!DOCTYPE dict [ <!ENTITY e "&e;"> ]><root>&e;</root>"#.as_ref(),
+                DtdEntityResolver::new(),
+            );
+
+            match String::deserialize(&mut de) {
+                Err(DeError::Custom(e)) => {
+                    assert_eq!(e, "cannot parse DTD: entity `e` references itself")
+                }
+                x => panic!("Expected `Err(Custom(_))`, but got `{:?}`", x),
+            }
+        }
+    }
 }
 
 /// A DOCTYPE declaration inside an element's text content used to split
@@ -2130,3 +2272,257 @@ mod xml_version {
         }
     }
 }
+
+/// Checks that errors contain positions when the reader tracks them
+mod track_position {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::errors::{Error, IllFormedError};
+    use quick_xml::reader::{NsReader, Position};
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Root {
+        first: String,
+        second: u32,
+    }
+
+    fn deserialize(xml: &str) -> Result<Root, DeError> {
+        let mut reader = NsReader::from_str(xml);
+        reader.config_mut().track_position = true;
+
+        let mut de = Deserializer::borrowing(reader);
+        Root::deserialize(&mut de)
+    }
+
+    #[test]
+    fn custom() {
+        let err = deserialize("<root>\n  <first>text</first>\n</root>").unwrap_err();
+        assert_eq!(
+            err.position(),
+            Some(Position {
+                byte: 36,
+                line: 3,
+                column: 8,
+            })
+        );
+        assert_eq!(err.to_string(), "line 3, column 8: missing field `second`");
+    }
+
+    #[test]
+    fn invalid_xml() {
+        match deserialize("<root>\n  <first>text</second>\n</root>") {
+            Err(DeError::InvalidXml(Error::Positioned { position, error })) => {
+                assert_eq!(
+                    position,
+                    Position {
+                        byte: 20,
+                        line: 2,
+                        column: 14,
+                    }
+                );
+                assert!(matches!(
+                    *error,
+                    Error::IllFormed(IllFormedError::MismatchedEndTag { .. })
+                ));
+            }
+            x => panic!(
+                "Expected `Err(InvalidXml(Positioned {{ .. }}))`, but got {:?}",
+                x
+            ),
+        }
+    }
+
+    #[test]
+    fn disabled() {
+        let err = from_str::<Root>("<root>\n  <first>text</first>\n</root>").unwrap_err();
+        assert_eq!(err.position(), None);
+        assert_eq!(err.to_string(), "missing field `second`");
+    }
+}
+
+/// Checks that attributes defaulted from the DTD are deserialized when the
+/// reader is configured to add them
+mod default_attributes {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::reader::NsReader;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Root {
+        #[serde(rename = "@lang")]
+        lang: String,
+        #[serde(rename = "@version")]
+        version: u32,
+        item: Vec<Item>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Item {
+        #[serde(rename = "@kind")]
+        kind: String,
+    }
+
+    const XML: &str = r#"<!DOCTYPE root [
+        <!ATTLIST root lang CDATA "en" version CDATA #FIXED "2">
+        <!ATTLIST item kind (a | b) "a">
+    ]><root lang="de"><item/><item kind="b"/></root>"#;
+
+    #[test]
+    fn enabled() {
+        let mut reader = NsReader::from_str(XML);
+        reader.config_mut().default_attributes = true;
+
+        let mut de = Deserializer::borrowing(reader);
+        assert_eq!(
+            Root::deserialize(&mut de).unwrap(),
+            Root {
+                lang: "de".into(),
+                version: 2,
+                item: vec![Item { kind: "a".into() }, Item { kind: "b".into() }],
+            }
+        );
+    }
+
+    #[test]
+    fn disabled() {
+        let err = from_str::<Root>(XML).unwrap_err();
+        assert_eq!(err.to_string(), "missing field `@kind`");
+    }
+}
+
+/// Tests for lazy deserialization of repeated elements using `Deserializer::iter_elements`
+mod iter_elements {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Item {
+        #[serde(rename = "@id")]
+        id: u32,
+        #[serde(default)]
+        name: Option<String>,
+    }
+
+    #[test]
+    fn from_str() {
+        let mut de = Deserializer::from_str(
+            r#"
+            <?xml version="1.0"?>
+            <!-- comment -->
+            <list>
+                <item id="1"><name>first</name></item>
+                <item id="2"/>
+            </list>
+            "#,
+        );
+        let items: Vec<Item> = de.iter_elements("item").collect::<Result<_, _>>().unwrap();
+        assert_eq!(
+            items,
+            vec![
+                Item {
+                    id: 1,
+                    name: Some("first".into()),
+                },
+                Item { id: 2, name: None },
+            ]
+        );
+        de.check_eof_reached();
+    }
+
+    /// Children with other names, text and nested elements with the same name are skipped
+    #[test]
+    fn skip_other() {
+        let mut de = Deserializer::from_reader(
+            r#"
+            <list>
+                text
+                <other><item id="0"/></other>
+                <item id="1"/>
+                <ns:item id="2" xmlns:ns="urn:ns"/>
+                <other/>
+            </list>
+            "#
+            .as_bytes(),
+        );
+        let ids: Vec<u32> = de
+            .iter_elements::<Item>("item")
+            .map(|item| item.unwrap().id)
+            .collect();
+        assert_eq!(ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn primitives() {
+        let mut de = Deserializer::from_str("<list><n>1</n><n>2</n><n>3</n></list>");
+        let sum: u32 = de.iter_elements::<u32>("n").map(Result::unwrap).sum();
+        assert_eq!(sum, 6);
+    }
+
+    #[test]
+    fn empty_container() {
+        let mut de = Deserializer::from_str("<list/>");
+        assert!(de.iter_elements::<Item>("item").next().is_none());
+        de.check_eof_reached();
+    }
+
+    #[test]
+    fn no_container() {
+        let mut de = Deserializer::from_str("");
+        let mut iter = de.iter_elements::<Item>("item");
+        match iter.next() {
+            Some(Err(DeError::UnexpectedEof)) => {}
+            x => panic!("Expected `Some(Err(UnexpectedEof))`, but got {:?}", x),
+        }
+        assert!(iter.next().is_none());
+    }
+
+    /// Iteration stops after the first error
+    #[test]
+    fn error() {
+        let mut de =
+            Deserializer::from_str(r#"<list><item id="1"/><item id="x"/><item id="3"/></list>"#);
+        let mut iter = de.iter_elements::<Item>("item");
+        assert_eq!(iter.next().unwrap().unwrap(), Item { id: 1, name: None });
+        assert!(iter.next().unwrap().is_err());
+        assert!(iter.next().is_none());
+    }
+
+    /// Deserializer can be used after the iteration
+    #[test]
+    fn after_iteration() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Trailer {
+            count: u32,
+        }
+
+        let mut de = Deserializer::from_str(
+            r#"<list><item id="1"/></list><trailer><count>1</count></trailer>"#,
+        );
+        assert_eq!(de.iter_elements::<Item>("item").count(), 1);
+        assert_eq!(Trailer::deserialize(&mut de).unwrap(), Trailer { count: 1 });
+    }
+
+    #[test]
+    fn rpm_primary() {
+        #[derive(Debug, Deserialize)]
+        struct Package {
+            name: String,
+            arch: String,
+        }
+
+        let file = std::fs::File::open("tests/documents/rpm_primary.xml").unwrap();
+        let mut de = Deserializer::from_reader(std::io::BufReader::new(file));
+        let names: Vec<String> = de
+            .iter_elements::<Package>("package")
+            .map(|package| {
+                let package = package.unwrap();
+                assert_eq!(package.arch, "x86_64");
+                package.name
+            })
+            .collect();
+        assert_eq!(names.len(), 12);
+        assert_eq!(names[0], "apr-util-devel");
+        assert_eq!(names[1], "apr-util-bdb");
+    }
+}
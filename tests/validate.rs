@@ -0,0 +1,333 @@
+use quick_xml::reader::Reader;
+use quick_xml::validate::{validate, Dtd, Validator, ValidityError, ValidityError::*};
+
+use pretty_assertions::assert_eq;
+
+/// Validates the document and returns found errors with their byte positions
+fn errors(xml: &str) -> Vec<(u64, ValidityError)> {
+    let mut reader = Reader::from_str(xml);
+    validate(&mut reader)
+        .unwrap()
+        .into_iter()
+        .map(|v| (v.position.byte, v.error))
+        .collect()
+}
+
+/// Validates the document with the specified internal subset and returns found
+/// errors without positions
+fn check(subset: &str, root: &str) -> Vec<ValidityError> {
+    let name = root[1..].split([' ', '>', '/']).next().unwrap();
+    let xml = format!("<!DOCTYPE {} [{}]>{}", name, subset, root);
+    errors(&xml).into_iter().map(|(_, e)| e).collect()
+}
+
+#[test]
+fn valid() {
+    let xml = r#"<?xml version="1.0"?>
+<!DOCTYPE book [
+    <!ELEMENT book (title, subtitle?, (chapter | appendix)+, index*)>
+    <!ELEMENT title (#PCDATA)>
+    <!ELEMENT subtitle (#PCDATA | em)*>
+    <!ELEMENT chapter ANY>
+    <!ELEMENT appendix EMPTY>
+    <!ELEMENT index (#PCDATA)>
+    <!ELEMENT em (#PCDATA)>
+    <!ENTITY logo SYSTEM "logo.png" NDATA png>
+    <!ENTITY company "ACME &amp; Co">
+    <!NOTATION png SYSTEM "image/png">
+    <!ATTLIST book
+        id      ID                  #REQUIRED
+        lang    NMTOKEN             "en"
+        status  (draft | final)     #IMPLIED
+        image   ENTITY              #IMPLIED
+        owner   CDATA               #FIXED "ACME &amp; Co"
+        version CDATA               #FIXED "1">
+    <!ATTLIST chapter
+        id      ID                  #IMPLIED
+        refs    IDREFS              #IMPLIED>
+]>
+<book id="b1" status=" final " image="logo" owner="&company;">
+    <title>Title</title>
+    <subtitle>Sub <em>title</em></subtitle>
+    <chapter id="c1" refs="b1  c2"><em>text</em></chapter>
+    <appendix/>
+    <chapter id="c2">text</chapter>
+    <index>1</index>
+    <index>2</index>
+</book>"#;
+    assert_eq!(errors(xml), []);
+}
+
+mod content {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn unexpected_element() {
+        assert_eq!(
+            check(
+                "<!ELEMENT a (b, c)><!ELEMENT b EMPTY><!ELEMENT c EMPTY>",
+                "<a><c/><b/><c/></a>"
+            ),
+            [UnexpectedElement {
+                parent: "a".into(),
+                child: "c".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn incomplete() {
+        assert_eq!(
+            check(
+                "<!ELEMENT a (b, c+)><!ELEMENT b EMPTY><!ELEMENT c EMPTY>",
+                "<a><b/></a>"
+            ),
+            [IncompleteContent("a".into())]
+        );
+    }
+
+    /// Models where the choice can be made only after several elements
+    #[test]
+    fn ambiguous() {
+        let subset =
+            "<!ELEMENT a ((b, c) | (b, d))*><!ELEMENT b EMPTY><!ELEMENT c EMPTY><!ELEMENT d EMPTY>";
+        assert_eq!(check(subset, "<a><b/><d/><b/><c/></a>"), []);
+        assert_eq!(
+            check(subset, "<a><b/><b/></a>"),
+            [
+                UnexpectedElement {
+                    parent: "a".into(),
+                    child: "b".into(),
+                },
+                IncompleteContent("a".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn mixed() {
+        let subset = "<!ELEMENT a (#PCDATA | b)*><!ELEMENT b (#PCDATA)><!ELEMENT c EMPTY>";
+        assert_eq!(check(subset, "<a>1<b>2</b>3<b/></a>"), []);
+        assert_eq!(
+            check(subset, "<a><b><c/></b></a>"),
+            [UnexpectedElement {
+                parent: "b".into(),
+                child: "c".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn text() {
+        let subset = "<!ELEMENT a (b)><!ELEMENT b EMPTY>";
+        // Whitespaces are allowed in the element content
+        assert_eq!(check(subset, "<a>\n  <b/>\n</a>"), []);
+        assert_eq!(
+            check(subset, "<a>text<b/><![CDATA[]]>&amp;</a>"),
+            [
+                UnexpectedText("a".into()),
+                UnexpectedText("a".into()),
+                UnexpectedText("a".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let subset = "<!ELEMENT a (b*)><!ELEMENT b EMPTY>";
+        assert_eq!(check(subset, "<a><b></b><b/></a>"), []);
+        assert_eq!(
+            check(subset, "<a><b> </b><b><b/></b></a>"),
+            [
+                UnexpectedText("b".into()),
+                UnexpectedElement {
+                    parent: "b".into(),
+                    child: "b".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn undeclared_element() {
+        assert_eq!(
+            check("<!ELEMENT a ANY>", r#"<a><b attr="x"/></a>"#),
+            [UndeclaredElement("b".into())]
+        );
+    }
+
+    #[test]
+    fn root_mismatch() {
+        assert_eq!(
+            errors("<!DOCTYPE a [<!ELEMENT a ANY><!ELEMENT b ANY>]><b/>"),
+            [(
+                47,
+                RootMismatch {
+                    expected: "a".into(),
+                    found: "b".into(),
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn missing_dtd() {
+        assert_eq!(errors("<a><b/></a>"), [(0, MissingDtd)]);
+    }
+}
+
+mod attributes {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn undeclared() {
+        assert_eq!(
+            check(
+                r#"<!ELEMENT a EMPTY><!ATTLIST a x CDATA #IMPLIED>"#,
+                r#"<a x="1" y="2"/>"#
+            ),
+            [UndeclaredAttribute {
+                element: "a".into(),
+                attribute: "y".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn required() {
+        assert_eq!(
+            check(
+                r#"<!ELEMENT a EMPTY><!ATTLIST a x CDATA #REQUIRED y CDATA "default">"#,
+                r#"<a/>"#
+            ),
+            [MissingAttribute {
+                element: "a".into(),
+                attribute: "x".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn fixed() {
+        let subset = r#"<!ELEMENT a EMPTY><!ATTLIST a x NMTOKENS #FIXED " 1  2 ">"#;
+        // Values are compared after normalization
+        assert_eq!(check(subset, r#"<a x="1 2 "/>"#), []);
+        assert_eq!(check(subset, r#"<a/>"#), []);
+        assert_eq!(
+            check(subset, r#"<a x="1 3"/>"#),
+            [FixedAttributeMismatch {
+                element: "a".into(),
+                attribute: "x".into(),
+                expected: "1 2".into(),
+                found: "1 3".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn types() {
+        let subset = r#"<!ELEMENT a EMPTY>
+            <!ENTITY pic SYSTEM "pic.png" NDATA png>
+            <!ENTITY text "text">
+            <!ATTLIST a
+                e   (one | two) #IMPLIED
+                n   NMTOKEN     #IMPLIED
+                ns  NMTOKENS    #IMPLIED
+                ent ENTITY      #IMPLIED
+                id  ID          #IMPLIED>"#;
+        assert_eq!(
+            check(subset, r#"<a e="one" n="-1" ns="a b" ent="pic" id="_1"/>"#),
+            []
+        );
+        let invalid = |attribute: &str, value: &str| InvalidAttributeValue {
+            element: "a".into(),
+            attribute: attribute.into(),
+            value: value.into(),
+        };
+        assert_eq!(
+            check(subset, r#"<a e="three" n="a b" ns="" ent="text" id="1"/>"#),
+            [
+                invalid("e", "three"),
+                invalid("n", "a b"),
+                invalid("ns", ""),
+                invalid("ent", "text"),
+                invalid("id", "1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn ids() {
+        let subset = r#"<!ELEMENT a (b*)><!ELEMENT b EMPTY>
+            <!ATTLIST b id ID #IMPLIED ref IDREF #IMPLIED refs IDREFS #IMPLIED>"#;
+        assert_eq!(
+            errors(&format!(
+                r#"<!DOCTYPE a [{}]><a><b ref="y" refs="x z"/><b id="x"/><b id="x"/></a>"#,
+                subset
+            )),
+            [
+                (167, DuplicateId("x".into())),
+                (133, UnknownId("y".into())),
+                (133, UnknownId("z".into())),
+            ]
+        );
+    }
+}
+
+/// Violations should be reported at the start of the event with line and column
+/// when position tracking is enabled
+#[test]
+fn position() {
+    let mut reader =
+        Reader::from_str("<!DOCTYPE a [<!ELEMENT a (b)><!ELEMENT b EMPTY>]>\n<a>\n  <c/>\n</a>");
+    reader.config_mut().track_position = true;
+
+    let violations = validate(&mut reader).unwrap();
+    assert_eq!(
+        violations.iter().map(|v| v.to_string()).collect::<Vec<_>>(),
+        [
+            "line 3, column 3: element `c` is not allowed here by the content model of `a`",
+            "line 3, column 3: element `c` is not declared",
+            "line 4, column 1: content of `a` ended before all required elements were found",
+        ]
+    );
+}
+
+/// Declarations from the internal subset should take precedence over the
+/// external DTD
+#[test]
+fn external_dtd() {
+    let dtd = Dtd::parse(r#"<!ELEMENT a (b)><!ELEMENT b EMPTY><!ATTLIST a x (1 | 2) #REQUIRED>"#)
+        .unwrap();
+    assert_eq!(dtd.root(), None);
+
+    let mut reader = Reader::from_str(
+        r#"<!DOCTYPE a SYSTEM "a.dtd" [<!ATTLIST a x CDATA #IMPLIED>]><a x="3"><c/></a>"#,
+    );
+    let mut validator = Validator::with_dtd(dtd);
+    loop {
+        let event = reader.read_event().unwrap();
+        validator.feed(&event, &reader).unwrap();
+        if let quick_xml::events::Event::Eof = event {
+            break;
+        }
+    }
+    assert_eq!(
+        validator
+            .take_violations()
+            .into_iter()
+            .map(|v| v.error)
+            .collect::<Vec<_>>(),
+        [
+            UnexpectedElement {
+                parent: "a".into(),
+                child: "c".into(),
+            },
+            UndeclaredElement("c".into()),
+            IncompleteContent("a".into()),
+        ]
+    );
+    assert_eq!(validator.violations(), []);
+}
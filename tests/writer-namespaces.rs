@@ -0,0 +1,253 @@
+use quick_xml::errors::{Error, IllFormedError};
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event::*};
+use quick_xml::name::{LocalName, Namespace, PrefixDeclaration, QName, ResolveResult::*};
+use quick_xml::reader::NsReader;
+use quick_xml::writer::NsWriter;
+
+use pretty_assertions::assert_eq;
+
+const A: Namespace = Namespace(b"urn:a");
+const B: Namespace = Namespace(b"urn:b");
+const NONE: Namespace = Namespace(b"");
+
+fn local(name: &str) -> LocalName<'_> {
+    QName(name.as_bytes()).into()
+}
+
+fn result(writer: NsWriter<Vec<u8>>) -> String {
+    String::from_utf8(writer.into_inner()).expect("utf-8 output")
+}
+
+#[test]
+fn generated_prefixes() {
+    let mut writer = NsWriter::new(Vec::new());
+    writer.write_start((A, local("root")), []).unwrap();
+    writer
+        .write_empty((B, local("child")), [((A, local("attr")), "value")])
+        .unwrap();
+    writer.write_empty((B, local("child")), []).unwrap();
+    writer.write_end().unwrap();
+
+    assert_eq!(
+        result(writer),
+        r#"<ns0:root xmlns:ns0="urn:a">"#.to_owned()
+            + r#"<ns1:child xmlns:ns1="urn:b" ns0:attr="value"/>"#
+            + r#"<ns1:child xmlns:ns1="urn:b"/>"#
+            + r#"</ns0:root>"#
+    );
+}
+
+#[test]
+fn reused_prefixes() {
+    let mut writer = NsWriter::new(Vec::new());
+    writer.write_start((A, local("root")), []).unwrap();
+    writer.write_start((B, local("child")), []).unwrap();
+    writer
+        .write_empty(
+            (A, local("leaf")),
+            [((A, local("a")), "1"), ((B, local("b")), "2")],
+        )
+        .unwrap();
+    writer.write_end().unwrap();
+    writer.write_end().unwrap();
+
+    assert_eq!(
+        result(writer),
+        r#"<ns0:root xmlns:ns0="urn:a">"#.to_owned()
+            + r#"<ns1:child xmlns:ns1="urn:b">"#
+            + r#"<ns0:leaf ns0:a="1" ns1:b="2"/>"#
+            + r#"</ns1:child>"#
+            + r#"</ns0:root>"#
+    );
+}
+
+#[test]
+fn preferred_prefixes() {
+    let mut writer = NsWriter::new(Vec::new());
+    writer.prefer_prefix(A, PrefixDeclaration::Default);
+    writer.prefer_prefix(B, PrefixDeclaration::Named(b"b"));
+    writer.write_start((A, local("root")), []).unwrap();
+    writer
+        .write_empty((B, local("child")), [((A, local("attr")), "value")])
+        .unwrap();
+    writer.write_empty((A, local("child")), []).unwrap();
+    writer.write_end().unwrap();
+
+    assert_eq!(
+        result(writer),
+        r#"<root xmlns="urn:a">"#.to_owned()
+            + r#"<b:child xmlns:b="urn:b" xmlns:ns0="urn:a" ns0:attr="value"/>"#
+            + r#"<child/>"#
+            + r#"</root>"#
+    );
+}
+
+/// Preferred prefix is not used when it is bound to another namespace in scope
+#[test]
+fn preferred_prefix_in_use() {
+    let mut writer = NsWriter::new(Vec::new());
+    writer.prefer_prefix(A, PrefixDeclaration::Named(b"p"));
+    writer.prefer_prefix(B, PrefixDeclaration::Named(b"p"));
+    writer.write_start((A, local("root")), []).unwrap();
+    writer.write_empty((B, local("child")), []).unwrap();
+    writer.write_end().unwrap();
+
+    assert_eq!(
+        result(writer),
+        r#"<p:root xmlns:p="urn:a"><ns0:child xmlns:ns0="urn:b"/></p:root>"#
+    );
+}
+
+/// Elements without namespace undeclare the default namespace
+#[test]
+fn empty_namespace() {
+    let mut writer = NsWriter::new(Vec::new());
+    writer.prefer_prefix(A, PrefixDeclaration::Default);
+    writer.write_start((NONE, local("root")), []).unwrap();
+    writer
+        .write_start((A, local("child")), [((NONE, local("attr")), "value")])
+        .unwrap();
+    writer.write_empty((NONE, local("leaf")), []).unwrap();
+    writer.write_end().unwrap();
+    writer.write_end().unwrap();
+
+    assert_eq!(
+        result(writer),
+        r#"<root><child xmlns="urn:a" attr="value"><leaf xmlns=""/></child></root>"#
+    );
+}
+
+#[test]
+fn xml_namespace() {
+    let mut writer = NsWriter::new(Vec::new());
+    writer
+        .write_empty(
+            (NONE, local("root")),
+            [(
+                (
+                    Namespace(b"http://www.w3.org/XML/1998/namespace"),
+                    local("lang"),
+                ),
+                "en",
+            )],
+        )
+        .unwrap();
+
+    assert_eq!(result(writer), r#"<root xml:lang="en"/>"#);
+}
+
+/// Namespaces declared in the events written by `write_event` are reused
+#[test]
+fn declared_by_event() {
+    let mut writer = NsWriter::new(Vec::new());
+    writer
+        .write_event(Start(
+            BytesStart::new("a:root").with_attributes([("xmlns:a", "urn:a")]),
+        ))
+        .unwrap();
+    writer.write_empty((A, local("child")), []).unwrap();
+    writer.write_end().unwrap();
+    writer.write_empty((A, local("root")), []).unwrap();
+
+    assert_eq!(
+        result(writer),
+        r#"<a:root xmlns:a="urn:a"><a:child/></a:root><ns0:root xmlns:ns0="urn:a"/>"#
+    );
+}
+
+#[test]
+fn end_event() {
+    let mut writer = NsWriter::new(Vec::new());
+    writer.write_start((A, local("root")), []).unwrap();
+    writer.write_event(End(BytesEnd::new("ns0:root"))).unwrap();
+    writer.write_empty((A, local("root")), []).unwrap();
+
+    assert_eq!(
+        result(writer),
+        r#"<ns0:root xmlns:ns0="urn:a"></ns0:root><ns0:root xmlns:ns0="urn:a"/>"#
+    );
+}
+
+#[test]
+fn escaping() {
+    let mut writer = NsWriter::new(Vec::new());
+    writer
+        .write_empty(
+            (Namespace(b"urn:a?b&c"), local("root")),
+            [((NONE, local("attr")), "<\"'&>")],
+        )
+        .unwrap();
+
+    assert_eq!(
+        result(writer),
+        r#"<ns0:root xmlns:ns0="urn:a?b&amp;c" attr="&lt;&quot;&apos;&amp;&gt;"/>"#
+    );
+}
+
+#[test]
+fn unmatched_end() {
+    let mut writer = NsWriter::new(Vec::new());
+    match writer.write_end() {
+        Err(Error::IllFormed(IllFormedError::UnmatchedEndTag(name))) => assert_eq!(name, ""),
+        x => panic!(
+            "Expected `Err(IllFormed(UnmatchedEndTag(_)))`, but got `{:?}`",
+            x
+        ),
+    }
+}
+
+#[test]
+fn indentation() {
+    let mut writer = NsWriter::new_with_indent(Vec::new(), b' ', 2);
+    writer.write_start((A, local("root")), []).unwrap();
+    writer.write_empty((A, local("child")), []).unwrap();
+    writer.write_end().unwrap();
+
+    assert_eq!(
+        result(writer),
+        "<ns0:root xmlns:ns0=\"urn:a\">\n  <ns0:child/>\n</ns0:root>"
+    );
+}
+
+/// Written names are resolved by `NsReader` to the same namespaces
+#[test]
+fn roundtrip() {
+    let mut writer = NsWriter::new(Vec::new());
+    writer.write_start((A, local("root")), []).unwrap();
+    writer.write_start((B, local("child")), []).unwrap();
+    writer.write_event(Text(BytesText::new("text"))).unwrap();
+    writer.write_end().unwrap();
+    writer
+        .write_empty((NONE, local("child")), [((B, local("attr")), "value")])
+        .unwrap();
+    writer.write_end().unwrap();
+
+    let xml = result(writer);
+    let mut reader = NsReader::from_str(&xml);
+    let mut names = Vec::new();
+    loop {
+        match reader.read_resolved_event().unwrap() {
+            (ns, Start(e)) | (ns, Empty(e)) => {
+                names.push((format!("{:?}", ns), e.local_name().as_ref().to_vec()));
+                for attr in e.attributes() {
+                    let attr = attr.unwrap();
+                    if attr.key.as_namespace_binding().is_none() {
+                        let (ns, local) = reader.resolver().resolve_attribute(attr.key);
+                        names.push((format!("{:?}", ns), local.as_ref().to_vec()));
+                    }
+                }
+            }
+            (_, Eof) => break,
+            _ => {}
+        }
+    }
+    assert_eq!(
+        names,
+        vec![
+            (format!("{:?}", Bound(A)), b"root".to_vec()),
+            (format!("{:?}", Bound(B)), b"child".to_vec()),
+            (format!("{:?}", Unbound), b"child".to_vec()),
+            (format!("{:?}", Bound(B)), b"attr".to_vec()),
+        ]
+    );
+}
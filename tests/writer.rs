@@ -234,3 +234,215 @@ fn eof() {
         "writer output (LHS)"
     );
 }
+
+mod check_well_formedness {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::errors::{Error, IllFormedError};
+    use quick_xml::events::{BytesRef, Event};
+    use std::io::ErrorKind;
+
+    fn writer() -> Writer<Vec<u8>> {
+        let mut writer = Writer::new(Vec::new());
+        writer.config_mut().check_well_formedness = true;
+        writer
+    }
+
+    /// Converts an error of the writer into the error of well-formedness
+    #[track_caller]
+    fn ill_formed(error: std::io::Error) -> IllFormedError {
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        match Error::from(error) {
+            Error::IllFormed(error) => error,
+            x => panic!("Expected `IllFormed(_)`, but got `{:?}`", x),
+        }
+    }
+
+    /// Checks that writing of the event fails with the specified error and
+    /// nothing is written
+    #[track_caller]
+    fn fails(writer: &mut Writer<Vec<u8>>, event: Event, expected: IllFormedError) {
+        let len = writer.get_ref().len();
+        let error = writer.write_event(event).unwrap_err();
+        assert_eq!(ill_formed(error), expected);
+        assert_eq!(writer.get_ref().len(), len);
+    }
+
+    #[test]
+    fn well_formed() {
+        let mut writer = writer();
+        writer
+            .write_event(Decl(BytesDecl::new("1.0", None, None)))
+            .unwrap();
+        writer.write_event(Text(BytesText::new("\n"))).unwrap();
+        writer.write_event(Comment(BytesText::new(" - "))).unwrap();
+        writer.write_event(Start(BytesStart::new("root"))).unwrap();
+        writer.write_event(Text(BytesText::new("text"))).unwrap();
+        writer.write_event(CData(BytesCData::new("]]"))).unwrap();
+        writer
+            .write_event(GeneralRef(BytesRef::new("entity")))
+            .unwrap();
+        writer.write_event(Empty(BytesStart::new("child"))).unwrap();
+        writer.write_event(End(BytesEnd::new("root"))).unwrap();
+        writer.write_event(PI(BytesPI::new("pi"))).unwrap();
+        writer.finish().unwrap();
+
+        assert_eq!(
+            String::from_utf8(writer.into_inner()).unwrap(),
+            "<?xml version=\"1.0\"?>\n<!-- - --><root>text<![CDATA[]]]]>&entity;<child/></root><?pi?>"
+        );
+    }
+
+    #[test]
+    fn unmatched_end() {
+        let mut writer = writer();
+        fails(
+            &mut writer,
+            End(BytesEnd::new("root")),
+            IllFormedError::UnmatchedEndTag("root".into()),
+        );
+    }
+
+    #[test]
+    fn mismatched_end() {
+        let mut writer = writer();
+        writer.write_event(Start(BytesStart::new("root"))).unwrap();
+        fails(
+            &mut writer,
+            End(BytesEnd::new("other")),
+            IllFormedError::MismatchedEndTag {
+                expected: "root".into(),
+                found: "other".into(),
+            },
+        );
+        // The element still can be closed
+        writer.write_event(End(BytesEnd::new("root"))).unwrap();
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn multiple_roots() {
+        let mut writer = writer();
+        writer.write_event(Empty(BytesStart::new("root"))).unwrap();
+        fails(
+            &mut writer,
+            Start(BytesStart::new("second")),
+            IllFormedError::MultipleRoots("second".into()),
+        );
+        fails(
+            &mut writer,
+            Empty(BytesStart::new("second")),
+            IllFormedError::MultipleRoots("second".into()),
+        );
+    }
+
+    #[test]
+    fn text_outside_root() {
+        let mut writer = writer();
+        writer.write_event(Text(BytesText::new(" \t\r\n"))).unwrap();
+        fails(
+            &mut writer,
+            Text(BytesText::new(" text ")),
+            IllFormedError::TextOutsideRoot,
+        );
+        fails(
+            &mut writer,
+            CData(BytesCData::new("")),
+            IllFormedError::TextOutsideRoot,
+        );
+        fails(
+            &mut writer,
+            GeneralRef(BytesRef::new("entity")),
+            IllFormedError::TextOutsideRoot,
+        );
+
+        writer.write_event(Empty(BytesStart::new("root"))).unwrap();
+        fails(
+            &mut writer,
+            Text(BytesText::new("text")),
+            IllFormedError::TextOutsideRoot,
+        );
+    }
+
+    #[test]
+    fn double_hyphen_in_comment() {
+        let mut writer = writer();
+        fails(
+            &mut writer,
+            Comment(BytesText::from_escaped("a--b")),
+            IllFormedError::DoubleHyphenInComment,
+        );
+        fails(
+            &mut writer,
+            Comment(BytesText::from_escaped("a-")),
+            IllFormedError::DoubleHyphenInComment,
+        );
+    }
+
+    #[test]
+    fn cdata_end_in_cdata() {
+        let mut writer = writer();
+        writer.write_event(Start(BytesStart::new("root"))).unwrap();
+        fails(
+            &mut writer,
+            CData(BytesCData::new("a]]>b")),
+            IllFormedError::CDataEndInCData,
+        );
+    }
+
+    #[test]
+    fn invalid_name() {
+        let mut writer = writer();
+        fails(
+            &mut writer,
+            Start(BytesStart::new("1root")),
+            IllFormedError::InvalidName("1root".into()),
+        );
+        fails(
+            &mut writer,
+            Empty(BytesStart::from_content("", 0)),
+            IllFormedError::InvalidName("".into()),
+        );
+    }
+
+    #[test]
+    fn unclosed() {
+        let mut writer = writer();
+        writer.write_event(Start(BytesStart::new("root"))).unwrap();
+        writer.write_event(Start(BytesStart::new("child"))).unwrap();
+        assert_eq!(
+            ill_formed(writer.finish().unwrap_err()),
+            IllFormedError::MissingEndTag("child".into())
+        );
+    }
+
+    #[test]
+    fn element_writer() {
+        let mut writer = writer();
+        writer
+            .create_element("root")
+            .write_inner_content(|w| {
+                w.create_element("child").write_empty()?;
+                Ok(())
+            })
+            .unwrap();
+        match writer.create_element("second").write_empty() {
+            Err(error) => assert_eq!(
+                ill_formed(error),
+                IllFormedError::MultipleRoots("second".into())
+            ),
+            Ok(_) => panic!("Expected `Err(_)`, but got `Ok(_)`"),
+        }
+    }
+
+    /// Checks are disabled by default
+    #[test]
+    fn disabled() {
+        let mut writer = Writer::new(Vec::new());
+        writer.write_event(Text(BytesText::new("text"))).unwrap();
+        writer.write_event(End(BytesEnd::new("root"))).unwrap();
+        writer.write_event(Empty(BytesStart::new("1"))).unwrap();
+        writer.write_event(Empty(BytesStart::new("2"))).unwrap();
+        writer.finish().unwrap();
+    }
+}
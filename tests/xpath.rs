@@ -0,0 +1,274 @@
+use quick_xml::events::Event;
+use quick_xml::reader::NsReader;
+use quick_xml::xpath::{Match, Matcher, XPath, XPathError};
+use quick_xml::XmlVersion;
+
+use pretty_assertions::assert_eq;
+
+const XML: &str = r#"
+<root xmlns:a="urn:a">
+    <item id="1"/>
+    <group id="2">
+        <item id="3" kind="x"/>
+        <item id="4"><item id="5" kind="y"/></item>
+        <a:item id="6" kind="x"/>
+    </group>
+    <item id="7" kind="x"/>
+    <a:group id="8" xmlns="urn:a">
+        <item id="9"/>
+    </a:group>
+</root>"#;
+
+/// Returns `id`s of matched elements
+fn select(path: XPath, xml: &str) -> Vec<String> {
+    let mut reader = NsReader::from_str(xml);
+    let mut matcher = Matcher::new(path);
+    let mut ids = Vec::new();
+    loop {
+        let event = reader.read_event().unwrap();
+        match (matcher.feed(&event, &reader).unwrap(), &event) {
+            (Some(Match::Start), Event::Start(e)) | (Some(Match::Empty), Event::Empty(e)) => {
+                let id = e.try_get_attribute("id").unwrap().unwrap();
+                ids.push(
+                    id.normalized_value(XmlVersion::Implicit1_0)
+                        .unwrap()
+                        .into_owned(),
+                );
+            }
+            (Some(Match::End), Event::End(_)) | (None, _) => {}
+            (m, e) => panic!("unexpected match {:?} for {:?}", m, e),
+        }
+        if let Event::Eof = event {
+            break;
+        }
+    }
+    assert!(!matcher.in_match());
+    ids
+}
+
+macro_rules! select {
+    ($name:ident: $path:literal => [$($id:literal),*]) => {
+        #[test]
+        fn $name() {
+            let path = XPath::with_namespaces($path, [("a", "urn:a")]).unwrap();
+            let expected: Vec<&str> = vec![$($id),*];
+            assert_eq!(select(path, XML), expected);
+        }
+    };
+}
+
+mod select {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    select!(absolute: "/root/item" => ["1", "7"]);
+    select!(relative: "root/group/item" => ["3", "4"]);
+    select!(descendant: "//item" => ["1", "3", "4", "5", "7"]);
+    select!(descendant_in_middle: "/root//item/item" => ["5"]);
+    select!(any: "/root/*" => ["1", "2", "7", "8"]);
+    select!(any_in_namespace: "//a:*" => ["6", "8", "9"]);
+    select!(namespace: "//a:item" => ["6", "9"]);
+    select!(default_namespace: "/root/a:group/item" => []);
+    select!(no_match: "/item" => []);
+
+    select!(attribute_exists: "//*[@kind]" => ["3", "5", "6", "7"]);
+    select!(attribute_equal: "//*[@kind = 'x']" => ["3", "6", "7"]);
+    select!(attribute_not_equal: "//item[@kind!=\"x\"]" => ["5"]);
+    select!(several_predicates: "//*[@kind][@id='6']" => ["6"]);
+
+    select!(position: "/root/item[2]" => ["7"]);
+    select!(position_descendant: "//item[1]" => ["1", "3", "5"]);
+    select!(position_after_predicate: "//item[@kind='x'][1]" => ["3", "7"]);
+    select!(predicate_after_position: "//item[1][@kind]" => ["3", "5"]);
+    select!(position_in_middle: "/root/*[2]/item" => ["3", "4"]);
+}
+
+/// Matches are reported for nested matched elements
+#[test]
+fn nested() {
+    let xml = "<a><a><b/></a><b/></a>";
+    let mut reader = NsReader::from_str(xml);
+    let mut matcher = Matcher::new("//a".parse().unwrap());
+    let mut matches = Vec::new();
+    loop {
+        let event = reader.read_event().unwrap();
+        if let Event::Eof = event {
+            break;
+        }
+        let m = matcher.feed(&event, &reader).unwrap();
+        matches.push((m, matcher.in_match()));
+    }
+    assert_eq!(
+        matches,
+        vec![
+            (Some(Match::Start), true),
+            (Some(Match::Start), true),
+            (None, true),
+            (Some(Match::End), true),
+            (None, true),
+            (Some(Match::End), false),
+        ]
+    );
+}
+
+/// Text of the matched elements can be collected using `in_match()`
+#[test]
+fn text() {
+    let xml = "<list><item>one<b>two</b></item><skip>three</skip><item>four</item></list>";
+    let mut reader = NsReader::from_str(xml);
+    let mut matcher = Matcher::new("/list/item".parse().unwrap());
+    let mut texts = Vec::new();
+    loop {
+        let event = reader.read_event().unwrap();
+        match matcher.feed(&event, &reader).unwrap() {
+            Some(Match::Start) => texts.push(String::new()),
+            _ if matcher.in_match() => {
+                if let Event::Text(e) = &event {
+                    texts.last_mut().unwrap().push_str(&e.decode().unwrap());
+                }
+            }
+            _ => {}
+        }
+        if let Event::Eof = event {
+            break;
+        }
+    }
+    assert_eq!(texts, ["onetwo", "four"]);
+}
+
+mod attributes {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// Returns values of the selected attributes
+    fn attributes(path: &str, xml: &str) -> Vec<String> {
+        let path = XPath::with_namespaces(path, [("a", "urn:a")]).unwrap();
+        let mut reader = NsReader::from_str(xml);
+        let mut matcher = Matcher::new(path);
+        let mut values = Vec::new();
+        loop {
+            let event = reader.read_event().unwrap();
+            if matcher.feed(&event, &reader).unwrap().is_some() {
+                if let Event::Start(e) | Event::Empty(e) = &event {
+                    for attr in matcher.attributes(e, &reader).unwrap() {
+                        values.push(
+                            attr.normalized_value(XmlVersion::Implicit1_0)
+                                .unwrap()
+                                .into_owned(),
+                        );
+                    }
+                }
+            }
+            if let Event::Eof = event {
+                break;
+            }
+        }
+        values
+    }
+
+    #[test]
+    fn child() {
+        assert_eq!(attributes("/root/item/@id", XML), ["1", "7"]);
+    }
+
+    #[test]
+    fn descendant() {
+        assert_eq!(attributes("/root/group//@kind", XML), ["x", "y", "x"]);
+        assert_eq!(attributes("//@kind", XML), ["x", "y", "x", "x"]);
+    }
+
+    #[test]
+    fn any() {
+        assert_eq!(attributes("/root/group/a:item/@*", XML), ["6", "x"]);
+    }
+
+    /// Namespace declarations are not attributes
+    #[test]
+    fn namespace_declarations() {
+        assert_eq!(attributes("/root/@*", XML), Vec::<String>::new());
+    }
+
+    #[test]
+    fn namespaced() {
+        let xml = r#"<root xmlns:b="urn:a" b:attr="1" attr="2"/>"#;
+        assert_eq!(attributes("/root/@a:attr", xml), ["1"]);
+        assert_eq!(attributes("/root/@attr", xml), ["2"]);
+    }
+
+    #[test]
+    fn not_attribute_path() {
+        let mut reader = NsReader::from_str(r#"<root attr="1"/>"#);
+        let mut matcher = Matcher::new("/root".parse().unwrap());
+        let event = reader.read_event().unwrap();
+        assert_eq!(matcher.feed(&event, &reader).unwrap(), Some(Match::Empty));
+        match event {
+            Event::Empty(e) => assert!(matcher.attributes(&e, &reader).unwrap().is_empty()),
+            e => panic!("Expected `Empty`, but got `{:?}`", e),
+        }
+    }
+}
+
+/// Attribute values are compared after unescaping and normalization
+#[test]
+fn normalized_value() {
+    let xml = "<root><item id='1' v='a&amp;b'/><item id='2' v='a\nb'/></root>";
+    assert_eq!(select("//item[@v='a&b']".parse().unwrap(), xml), ["1"]);
+    assert_eq!(select("//item[@v='a b']".parse().unwrap(), xml), ["2"]);
+}
+
+#[test]
+fn reset() {
+    let path: XPath = "/root/item[1]".parse().unwrap();
+    let mut matcher = Matcher::new(path.clone());
+    let mut reader = NsReader::from_str("<root><item/>");
+    matcher
+        .feed(&reader.read_event().unwrap(), &reader)
+        .unwrap();
+    matcher
+        .feed(&reader.read_event().unwrap(), &reader)
+        .unwrap();
+
+    matcher.reset();
+    let mut reader = NsReader::from_str("<root><item/></root>");
+    let event = reader.read_event().unwrap();
+    assert_eq!(matcher.feed(&event, &reader).unwrap(), None);
+    let event = reader.read_event().unwrap();
+    assert_eq!(matcher.feed(&event, &reader).unwrap(), Some(Match::Empty));
+    assert_eq!(matcher.path(), &path);
+}
+
+mod errors {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    macro_rules! err {
+        ($name:ident: $path:literal => $error:expr) => {
+            #[test]
+            fn $name() {
+                assert_eq!(XPath::new($path), Err($error));
+            }
+        };
+    }
+
+    err!(empty: "" => XPathError::UnexpectedEof("name"));
+    err!(root: "/" => XPathError::UnexpectedEof("name"));
+    err!(trailing_slash: "/a/" => XPathError::UnexpectedEof("name"));
+    err!(axis: "/child::a" => XPathError::Unexpected(6, "`/`, `//` or `[`"));
+    err!(function: "/a/text()" => XPathError::Unexpected(7, "`/`, `//` or `[`"));
+    err!(unbound_prefix: "/p:a" => XPathError::UnboundPrefix("p".to_string()));
+    err!(unclosed_predicate: "/a[1" => XPathError::UnexpectedEof("]"));
+    err!(zero_position: "/a[0]" => XPathError::Unexpected(3, "`@` or position"));
+    err!(last: "/a[last()]" => XPathError::Unexpected(3, "`@` or position"));
+    err!(unclosed_literal: "/a[@b='c]" => XPathError::UnexpectedEof("closing quote"));
+    err!(no_literal: "/a[@b=c]" => XPathError::Unexpected(6, "literal"));
+    err!(after_attribute: "/a/@b/c" => XPathError::Unexpected(5, "end of expression"));
+}
+
+#[test]
+fn xml_prefix() {
+    let xml = r#"<root><item id="1" xml:lang="en"/><item id="2" xml:lang="de"/></root>"#;
+    assert_eq!(
+        select("//item[@xml:lang='de']".parse().unwrap(), xml),
+        ["2"]
+    );
+}
@@ -0,0 +1,250 @@
+use quick_xml::de::from_str;
+use quick_xml::se::to_string;
+use quick_xml::xsd::{codegen, Schema};
+
+use pretty_assertions::assert_eq;
+
+#[allow(dead_code)]
+mod order {
+    include!("documents/xsd/order.rs");
+}
+
+use order::*;
+
+/// Checks that generated code is up to date. To update it, run
+///
+/// ```shell
+/// cargo run --example xsd_codegen --features xsd -- tests/documents/xsd/order.xsd > tests/documents/xsd/order.rs
+/// ```
+#[test]
+fn generated() {
+    let schema = Schema::parse(include_str!("documents/xsd/order.xsd")).unwrap();
+    assert_eq!(
+        codegen::generate(&schema),
+        include_str!("documents/xsd/order.rs")
+    );
+}
+
+#[test]
+fn roundtrip() {
+    let xml = r#"
+        <order xmlns="urn:example:order" id="42" status="in-progress" tags="gift urgent">
+            <customer vip="true">
+                <name>Jane</name>
+                <email>jane@example.com</email>
+                <email>jane@example.org</email>
+            </customer>
+            <item sku="ABC-1" qty="2" price="9.5">Pencil</item>
+            <item sku="XYZ-20" qty="1">Eraser</item>
+            <delivery>
+                <city>Springfield</city>
+                <street>Main st.</street>
+            </delivery>
+            <note>Leave at <b>the door</b></note>
+            <category name="office">
+                <category name="stationery"/>
+            </category>
+            <history>
+                <event>placed</event>
+                <date>2024-05-01</date>
+                <event>shipped</event>
+                <date>2024-05-03</date>
+            </history>
+        </order>"#;
+    let order: Order = from_str(xml).unwrap();
+    assert_eq!(
+        order,
+        Order {
+            id: 42,
+            status: Some(Status::InProgress),
+            tags: Some(vec!["gift".into(), "urgent".into()]),
+            customer: Customer {
+                vip: Some(true),
+                name: "Jane".into(),
+                email: vec!["jane@example.com".into(), "jane@example.org".into()],
+            },
+            item: vec![
+                Item {
+                    sku: "ABC-1".into(),
+                    qty: "2".into(),
+                    price: Some("9.5".into()),
+                    text: "Pencil".into(),
+                },
+                Item {
+                    sku: "XYZ-20".into(),
+                    qty: "1".into(),
+                    price: None,
+                    text: "Eraser".into(),
+                },
+            ],
+            choice: OrderChoice::Delivery(Address {
+                street: "Main st.".into(),
+                city: "Springfield".into(),
+                postal_code: None,
+            }),
+            note: Some(Note {
+                content: vec![
+                    NoteContent::Text("Leave at ".into()),
+                    NoteContent::B("the door".into()),
+                ],
+            }),
+            category: Some(Category {
+                name: "office".into(),
+                category: Some(Box::new(Category {
+                    name: "stationery".into(),
+                    category: None,
+                })),
+            }),
+            history: Some(History {
+                content: vec![
+                    HistoryContent::Event("placed".into()),
+                    HistoryContent::Date("2024-05-01".into()),
+                    HistoryContent::Event("shipped".into()),
+                    HistoryContent::Date("2024-05-03".into()),
+                ],
+            }),
+        }
+    );
+
+    // The serializer does not support text items in sequences yet
+    let mut order = order;
+    order.note = Some(Note {
+        content: vec![NoteContent::B("Fragile".into()), NoteContent::Br(Br {})],
+    });
+    assert_eq!(
+        to_string(&order).unwrap(),
+        concat!(
+            r#"<order id="42" status="in-progress" tags="gift urgent">"#,
+            r#"<customer vip="true"><name>Jane</name><email>jane@example.com</email><email>jane@example.org</email></customer>"#,
+            r#"<item sku="ABC-1" qty="2" price="9.5">Pencil</item>"#,
+            r#"<item sku="XYZ-20" qty="1">Eraser</item>"#,
+            "<delivery><street>Main st.</street><city>Springfield</city></delivery>",
+            "<note><b>Fragile</b><br/></note>",
+            r#"<category name="office"><category name="stationery"/></category>"#,
+            "<history>",
+            "<event>placed</event><date>2024-05-01</date>",
+            "<event>shipped</event><date>2024-05-03</date>",
+            "</history>",
+            "</order>",
+        )
+    );
+}
+
+/// A global element with a named type is represented by a newtype struct
+#[test]
+fn global_element() {
+    let xml = "<customer><name>Jane</name></customer>";
+    let customer: CustomerElement = from_str(xml).unwrap();
+    assert_eq!(
+        customer,
+        CustomerElement(Customer {
+            vip: None,
+            name: "Jane".into(),
+            email: vec![],
+        })
+    );
+    assert_eq!(to_string(&customer).unwrap(), xml);
+}
+
+/// Content models which cannot be represented by fields are represented by
+/// a list of enum values
+#[test]
+fn content_enum() {
+    let schema = Schema::parse(
+        r#"
+        <xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+            <xs:element name="list">
+                <xs:complexType>
+                    <xs:sequence>
+                        <xs:element name="type" type="xs:int"/>
+                        <xs:choice maxOccurs="unbounded">
+                            <xs:element name="list-item" type="xs:string"/>
+                            <xs:sequence>
+                                <xs:element name="type" type="xs:int"/>
+                                <xs:element name="empty" type="xs:anyType"/>
+                            </xs:sequence>
+                        </xs:choice>
+                    </xs:sequence>
+                </xs:complexType>
+            </xs:element>
+        </xs:schema>"#,
+    )
+    .unwrap();
+    assert_eq!(
+        codegen::generate(&schema),
+        r#"// Generated by quick-xml from an XML Schema.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename = "list")]
+pub struct List {
+    #[serde(rename = "$value", default)]
+    pub content: Vec<ListContent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum ListContent {
+    #[serde(rename = "type")]
+    Type(i32),
+    #[serde(rename = "list-item")]
+    ListItem(String),
+    #[serde(rename = "empty")]
+    Empty,
+}
+"#
+    );
+}
+
+#[test]
+fn identifiers() {
+    let schema = Schema::parse(
+        r#"
+        <xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+            <xs:complexType name="String">
+                <xs:sequence>
+                    <xs:element name="type" type="xs:string"/>
+                    <xs:element name="Type" type="xs:string"/>
+                    <xs:element name="self" type="xs:string"/>
+                </xs:sequence>
+                <xs:attribute name="xml-lang" type="xs:language"/>
+            </xs:complexType>
+            <xs:simpleType name="size">
+                <xs:restriction base="xs:string">
+                    <xs:enumeration value="1x"/>
+                    <xs:enumeration value="XL"/>
+                    <xs:enumeration value=""/>
+                </xs:restriction>
+            </xs:simpleType>
+        </xs:schema>"#,
+    )
+    .unwrap();
+    assert_eq!(
+        codegen::generate(&schema),
+        r#"// Generated by quick-xml from an XML Schema.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct String2 {
+    #[serde(rename = "@xml-lang", skip_serializing_if = "Option::is_none")]
+    pub xml_lang: Option<String>,
+    pub r#type: String,
+    #[serde(rename = "Type")]
+    pub type2: String,
+    #[serde(rename = "self")]
+    pub self_: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum Size {
+    #[serde(rename = "1x")]
+    V1x,
+    #[serde(rename = "XL")]
+    Xl,
+    #[serde(rename = "")]
+    Empty,
+}
+"#
+    );
+}
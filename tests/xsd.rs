@@ -0,0 +1,1015 @@
+use quick_xml::reader::NsReader;
+use quick_xml::xsd::ValidityError;
+use quick_xml::xsd::{validate, Schema, SchemaError, Validator, ValidityError::*, ValueError};
+
+use pretty_assertions::assert_eq;
+
+/// Wraps declarations into the `<xs:schema>` element
+fn schema(declarations: &str) -> String {
+    format!(
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">{}</xs:schema>"#,
+        declarations
+    )
+}
+
+/// Validates the document against the schema with the specified declarations
+/// and returns found errors with paths of elements
+fn check(declarations: &str, xml: &str) -> Vec<(String, ValidityError)> {
+    let schema = Schema::parse(&schema(declarations)).unwrap();
+    let mut reader = NsReader::from_str(xml);
+    validate(&schema, &mut reader)
+        .unwrap()
+        .into_iter()
+        .map(|v| (v.path, v.error))
+        .collect()
+}
+
+/// Checks the value of the `<a>` element of the simple type with the specified definition
+fn check_value(ty: &str, value: &str) -> Vec<ValidityError> {
+    let declarations = format!(
+        r#"<xs:element name="a"><xs:simpleType>{}</xs:simpleType></xs:element>"#,
+        ty
+    );
+    check(&declarations, &format!("<a>{}</a>", value))
+        .into_iter()
+        .map(|(_, e)| e)
+        .collect()
+}
+
+fn invalid(value: &str, error: ValueError) -> ValidityError {
+    InvalidValue {
+        value: value.into(),
+        error,
+    }
+}
+
+#[test]
+fn valid() {
+    let xsd = r#"
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           xmlns:o="urn:orders"
+           targetNamespace="urn:orders"
+           elementFormDefault="qualified">
+    <xs:element name="order" type="o:Order"/>
+    <xs:complexType name="Order">
+        <xs:sequence>
+            <xs:element name="customer" type="xs:string"/>
+            <xs:element name="note" type="xs:string" minOccurs="0"/>
+            <xs:element name="item" type="o:Item" maxOccurs="unbounded"/>
+            <xs:choice>
+                <xs:element name="pickup" type="xs:date"/>
+                <xs:element name="delivery" type="xs:dateTime"/>
+            </xs:choice>
+        </xs:sequence>
+        <xs:attribute name="id" type="xs:ID" use="required"/>
+        <xs:attribute name="status" type="o:Status"/>
+    </xs:complexType>
+    <xs:complexType name="Item">
+        <xs:simpleContent>
+            <xs:extension base="o:Quantity">
+                <xs:attribute name="sku" type="xs:token" use="required"/>
+            </xs:extension>
+        </xs:simpleContent>
+    </xs:complexType>
+    <xs:simpleType name="Quantity">
+        <xs:restriction base="xs:positiveInteger">
+            <xs:maxInclusive value="1000"/>
+        </xs:restriction>
+    </xs:simpleType>
+    <xs:simpleType name="Status">
+        <xs:restriction base="xs:token">
+            <xs:enumeration value="new"/>
+            <xs:enumeration value="shipped"/>
+        </xs:restriction>
+    </xs:simpleType>
+</xs:schema>"#;
+    let xml = r#"<?xml version="1.0"?>
+<order xmlns="urn:orders" id="o1" status=" shipped ">
+    <customer>ACME &amp; Co</customer>
+    <item sku="A-1">1</item>
+    <item sku="B-2"> 1000 </item>
+    <delivery>2024-01-31T12:00:00Z</delivery>
+</order>"#;
+    let schema = Schema::parse(xsd).unwrap();
+    let mut reader = NsReader::from_str(xml);
+    assert_eq!(validate(&schema, &mut reader).unwrap(), []);
+}
+
+mod content {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const SEQUENCE: &str = r#"
+        <xs:element name="a">
+            <xs:complexType>
+                <xs:sequence>
+                    <xs:element name="b" type="xs:string"/>
+                    <xs:element name="c" type="xs:string" minOccurs="0" maxOccurs="2"/>
+                    <xs:element name="d" type="xs:string"/>
+                </xs:sequence>
+            </xs:complexType>
+        </xs:element>"#;
+
+    #[test]
+    fn sequence() {
+        assert_eq!(check(SEQUENCE, "<a><b/><d/></a>"), []);
+        assert_eq!(check(SEQUENCE, "<a><b/><c/><c/><d/></a>"), []);
+        assert_eq!(
+            check(SEQUENCE, "<a><b/><c/><c/><c/><d/></a>"),
+            [(
+                "/a/c[3]".into(),
+                UnexpectedElement {
+                    element: "c".into(),
+                    expected: vec!["d".into()],
+                }
+            )]
+        );
+        assert_eq!(
+            check(SEQUENCE, "<a><c/><b/><d/></a>"),
+            [(
+                "/a/c[1]".into(),
+                UnexpectedElement {
+                    element: "c".into(),
+                    expected: vec!["b".into()],
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn incomplete() {
+        assert_eq!(
+            check(SEQUENCE, "<a><b/></a>"),
+            [(
+                "/a".into(),
+                IncompleteContent {
+                    expected: vec!["c".into(), "d".into()],
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn choice() {
+        let xsd = r#"
+            <xs:element name="a">
+                <xs:complexType>
+                    <xs:choice minOccurs="2" maxOccurs="unbounded">
+                        <xs:element name="b"/>
+                        <xs:sequence>
+                            <xs:element name="c"/>
+                            <xs:element name="d"/>
+                        </xs:sequence>
+                    </xs:choice>
+                </xs:complexType>
+            </xs:element>"#;
+        assert_eq!(check(xsd, "<a><c/><d/><b/><b/></a>"), []);
+        assert_eq!(
+            check(xsd, "<a><b/></a>"),
+            [(
+                "/a".into(),
+                IncompleteContent {
+                    expected: vec!["b".into(), "c".into()],
+                }
+            )]
+        );
+        assert_eq!(
+            check(xsd, "<a><c/><b/><d/></a>"),
+            [
+                (
+                    "/a/b[1]".into(),
+                    UnexpectedElement {
+                        element: "b".into(),
+                        expected: vec!["d".into()],
+                    }
+                ),
+                (
+                    "/a".into(),
+                    IncompleteContent {
+                        expected: vec!["b".into(), "c".into()],
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn all() {
+        let xsd = r#"
+            <xs:element name="a">
+                <xs:complexType>
+                    <xs:all>
+                        <xs:element name="b"/>
+                        <xs:element name="c" minOccurs="0"/>
+                        <xs:element name="d"/>
+                    </xs:all>
+                </xs:complexType>
+            </xs:element>"#;
+        assert_eq!(check(xsd, "<a><d/><c/><b/></a>"), []);
+        assert_eq!(check(xsd, "<a><d/><b/></a>"), []);
+        assert_eq!(
+            check(xsd, "<a><d/><d/></a>"),
+            [
+                (
+                    "/a/d[2]".into(),
+                    UnexpectedElement {
+                        element: "d".into(),
+                        expected: vec!["b".into(), "c".into()],
+                    }
+                ),
+                (
+                    "/a".into(),
+                    IncompleteContent {
+                        expected: vec!["b".into(), "c".into()],
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn groups() {
+        let xsd = r#"
+            <xs:group name="g">
+                <xs:sequence>
+                    <xs:element ref="b"/>
+                    <xs:element name="c" type="xs:int"/>
+                </xs:sequence>
+            </xs:group>
+            <xs:element name="b" type="xs:boolean"/>
+            <xs:element name="a">
+                <xs:complexType>
+                    <xs:group ref="g" maxOccurs="2"/>
+                </xs:complexType>
+            </xs:element>"#;
+        assert_eq!(check(xsd, "<a><b>true</b><c>1</c><b>0</b><c>2</c></a>"), []);
+        assert_eq!(
+            check(xsd, "<a><b>yes</b><c>1</c></a>"),
+            [(
+                "/a/b[1]".into(),
+                invalid("yes", ValueError::Invalid("boolean"))
+            )]
+        );
+    }
+
+    #[test]
+    fn text() {
+        let xsd = r#"
+            <xs:element name="a">
+                <xs:complexType>
+                    <xs:sequence>
+                        <xs:element name="b" minOccurs="0">
+                            <xs:complexType mixed="true">
+                                <xs:sequence>
+                                    <xs:element name="c" minOccurs="0"/>
+                                </xs:sequence>
+                            </xs:complexType>
+                        </xs:element>
+                        <xs:element name="e" minOccurs="0">
+                            <xs:complexType/>
+                        </xs:element>
+                    </xs:sequence>
+                </xs:complexType>
+            </xs:element>"#;
+        // Whitespaces are allowed in the element content
+        assert_eq!(check(xsd, "<a>\n  <b>1<c/>2</b>\n  <e/>\n</a>"), []);
+        assert_eq!(
+            check(xsd, "<a>text<e> </e><![CDATA[]]></a>"),
+            [
+                ("/a".into(), UnexpectedText),
+                ("/a/e[1]".into(), UnexpectedText),
+                ("/a".into(), UnexpectedText),
+            ]
+        );
+    }
+
+    #[test]
+    fn simple_type_with_children() {
+        assert_eq!(
+            check(
+                r#"<xs:element name="a" type="xs:string"/>"#,
+                "<a>text<b>skipped<c/></b></a>"
+            ),
+            [(
+                "/a/b[1]".into(),
+                UnexpectedElement {
+                    element: "b".into(),
+                    expected: vec![],
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn unknown_root() {
+        assert_eq!(
+            check(r#"<xs:element name="a"/>"#, "<b><c/></b>"),
+            [("/b".into(), UnknownElement("b".into()))]
+        );
+    }
+
+    #[test]
+    fn any() {
+        let xsd = r###"
+            <xs:element name="known" type="xs:int"/>
+            <xs:element name="a">
+                <xs:complexType>
+                    <xs:sequence>
+                        <xs:any namespace="##local" processContents="lax" maxOccurs="unbounded"/>
+                        <xs:any namespace="urn:x" processContents="strict" minOccurs="0"/>
+                        <xs:any namespace="##other" processContents="skip" minOccurs="0"/>
+                    </xs:sequence>
+                </xs:complexType>
+            </xs:element>"###;
+        assert_eq!(
+            check(
+                xsd,
+                "<a><unknown><c/></unknown><known>1</known><known>x</known></a>"
+            ),
+            [(
+                "/a/known[2]".into(),
+                invalid("x", ValueError::Invalid("int"))
+            )]
+        );
+        assert_eq!(
+            check(
+                xsd,
+                r#"<a xmlns:x="urn:x" xmlns:y="urn:y"><b/><x:b/><y:b/></a>"#
+            ),
+            [("/a/x:b[1]".into(), UnknownElement("x:b".into()))]
+        );
+    }
+
+    #[test]
+    fn extension() {
+        let xsd = r#"
+            <xs:complexType name="Base">
+                <xs:sequence>
+                    <xs:element name="b"/>
+                </xs:sequence>
+                <xs:attribute name="x"/>
+            </xs:complexType>
+            <xs:element name="a">
+                <xs:complexType>
+                    <xs:complexContent>
+                        <xs:extension base="Base">
+                            <xs:sequence>
+                                <xs:element name="c"/>
+                            </xs:sequence>
+                            <xs:attribute name="y" use="required"/>
+                        </xs:extension>
+                    </xs:complexContent>
+                </xs:complexType>
+            </xs:element>"#;
+        assert_eq!(check(xsd, r#"<a x="1" y="2"><b/><c/></a>"#), []);
+        assert_eq!(
+            check(xsd, "<a><c/></a>"),
+            [
+                ("/a".into(), MissingAttribute("y".into())),
+                (
+                    "/a/c[1]".into(),
+                    UnexpectedElement {
+                        element: "c".into(),
+                        expected: vec!["b".into()],
+                    }
+                ),
+                (
+                    "/a".into(),
+                    IncompleteContent {
+                        expected: vec!["b".into()],
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn recursive() {
+        let xsd = r#"
+            <xs:element name="node" type="Node"/>
+            <xs:complexType name="Node">
+                <xs:sequence>
+                    <xs:element name="node" type="Node" minOccurs="0" maxOccurs="unbounded"/>
+                </xs:sequence>
+                <xs:attribute name="depth" type="xs:nonNegativeInteger"/>
+            </xs:complexType>"#;
+        assert_eq!(
+            check(
+                xsd,
+                r#"<node><node depth="1"><node depth="-2"/></node><node/></node>"#
+            ),
+            [(
+                "/node/node[1]/node[1]".into(),
+                InvalidAttributeValue {
+                    attribute: "depth".into(),
+                    value: "-2".into(),
+                    error: ValueError::Invalid("nonNegativeInteger"),
+                }
+            )]
+        );
+    }
+}
+
+mod attributes {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const XSD: &str = r#"
+        <xs:attributeGroup name="common">
+            <xs:attribute name="lang" type="xs:language"/>
+        </xs:attributeGroup>
+        <xs:element name="a">
+            <xs:complexType>
+                <xs:attribute name="required" type="xs:int" use="required"/>
+                <xs:attribute name="optional" type="xs:boolean"/>
+                <xs:attribute name="fixed" type="xs:decimal" fixed="1.5"/>
+                <xs:attribute name="prohibited" use="prohibited"/>
+                <xs:attributeGroup ref="common"/>
+            </xs:complexType>
+        </xs:element>"#;
+
+    #[test]
+    fn valid() {
+        assert_eq!(
+            check(
+                XSD,
+                r#"<a required=" 1 " optional="true" fixed="01.50" lang="en"/>"#
+            ),
+            []
+        );
+    }
+
+    #[test]
+    fn required() {
+        assert_eq!(
+            check(XSD, r#"<a optional="0"/>"#),
+            [("/a".into(), MissingAttribute("required".into()))]
+        );
+    }
+
+    #[test]
+    fn undeclared() {
+        assert_eq!(
+            check(XSD, r#"<a required="1" prohibited="" other=""/>"#),
+            [
+                ("/a".into(), UndeclaredAttribute("prohibited".into())),
+                ("/a".into(), UndeclaredAttribute("other".into())),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_value() {
+        assert_eq!(
+            check(XSD, r#"<a required="x" fixed="1.6" lang="?"/>"#),
+            [
+                (
+                    "/a".into(),
+                    InvalidAttributeValue {
+                        attribute: "required".into(),
+                        value: "x".into(),
+                        error: ValueError::Invalid("int"),
+                    }
+                ),
+                (
+                    "/a".into(),
+                    InvalidAttributeValue {
+                        attribute: "fixed".into(),
+                        value: "1.6".into(),
+                        error: ValueError::Fixed("1.5".into()),
+                    }
+                ),
+                (
+                    "/a".into(),
+                    InvalidAttributeValue {
+                        attribute: "lang".into(),
+                        value: "?".into(),
+                        error: ValueError::Invalid("language"),
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn wildcard() {
+        let xsd = r#"
+            <xs:element name="a">
+                <xs:complexType>
+                    <xs:anyAttribute namespace="urn:x"/>
+                </xs:complexType>
+            </xs:element>"#;
+        assert_eq!(
+            check(xsd, r#"<a xmlns:x="urn:x" xmlns:y="urn:y" x:b="" y:b=""/>"#),
+            [("/a".into(), UndeclaredAttribute("y:b".into()))]
+        );
+    }
+
+    #[test]
+    fn nil() {
+        let xsd = r#"
+            <xs:element name="a">
+                <xs:complexType>
+                    <xs:sequence>
+                        <xs:element name="b" type="xs:int" nillable="true" maxOccurs="2"/>
+                        <xs:element name="c" type="xs:int" minOccurs="0"/>
+                    </xs:sequence>
+                </xs:complexType>
+            </xs:element>"#;
+        let xml = r#"<a xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+            <b xsi:nil="true"/>
+            <b xsi:nil="true">1</b>
+            <c xsi:nil="true">1</c>
+        </a>"#;
+        assert_eq!(
+            check(xsd, xml),
+            [
+                ("/a/b[2]".into(), NilContent),
+                ("/a/c[1]".into(), NotNillable),
+            ]
+        );
+    }
+}
+
+mod values {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn builtin() {
+        let xsd = r#"
+            <xs:element name="a">
+                <xs:complexType>
+                    <xs:sequence>
+                        <xs:element name="byte" type="xs:byte"/>
+                        <xs:element name="date" type="xs:date"/>
+                        <xs:element name="duration" type="xs:duration"/>
+                        <xs:element name="base64" type="xs:base64Binary"/>
+                        <xs:element name="name" type="xs:NCName"/>
+                    </xs:sequence>
+                </xs:complexType>
+            </xs:element>"#;
+        assert_eq!(
+            check(
+                xsd,
+                "<a><byte>-128</byte><date>2024-02-29</date><duration>PT1H</duration>\
+                <base64>YWJj</base64><name>a.b</name></a>"
+            ),
+            []
+        );
+        assert_eq!(
+            check(
+                xsd,
+                "<a><byte>128</byte><date>2023-02-29</date><duration>P1H</duration>\
+                <base64>YWJ</base64><name>a:b</name></a>"
+            ),
+            [
+                (
+                    "/a/byte[1]".into(),
+                    invalid("128", ValueError::Invalid("byte"))
+                ),
+                (
+                    "/a/date[1]".into(),
+                    invalid("2023-02-29", ValueError::Invalid("date"))
+                ),
+                (
+                    "/a/duration[1]".into(),
+                    invalid("P1H", ValueError::Invalid("duration"))
+                ),
+                (
+                    "/a/base64[1]".into(),
+                    invalid("YWJ", ValueError::Invalid("base64Binary"))
+                ),
+                (
+                    "/a/name[1]".into(),
+                    invalid("a:b", ValueError::Invalid("NCName"))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn pattern() {
+        let ty = r#"<xs:restriction base="xs:string"><xs:pattern value="\d{3}-[A-Z]+"/></xs:restriction>"#;
+        assert_eq!(check_value(ty, "123-AB"), []);
+        assert_eq!(
+            check_value(ty, "123-AB "),
+            [invalid(
+                "123-AB ",
+                ValueError::Pattern(r"\d{3}-[A-Z]+".into())
+            )]
+        );
+    }
+
+    #[test]
+    fn enumeration() {
+        let ty = r#"<xs:restriction base="xs:token">
+            <xs:enumeration value="red"/>
+            <xs:enumeration value=" dark  blue "/>
+        </xs:restriction>"#;
+        assert_eq!(check_value(ty, "\n dark blue\n"), []);
+        assert_eq!(
+            check_value(ty, "green"),
+            [invalid(
+                "green",
+                ValueError::Enumeration(vec!["red".into(), " dark  blue ".into()])
+            )]
+        );
+
+        // Numeric values are compared by value
+        let ty = r#"<xs:restriction base="xs:decimal">
+            <xs:enumeration value="1.5"/>
+        </xs:restriction>"#;
+        assert_eq!(check_value(ty, "01.50"), []);
+    }
+
+    #[test]
+    fn length() {
+        let ty = r#"<xs:restriction base="xs:string">
+            <xs:minLength value="2"/>
+            <xs:maxLength value="3"/>
+        </xs:restriction>"#;
+        assert_eq!(check_value(ty, "ёж"), []);
+        assert_eq!(
+            check_value(ty, "a"),
+            [invalid("a", ValueError::MinLength { min: 2, found: 1 })]
+        );
+        assert_eq!(
+            check_value(ty, "abcd"),
+            [invalid("abcd", ValueError::MaxLength { max: 3, found: 4 })]
+        );
+
+        let ty = r#"<xs:restriction base="xs:hexBinary"><xs:length value="2"/></xs:restriction>"#;
+        assert_eq!(check_value(ty, "0aFF"), []);
+        assert_eq!(
+            check_value(ty, "0a"),
+            [invalid(
+                "0a",
+                ValueError::Length {
+                    expected: 2,
+                    found: 1
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn bounds() {
+        let ty = r#"<xs:restriction base="xs:decimal">
+            <xs:minExclusive value="0"/>
+            <xs:maxInclusive value="100"/>
+            <xs:totalDigits value="5"/>
+            <xs:fractionDigits value="2"/>
+        </xs:restriction>"#;
+        assert_eq!(check_value(ty, "100.00"), []);
+        assert_eq!(
+            check_value(ty, "0"),
+            [invalid("0", ValueError::MinExclusive("0".into()))]
+        );
+        assert_eq!(
+            check_value(ty, "100.01"),
+            [invalid("100.01", ValueError::MaxInclusive("100".into()))]
+        );
+        assert_eq!(
+            check_value(ty, "1.125"),
+            [invalid("1.125", ValueError::FractionDigits(2))]
+        );
+
+        let ty = r#"<xs:restriction base="xs:date"><xs:minInclusive value="2024-01-01"/></xs:restriction>"#;
+        assert_eq!(check_value(ty, "2024-01-01"), []);
+        assert_eq!(
+            check_value(ty, "2023-12-31"),
+            [invalid(
+                "2023-12-31",
+                ValueError::MinInclusive("2024-01-01".into())
+            )]
+        );
+        assert_eq!(check_value(ty, "10000-01-01"), []);
+        assert_eq!(check_value(ty, "2024-01-01-05:00"), []);
+
+        // Values with and without timezone are ordered if they differ by more than 14 hours
+        let ty = r#"<xs:restriction base="xs:dateTime">
+            <xs:maxExclusive value="2024-01-01T00:00:00Z"/>
+        </xs:restriction>"#;
+        assert_eq!(check_value(ty, "2024-01-01T09:00:00+10:00"), []);
+        assert_eq!(check_value(ty, "2024-01-01T10:00:00"), []);
+        assert_eq!(
+            check_value(ty, "2024-01-01T00:00:00.5Z"),
+            [invalid(
+                "2024-01-01T00:00:00.5Z",
+                ValueError::MaxExclusive("2024-01-01T00:00:00Z".into())
+            )]
+        );
+        assert_eq!(
+            check_value(ty, "2024-01-01T15:00:00"),
+            [invalid(
+                "2024-01-01T15:00:00",
+                ValueError::MaxExclusive("2024-01-01T00:00:00Z".into())
+            )]
+        );
+
+        let ty = r#"<xs:restriction base="xs:duration">
+            <xs:maxInclusive value="P30D"/>
+        </xs:restriction>"#;
+        assert_eq!(check_value(ty, "P4D"), []);
+        assert_eq!(check_value(ty, "PT720H"), []);
+        // One month can be longer or shorter than 30 days, so it is not compared
+        assert_eq!(check_value(ty, "P1M"), []);
+        assert_eq!(
+            check_value(ty, "P1M3D"),
+            [invalid("P1M3D", ValueError::MaxInclusive("P30D".into()))]
+        );
+        assert_eq!(
+            check_value(ty, "P30DT0.001S"),
+            [invalid(
+                "P30DT0.001S",
+                ValueError::MaxInclusive("P30D".into())
+            )]
+        );
+    }
+
+    #[test]
+    fn list() {
+        let ty = r#"<xs:restriction>
+            <xs:simpleType><xs:list itemType="xs:int"/></xs:simpleType>
+            <xs:maxLength value="2"/>
+        </xs:restriction>"#;
+        assert_eq!(check_value(ty, " 1\n 2 "), []);
+        assert_eq!(
+            check_value(ty, "1 x"),
+            [invalid("1 x", ValueError::Invalid("int"))]
+        );
+        assert_eq!(
+            check_value(ty, "1 2 3"),
+            [invalid("1 2 3", ValueError::MaxLength { max: 2, found: 3 })]
+        );
+    }
+
+    #[test]
+    fn union() {
+        let ty = r#"<xs:union memberTypes="xs:date">
+            <xs:simpleType>
+                <xs:restriction base="xs:token"><xs:enumeration value="never"/></xs:restriction>
+            </xs:simpleType>
+        </xs:union>"#;
+        assert_eq!(check_value(ty, "2024-01-01"), []);
+        assert_eq!(check_value(ty, " never "), []);
+        assert_eq!(
+            check_value(ty, "soon"),
+            [invalid("soon", ValueError::Union)]
+        );
+    }
+}
+
+mod schema {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn error(declarations: &str) -> String {
+        Schema::parse(&schema(declarations))
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn not_schema() {
+        assert!(matches!(
+            Schema::parse("<schema/>"),
+            Err(SchemaError::NotSchema(name)) if name == "schema"
+        ));
+        assert!(matches!(
+            Schema::parse("<xs:schema>"),
+            Err(SchemaError::Xml(_))
+        ));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            error(r#"<xs:element name="a" type="b"/>"#),
+            "type `b` is not defined"
+        );
+        assert_eq!(
+            error(r#"<xs:element name="a" type="x:b"/>"#),
+            "prefix `x` is not bound to a namespace"
+        );
+        assert_eq!(
+            error(r#"<xs:element name="a"/><xs:element name="a"/>"#),
+            "element `a` is already defined"
+        );
+        assert_eq!(
+            error(r#"<xs:element type="xs:int"/>"#),
+            "required attribute `name` of `xs:element` is not specified"
+        );
+        assert_eq!(
+            error(
+                r#"<xs:group name="g"><xs:sequence><xs:group ref="g"/></xs:sequence></xs:group>"#
+            ),
+            "`g` has a circular definition"
+        );
+        assert_eq!(
+            error(
+                r#"<xs:simpleType name="t"><xs:restriction base="xs:string">
+                    <xs:pattern value="\p{IsBasicLatin}"/>
+                </xs:restriction></xs:simpleType>"#
+            ),
+            r"invalid pattern `\p{IsBasicLatin}`: block escape `\p{IsBasicLatin}` is not supported"
+        );
+        assert_eq!(
+            error(r#"<xs:redefine schemaLocation="a.xsd"/>"#),
+            "`xs:redefine` is not supported"
+        );
+        assert_eq!(
+            error(
+                r#"<xs:complexType name="t"><xs:simpleContent>
+                    <xs:extension base="xs:anyType"/>
+                </xs:simpleContent></xs:complexType>
+                <xs:element name="a"><xs:simpleType><xs:restriction base="t"/></xs:simpleType></xs:element>"#
+            ),
+            "type `t` is not a simple type"
+        );
+        assert_eq!(
+            error(
+                r#"<xs:simpleType name="t"><xs:restriction base="xs:int">
+                    <xs:minInclusive value="abc"/>
+                </xs:restriction></xs:simpleType>"#
+            ),
+            "value `abc` is not valid for attribute `value` of `xs:minInclusive`"
+        );
+        assert_eq!(
+            error(
+                r#"<xs:simpleType name="t"><xs:restriction base="xs:unsignedByte">
+                    <xs:maxExclusive value="256"/>
+                </xs:restriction></xs:simpleType>"#
+            ),
+            "value `256` is not valid for attribute `value` of `xs:maxExclusive`"
+        );
+    }
+
+    /// Bounded occurrences are unrolled, so the size of nested repetitions is limited
+    #[test]
+    fn large_occurrences() {
+        assert_eq!(
+            error(
+                r#"<xs:element name="a"><xs:complexType>
+                    <xs:sequence maxOccurs="20000">
+                        <xs:element name="b" type="xs:int" maxOccurs="20000"/>
+                    </xs:sequence>
+                </xs:complexType></xs:element>"#
+            ),
+            "`content model with more than 100000 states` is not supported"
+        );
+        assert_eq!(
+            error(
+                r#"<xs:element name="a"><xs:complexType>
+                    <xs:sequence minOccurs="1000" maxOccurs="1000">
+                        <xs:element name="b" type="xs:int" minOccurs="1000" maxOccurs="1000"/>
+                    </xs:sequence>
+                </xs:complexType></xs:element>"#
+            ),
+            "`content model with more than 100000 states` is not supported"
+        );
+
+        let declarations = r#"<xs:element name="a"><xs:complexType>
+            <xs:sequence maxOccurs="200">
+                <xs:element name="b" type="xs:int"/>
+                <xs:element name="c" type="xs:int" maxOccurs="200"/>
+            </xs:sequence>
+        </xs:complexType></xs:element>"#;
+        let xml = format!("<a>{}</a>", "<b>1</b><c>2</c><c>3</c>".repeat(200));
+        assert_eq!(check(declarations, &xml), []);
+    }
+
+    /// Components can be referenced across schema documents
+    #[test]
+    fn parse_all() {
+        let common = r#"
+            <xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:common">
+                <xs:simpleType name="Code">
+                    <xs:restriction base="xs:string"><xs:length value="3"/></xs:restriction>
+                </xs:simpleType>
+            </xs:schema>"#;
+        let main = r#"
+            <xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:c="urn:common">
+                <xs:import namespace="urn:common" schemaLocation="common.xsd"/>
+                <xs:element name="code" type="c:Code"/>
+            </xs:schema>"#;
+        let schema = Schema::parse_all([main, common]).unwrap();
+        let mut reader = NsReader::from_str("<code>ab</code>");
+        let errors: Vec<_> = validate(&schema, &mut reader)
+            .unwrap()
+            .into_iter()
+            .map(|v| v.error)
+            .collect();
+        assert_eq!(
+            errors,
+            [invalid(
+                "ab",
+                ValueError::Length {
+                    expected: 3,
+                    found: 2
+                }
+            )]
+        );
+    }
+}
+
+/// Elements are matched by their expanded names
+#[test]
+fn namespaces() {
+    let xsd = r#"
+        <xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+                   xmlns="urn:a"
+                   targetNamespace="urn:a">
+            <xs:element name="a">
+                <xs:complexType>
+                    <xs:sequence>
+                        <xs:element name="local"/>
+                        <xs:element ref="global"/>
+                    </xs:sequence>
+                    <xs:attribute name="x" form="qualified"/>
+                </xs:complexType>
+            </xs:element>
+            <xs:element name="global"/>
+        </xs:schema>"#;
+    let schema = Schema::parse(xsd).unwrap();
+    let errors = |xml| {
+        let mut reader = NsReader::from_str(xml);
+        validate(&schema, &mut reader)
+            .unwrap()
+            .into_iter()
+            .map(|v| v.error)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        errors(r#"<p:a xmlns:p="urn:a" p:x=""><local/><p:global/></p:a>"#),
+        []
+    );
+    assert_eq!(
+        errors(r#"<a xmlns="urn:a" x=""><local/><global/></a>"#),
+        [
+            UndeclaredAttribute("x".into()),
+            UnexpectedElement {
+                element: "local".into(),
+                expected: vec!["local".into()],
+            },
+            UnexpectedElement {
+                element: "global".into(),
+                expected: vec!["local".into()],
+            },
+            IncompleteContent {
+                expected: vec!["local".into()],
+            },
+        ]
+    );
+    assert_eq!(errors("<a/>"), [UnknownElement("a".into())]);
+}
+
+/// Violations should be reported at the start of the event with line and column
+/// when position tracking is enabled
+#[test]
+fn position() {
+    let schema = Schema::parse(&schema(
+        r#"<xs:element name="a">
+            <xs:complexType>
+                <xs:sequence><xs:element name="b" type="xs:int" maxOccurs="2"/></xs:sequence>
+            </xs:complexType>
+        </xs:element>"#,
+    ))
+    .unwrap();
+    let mut reader = NsReader::from_str("<a>\n  <b>1</b>\n  <b>x</b>\n  <c/>\n</a>");
+    reader.config_mut().track_position = true;
+
+    let violations = validate(&schema, &mut reader).unwrap();
+    assert_eq!(
+        violations.iter().map(|v| v.to_string()).collect::<Vec<_>>(),
+        [
+            "line 3, column 7: /a/b[2]: value `x` is not valid: value is not a valid `xs:int`",
+            "line 4, column 3: /a/c[1]: element `c` is not allowed here",
+        ]
+    );
+}
+
+/// Validator can be fed by events one by one
+#[test]
+fn feed() {
+    let schema = Schema::parse(&schema(r#"<xs:element name="a" type="xs:int"/>"#)).unwrap();
+    let mut validator = Validator::new(&schema);
+    let mut reader = NsReader::from_str("<a>x</a>");
+    loop {
+        let event = reader.read_event().unwrap();
+        validator.feed(&event, &reader).unwrap();
+        if let quick_xml::events::Event::Eof = event {
+            break;
+        }
+    }
+    assert_eq!(
+        validator
+            .take_violations()
+            .into_iter()
+            .map(|v| (v.path, v.position.byte, v.error))
+            .collect::<Vec<_>>(),
+        [("/a".into(), 4, invalid("x", ValueError::Invalid("int")))]
+    );
+    assert_eq!(validator.violations(), []);
+}